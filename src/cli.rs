@@ -1,9 +1,37 @@
 //! CLI definitions using clap
 
+use clap::builder::{PossibleValue, PossibleValuesParser};
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
-use crate::organizer::ConflictStrategy;
+use crate::classifier::Classifier;
+use crate::commands::preview::PreviewMode;
+use crate::duplicates::MergePolicy;
+use crate::export::Format;
+use crate::organizer::{CollisionSuffix, ConflictStrategy, ExtensionCase, LinkKind};
+use crate::progress::ProgressMode;
+use crate::scanner::SizeUnits;
+use crate::template::PRESETS;
+
+/// Valid `--preset` values, so completions and typo errors list them
+fn preset_values() -> PossibleValuesParser {
+    PossibleValuesParser::new(
+        PRESETS
+            .iter()
+            .map(|(name, aliases)| PossibleValue::new(*name).aliases(aliases.iter().copied())),
+    )
+}
+
+/// Parse a `--category` value: a category folder name, matched case-insensitively,
+/// including categories defined in the config (only loaded when the option is given)
+fn parse_category(s: &str) -> Result<String, String> {
+    let names = Classifier::configured().category_names();
+    names
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(s))
+        .map(|name| name.to_string())
+        .ok_or_else(|| format!("Invalid category '{}'. Use: {}", s, names.join(", ")))
+}
 
 /// Parse conflict strategy from string
 fn parse_conflict_strategy(s: &str) -> Result<ConflictStrategy, String> {
@@ -21,6 +49,124 @@ fn parse_conflict_strategy(s: &str) -> Result<ConflictStrategy, String> {
     }
 }
 
+/// Parse duplicate merge policy from string
+fn parse_merge_policy(s: &str) -> Result<MergePolicy, String> {
+    match s.to_lowercase().as_str() {
+        "newest" => Ok(MergePolicy::Newest),
+        _ => Err(format!("Invalid merge policy '{}'. Use: newest", s)),
+    }
+}
+
+/// Parse link kind from string
+fn parse_link_kind(s: &str) -> Result<LinkKind, String> {
+    match s.to_lowercase().as_str() {
+        "symlink" => Ok(LinkKind::Symlink),
+        _ => Err(format!("Invalid link kind '{}'. Use: symlink", s)),
+    }
+}
+
+/// Parse a `--throttle` rate in operations per second (must be positive, and not so
+/// small that the pause between operations can't be represented)
+fn parse_rate(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(rate)
+            if rate > 0.0
+                && rate.is_finite()
+                && std::time::Duration::try_from_secs_f64(1.0 / rate).is_ok() =>
+        {
+            Ok(rate)
+        }
+        _ => Err(format!(
+            "Invalid rate '{}'. Use a positive number of operations per second (e.g. 20 or 0.5)",
+            s
+        )),
+    }
+}
+
+/// Parse extension folder case from string
+fn parse_extension_case(s: &str) -> Result<ExtensionCase, String> {
+    match s.to_lowercase().as_str() {
+        "upper" => Ok(ExtensionCase::Upper),
+        "lower" => Ok(ExtensionCase::Lower),
+        "keep" => Ok(ExtensionCase::Keep),
+        _ => Err(format!(
+            "Invalid extension case '{}'. Use: upper, lower, or keep",
+            s
+        )),
+    }
+}
+
+/// Parse progress mode from string
+fn parse_progress_mode(s: &str) -> Result<ProgressMode, String> {
+    match s.to_lowercase().as_str() {
+        "bar" => Ok(ProgressMode::Bar),
+        "plain" => Ok(ProgressMode::Plain),
+        "off" => Ok(ProgressMode::Off),
+        _ => Err(format!(
+            "Invalid progress mode '{}'. Use: bar, plain, or off",
+            s
+        )),
+    }
+}
+
+/// Parse a history label, which must have some visible text
+fn parse_label(s: &str) -> Result<String, String> {
+    let label = s.trim();
+    if label.is_empty() {
+        return Err("label cannot be empty".to_string());
+    }
+    Ok(label.to_string())
+}
+
+/// Parse a collision suffix pattern
+fn parse_collision_suffix(s: &str) -> Result<CollisionSuffix, String> {
+    CollisionSuffix::parse(s).map_err(|e| e.to_string())
+}
+
+/// Parse an output format from string
+fn parse_format(s: &str) -> Result<Format, String> {
+    match s.to_lowercase().as_str() {
+        "table" => Ok(Format::Table),
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        _ => Err(format!("Invalid format '{}'. Use: table, json, or csv", s)),
+    }
+}
+
+/// The output format, honouring the older `--json`/`--csv` shorthands
+pub fn resolve_format(format: Format, json: bool, csv: bool) -> Format {
+    if json {
+        Format::Json
+    } else if csv {
+        Format::Csv
+    } else {
+        format
+    }
+}
+
+/// Parse size display units from string
+fn parse_size_units(s: &str) -> Result<SizeUnits, String> {
+    match s.to_lowercase().as_str() {
+        "binary" => Ok(SizeUnits::Binary),
+        "si" => Ok(SizeUnits::Si),
+        "iec" => Ok(SizeUnits::Iec),
+        _ => Err(format!("Invalid units '{}'. Use: binary, si, or iec", s)),
+    }
+}
+
+/// Parse preview mode from string
+fn parse_preview_mode(s: &str) -> Result<PreviewMode, String> {
+    match s.to_lowercase().as_str() {
+        "organize" => Ok(PreviewMode::Organize),
+        "duplicates" | "dupes" => Ok(PreviewMode::Duplicates),
+        "clean" => Ok(PreviewMode::Clean),
+        _ => Err(format!(
+            "Invalid preview mode '{}'. Use: organize, duplicates, or clean",
+            s
+        )),
+    }
+}
+
 /// Neat - A smart CLI tool to organize and clean up messy directories
 #[derive(Parser)]
 #[command(name = "neatcli")]
@@ -30,15 +176,48 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
 
-    /// Enable verbose output
-    #[arg(short, long, global = true)]
-    pub verbose: bool,
+    /// Enable verbose output; repeat (-vv, -vvv) for more detailed diagnostic logs
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Append diagnostic logs (scan decisions, rule matches, moves) to this file
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
 
     /// Suppress all output except errors
     #[arg(short, long, global = true)]
     pub quiet: bool,
+
+    /// Print only summary totals instead of per-file previews
+    #[arg(long, global = true)]
+    pub summary_only: bool,
+
+    /// Disable colored output (also honors the NO_COLOR environment variable)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Progress display: bar, plain (text lines for CI logs), or off.
+    /// Defaults to bar on a terminal and plain otherwise
+    #[arg(long, global = true, value_parser = parse_progress_mode)]
+    pub progress: Option<ProgressMode>,
+
+    /// Units for displayed sizes: binary (1024, KB), si (1000, kB) or iec (1024, KiB)
+    #[arg(long, global = true, value_name = "UNITS", value_parser = parse_size_units)]
+    pub units: Option<SizeUnits>,
+
+    /// Read file metadata on all cores while scanning (faster on SSDs with very
+    /// large trees; may be slower on spinning disks)
+    #[arg(long, global = true)]
+    pub fast_scan: bool,
+
+    /// Hash moved files (up to 16 MB) so `undo` can tell if one was replaced
+    /// since; off by default, as it reads every moved file
+    #[arg(long, global = true)]
+    pub verify_undo: bool,
 }
 
+// Parsed once at startup, so the size of the largest variant doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
     /// Organize files by type or date
@@ -59,6 +238,23 @@ pub enum Commands {
         #[arg(long, group = "organize_mode")]
         by_extension: bool,
 
+        /// Folder name case for --by-extension (upper, lower, keep)
+        #[arg(long, value_parser = parse_extension_case, default_value = "upper")]
+        ext_case: ExtensionCase,
+
+        /// Merge folders that would hold fewer than N files into Other
+        /// (--by-type and --by-extension only)
+        #[arg(
+            long,
+            alias = "group-small-categories",
+            value_name = "N",
+            conflicts_with_all = [
+                "by_date", "by_camera", "by_date_taken", "by_artist", "by_album",
+                "by_size", "by_alpha", "smart", "template", "preset", "name_regex",
+            ]
+        )]
+        group_small: Option<usize>,
+
         /// Organize images by camera model (from EXIF data)
         #[arg(long, group = "organize_mode")]
         by_camera: bool,
@@ -75,6 +271,35 @@ pub enum Commands {
         #[arg(long, group = "organize_mode")]
         by_album: bool,
 
+        /// Organize by size bucket (Tiny <1MB, Small <100MB, Large <1GB, Huge)
+        #[arg(long, group = "organize_mode")]
+        by_size: bool,
+
+        /// Organize into A/, B/, ... folders by the first letter of the file name
+        /// (# for names starting with a digit or symbol)
+        #[arg(long, alias = "group-by-first-letter", group = "organize_mode")]
+        by_alpha: bool,
+
+        /// Pick the mode per file: photos by date taken, music by artist/album,
+        /// everything else by type
+        #[arg(long, group = "organize_mode")]
+        smart: bool,
+
+        /// Put organized folders under DIR instead of inside each scanned directory
+        #[arg(long, short = 'o', value_name = "DIR")]
+        output_dir: Option<PathBuf>,
+
+        /// Mirror each file's path relative to the scanned directory under
+        /// --output-dir, ignoring the organize mode
+        #[arg(
+            long,
+            requires = "output_dir",
+            conflicts_with_all = [
+                "organize_mode", "template", "preset", "name_regex", "group_small",
+            ]
+        )]
+        keep_structure: bool,
+
         /// Preview changes without executing (default behavior)
         #[arg(long, short = 'n')]
         dry_run: bool,
@@ -83,10 +308,27 @@ pub enum Commands {
         #[arg(long, short)]
         execute: bool,
 
+        /// In the preview, show why each file goes where it does (mode, template, ...)
+        #[arg(long, conflicts_with = "execute")]
+        explain: bool,
+
+        /// After the preview, report how many moves would collide with existing
+        /// files or with each other, by destination folder
+        #[arg(long, conflicts_with = "execute")]
+        simulate_conflicts: bool,
+
+        /// Print the preview as `- old/path` / `+ new/path` lines, sorted for clean diffs
+        #[arg(long, conflicts_with_all = ["execute", "explain"])]
+        diff: bool,
+
         /// Patterns to ignore (can be specified multiple times)
         #[arg(long, short = 'I')]
         ignore: Vec<String>,
 
+        /// Don't skip OS junk files (.DS_Store, Thumbs.db, __MACOSX, desktop.ini)
+        #[arg(long)]
+        no_default_ignores: bool,
+
         /// Minimum file size to include (e.g., 1MB, 10KB, 1G)
         #[arg(long)]
         min_size: Option<String>,
@@ -99,14 +341,131 @@ pub enum Commands {
         #[arg(long)]
         after: Option<String>,
 
+        /// Only include files modified within this long ago (e.g., 7d, 2w, 12h)
+        #[arg(long, value_name = "DURATION", conflicts_with = "after")]
+        since: Option<String>,
+
         /// Only include files modified before this date (YYYY-MM-DD)
         #[arg(long)]
         before: Option<String>,
 
+        /// Only include photos taken on or after this date (EXIF, YYYY-MM-DD)
+        #[arg(long)]
+        taken_after: Option<String>,
+
+        /// Only include photos taken on or before this date (EXIF, YYYY-MM-DD)
+        #[arg(long)]
+        taken_before: Option<String>,
+
+        /// With --taken-after/--taken-before, exclude files without an EXIF date
+        /// instead of filtering them by modification time
+        #[arg(long)]
+        taken_only: bool,
+
+        /// Only include images at least this many pixels wide
+        #[arg(long, value_name = "PX")]
+        min_width: Option<u32>,
+
+        /// Only include images at least this many pixels tall
+        #[arg(long, value_name = "PX")]
+        min_height: Option<u32>,
+
+        /// Only include images at most this many pixels wide
+        #[arg(long, value_name = "PX")]
+        max_width: Option<u32>,
+
+        /// Only include images at most this many pixels tall
+        #[arg(long, value_name = "PX")]
+        max_height: Option<u32>,
+
+        /// With dimension filters, exclude files that aren't readable images
+        /// instead of keeping them
+        #[arg(long)]
+        images_only: bool,
+
         /// Copy files instead of moving
         #[arg(long, short = 'c')]
         copy: bool,
 
+        /// Give copies the source's modified and accessed times
+        #[arg(long, requires = "copy")]
+        preserve_times: bool,
+
+        /// Leave a symlink at each old path pointing to the moved file
+        #[arg(long, conflicts_with = "copy")]
+        leave_symlink: bool,
+
+        /// When a file is identical to one already moved into the same folder,
+        /// hard-link it to that copy instead of storing the content twice
+        #[arg(long, conflicts_with_all = ["copy", "link", "atomic"])]
+        link_duplicates_in_place: bool,
+
+        /// Build an organized view of links to the files instead of moving them;
+        /// the originals stay where they are and undo removes the link tree
+        #[arg(
+            long,
+            value_name = "KIND",
+            value_parser = parse_link_kind,
+            conflicts_with_all = ["copy", "leave_symlink", "atomic", "delete_empty_after"]
+        )]
+        link: Option<LinkKind>,
+
+        /// Stop at the first error instead of recording it and continuing
+        #[arg(long)]
+        fail_fast: bool,
+
+        /// Perform at most this many moves per second, to avoid saturating a
+        /// network drive (default: unlimited)
+        #[arg(long, value_name = "OPS", value_parser = parse_rate, conflicts_with = "atomic")]
+        throttle: Option<f64>,
+
+        /// Apply every move or none: files are staged first and all returned to
+        /// their original places if any move fails (--on-conflict skip or rename)
+        #[arg(long, conflicts_with_all = ["copy", "leave_symlink", "fail_fast"])]
+        atomic: bool,
+
+        /// After moving, remove folders under the organized path that are left empty
+        #[arg(long, conflicts_with = "copy")]
+        delete_empty_after: bool,
+
+        /// After moving, move folders under the organized path that the moves left
+        /// empty into an Empty/ folder instead of leaving them in place
+        #[arg(
+            long,
+            conflicts_with_all = ["copy", "link", "atomic", "delete_empty_after", "apply"]
+        )]
+        include_empty_dirs: bool,
+
+        /// Append a timestamped summary of each executed run to this file
+        #[arg(long, value_name = "PATH")]
+        report: Option<PathBuf>,
+
+        /// Run this shell command once after an executed run; {count}, {bytes} and
+        /// {path} are replaced with the files moved, their total size and the folder
+        #[arg(long, value_name = "CMD")]
+        follow_up_command: Option<String>,
+
+        /// After an executed run, print its totals as one line of JSON
+        #[arg(long)]
+        summary_json: bool,
+
+        /// Print the planned moves as a table, JSON or CSV (json and csv only preview)
+        #[arg(
+            long,
+            value_parser = parse_format,
+            default_value = "table",
+            conflicts_with_all = ["execute", "plan", "apply"]
+        )]
+        format: Format,
+
+        /// Save the planned moves to a JSON file instead of previewing or moving
+        #[arg(long, value_name = "FILE", conflicts_with = "execute")]
+        plan: Option<PathBuf>,
+
+        /// Apply moves from a plan file written by --plan, without rescanning
+        #[arg(long, value_name = "FILE", conflicts_with = "plan")]
+        apply: Option<PathBuf>,
+
         /// Scan subdirectories recursively
         #[arg(long, short = 'r')]
         recursive: bool,
@@ -127,21 +486,104 @@ pub enum Commands {
         #[arg(long)]
         regex: Option<String>,
 
+        /// Match --startswith, --endswith, --contains, --regex and --only-ext/--not-ext
+        /// with exact case (by default case is ignored)
+        #[arg(long)]
+        case_sensitive: bool,
+
         /// Filter by MIME type (e.g., "image/*", "application/pdf")
         #[arg(long)]
         mime: Option<String>,
 
+        /// Detect MIME types from file contents (magic bytes) instead of extensions
+        #[arg(long, requires = "mime")]
+        sniff: bool,
+
+        /// Only include these extensions, comma-separated (e.g., jpg,png,gif)
+        #[arg(
+            long,
+            alias = "include-ext",
+            value_name = "EXTS",
+            value_delimiter = ','
+        )]
+        only_ext: Vec<String>,
+
+        /// Never include these extensions, comma-separated (e.g., tmp,part)
+        #[arg(
+            long,
+            alias = "exclude-ext",
+            value_name = "EXTS",
+            value_delimiter = ','
+        )]
+        not_ext: Vec<String>,
+
+        /// Only include extensions listed in this file, one per line (# comments allowed)
+        #[arg(long, alias = "include-ext-from", value_name = "PATH")]
+        only_ext_file: Option<PathBuf>,
+
+        /// Never include extensions listed in this file, one per line (# comments allowed)
+        #[arg(long, alias = "exclude-ext-from", value_name = "PATH")]
+        not_ext_file: Option<PathBuf>,
+
         /// Filter files containing this text in their content (TXT, MD, JSON, etc.)
         #[arg(long)]
         content: Option<String>,
 
+        /// Only organize files of this category
+        #[arg(long, value_parser = parse_category)]
+        category: Option<String>,
+
         /// Custom destination template (e.g., "{year}/{month}/{category}/{filename}")
         #[arg(long)]
         template: Option<String>,
 
+        /// Use a built-in template preset (e.g., photos, music, by-date)
+        #[arg(long, conflicts_with = "template", value_parser = preset_values(), ignore_case = true)]
+        preset: Option<String>,
+
+        /// Route files by named regex captures, e.g. "(?P<proj>\w+)_\d+\.log" (use {proj} in --template)
+        #[arg(long, value_name = "REGEX")]
+        name_regex: Option<String>,
+
+        /// Folder for files that don't match --name-regex (default: leave them in place)
+        #[arg(long, value_name = "FOLDER", requires = "name_regex")]
+        regex_fallback: Option<String>,
+
         /// How to handle file conflicts (skip, overwrite, rename, ask)
         #[arg(long, value_parser = parse_conflict_strategy, default_value = "rename")]
         on_conflict: ConflictStrategy,
+
+        /// Suffix for renamed conflicts: {n} counts up, {date}/{time} stamp the rename
+        /// (e.g. " ({n})" or "-{date}"; default "_{n}", or `collision_suffix` in the config)
+        #[arg(long, value_name = "PATTERN", value_parser = parse_collision_suffix)]
+        collision_suffix: Option<CollisionSuffix>,
+
+        /// Note shown in `neat history` and `undo` for this run (e.g. "Q2 photo import")
+        #[arg(long, value_name = "LABEL", alias = "batch-name", value_parser = parse_label)]
+        label: Option<String>,
+    },
+
+    /// Preview what organize, duplicates or clean would do (never modifies files)
+    Preview {
+        /// Target directory to preview
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// What to preview (organize, duplicates, clean)
+        #[arg(long, value_parser = parse_preview_mode, default_value = "organize")]
+        mode: PreviewMode,
+
+        /// Include subdirectories (organize mode)
+        #[arg(long, short = 'r')]
+        recursive: bool,
+
+        /// Custom destination template (organize mode)
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Also list files older than duration, e.g. 30d (clean mode)
+        #[arg(long)]
+        older_than: Option<String>,
     },
 
     /// Clean old files from a directory
@@ -154,10 +596,48 @@ pub enum Commands {
         #[arg(long)]
         older_than: Option<String>,
 
+        /// Delete files larger than this size (e.g., 1GB); combines with --older-than
+        #[arg(long, value_name = "SIZE", conflicts_with_all = ["duplicates", "keep_recent"])]
+        larger_than: Option<String>,
+
+        /// Delete files smaller than this size (e.g., 1KB); combines with --older-than
+        #[arg(long, value_name = "SIZE", conflicts_with_all = ["duplicates", "keep_recent"])]
+        smaller_than: Option<String>,
+
         /// Remove empty folders
         #[arg(long)]
         empty_folders: bool,
 
+        /// With --empty-folders, treat folders holding only OS junk (.DS_Store,
+        /// Thumbs.db, desktop.ini) as empty and delete the junk with them
+        #[arg(long, requires = "empty_folders")]
+        remove_junk: bool,
+
+        /// Remove zero-byte files
+        #[arg(long)]
+        empty_files: bool,
+
+        /// Remove symlinks whose target no longer exists
+        #[arg(long)]
+        broken_symlinks: bool,
+
+        /// Also remove duplicate copies (keeping one of each), found in the same
+        /// scan as --older-than
+        #[arg(long)]
+        duplicates: bool,
+
+        /// Remove all but the N most recently modified files
+        #[arg(long, value_name = "N", conflicts_with_all = ["older_than", "duplicates"])]
+        keep_recent: Option<usize>,
+
+        /// With --keep-recent, keep N files in each folder instead of N overall
+        #[arg(long, requires = "keep_recent")]
+        per_dir: bool,
+
+        /// Maximum directory depth to scan (default: unlimited)
+        #[arg(long, value_name = "N")]
+        depth: Option<usize>,
+
         /// Preview changes without executing
         #[arg(long, short = 'n')]
         dry_run: bool,
@@ -170,6 +650,14 @@ pub enum Commands {
         #[arg(long)]
         trash: bool,
 
+        /// Delete at most this many files per second (default: unlimited)
+        #[arg(long, value_name = "OPS", value_parser = parse_rate)]
+        throttle: Option<f64>,
+
+        /// After an executed run, print its totals as one line of JSON
+        #[arg(long)]
+        summary_json: bool,
+
         /// Minimum file size to include (e.g., 1MB, 10KB)
         #[arg(long)]
         min_size: Option<String>,
@@ -185,6 +673,14 @@ pub enum Commands {
         /// Only include files modified before this date (YYYY-MM-DD)
         #[arg(long)]
         before: Option<String>,
+
+        /// Ask to type DELETE before removing more than this much (e.g., 10GB)
+        #[arg(long, value_name = "SIZE")]
+        confirm_threshold: Option<String>,
+
+        /// Note shown in `neat history` and `undo` for this run (e.g. "old downloads")
+        #[arg(long, value_name = "LABEL", alias = "batch-name", value_parser = parse_label)]
+        label: Option<String>,
     },
 
     /// Find duplicate files by content
@@ -197,6 +693,33 @@ pub enum Commands {
         #[arg(long)]
         delete: bool,
 
+        /// With --delete, choose the copy to keep in each group from a list
+        #[arg(long, short, requires = "delete", conflicts_with_all = ["merge", "prefer_keep"])]
+        interactive: bool,
+
+        /// Keep one file per group chosen by policy and delete the rest
+        /// (newest: the most recently modified copy)
+        #[arg(long, value_name = "POLICY", value_parser = parse_merge_policy)]
+        merge: Option<MergePolicy>,
+
+        /// Compare these folders with each other instead of scanning PATH; only
+        /// groups with copies in at least two of them are reported
+        #[arg(long, value_name = "DIR", num_args = 2..)]
+        across: Vec<PathBuf>,
+
+        /// With --across, keep the copies in this folder and delete the others
+        #[arg(
+            long,
+            value_name = "DIR",
+            requires = "across",
+            conflicts_with = "merge"
+        )]
+        prefer_keep: Option<PathBuf>,
+
+        /// Show wasted space per folder instead of listing each group
+        #[arg(long, conflicts_with_all = ["format", "json", "csv", "jsonl"])]
+        by_dir: bool,
+
         /// Preview changes without executing
         #[arg(long, short = 'n')]
         dry_run: bool,
@@ -225,13 +748,42 @@ pub enum Commands {
         #[arg(long)]
         before: Option<String>,
 
-        /// Output results as JSON
-        #[arg(long)]
+        /// Output results as a table, JSON or CSV
+        #[arg(long, value_parser = parse_format, default_value = "table")]
+        format: Format,
+
+        /// Shorthand for --format json
+        #[arg(long, hide = true, conflicts_with_all = ["format", "csv"])]
         json: bool,
 
-        /// Output results as CSV
-        #[arg(long)]
+        /// Shorthand for --format csv
+        #[arg(long, hide = true, conflicts_with = "format")]
         csv: bool,
+
+        /// Only look for duplicates among files matching this glob (e.g., "*.jpg")
+        #[arg(long, alias = "dedupe-within", value_name = "GLOB")]
+        pattern: Option<String>,
+
+        /// Maximum directory depth to scan (default: unlimited)
+        #[arg(long, value_name = "N")]
+        depth: Option<usize>,
+
+        /// Don't skip OS junk files (.DS_Store, Thumbs.db, __MACOSX, desktop.ini)
+        #[arg(long)]
+        no_default_ignores: bool,
+
+        /// Maximum number of hashing threads (lower is often faster on spinning disks)
+        #[arg(long, value_name = "N")]
+        threads: Option<usize>,
+
+        /// Stream results as newline-delimited JSON, one group per line
+        #[arg(long, alias = "json-lines", conflicts_with_all = ["format", "json", "csv"])]
+        jsonl: bool,
+
+        /// Also report loose files whose contents are stored in a zip archive
+        /// (with --delete, offers to remove the loose copies)
+        #[arg(long, conflicts_with_all = ["format", "json", "csv", "jsonl"])]
+        peek_archives: bool,
     },
 
     /// Find visually similar images using perceptual hashing
@@ -244,6 +796,11 @@ pub enum Commands {
         #[arg(long, short, default_value = "10")]
         threshold: u32,
 
+        /// Compare videos by frames sampled with ffmpeg instead of comparing images
+        /// (needs the `video` build feature and ffmpeg installed)
+        #[arg(long)]
+        video: bool,
+
         /// Delete similar images (keeps the first in each group)
         #[arg(long)]
         delete: bool,
@@ -259,6 +816,23 @@ pub enum Commands {
         /// Move similar images to trash instead of permanent deletion
         #[arg(long)]
         trash: bool,
+
+        /// Maximum number of hashing threads (lower is often faster on spinning disks)
+        #[arg(long, value_name = "N")]
+        threads: Option<usize>,
+
+        /// Only include photos taken on or after this date (EXIF, YYYY-MM-DD)
+        #[arg(long)]
+        taken_after: Option<String>,
+
+        /// Only include photos taken on or before this date (EXIF, YYYY-MM-DD)
+        #[arg(long)]
+        taken_before: Option<String>,
+
+        /// With --taken-after/--taken-before, exclude files without an EXIF date
+        /// instead of filtering them by modification time
+        #[arg(long)]
+        taken_only: bool,
     },
 
     /// Show statistics about a directory
@@ -267,16 +841,113 @@ pub enum Commands {
         #[arg(default_value = ".")]
         path: PathBuf,
 
-        /// Output results as JSON
-        #[arg(long)]
+        /// Output results as a table, JSON or CSV
+        #[arg(long, value_parser = parse_format, default_value = "table")]
+        format: Format,
+
+        /// Shorthand for --format json
+        #[arg(long, hide = true, conflicts_with = "format")]
         json: bool,
+
+        /// Show what changed since a snapshot saved with --format json
+        #[arg(long, value_name = "FILE", conflicts_with_all = ["format", "json"])]
+        compare: Option<PathBuf>,
+
+        /// Maximum directory depth to scan (default: unlimited)
+        #[arg(long, value_name = "N")]
+        depth: Option<usize>,
+
+        /// Also estimate space wasted by duplicate files (hashes every file, slow)
+        #[arg(long, alias = "duplicates")]
+        with_duplicates: bool,
+    },
+
+    /// List every file with its size, modified date, category and MIME type
+    /// (read-only; use --format json or csv to feed other tools)
+    Export {
+        /// Target directory to inventory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Output as a table, JSON or CSV
+        #[arg(long, value_parser = parse_format, default_value = "table")]
+        format: Format,
+
+        /// Maximum directory depth to scan (default: unlimited)
+        #[arg(long, value_name = "N")]
+        depth: Option<usize>,
+    },
+
+    /// Show how a file is classified: extension, category, MIME type, metadata
+    /// and matching config rule
+    Classify {
+        /// File to inspect
+        path: PathBuf,
+    },
+
+    /// Show which files and folders take up the most space
+    Size {
+        /// Target directory to measure
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Number of entries to show
+        #[arg(long, default_value = "10", value_name = "N")]
+        top: usize,
+    },
+
+    /// Undo organizing by type: move files out of category folders (Images/,
+    /// Documents/, ...) back into the folder and remove the emptied category folders
+    Flatten {
+        /// Folder holding the category folders
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Preview changes without executing
+        #[arg(long, short = 'n')]
+        dry_run: bool,
+
+        /// Actually move the files
+        #[arg(long, short)]
+        execute: bool,
+    },
+
+    /// Trim whitespace, normalize unicode (NFC) and remove zero-width characters in file names
+    TidyNames {
+        /// Target directory
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Include subdirectories
+        #[arg(long, short = 'r')]
+        recursive: bool,
+
+        /// Preview changes without executing
+        #[arg(long, short = 'n')]
+        dry_run: bool,
+
+        /// Actually rename the files
+        #[arg(long, short)]
+        execute: bool,
     },
 
     /// Undo the last operation
-    Undo,
+    Undo {
+        /// Show what would be restored, without touching files or history
+        #[arg(long, short = 'n', alias = "list")]
+        dry_run: bool,
+    },
 
     /// Show operation history
-    History,
+    History {
+        /// Output as a table, or every batch and its operations as JSON or CSV
+        #[arg(long, value_parser = parse_format, default_value = "table")]
+        format: Format,
+
+        /// Shorthand for --format json
+        #[arg(long, hide = true, conflicts_with = "format")]
+        json: bool,
+    },
 
     /// Watch a directory and auto-organize new files
     Watch {
@@ -300,9 +971,26 @@ pub enum Commands {
         #[arg(long, short)]
         config: Option<PathBuf>,
 
+        /// Reload rules whenever the config file changes, without restarting
+        #[arg(long)]
+        config_reload: bool,
+
         /// Automatically move files (otherwise just preview)
         #[arg(long, short)]
         auto: bool,
+
+        /// Use a saved profile's mode, filters, ignore patterns and conflict handling
+        #[arg(long, value_name = "NAME", conflicts_with = "organize_mode")]
+        profile: Option<String>,
+
+        /// How long to wait for changes to settle before organizing (e.g. 10s, 1m; default 2s)
+        #[arg(long, value_name = "DURATION")]
+        debounce: Option<String>,
+
+        /// Print a tally of detected files and where they went (or would go)
+        /// every DURATION (e.g. 1m, 1h)
+        #[arg(long, value_name = "DURATION")]
+        stats_interval: Option<String>,
     },
 
     /// Manage configuration
@@ -353,6 +1041,13 @@ pub enum ConfigAction {
         #[arg(short, long)]
         path: Option<PathBuf>,
     },
+
+    /// Interactively build a rule and append it to the config file
+    AddRule {
+        /// Path to config file (default: ~/.neat/config.toml)
+        #[arg(short, long)]
+        path: Option<PathBuf>,
+    },
 }
 
 /// Quick action presets for common workflows
@@ -372,6 +1067,17 @@ pub enum QuickAction {
         dry_run: bool,
     },
 
+    /// Sweep screenshots off ~/Desktop into Screenshots/<year>/<month>
+    Screenshots {
+        /// Also sweep screenshots from ~/Downloads
+        #[arg(long)]
+        downloads: bool,
+
+        /// Preview changes without executing
+        #[arg(long, short = 'n')]
+        dry_run: bool,
+    },
+
     /// Organize photos by date taken (EXIF)
     Photos {
         /// Target directory (default: ~/Pictures)
@@ -400,13 +1106,17 @@ pub enum QuickAction {
         #[arg(long, short, default_value = "30")]
         days: u32,
 
-        /// Move to trash instead of preview
+        /// Move to trash instead of deleting permanently
         #[arg(long)]
         trash: bool,
 
         /// Preview changes without executing
         #[arg(long, short = 'n')]
         dry_run: bool,
+
+        /// Skip the confirmation prompt
+        #[arg(long, short = 'y', conflicts_with = "dry_run")]
+        yes: bool,
     },
 }
 
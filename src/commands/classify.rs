@@ -0,0 +1,103 @@
+//! Classify command handler
+
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use colored::*;
+
+use crate::classifier::Classifier;
+use crate::config::Config as NeatConfig;
+use crate::filters::{get_mime_type, sniff_mime};
+use crate::metadata::{is_audio_supported, is_exif_supported, AudioMetadata, ImageMetadata};
+use crate::scanner::FileInfo;
+
+/// Show how neat sees a single file: extension, category, MIME type, metadata and
+/// the config rule that would route it
+pub fn run(path: &Path) -> Result<()> {
+    let canonical_path = path
+        .canonicalize()
+        .with_context(|| format!("Path does not exist: {:?}", path))?;
+    if !canonical_path.is_file() {
+        bail!("Not a file: {:?}", path);
+    }
+
+    let file = FileInfo::from_path(&canonical_path)?;
+
+    println!(
+        "{} Classifying {}\n",
+        "→".cyan(),
+        canonical_path.display().to_string().bold()
+    );
+
+    let extension = match (&file.extension, &file.extension_raw) {
+        (Some(ext), Some(raw)) if ext != raw => format!("{} (on disk: {})", ext, raw),
+        (Some(ext), _) => ext.clone(),
+        (None, _) => "none".dimmed().to_string(),
+    };
+    print_field("Extension", &extension);
+
+    let category = Classifier::configured().category_name(file.extension.as_deref());
+    print_field("Category", &category.cyan().to_string());
+
+    let mime = match (get_mime_type(&canonical_path), sniff_mime(&canonical_path)) {
+        (Some(mime), Some(sniffed)) if mime != sniffed => {
+            format!("{} (content looks like {})", mime, sniffed)
+        }
+        (Some(mime), _) => mime,
+        (None, _) => "unknown".dimmed().to_string(),
+    };
+    print_field("MIME type", &mime);
+
+    if is_exif_supported(&canonical_path) {
+        let exif = match ImageMetadata::from_path(&canonical_path) {
+            Some(meta) => {
+                let mut parts = Vec::new();
+                if let Some(camera) = meta.camera_folder_name() {
+                    parts.push(format!("camera {}", camera));
+                }
+                if let Some(taken) = meta.date_taken {
+                    parts.push(format!("taken {}", taken));
+                }
+                if parts.is_empty() {
+                    "present, no camera or date".to_string()
+                } else {
+                    parts.join(", ")
+                }
+            }
+            None => "none".dimmed().to_string(),
+        };
+        print_field("EXIF", &exif);
+    }
+
+    if is_audio_supported(&canonical_path) {
+        let tags = match AudioMetadata::from_path(&canonical_path) {
+            Some(meta) => format!(
+                "artist {}, album {}",
+                meta.artist_folder_name().unwrap_or_else(|| "-".to_string()),
+                meta.album_folder_name().unwrap_or_else(|| "-".to_string())
+            ),
+            None => "none".dimmed().to_string(),
+        };
+        print_field("Audio tags", &tags);
+    }
+
+    let rule = match NeatConfig::load_default()? {
+        Some(config) => match config.find_matching_rule(&file.name) {
+            Some(rule) => format!(
+                "{} ({} → {})",
+                rule.name.green(),
+                rule.pattern,
+                rule.destination
+            ),
+            None => "no rule matches".dimmed().to_string(),
+        },
+        None => "no config file".dimmed().to_string(),
+    };
+    print_field("Config rule", &rule);
+
+    Ok(())
+}
+
+fn print_field(label: &str, value: &str) {
+    println!("  {:12} {}", label.bold(), value);
+}
@@ -1,27 +1,43 @@
 //! Clean command handler
 
+use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 
 use anyhow::{Context, Result};
 use colored::*;
+use serde::Serialize;
 
 use crate::cleaner;
-use crate::scanner::{parse_date, parse_size, scan_directory, ScanOptions};
+use crate::scanner::{parse_date, parse_size, scan_directory, FileInfo, ScanOptions, SizeUnits};
 
 /// Clean old files and empty folders
 #[allow(clippy::too_many_arguments)]
 pub fn run(
     path: &Path,
     older_than: Option<String>,
+    larger_than: Option<String>,
+    smaller_than: Option<String>,
     empty_folders: bool,
+    remove_junk: bool,
+    empty_files: bool,
+    broken_symlinks: bool,
+    duplicates: bool,
+    keep_recent: Option<usize>,
+    per_dir: bool,
+    depth: Option<usize>,
     dry_run: bool,
     execute: bool,
     use_trash: bool,
+    throttle: Option<f64>,
     min_size: Option<String>,
     max_size: Option<String>,
     after: Option<String>,
     before: Option<String>,
+    confirm_threshold: Option<String>,
+    summary_only: bool,
+    summary_json: bool,
+    units: SizeUnits,
 ) -> Result<()> {
     let canonical_path = path
         .canonicalize()
@@ -47,7 +63,75 @@ pub fn run(
         .transpose()
         .map_err(|e| anyhow::anyhow!("{}", e))?;
 
-    if let Some(duration_str) = older_than {
+    let confirm_threshold = confirm_threshold
+        .map(|s| parse_size(&s))
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let size_limits = cleaner::SizeLimits {
+        larger_than: larger_than
+            .map(|s| parse_size(&s))
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("{}", e))?,
+        smaller_than: smaller_than
+            .map(|s| parse_size(&s))
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("{}", e))?,
+    };
+
+    let mut summary = CleanSummary::default();
+    // Files picked by every check, removed together so the prompts and
+    // --confirm-threshold cover the whole run
+    let mut selected: Vec<FileInfo> = Vec::new();
+
+    if duplicates {
+        // One scan serves both the age check and duplicate detection
+        println!(
+            "{} Scanning {} for old files and duplicates...",
+            "→".cyan(),
+            canonical_path.display().to_string().bold()
+        );
+
+        let max_age = older_than
+            .as_deref()
+            .map(cleaner::parse_duration)
+            .transpose()?;
+        let options = ScanOptions {
+            max_depth: depth,
+            min_size: min_size_bytes,
+            max_size: max_size_bytes,
+            after_date,
+            before_date,
+            ..Default::default()
+        };
+
+        let files = scan_directory(&canonical_path, &options)?;
+        let found = cleaner::find_old_and_duplicates(&files, max_age)?;
+        let to_remove = found.all();
+
+        if to_remove.is_empty() {
+            println!("{}", "No old files or duplicates found.".green());
+        } else {
+            if !summary_only {
+                if let Some(ref duration_str) = older_than {
+                    print_file_list(&format!("Older than {}:", duration_str), &found.old, units);
+                }
+                print_file_list("Duplicate copies:", &found.duplicates, units);
+            }
+            println!(
+                "\n{} {} files ({}) to remove: {} old, {} duplicates",
+                "Summary:".bold(),
+                to_remove.len(),
+                units.format(to_remove.iter().map(|f| f.size).sum()),
+                found.old.len(),
+                found.duplicates.len()
+            );
+
+            if execute && !dry_run {
+                selected.extend(to_remove.iter().map(|&file| file.clone()));
+            }
+        }
+    } else if let Some(duration_str) = older_than {
         let duration = cleaner::parse_duration(&duration_str)?;
 
         println!(
@@ -59,7 +143,7 @@ pub fn run(
 
         let options = ScanOptions {
             include_hidden: false,
-            max_depth: None,
+            max_depth: depth,
             follow_symlinks: false,
             ignore_patterns: Vec::new(),
             min_size: min_size_bytes,
@@ -70,15 +154,190 @@ pub fn run(
         };
 
         let files = scan_directory(&canonical_path, &options)?;
-        let old_files = cleaner::find_old_files(&files, duration);
+        let mut old_files = cleaner::find_old_files(&files, duration);
+        let mut label = duration_str;
+        if size_limits.is_set() {
+            old_files.retain(|f| size_limits.matches(f));
+            label = format!("{} and {}", label, size_limits.describe(units));
+        }
 
         if execute && !dry_run {
-            cleaner::execute_clean(&old_files, false, use_trash)?;
+            selected.extend(old_files.iter().map(|&file| file.clone()));
+        } else if summary_only {
+            cleaner::print_clean_summary(&old_files, &label, units);
+        } else {
+            cleaner::preview_clean(&old_files, &label, units);
+        }
+    } else if size_limits.is_set() {
+        let description = size_limits.describe(units);
+        println!(
+            "{} Scanning {} for files {}...",
+            "→".cyan(),
+            canonical_path.display().to_string().bold(),
+            description.cyan()
+        );
+
+        let options = ScanOptions {
+            max_depth: depth,
+            min_size: min_size_bytes,
+            max_size: max_size_bytes,
+            after_date,
+            before_date,
+            ..Default::default()
+        };
+
+        let files = scan_directory(&canonical_path, &options)?;
+        let matched = cleaner::find_by_size(&files, size_limits);
+
+        if matched.is_empty() {
+            println!("{}", format!("No files {} found.", description).green());
+        } else {
+            if !summary_only {
+                print_file_list(&format!("Files {}:", description), &matched, units);
+            }
+            println!(
+                "\n{} {} files ({}) to remove",
+                "Summary:".bold(),
+                matched.len(),
+                units.format(matched.iter().map(|f| f.size).sum())
+            );
+
+            if execute && !dry_run {
+                selected.extend(matched.iter().map(|&file| file.clone()));
+            }
+        }
+    }
+
+    if let Some(keep) = keep_recent {
+        let scope = if per_dir { " in each folder" } else { "" };
+        println!(
+            "{} Scanning {} to keep the {} newest files{}...",
+            "→".cyan(),
+            canonical_path.display().to_string().bold(),
+            keep.to_string().cyan(),
+            scope
+        );
+
+        let options = ScanOptions {
+            max_depth: depth,
+            min_size: min_size_bytes,
+            max_size: max_size_bytes,
+            after_date,
+            before_date,
+            ..Default::default()
+        };
+
+        let files = scan_directory(&canonical_path, &options)?;
+        let older = cleaner::find_all_but_newest(&files, keep, per_dir);
+
+        if older.is_empty() {
+            println!("{}", "Nothing beyond the newest files to remove.".green());
+        } else {
+            if !summary_only {
+                print_file_list("Older than the newest files kept:", &older, units);
+            }
+            println!(
+                "\n{} {} files ({}) to remove",
+                "Summary:".bold(),
+                older.len(),
+                units.format(older.iter().map(|f| f.size).sum())
+            );
+
+            if execute && !dry_run {
+                selected.extend(older.iter().map(|&file| file.clone()));
+            }
+        }
+    }
+
+    if empty_files {
+        println!(
+            "{} Scanning for empty files in {}...",
+            "→".cyan(),
+            canonical_path.display().to_string().bold()
+        );
+
+        let options = ScanOptions {
+            max_depth: depth,
+            after_date,
+            before_date,
+            ..Default::default()
+        };
+
+        let files = scan_directory(&canonical_path, &options)?;
+        let empty = cleaner::find_empty_files(&files);
+
+        if empty.is_empty() {
+            println!("{}", "No empty files found.".green());
+        } else {
+            if !summary_only {
+                println!("\n{}", "Empty files:".yellow().bold());
+                for file in &empty {
+                    println!("  {} {}", "○".yellow(), file.path.display());
+                }
+            }
+            println!("\n{} {} empty files found", "Summary:".bold(), empty.len());
+
+            if execute && !dry_run {
+                selected.extend(empty.iter().map(|&file| file.clone()));
+            }
+        }
+    }
+
+    if !selected.is_empty() {
+        // A file can be picked by more than one check
+        let mut seen = HashSet::new();
+        selected.retain(|file| seen.insert(file.path.clone()));
+        let files: Vec<&FileInfo> = selected.iter().collect();
+        summary.add_files(cleaner::execute_clean(
+            &files,
+            false,
+            use_trash,
+            throttle,
+            confirm_threshold,
+            units,
+        )?);
+    }
+
+    if broken_symlinks {
+        println!(
+            "{} Scanning for broken symlinks in {}...",
+            "→".cyan(),
+            canonical_path.display().to_string().bold()
+        );
+
+        let links = cleaner::find_broken_symlinks(&canonical_path, depth);
+
+        if links.is_empty() {
+            println!("{}", "No broken symlinks found.".green());
         } else {
-            cleaner::preview_clean(&old_files, &duration_str);
+            if !summary_only {
+                println!("\n{}", "Broken symlinks:".yellow().bold());
+                for link in &links {
+                    let target = fs::read_link(link)
+                        .map(|t| t.display().to_string())
+                        .unwrap_or_default();
+                    println!(
+                        "  {} {} {} {}",
+                        "○".yellow(),
+                        link.display(),
+                        "→".dimmed(),
+                        target.dimmed()
+                    );
+                }
+            }
+            println!(
+                "\n{} {} broken symlinks found",
+                "Summary:".bold(),
+                links.len()
+            );
+
+            if execute && !dry_run {
+                summary.removed_symlinks += cleaner::execute_remove_symlinks(&links, false)?;
+            }
         }
     }
 
+    // Empty files are removed first so folders holding only empty files become empty
     if empty_folders {
         println!(
             "{} Scanning for empty folders in {}...",
@@ -86,14 +345,21 @@ pub fn run(
             canonical_path.display().to_string().bold()
         );
 
-        let empty_dirs = cleaner::find_empty_dirs(&canonical_path)?;
+        let junk: &[&str] = if remove_junk {
+            &cleaner::JUNK_FILES
+        } else {
+            &[]
+        };
+        let empty_dirs = cleaner::find_empty_dirs_ignoring(&canonical_path, depth, junk)?;
 
         if empty_dirs.is_empty() {
             println!("{}", "No empty folders found.".green());
         } else {
-            println!("\n{}", "Empty folders:".yellow().bold());
-            for dir in &empty_dirs {
-                println!("  {} {}", "○".yellow(), dir.display());
+            if !summary_only {
+                println!("\n{}", "Empty folders:".yellow().bold());
+                for dir in &empty_dirs {
+                    println!("  {} {}", "○".yellow(), dir.display());
+                }
             }
             println!(
                 "\n{} {} empty folders found",
@@ -103,13 +369,54 @@ pub fn run(
 
             if execute && !dry_run {
                 for dir in empty_dirs {
-                    if let Err(e) = fs::remove_dir(&dir) {
-                        eprintln!("{} Failed to remove {}: {}", "✗".red(), dir.display(), e);
+                    match cleaner::remove_empty_dir(&dir, junk) {
+                        Ok(()) => summary.removed_dirs += 1,
+                        Err(e) => {
+                            eprintln!("{} Failed to remove {}: {}", "✗".red(), dir.display(), e)
+                        }
                     }
                 }
             }
         }
     }
 
+    if summary_json && execute && !dry_run {
+        let json = serde_json::to_string(&summary).context("Failed to serialize run summary")?;
+        println!("{}", json);
+    }
+
     Ok(())
 }
+
+/// Totals of an executed clean, printed by --summary-json
+#[derive(Debug, Default, Serialize)]
+struct CleanSummary {
+    removed: usize,
+    total_size: u64,
+    removed_symlinks: usize,
+    removed_dirs: usize,
+}
+
+impl CleanSummary {
+    /// Count the (files, bytes) removed by one `execute_clean` call
+    fn add_files(&mut self, (count, size): (usize, u64)) {
+        self.removed += count;
+        self.total_size += size;
+    }
+}
+
+/// Print a titled list of files, skipping empty lists
+fn print_file_list(title: &str, files: &[&FileInfo], units: SizeUnits) {
+    if files.is_empty() {
+        return;
+    }
+    println!("\n{}", title.yellow().bold());
+    for file in files {
+        println!(
+            "  {} {} ({})",
+            "○".yellow(),
+            file.path.display(),
+            units.format(file.size).dimmed()
+        );
+    }
+}
@@ -1,10 +1,12 @@
 //! Config command handler
 
-use anyhow::Result;
+use std::path::Path;
+
+use anyhow::{Context, Result};
 use colored::*;
 
 use crate::cli::ConfigAction;
-use crate::config::Config as NeatConfig;
+use crate::config::{validate_destination, Config as NeatConfig, Rule, RULE_VARIABLES};
 
 /// Manage configuration
 pub fn run(action: ConfigAction) -> Result<()> {
@@ -18,21 +20,27 @@ pub fn run(action: ConfigAction) -> Result<()> {
             });
 
             if config_path.exists() {
+                let config = NeatConfig::load(&config_path)?;
+                let added = config.missing_sample_rules();
+                if added.is_empty() {
+                    println!(
+                        "{} Config already has all sample rules: {}",
+                        "ℹ".blue(),
+                        config_path.display()
+                    );
+                    return Ok(());
+                }
+
+                NeatConfig::append_rules(&config_path, &added)?;
                 println!(
-                    "{} Config file already exists: {}",
-                    "⚠".yellow(),
-                    config_path.display()
+                    "{} Merged sample rules into {}",
+                    "✓".green(),
+                    config_path.display().to_string().cyan()
                 );
-
-                let overwrite = dialoguer::Confirm::new()
-                    .with_prompt("Overwrite?")
-                    .default(false)
-                    .interact()?;
-
-                if !overwrite {
-                    println!("{}", "Cancelled.".yellow());
-                    return Ok(());
+                for rule in added {
+                    println!("  {} {}", "•".dimmed(), rule.name);
                 }
+                return Ok(());
             }
 
             NeatConfig::create_sample(&config_path)?;
@@ -106,8 +114,195 @@ pub fn run(action: ConfigAction) -> Result<()> {
             println!("  Include hidden: {}", config.settings.include_hidden);
             println!("  Follow symlinks: {}", config.settings.follow_symlinks);
             println!("  Default mode: {}", config.settings.default_organize_mode);
+            let folders = &config.settings.fallback_folders;
+            println!("  Unknown camera folder: {}", folders.camera);
+            println!("  Unknown artist folder: {}", folders.artist);
+            println!("  Unknown album folder: {}", folders.album);
+        }
+
+        ConfigAction::AddRule { path } => {
+            let config_path = path
+                .or_else(NeatConfig::default_path)
+                .context("Could not find the home directory")?;
+
+            println!(
+                "{} Adding a rule to {}",
+                "→".cyan(),
+                config_path.display().to_string().bold()
+            );
+            println!(
+                "  {} Destination variables: {}\n",
+                "ℹ".blue(),
+                RULE_VARIABLES
+                    .iter()
+                    .map(|v| format!("{{{}}}", v))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+                    .yellow()
+            );
+
+            let rule = build_rule(&mut |prompt, default| {
+                let mut input = dialoguer::Input::<String>::new().with_prompt(prompt);
+                if let Some(default) = default {
+                    input = input.default(default.to_string());
+                }
+                Ok(input.interact_text()?)
+            })?;
+
+            add_rule(&config_path, rule.clone())?;
+            println!(
+                "{} Added rule {}: {} → {}",
+                "✓".green(),
+                rule.name.bold(),
+                rule.pattern.yellow(),
+                rule.destination.green()
+            );
         }
     }
 
     Ok(())
 }
+
+/// Ask for each field of a rule, re-asking until the answer is valid. `ask` gets
+/// the prompt and an optional default and returns the answer.
+fn build_rule(ask: &mut dyn FnMut(&str, Option<&str>) -> Result<String>) -> Result<Rule> {
+    let name = ask_valid(ask, "Rule name", None, |answer| {
+        if answer.is_empty() {
+            anyhow::bail!("Rule name cannot be empty");
+        }
+        Ok(())
+    })?;
+    let pattern = ask_valid(ask, "Glob pattern (e.g. *invoice*.pdf)", None, |answer| {
+        glob::Pattern::new(answer)
+            .map(|_| ())
+            .map_err(|e| anyhow::anyhow!("Invalid glob pattern: {}", e))
+    })?;
+    let destination = ask_valid(
+        ask,
+        "Destination (e.g. Documents/{year})",
+        None,
+        validate_destination,
+    )?;
+    let priority = ask_valid(ask, "Priority (higher runs first)", Some("0"), |answer| {
+        answer
+            .parse::<i32>()
+            .map(|_| ())
+            .map_err(|_| anyhow::anyhow!("Priority must be a whole number"))
+    })?;
+
+    let rule = Rule {
+        name,
+        pattern,
+        destination,
+        priority: priority.parse()?,
+        post_action: None,
+    };
+    rule.validate()?;
+    Ok(rule)
+}
+
+fn ask_valid(
+    ask: &mut dyn FnMut(&str, Option<&str>) -> Result<String>,
+    prompt: &str,
+    default: Option<&str>,
+    validate: impl Fn(&str) -> Result<()>,
+) -> Result<String> {
+    loop {
+        let answer = ask(prompt, default)?.trim().to_string();
+        match validate(&answer) {
+            Ok(()) => return Ok(answer),
+            Err(e) => println!("{} {}", "✗".red(), e),
+        }
+    }
+}
+
+/// Append a rule to the config at `path`, creating the file if it doesn't exist
+fn add_rule(path: &Path, rule: Rule) -> Result<()> {
+    if path.exists() {
+        // Refuse to touch a config that doesn't load as it is
+        NeatConfig::load(path)?;
+    }
+    NeatConfig::append_rules(path, &[rule])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    fn scripted(answers: &[&str]) -> impl FnMut(&str, Option<&str>) -> Result<String> {
+        let mut answers: VecDeque<String> = answers.iter().map(|a| a.to_string()).collect();
+        move |prompt, _| {
+            answers
+                .pop_front()
+                .ok_or_else(|| anyhow::anyhow!("No scripted answer for {:?}", prompt))
+        }
+    }
+
+    #[test]
+    fn test_add_rule_wizard_persists_rule() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config_path = tmp.path().join(".neat").join("config.toml");
+        NeatConfig::create_sample(&config_path).unwrap();
+
+        // An invalid glob, an unknown variable and a non-numeric priority are each re-asked
+        let mut ask = scripted(&[
+            "Receipts",
+            "[receipt",
+            "*receipt*.pdf",
+            "Documents/Receipts/{decade}",
+            "Documents/Receipts/{year}",
+            "high",
+            "7",
+        ]);
+        let rule = build_rule(&mut ask).unwrap();
+        add_rule(&config_path, rule).unwrap();
+
+        let config = NeatConfig::load(&config_path).unwrap();
+        assert_eq!(config.rules.len(), 4);
+        let added = config.rules.last().unwrap();
+        assert_eq!(added.name, "Receipts");
+        assert_eq!(added.pattern, "*receipt*.pdf");
+        assert_eq!(added.destination, "Documents/Receipts/{year}");
+        assert_eq!(added.priority, 7);
+        assert_eq!(
+            config.find_matching_rule("march_receipt.pdf").unwrap().name,
+            "Receipts"
+        );
+    }
+
+    #[test]
+    fn test_add_rule_keeps_comments_and_sizes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config_path = tmp.path().join("config.toml");
+        let original = "# My rules\n[thresholds.Videos]\nmax_size = \"500MB\"\n";
+        std::fs::write(&config_path, original).unwrap();
+
+        let rule = build_rule(&mut scripted(&[
+            "Receipts",
+            "*receipt*.pdf",
+            "Receipts",
+            "0",
+        ]));
+        add_rule(&config_path, rule.unwrap()).unwrap();
+
+        let content = std::fs::read_to_string(&config_path).unwrap();
+        assert!(content.starts_with(original));
+        assert!(content.contains("[[rules]]"));
+        let config = NeatConfig::load(&config_path).unwrap();
+        assert_eq!(config.rules.len(), 1);
+    }
+
+    #[test]
+    fn test_add_rule_creates_missing_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config_path = tmp.path().join("config.toml");
+
+        let mut ask = scripted(&["Everything", "*", "Archive/{ext}", "0"]);
+        add_rule(&config_path, build_rule(&mut ask).unwrap()).unwrap();
+
+        let config = NeatConfig::load(&config_path).unwrap();
+        assert_eq!(config.rules.len(), 1);
+        assert_eq!(config.rules[0].destination, "Archive/{ext}");
+    }
+}
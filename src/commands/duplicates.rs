@@ -1,20 +1,32 @@
 //! Duplicates command handler
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use colored::*;
 
-use crate::duplicates::{display_duplicates, find_duplicates};
-use crate::export;
-use crate::scanner::{parse_date, parse_size, scan_directory, ScanOptions};
+use crate::duplicates::{
+    display_archive_duplicates, display_duplicates, display_wasted_by_dir, find_archive_duplicates,
+    find_duplicates, for_each_duplicate_group, loose_archive_copies, print_duplicates_summary,
+    with_thread_limit, DuplicateGroup, MergePolicy,
+};
+use crate::export::{self, Format};
+use crate::scanner::{
+    compile_glob, matches_glob, parse_date, parse_size, scan_directory, FileInfo, ScanOptions,
+    SizeUnits,
+};
 
 /// Find and optionally delete duplicate files
 #[allow(clippy::too_many_arguments)]
 pub fn run(
     path: &Path,
     delete: bool,
+    interactive: bool,
+    merge: Option<MergePolicy>,
+    across: &[PathBuf],
+    prefer_keep: Option<&Path>,
+    by_dir: bool,
     dry_run: bool,
     execute: bool,
     use_trash: bool,
@@ -22,12 +34,53 @@ pub fn run(
     max_size: Option<String>,
     after: Option<String>,
     before: Option<String>,
-    json: bool,
-    csv: bool,
+    format: Format,
+    pattern: Option<String>,
+    depth: Option<usize>,
+    no_default_ignores: bool,
+    threads: Option<usize>,
+    jsonl: bool,
+    peek_archives: bool,
+    summary_only: bool,
+    units: SizeUnits,
 ) -> Result<()> {
-    let canonical_path = path
-        .canonicalize()
-        .with_context(|| format!("Path does not exist: {:?}", path))?;
+    // Either one tree, or several compared only with each other
+    let paths: Vec<&Path> = if across.is_empty() {
+        vec![path]
+    } else {
+        across.iter().map(PathBuf::as_path).collect()
+    };
+    let roots: Vec<PathBuf> = paths
+        .iter()
+        .map(|root| {
+            root.canonicalize()
+                .with_context(|| format!("Path does not exist: {:?}", root))
+        })
+        .collect::<Result<_>>()?;
+    for (i, a) in roots.iter().enumerate() {
+        if let Some(b) = roots[i + 1..]
+            .iter()
+            .find(|b| a.starts_with(b) || b.starts_with(a))
+        {
+            bail!(
+                "--across folders must not overlap: {} and {}",
+                a.display(),
+                b.display()
+            );
+        }
+    }
+    let prefer_keep = prefer_keep
+        .map(|keep| {
+            let keep = keep
+                .canonicalize()
+                .with_context(|| format!("Path does not exist: {:?}", keep))?;
+            if !roots.contains(&keep) {
+                bail!("--prefer-keep must be one of the --across folders");
+            }
+            Ok(keep)
+        })
+        .transpose()?;
+    let cross_tree = roots.len() > 1;
 
     // Parse size filters
     let min_size_bytes = min_size
@@ -49,93 +102,303 @@ pub fn run(
         .transpose()
         .map_err(|e| anyhow::anyhow!("{}", e))?;
 
-    if !json && !csv {
+    let pattern = pattern
+        .map(|p| compile_glob(&p).with_context(|| format!("Invalid --pattern '{}'", p)))
+        .transpose()?;
+
+    if !format.is_machine() && !jsonl {
+        let names: Vec<String> = roots.iter().map(|r| r.display().to_string()).collect();
         println!(
             "{} Scanning {} for duplicate files...",
             "→".cyan(),
-            canonical_path.display().to_string().bold()
+            names.join(", ").bold()
         );
     }
 
     let options = ScanOptions {
         include_hidden: false,
-        max_depth: None,
+        max_depth: depth,
         follow_symlinks: false,
         ignore_patterns: Vec::new(),
         min_size: min_size_bytes,
         max_size: max_size_bytes,
         after_date,
         before_date,
+        no_default_ignores,
         ..Default::default()
     };
 
-    let files = scan_directory(&canonical_path, &options)?;
-    if !json && !csv {
+    let mut files = Vec::new();
+    for root in &roots {
+        files.extend(scan_directory(root, &options)?);
+    }
+    if let Some(ref patterns) = pattern {
+        files.retain(|f| matches_glob(patterns, &f.path));
+    }
+    if !format.is_machine() && !jsonl {
         println!("  Found {} files to analyze", files.len());
     }
 
-    let duplicates = find_duplicates(&files)?;
+    // Stream groups as they are confirmed instead of collecting them
+    if jsonl {
+        let mut stdout = std::io::stdout();
+        let mut write_error = None;
+        with_thread_limit(threads, || {
+            for_each_duplicate_group(&files, |group| {
+                if cross_tree && group.roots_spanned(&roots) < 2 {
+                    return;
+                }
+                if write_error.is_none() {
+                    if let Err(e) = export::export_duplicates_jsonl(&group, &mut stdout) {
+                        write_error = Some(e);
+                    }
+                }
+            })
+        })??;
+        if let Some(e) = write_error {
+            return Err(e.into());
+        }
+        return Ok(());
+    }
+
+    let mut duplicates = with_thread_limit(threads, || find_duplicates(&files))??;
+    if cross_tree {
+        duplicates.retain(|group| group.roots_spanned(&roots) >= 2);
+    }
+
+    // The file listed first in each group is the one kept
+    if merge == Some(MergePolicy::Newest) {
+        for group in &mut duplicates {
+            group.keep_newest();
+        }
+    }
+    if let Some(ref keep) = prefer_keep {
+        for group in &mut duplicates {
+            group.keep_in(keep);
+        }
+    }
 
     // Handle export formats
-    if json {
-        export::export_duplicates_json(&duplicates, &mut std::io::stdout())?;
+    if format.is_machine() {
+        export::export_duplicates(&duplicates, format, &mut std::io::stdout())?;
         return Ok(());
     }
-    if csv {
-        export::export_duplicates_csv(&duplicates, &mut std::io::stdout())?;
-        return Ok(());
+
+    if by_dir {
+        display_wasted_by_dir(
+            &duplicates,
+            (!cross_tree).then(|| roots[0].as_path()),
+            units,
+        );
+    } else if summary_only {
+        print_duplicates_summary(&duplicates, units);
+    } else {
+        display_duplicates(&duplicates, units);
     }
 
-    display_duplicates(&duplicates);
+    if interactive && execute && !dry_run && !duplicates.is_empty() {
+        let total = duplicates.len();
+        let removals = choose_removals(&duplicates, |i, group| {
+            prompt_keeper(i, total, group, units)
+        })?;
+        if removals.is_empty() {
+            println!("{}", "Nothing to delete.".yellow());
+            return Ok(());
+        }
 
-    if delete && execute && !dry_run && !duplicates.is_empty() {
         let action = if use_trash { "Move to trash" } else { "Delete" };
         let confirmed = dialoguer::Confirm::new()
-            .with_prompt(format!(
-                "{} duplicate files (keeping first in each group)?",
-                action
-            ))
+            .with_prompt(format!("{} {} duplicate files?", action, removals.len()))
+            .default(false)
+            .interact()?;
+        if confirmed {
+            print_deleted(delete_files(&removals, use_trash), use_trash);
+        }
+    } else if (delete || merge.is_some()) && execute && !dry_run && !duplicates.is_empty() {
+        let action = if use_trash { "Move to trash" } else { "Delete" };
+        let keeping = match (merge, &prefer_keep) {
+            (_, Some(keep)) => format!("the copies in {}", keep.display()),
+            (Some(MergePolicy::Newest), None) => "the newest in each group".to_string(),
+            (None, None) => "the first in each group".to_string(),
+        };
+        let confirmed = dialoguer::Confirm::new()
+            .with_prompt(format!("{} duplicate files (keeping {})?", action, keeping))
             .default(false)
             .interact()?;
 
         if confirmed {
-            let mut deleted = 0;
-            for group in &duplicates {
-                // Skip the first file (the one we keep)
-                for file in group.files.iter().skip(1) {
-                    let result = if use_trash {
-                        trash::delete(&file.path).map_err(|e| anyhow::anyhow!("{}", e))
-                    } else {
-                        fs::remove_file(&file.path).map_err(Into::into)
-                    };
-
-                    match result {
-                        Ok(_) => deleted += 1,
-                        Err(e) => {
-                            eprintln!(
-                                "{} Failed to {} {}: {}",
-                                "✗".red(),
-                                if use_trash { "trash" } else { "delete" },
-                                file.path.display(),
-                                e
-                            );
-                        }
-                    }
-                }
+            // Skip the first file (the one we keep), or everything in --prefer-keep
+            let removals: Vec<&FileInfo> = duplicates
+                .iter()
+                .flat_map(|group| match prefer_keep {
+                    Some(ref keep) => group.removable_outside(keep),
+                    None => group.files.iter().skip(1).collect(),
+                })
+                .collect();
+            print_deleted(delete_files(&removals, use_trash), use_trash);
+        }
+    }
+
+    if peek_archives {
+        let archived = find_archive_duplicates(&files)?;
+        display_archive_duplicates(&archived, units);
+
+        // Only loose copies are ever removed; archives are left untouched
+        let removals: Vec<&FileInfo> = loose_archive_copies(&archived)
+            .into_iter()
+            .filter(|file| file.path.exists())
+            .collect();
+        if delete && execute && !dry_run && !removals.is_empty() {
+            let action = if use_trash { "Move to trash" } else { "Delete" };
+            let confirmed = dialoguer::Confirm::new()
+                .with_prompt(format!(
+                    "{} {} loose files already stored in archives?",
+                    action,
+                    removals.len()
+                ))
+                .default(false)
+                .interact()?;
+            if confirmed {
+                print_deleted(delete_files(&removals, use_trash), use_trash);
             }
-            let action_past = if use_trash {
-                "Moved to trash"
-            } else {
-                "Deleted"
-            };
-            println!(
-                "\n{} {} {} duplicate files",
-                "✓".green(),
-                action_past,
-                deleted.to_string().green()
-            );
         }
     }
 
     Ok(())
 }
+
+/// Ask `select` which file to keep in each group (None skips the group) and
+/// collect every other file for deletion
+fn choose_removals<F>(groups: &[DuplicateGroup], mut select: F) -> Result<Vec<&FileInfo>>
+where
+    F: FnMut(usize, &DuplicateGroup) -> Result<Option<usize>>,
+{
+    let mut removals = Vec::new();
+    for (i, group) in groups.iter().enumerate() {
+        if let Some(keep) = select(i, group)? {
+            removals.extend(
+                group
+                    .files
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != keep)
+                    .map(|(_, file)| file),
+            );
+        }
+    }
+    Ok(removals)
+}
+
+/// Let the user pick the copy to keep in one group (Esc or the last entry skips it)
+fn prompt_keeper(
+    index: usize,
+    total: usize,
+    group: &DuplicateGroup,
+    units: SizeUnits,
+) -> Result<Option<usize>> {
+    let mut items: Vec<String> = group
+        .files
+        .iter()
+        .map(|file| {
+            let modified = chrono::DateTime::<chrono::Local>::from(file.modified);
+            format!(
+                "{}  ({})",
+                file.path.display(),
+                modified.format("%Y-%m-%d %H:%M")
+            )
+        })
+        .collect();
+    items.push("Skip this group (keep all)".to_string());
+
+    let choice = dialoguer::Select::new()
+        .with_prompt(format!(
+            "Group {}/{} ({} each) - keep which copy?",
+            index + 1,
+            total,
+            units.format(group.size)
+        ))
+        .items(&items)
+        .default(0)
+        .interact_opt()?;
+
+    Ok(choice.filter(|&i| i < group.files.len()))
+}
+
+/// Delete (or trash) each file, reporting failures; returns how many were removed
+fn delete_files(files: &[&FileInfo], use_trash: bool) -> usize {
+    let mut deleted = 0;
+    for file in files {
+        let result = if use_trash {
+            trash::delete(&file.path).map_err(|e| anyhow::anyhow!("{}", e))
+        } else {
+            fs::remove_file(&file.path).map_err(Into::into)
+        };
+
+        match result {
+            Ok(_) => deleted += 1,
+            Err(e) => {
+                eprintln!(
+                    "{} Failed to {} {}: {}",
+                    "✗".red(),
+                    if use_trash { "trash" } else { "delete" },
+                    file.path.display(),
+                    e
+                );
+            }
+        }
+    }
+    deleted
+}
+
+fn print_deleted(deleted: usize, use_trash: bool) {
+    let action_past = if use_trash {
+        "Moved to trash"
+    } else {
+        "Deleted"
+    };
+    println!(
+        "\n{} {} {} duplicate files",
+        "✓".green(),
+        action_past,
+        deleted.to_string().green()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(names: &[&str]) -> DuplicateGroup {
+        DuplicateGroup {
+            hash: String::new(),
+            files: names
+                .iter()
+                .map(|name| FileInfo::fixture(name, 10))
+                .collect(),
+            size: 10,
+        }
+    }
+
+    #[test]
+    fn test_choose_removals_keeps_selected_file() {
+        let groups = vec![
+            group(&["a1", "a2", "a3"]),
+            group(&["b1", "b2"]),
+            group(&["c1", "c2"]),
+        ];
+        // Keep the second copy of a, skip b, keep the first copy of c
+        let choices = [Some(1), None, Some(0)];
+
+        let removals = choose_removals(&groups, |i, _| Ok(choices[i])).unwrap();
+
+        let names: Vec<&str> = removals.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["a1", "a3", "c2"]);
+    }
+
+    #[test]
+    fn test_choose_removals_propagates_selector_error() {
+        let groups = vec![group(&["a1", "a2"])];
+        let result = choose_removals(&groups, |_, _| anyhow::bail!("no terminal"));
+        assert!(result.is_err());
+    }
+}
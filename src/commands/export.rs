@@ -0,0 +1,72 @@
+//! Export command handler
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use colored::*;
+
+use crate::classifier::Classifier;
+use crate::export::{export_inventory, Format, InventoryEntry};
+use crate::filters::get_mime_type;
+use crate::scanner::{scan_directory, FileInfo, ScanOptions, SizeUnits};
+
+/// List every file under a directory with its size, date, category and MIME type,
+/// without changing anything
+pub fn run(path: &Path, format: Format, depth: Option<usize>, units: SizeUnits) -> Result<()> {
+    let canonical_path = path
+        .canonicalize()
+        .with_context(|| format!("Path does not exist: {:?}", path))?;
+
+    let options = ScanOptions {
+        max_depth: depth,
+        ..Default::default()
+    };
+    let mut files = scan_directory(&canonical_path, &options)?;
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let classifier = Classifier::configured();
+    let entries: Vec<InventoryEntry> = files
+        .iter()
+        .map(|file| inventory_entry(file, classifier))
+        .collect();
+
+    if format.is_machine() {
+        export_inventory(&entries, format, &mut std::io::stdout())?;
+        return Ok(());
+    }
+
+    for entry in &entries {
+        let relative = Path::new(&entry.path)
+            .strip_prefix(&canonical_path)
+            .unwrap_or(Path::new(&entry.path));
+        println!(
+            "  {:>10}  {:12} {}",
+            units.format(entry.size).yellow(),
+            entry.category.cyan(),
+            relative.display()
+        );
+    }
+    println!(
+        "\n{}: {} files. Use {} or {} for the full inventory.",
+        "Total".bold(),
+        entries.len(),
+        "--format json".yellow(),
+        "--format csv".yellow()
+    );
+
+    Ok(())
+}
+
+fn inventory_entry(file: &FileInfo, classifier: &Classifier) -> InventoryEntry {
+    InventoryEntry {
+        path: file.path.display().to_string(),
+        size: file.size,
+        modified: DateTime::<Utc>::from(file.modified).to_rfc3339(),
+        extension: file.extension.clone(),
+        category: classifier
+            .category_name(file.extension.as_deref())
+            .to_string(),
+        mime: get_mime_type(&file.path),
+    }
+}
@@ -0,0 +1,85 @@
+//! Flatten command handler
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use colored::*;
+
+use crate::classifier::Classifier;
+use crate::organizer::{
+    category_folders, execute_moves_with_options, plan_flatten, print_results,
+    with_renamed_conflicts, CollisionSuffix, ConflictStrategy, MoveOptions,
+};
+use crate::scanner::{scan_directory, ScanOptions, SizeUnits};
+
+/// Move files out of category folders (`Images/`, `Documents/`, ...) back into
+/// `path`, then remove the category folders left empty
+pub fn run(
+    path: &Path,
+    dry_run: bool,
+    execute: bool,
+    summary_only: bool,
+    units: SizeUnits,
+) -> Result<()> {
+    let canonical_path = path
+        .canonicalize()
+        .with_context(|| format!("Path does not exist: {:?}", path))?;
+
+    println!(
+        "{} Scanning {} for category folders...",
+        "→".cyan(),
+        canonical_path.display().to_string().bold()
+    );
+
+    let folders = category_folders(&canonical_path, &Classifier::configured().category_names())?;
+    if folders.is_empty() {
+        println!("{}", "No category folders found.".yellow());
+        return Ok(());
+    }
+
+    let mut files = Vec::new();
+    for folder in &folders {
+        files.extend(scan_directory(folder, &ScanOptions::default())?);
+    }
+    let moves = plan_flatten(&files, &canonical_path);
+
+    if execute && !dry_run {
+        // Files with the same name from different folders are all kept
+        let options = MoveOptions {
+            remove_if_empty: &folders,
+            ..Default::default()
+        };
+        let result =
+            execute_moves_with_options(&moves, "flatten", ConflictStrategy::Rename, &options)?;
+        print_results(&result, units);
+        return Ok(());
+    }
+
+    if !summary_only {
+        println!("\n{}", "Moves:".bold().cyan());
+        for mv in with_renamed_conflicts(&moves, &CollisionSuffix::default()) {
+            let from = mv.from.strip_prefix(&canonical_path).unwrap_or(&mv.from);
+            let to = mv.to.strip_prefix(&canonical_path).unwrap_or(&mv.to);
+            println!(
+                "  {} {} {}",
+                from.display(),
+                "→".dimmed(),
+                to.display().to_string().green()
+            );
+        }
+    }
+
+    println!(
+        "\n{} {} files would move out of {} folders",
+        "Summary:".bold(),
+        moves.len(),
+        folders.len()
+    );
+    println!(
+        "\n{} Use {} to move these files.",
+        "⚠".yellow(),
+        "--execute".yellow()
+    );
+
+    Ok(())
+}
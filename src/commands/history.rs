@@ -3,12 +3,18 @@
 use anyhow::Result;
 use colored::*;
 
+use crate::export::{export_history, Format};
 use crate::logger::History;
 
-/// Show operation history
-pub fn run() -> Result<()> {
+/// Show operation history, or print every batch as JSON or CSV
+pub fn run(format: Format) -> Result<()> {
     let history = History::load()?;
 
+    if format.is_machine() {
+        export_history(&history.batches, format, &mut std::io::stdout())?;
+        return Ok(());
+    }
+
     if history.is_empty() {
         println!("{}", "No operation history.".yellow());
         return Ok(());
@@ -24,12 +30,21 @@ pub fn run() -> Result<()> {
         }
 
         let timestamp = batch.timestamp.format("%Y-%m-%d %H:%M:%S");
-        println!(
-            "  {} {} ({} files)",
-            timestamp.to_string().dimmed(),
-            batch.command.cyan(),
-            batch.operations.len()
-        );
+        match &batch.label {
+            Some(label) => println!(
+                "  {} {} [{}] ({} files)",
+                timestamp.to_string().dimmed(),
+                label.cyan(),
+                batch.command.dimmed(),
+                batch.operations.len()
+            ),
+            None => println!(
+                "  {} {} ({} files)",
+                timestamp.to_string().dimmed(),
+                batch.command.cyan(),
+                batch.operations.len()
+            ),
+        }
     }
 
     Ok(())
@@ -1,13 +1,19 @@
 //! Command handlers for neatcli
 
+pub mod classify;
 pub mod clean;
 pub mod config;
 pub mod duplicates;
+pub mod export;
+pub mod flatten;
 pub mod history;
 pub mod organize;
+pub mod preview;
 pub mod profile;
 pub mod quick;
 pub mod similar;
+pub mod size;
 pub mod stats;
+pub mod tidy_names;
 pub mod undo;
 pub mod watch;
@@ -1,17 +1,32 @@
 //! Organize command handler
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use colored::*;
 
+use crate::classifier::Classifier;
+use crate::cleaner::parse_duration;
+use crate::config::{Config as NeatConfig, FallbackFolders, LOCAL_CONFIG_FILE};
+use crate::export::{self, Format};
+use crate::filters::{
+    filter_by_date_taken, filter_by_dimensions, regex_captures, DimensionFilter, TakenDateFilter,
+};
+use crate::hooks::execute_run_hook;
 use crate::organizer::{
-    execute_copies, execute_moves, plan_moves, plan_moves_with_template, preview_moves,
-    print_results, ConflictStrategy, OrganizeMode,
+    append_report, common_ancestor, default_name_regex_template, execute_copies_with_options,
+    execute_links_with_options, execute_moves_with_options, find_collisions, group_small_folders,
+    load_plan, moves_diff, plan_moves_mirrored, plan_moves_with_fallbacks,
+    plan_moves_with_name_regex, plan_moves_with_rules, plan_moves_with_template, preview_moves,
+    preview_moves_explained, print_collision_report, print_moves_summary, print_results, save_plan,
+    validate_plan, with_renamed_conflicts, CollisionSuffix, ConflictStrategy, ExtensionCase,
+    LinkKind, MoveOptions, MoveReason, OrganizeMode, OrganizeResult, PlannedMove, EMPTY_FOLDER,
 };
 use crate::scanner::{
-    format_size, parse_date, parse_size, scan_directory, total_size, ScanOptions,
+    load_list_file, parse_date, parse_size, scan_directory, total_size, ScanOptions, SizeUnits,
 };
+use crate::template::get_preset_template;
 
 /// Organize files in directories by type, date, extension, or metadata
 #[allow(clippy::too_many_arguments)]
@@ -20,29 +35,147 @@ pub fn run(
     _by_type: bool,
     by_date: bool,
     by_extension: bool,
+    ext_case: ExtensionCase,
+    group_small: Option<usize>,
     by_camera: bool,
     by_date_taken: bool,
     by_artist: bool,
     by_album: bool,
+    by_size: bool,
+    by_alpha: bool,
+    smart: bool,
+    output_dir: Option<&Path>,
+    keep_structure: bool,
     dry_run: bool,
     execute: bool,
+    explain: bool,
+    simulate_conflicts: bool,
+    diff: bool,
     verbose: bool,
+    summary_only: bool,
     ignore: Vec<String>,
+    no_default_ignores: bool,
     min_size: Option<String>,
     max_size: Option<String>,
     after: Option<String>,
+    since: Option<String>,
     before: Option<String>,
+    taken_after: Option<String>,
+    taken_before: Option<String>,
+    taken_only: bool,
+    min_width: Option<u32>,
+    min_height: Option<u32>,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+    images_only: bool,
     copy: bool,
+    preserve_times: bool,
+    leave_symlink: bool,
+    link_duplicates: bool,
+    link: Option<LinkKind>,
+    fail_fast: bool,
+    throttle: Option<f64>,
+    atomic: bool,
+    delete_empty_after: bool,
+    include_empty_dirs: bool,
+    report: Option<&Path>,
+    follow_up: Option<&str>,
+    summary_json: bool,
+    format: Format,
+    plan: Option<&Path>,
+    apply: Option<&Path>,
     recursive: bool,
     startswith: Option<String>,
     endswith: Option<String>,
     contains: Option<String>,
     regex: Option<String>,
+    case_sensitive: bool,
     mime: Option<String>,
+    sniff: bool,
+    mut only_ext: Vec<String>,
+    mut not_ext: Vec<String>,
+    only_ext_file: Option<&Path>,
+    not_ext_file: Option<&Path>,
     content_filter: Option<String>,
+    category: Option<String>,
     template: Option<String>,
+    preset: Option<String>,
+    name_regex: Option<String>,
+    regex_fallback: Option<String>,
     on_conflict: ConflictStrategy,
+    collision_suffix: Option<CollisionSuffix>,
+    units: SizeUnits,
 ) -> Result<()> {
+    // --collision-suffix wins over the config setting (and a folder's .neat.toml)
+    let suffix_from_cli = collision_suffix.is_some();
+    let collision_suffix = match collision_suffix {
+        Some(suffix) => Some(suffix),
+        None => NeatConfig::load_default_or_warn()
+            .and_then(|config| config.settings.collision_suffix)
+            .map(|suffix| CollisionSuffix::parse(&suffix))
+            .transpose()
+            .context("Invalid collision_suffix in config")?,
+    };
+    let move_options = MoveOptions {
+        leave_symlink,
+        link_duplicates,
+        fail_fast,
+        preserve_times,
+        atomic,
+        delete_empty_in: None,
+        collect_empty_in: None,
+        remove_if_empty: &[],
+        confine_to: None,
+        throttle,
+        collision_suffix: collision_suffix.as_ref(),
+    };
+
+    // A saved plan is executed as-is; nothing is rescanned
+    if let Some(apply) = apply {
+        return apply_plan(
+            apply,
+            dry_run,
+            execute,
+            summary_only,
+            copy,
+            link,
+            delete_empty_after,
+            move_options,
+            report,
+            follow_up,
+            summary_json,
+            on_conflict,
+            units,
+        );
+    }
+
+    // Long extension lists can come from files, merged with any given inline
+    if let Some(file) = only_ext_file {
+        let listed = load_list_file(file).context("Invalid --only-ext-file")?;
+        if listed.is_empty() {
+            bail!("--only-ext-file lists no extensions: {}", file.display());
+        }
+        only_ext.extend(listed);
+    }
+    if let Some(file) = not_ext_file {
+        not_ext.extend(load_list_file(file).context("Invalid --not-ext-file")?);
+    }
+
+    // Resolve a built-in preset into its template (clap already validated the name)
+    let template = preset
+        .and_then(|name| get_preset_template(&name))
+        .map(str::to_string)
+        .or(template);
+
+    // Compile the routing regex; its named groups become template variables
+    let name_regex = name_regex
+        .map(|p| regex::Regex::new(&p).with_context(|| format!("Invalid --name-regex '{}'", p)))
+        .transpose()?;
+    let template = match (&name_regex, template) {
+        (Some(re), None) => Some(default_name_regex_template(re)),
+        (_, template) => template,
+    };
+
     // Determine mode
     let mode = if by_date {
         OrganizeMode::ByDate
@@ -56,20 +189,33 @@ pub fn run(
         OrganizeMode::ByArtist
     } else if by_album {
         OrganizeMode::ByAlbum
+    } else if by_size {
+        OrganizeMode::BySizeBucket
+    } else if by_alpha {
+        OrganizeMode::ByFirstLetter
+    } else if smart {
+        OrganizeMode::Smart
     } else {
         OrganizeMode::ByType // Default
     };
 
-    let mode_name = match mode {
-        OrganizeMode::ByType => "type",
-        OrganizeMode::ByDate => "date",
-        OrganizeMode::ByExtension => "extension",
-        OrganizeMode::ByCamera => "camera",
-        OrganizeMode::ByDateTaken => "date taken",
-        OrganizeMode::ByArtist => "artist",
-        OrganizeMode::ByAlbum => "album",
+    // Folder names for files without metadata can be renamed in the config
+    let fallback_folders = if matches!(
+        mode,
+        OrganizeMode::ByCamera
+            | OrganizeMode::ByArtist
+            | OrganizeMode::ByAlbum
+            | OrganizeMode::Smart
+    ) {
+        NeatConfig::load_default_or_warn()
+            .map(|config| config.settings.fallback_folders)
+            .unwrap_or_default()
+    } else {
+        FallbackFolders::default()
     };
 
+    let mode_name = mode.name();
+
     // Parse size filters once (shared across all paths)
     let min_size_bytes = min_size
         .map(|s| parse_size(&s))
@@ -85,36 +231,220 @@ pub fn run(
         .map(|s| parse_date(&s))
         .transpose()
         .map_err(|e| anyhow::anyhow!("{}", e))?;
+    let after_date = match since {
+        Some(window) => Some(
+            std::time::SystemTime::now()
+                - parse_duration(&window)
+                    .with_context(|| format!("Invalid --since '{}'", window))?,
+        ),
+        None => after_date,
+    };
     let before_date = before
         .map(|s| parse_date(&s))
         .transpose()
         .map_err(|e| anyhow::anyhow!("{}", e))?;
 
+    // EXIF date filters (photos are only opened when one is set)
+    let taken_filter =
+        TakenDateFilter::from_args(taken_after.as_deref(), taken_before.as_deref(), taken_only)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    // The output folder may not exist yet, so it can't be canonicalized
+    let output_dir = output_dir
+        .map(std::path::absolute)
+        .transpose()
+        .context("Invalid --output-dir")?;
+
+    let dimension_filter = DimensionFilter {
+        min_width,
+        min_height,
+        max_width,
+        max_height,
+        images_only,
+    };
+
     // Process each path
+    let mut planned = Vec::new();
+    let mut executed = Vec::new();
     for path in paths {
-        organize_single_path(
+        let moves = organize_single_path(
             path,
             mode,
             mode_name,
+            ext_case,
+            group_small,
+            &fallback_folders,
+            output_dir.as_deref(),
+            keep_structure,
             dry_run,
             execute,
+            explain,
+            simulate_conflicts,
+            diff,
             verbose,
+            summary_only,
             &ignore,
+            no_default_ignores,
             min_size_bytes,
             max_size_bytes,
             after_date,
             before_date,
+            &taken_filter,
+            &dimension_filter,
             copy,
+            link,
+            delete_empty_after,
+            include_empty_dirs,
+            move_options,
+            suffix_from_cli,
+            report,
+            format,
+            plan.is_some() || format.is_machine(),
             recursive,
             startswith.clone(),
             endswith.clone(),
             contains.clone(),
             regex.clone(),
+            case_sensitive,
             mime.clone(),
+            sniff,
+            &only_ext,
+            &not_ext,
             content_filter.clone(),
+            category.as_deref(),
             template.clone(),
+            name_regex.as_ref(),
+            regex_fallback.as_deref(),
             on_conflict,
+            &mut executed,
+            units,
         )?;
+        planned.extend(moves);
+    }
+
+    // Totals cover every path, so the follow-up runs and --summary-json prints once
+    if !executed.is_empty() {
+        let folders: Vec<PathBuf> = executed.iter().map(|(path, _)| path.clone()).collect();
+        let mut total = OrganizeResult::default();
+        for (_, result) in executed {
+            total.merge(result);
+        }
+        if let Some(command) = follow_up {
+            let folder = shared_folder(&folders).unwrap_or_else(|| PathBuf::from("/"));
+            run_follow_up(command, &total, &folder);
+        }
+        if summary_json {
+            print_summary_json(&total)?;
+        }
+    }
+
+    if format.is_machine() {
+        export::export_moves(&planned, format, &mut std::io::stdout())?;
+    }
+
+    if let Some(plan) = plan {
+        save_plan(&planned, plan)?;
+        println!(
+            "{} Saved {} planned moves to {}",
+            "✓".green(),
+            planned.len(),
+            plan.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Execute (or preview) the moves recorded in a plan file
+#[allow(clippy::too_many_arguments)]
+fn apply_plan(
+    plan: &Path,
+    dry_run: bool,
+    execute: bool,
+    summary_only: bool,
+    copy: bool,
+    link: Option<LinkKind>,
+    delete_empty_after: bool,
+    move_options: MoveOptions,
+    report: Option<&Path>,
+    follow_up: Option<&str>,
+    summary_json: bool,
+    on_conflict: ConflictStrategy,
+    units: SizeUnits,
+) -> Result<()> {
+    let moves = load_plan(plan)?;
+    println!(
+        "{} Loaded {} planned moves from {}",
+        "→".cyan(),
+        moves.len(),
+        plan.display().to_string().bold()
+    );
+
+    // Refuse to apply a plan whose sources changed since it was written
+    let problems = validate_plan(&moves);
+    if !problems.is_empty() {
+        let mut message = format!("Plan is out of date ({} stale entries):", problems.len());
+        for problem in problems.iter().take(10) {
+            message.push_str("\n  ");
+            message.push_str(problem);
+        }
+        if problems.len() > 10 {
+            message.push_str(&format!("\n  ... and {} more", problems.len() - 10));
+        }
+        bail!(message);
+    }
+
+    if moves.is_empty() {
+        println!("{}", "No files to move.".yellow());
+        return Ok(());
+    }
+
+    let base = common_ancestor(moves.iter().map(|mv| mv.from.as_path()))
+        .unwrap_or_else(|| PathBuf::from("/"));
+    // The folder the plan's paths share as written; a destination that `..` or a
+    // symlink takes outside it is refused
+    let plan_base = common_ancestor(
+        moves
+            .iter()
+            .flat_map(|mv| [mv.from.as_path(), mv.to.as_path()]),
+    )
+    .unwrap_or_else(|| PathBuf::from("/"));
+
+    if execute && !dry_run {
+        let command_name = if link.is_some() {
+            "link --apply"
+        } else if copy {
+            "copy --apply"
+        } else {
+            "organize --apply"
+        };
+        let move_options = MoveOptions {
+            delete_empty_in: delete_empty_after.then_some(base.as_path()),
+            confine_to: Some(plan_base.as_path()),
+            ..move_options
+        };
+        let result = if let Some(kind) = link {
+            execute_links_with_options(&moves, command_name, kind, on_conflict, &move_options)?
+        } else if copy {
+            execute_copies_with_options(&moves, command_name, on_conflict, &move_options)?
+        } else {
+            execute_moves_with_options(&moves, command_name, on_conflict, &move_options)?
+        };
+        print_results(&result, units);
+
+        if let Some(report) = report {
+            append_report(report, command_name, &base, &result, units)?;
+        }
+        if let Some(command) = follow_up {
+            run_follow_up(command, &result, &base);
+        }
+        if summary_json {
+            print_summary_json(&result)?;
+        }
+    } else if summary_only {
+        print_moves_summary(&moves, units);
+    } else {
+        preview_moves(&moves, &base, units);
     }
 
     Ok(())
@@ -126,46 +456,111 @@ fn organize_single_path(
     path: &Path,
     mode: OrganizeMode,
     mode_name: &str,
+    ext_case: ExtensionCase,
+    group_small: Option<usize>,
+    fallback_folders: &FallbackFolders,
+    output_dir: Option<&Path>,
+    keep_structure: bool,
     dry_run: bool,
     execute: bool,
+    explain: bool,
+    simulate_conflicts: bool,
+    diff: bool,
     verbose: bool,
+    summary_only: bool,
     ignore: &[String],
+    no_default_ignores: bool,
     min_size_bytes: Option<u64>,
     max_size_bytes: Option<u64>,
     after_date: Option<std::time::SystemTime>,
     before_date: Option<std::time::SystemTime>,
+    taken_filter: &TakenDateFilter,
+    dimension_filter: &DimensionFilter,
     copy: bool,
+    link: Option<LinkKind>,
+    delete_empty_after: bool,
+    include_empty_dirs: bool,
+    move_options: MoveOptions,
+    suffix_from_cli: bool,
+    report: Option<&Path>,
+    format: Format,
+    planning: bool,
     recursive: bool,
     startswith: Option<String>,
     endswith: Option<String>,
     contains: Option<String>,
     regex: Option<String>,
+    case_sensitive: bool,
     mime: Option<String>,
+    sniff: bool,
+    only_ext: &[String],
+    not_ext: &[String],
     content_filter: Option<String>,
+    category: Option<&str>,
     template: Option<String>,
+    name_regex: Option<&regex::Regex>,
+    regex_fallback: Option<&str>,
     on_conflict: ConflictStrategy,
-) -> Result<()> {
+    executed: &mut Vec<(PathBuf, OrganizeResult)>,
+    units: SizeUnits,
+) -> Result<Vec<PlannedMove>> {
     let canonical_path = path
         .canonicalize()
         .with_context(|| format!("Path does not exist: {:?}", path))?;
 
-    let action = if copy { "copying" } else { "organizing" };
+    let action = if link.is_some() {
+        "linking"
+    } else if copy {
+        "copying"
+    } else {
+        "organizing"
+    };
     let recursive_msg = if recursive { " (recursive)" } else { "" };
 
-    let template_display = if let Some(ref t) = template {
+    let template_display = if keep_structure {
+        format!(
+            ", mirroring into {}",
+            output_dir.unwrap_or(&canonical_path).display()
+        )
+    } else if let Some(ref t) = template {
         format!(" with template '{}'", t)
     } else {
         format!(" by {}", mode_name.cyan())
     };
 
-    println!(
-        "{} Scanning {} ({}{}{})...",
-        "→".cyan(),
-        canonical_path.display().to_string().bold(),
-        action,
-        template_display,
-        recursive_msg
-    );
+    // JSON/CSV output is only the plan, so scripts can parse stdout as-is
+    let quiet = format.is_machine();
+
+    if !quiet {
+        println!(
+            "{} Scanning {} ({}{}{})...",
+            "→".cyan(),
+            canonical_path.display().to_string().bold(),
+            action,
+            template_display,
+            recursive_msg
+        );
+    }
+
+    // A .neat.toml in the folder brings its own rules and overrides global settings
+    let local_config = NeatConfig::load_local(&canonical_path)?;
+    let fallback_folders = local_config
+        .as_ref()
+        .map_or(fallback_folders, |config| &config.settings.fallback_folders);
+    let local_suffix = match &local_config {
+        Some(config) if !suffix_from_cli => config
+            .settings
+            .collision_suffix
+            .as_deref()
+            .map(CollisionSuffix::parse)
+            .transpose()
+            .with_context(|| format!("Invalid collision_suffix in {}", LOCAL_CONFIG_FILE))?,
+        _ => None,
+    };
+    let move_options = MoveOptions {
+        collision_suffix: local_suffix.as_ref().or(move_options.collision_suffix),
+        ..move_options
+    };
 
     // Load ignore patterns from .neatignore file and CLI
     let mut ignore_patterns = crate::scanner::load_ignore_patterns(&canonical_path);
@@ -185,7 +580,12 @@ fn organize_single_path(
         name_endswith: endswith,
         name_contains: contains,
         regex_pattern: regex,
+        case_sensitive,
         mime_filter: mime,
+        sniff_mime: sniff,
+        only_extensions: only_ext.to_vec(),
+        exclude_extensions: not_ext.to_vec(),
+        no_default_ignores,
     };
 
     let files = scan_directory(&canonical_path, &options)?;
@@ -201,44 +601,214 @@ fn organize_single_path(
         files
     };
 
+    let files = filter_by_date_taken(files, taken_filter);
+    let files = filter_by_dimensions(files, dimension_filter);
+
+    // Keep only the requested category
+    let files: Vec<_> = if let Some(category) = category {
+        let classifier = Classifier::configured();
+        files
+            .into_iter()
+            .filter(|f| {
+                classifier
+                    .category_name(f.extension.as_deref())
+                    .eq_ignore_ascii_case(category)
+            })
+            .collect()
+    } else {
+        files
+    };
+
     if files.is_empty() {
-        println!("{}", "No files found to organize.".yellow());
-        return Ok(());
+        if !quiet {
+            println!("{}", "No files found to organize.".yellow());
+        }
+        return Ok(Vec::new());
     }
 
-    if verbose {
+    if verbose && !quiet {
         println!(
             "  Found {} files ({})",
             files.len(),
-            format_size(total_size(&files))
+            units.format(total_size(&files))
         );
     }
 
+    // Organized folders go under the output directory when one is given
+    let dest_base = output_dir.unwrap_or(&canonical_path);
+
     // Plan moves - use template if provided, otherwise use mode
-    let moves = if let Some(ref t) = template {
-        plan_moves_with_template(&files, &canonical_path, t)
+    let planned = if keep_structure {
+        plan_moves_mirrored(&files, &canonical_path, dest_base)
+    } else if let (Some(re), Some(t)) = (name_regex, &template) {
+        plan_moves_with_name_regex(&files, dest_base, t, re, regex_fallback)
+    } else if let Some(ref t) = template {
+        plan_moves_with_template(&files, dest_base, t)
+    } else if let Some(ref config) = local_config {
+        plan_moves_with_rules(&files, dest_base, mode, ext_case, fallback_folders, config)
+    } else {
+        plan_moves_with_fallbacks(&files, dest_base, mode, ext_case, fallback_folders)
+    };
+
+    // Remember where each file was planned to go, to explain what --group-small changed
+    let ungrouped: HashMap<PathBuf, PathBuf> = if explain && group_small.is_some() {
+        planned
+            .iter()
+            .map(|mv| (mv.from.clone(), mv.to.clone()))
+            .collect()
     } else {
-        plan_moves(&files, &canonical_path, mode)
+        HashMap::new()
+    };
+
+    // Fold tiny category folders into Other
+    let moves = match group_small {
+        Some(min_files) => group_small_folders(planned, dest_base, min_files),
+        None => planned,
     };
 
     if moves.is_empty() {
-        println!("{}", "All files are already organized.".green());
-        return Ok(());
+        if !quiet {
+            println!("{}", "All files are already organized.".green());
+        }
+        return Ok(Vec::new());
     }
 
     // Dry-run is default if --execute is not specified
-    if execute && !dry_run {
-        if copy {
-            let result = execute_copies(&moves, &format!("copy --by-{}", mode_name), on_conflict)?;
-            print_results(&result);
+    if planning {
+        return Ok(moves);
+    } else if execute && !dry_run {
+        let verb = if link.is_some() {
+            "link"
+        } else if copy {
+            "copy"
+        } else {
+            "organize"
+        };
+        let command_name = if keep_structure {
+            format!("{} --keep-structure", verb)
+        } else {
+            format!("{} --by-{}", verb, mode_name)
+        };
+        let empty_bucket = dest_base.join(EMPTY_FOLDER);
+        let move_options = MoveOptions {
+            delete_empty_in: delete_empty_after.then_some(canonical_path.as_path()),
+            collect_empty_in: include_empty_dirs
+                .then_some((canonical_path.as_path(), empty_bucket.as_path())),
+            confine_to: Some(dest_base),
+            ..move_options
+        };
+        let result = if let Some(kind) = link {
+            execute_links_with_options(&moves, &command_name, kind, on_conflict, &move_options)?
+        } else if copy {
+            execute_copies_with_options(&moves, &command_name, on_conflict, &move_options)?
         } else {
-            let result =
-                execute_moves(&moves, &format!("organize --by-{}", mode_name), on_conflict)?;
-            print_results(&result);
+            execute_moves_with_options(&moves, &command_name, on_conflict, &move_options)?
+        };
+        print_results(&result, units);
+
+        if let Some(report) = report {
+            append_report(report, &command_name, &canonical_path, &result, units)?;
         }
+        executed.push((canonical_path.clone(), result));
+    } else if summary_only {
+        print_moves_summary(&moves, units);
+    } else if diff {
+        let moves = match on_conflict {
+            ConflictStrategy::Rename => with_renamed_conflicts(&moves, move_options.suffix()),
+            _ => moves.clone(),
+        };
+        print!("{}", moves_diff(&moves, dest_base));
+    } else if explain {
+        let reasons: Vec<MoveReason> = moves
+            .iter()
+            .map(|mv| {
+                if ungrouped.get(&mv.from).is_some_and(|to| *to != mv.to) {
+                    MoveReason::GroupSmall
+                } else if keep_structure {
+                    MoveReason::KeepStructure
+                } else if let Some(re) = name_regex {
+                    let name = mv.from.file_name().unwrap_or_default().to_string_lossy();
+                    if regex_captures(&name, re).is_some() {
+                        MoveReason::NameRegex
+                    } else {
+                        MoveReason::NameRegexFallback
+                    }
+                } else if template.is_some() {
+                    MoveReason::Template
+                } else if let Some(rule) = local_config.as_ref().and_then(|config| {
+                    config.find_matching_rule(&mv.from.file_name()?.to_string_lossy())
+                }) {
+                    MoveReason::Rule(rule.name.clone())
+                } else if in_fallback_folder(&mv.to, dest_base, mode, fallback_folders) {
+                    MoveReason::ModeFallback(mode)
+                } else {
+                    MoveReason::Mode(mode)
+                }
+            })
+            .collect();
+        preview_moves_explained(&moves, &reasons, dest_base, units);
     } else {
-        preview_moves(&moves, &canonical_path);
+        preview_moves(&moves, dest_base, units);
+    }
+
+    if simulate_conflicts {
+        print_collision_report(&moves, &find_collisions(&moves), dest_base);
     }
 
+    Ok(moves)
+}
+
+/// Print the totals of a finished run as a single line of JSON (--summary-json)
+fn print_summary_json(result: &OrganizeResult) -> Result<()> {
+    let json = serde_json::to_string(result).context("Failed to serialize run summary")?;
+    println!("{}", json);
     Ok(())
 }
+
+/// The deepest folder containing every organized folder (`{path}` for the follow-up)
+fn shared_folder(folders: &[PathBuf]) -> Option<PathBuf> {
+    let (first, rest) = folders.split_first()?;
+    first
+        .ancestors()
+        .find(|dir| rest.iter().all(|folder| folder.starts_with(dir)))
+        .map(Path::to_path_buf)
+}
+
+/// Run the --follow-up-command hook for a finished run and report how it exited
+fn run_follow_up(command: &str, result: &OrganizeResult, path: &Path) {
+    let count = result.moved + result.copied + result.linked;
+    match execute_run_hook(command, count, result.total_size, path) {
+        Ok(status) if status.success() => println!("{} Follow-up command finished", "⚡".cyan()),
+        Ok(status) => println!(
+            "{} Follow-up command exited with {}",
+            "⚠".yellow(),
+            status
+                .code()
+                .map_or_else(|| "a signal".to_string(), |code| format!("status {}", code))
+        ),
+        Err(e) => println!("{} Follow-up command failed: {}", "⚠".yellow(), e),
+    }
+}
+
+/// Whether a metadata mode sent `dest` to its folder for files without that metadata
+fn in_fallback_folder(
+    dest: &Path,
+    base: &Path,
+    mode: OrganizeMode,
+    fallback_folders: &FallbackFolders,
+) -> bool {
+    let fallbacks = match mode {
+        OrganizeMode::ByCamera => vec![&fallback_folders.camera],
+        OrganizeMode::ByArtist => vec![&fallback_folders.artist],
+        OrganizeMode::ByAlbum | OrganizeMode::Smart => {
+            vec![&fallback_folders.artist, &fallback_folders.album]
+        }
+        _ => return false,
+    };
+    let folder = dest.parent().unwrap_or(base);
+    folder
+        .strip_prefix(base)
+        .unwrap_or(folder)
+        .components()
+        .any(|c| fallbacks.iter().any(|f| c.as_os_str() == f.as_str()))
+}
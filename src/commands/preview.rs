@@ -0,0 +1,125 @@
+//! Preview command handler
+//!
+//! Read-only counterpart of organize, duplicates and clean: it runs the same
+//! planning code but never calls any execute function.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use colored::*;
+
+use crate::cleaner;
+use crate::duplicates::{display_duplicates, find_duplicates, print_duplicates_summary};
+use crate::organizer::{
+    plan_moves, plan_moves_with_template, preview_moves, print_moves_summary, OrganizeMode,
+};
+use crate::scanner::{scan_directory, ScanOptions, SizeUnits};
+
+/// What to preview
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PreviewMode {
+    /// Planned moves of `organize` (by type, or by `--template`)
+    #[default]
+    Organize,
+    /// Duplicate groups found by `duplicates`
+    Duplicates,
+    /// Old files and empty folders `clean` would remove
+    Clean,
+}
+
+/// Show what a command would do without touching the filesystem
+pub fn run(
+    path: &Path,
+    mode: PreviewMode,
+    recursive: bool,
+    template: Option<String>,
+    older_than: Option<String>,
+    summary_only: bool,
+    units: SizeUnits,
+) -> Result<()> {
+    let canonical_path = path
+        .canonicalize()
+        .with_context(|| format!("Path does not exist: {:?}", path))?;
+
+    match mode {
+        PreviewMode::Organize => {
+            println!(
+                "{} Previewing organize for {}...",
+                "→".cyan(),
+                canonical_path.display().to_string().bold()
+            );
+
+            let options = ScanOptions {
+                max_depth: if recursive { None } else { Some(1) },
+                ignore_patterns: crate::scanner::load_ignore_patterns(&canonical_path),
+                ..Default::default()
+            };
+
+            let files = scan_directory(&canonical_path, &options)?;
+            let moves = match template {
+                Some(ref t) => plan_moves_with_template(&files, &canonical_path, t),
+                None => plan_moves(&files, &canonical_path, OrganizeMode::ByType),
+            };
+
+            if moves.is_empty() {
+                println!("{}", "All files are already organized.".green());
+            } else if summary_only {
+                print_moves_summary(&moves, units);
+            } else {
+                preview_moves(&moves, &canonical_path, units);
+            }
+        }
+        PreviewMode::Duplicates => {
+            println!(
+                "{} Previewing duplicates in {}...",
+                "→".cyan(),
+                canonical_path.display().to_string().bold()
+            );
+
+            let files = scan_directory(&canonical_path, &ScanOptions::default())?;
+            let duplicates = find_duplicates(&files)?;
+            if summary_only {
+                print_duplicates_summary(&duplicates, units);
+            } else {
+                display_duplicates(&duplicates, units);
+            }
+        }
+        PreviewMode::Clean => {
+            println!(
+                "{} Previewing clean for {}...",
+                "→".cyan(),
+                canonical_path.display().to_string().bold()
+            );
+
+            if let Some(duration_str) = older_than {
+                let duration = cleaner::parse_duration(&duration_str)?;
+                let files = scan_directory(&canonical_path, &ScanOptions::default())?;
+                let old_files = cleaner::find_old_files(&files, duration);
+                if summary_only {
+                    cleaner::print_clean_summary(&old_files, &duration_str, units);
+                } else {
+                    cleaner::preview_clean(&old_files, &duration_str, units);
+                }
+            }
+
+            let empty_dirs = cleaner::find_empty_dirs(&canonical_path, None)?;
+            if empty_dirs.is_empty() {
+                println!("{}", "No empty folders found.".green());
+            } else {
+                if !summary_only {
+                    println!("\n{}", "Empty folders:".yellow().bold());
+                    for dir in &empty_dirs {
+                        println!("  {} {}", "○".yellow(), dir.display());
+                    }
+                }
+                println!(
+                    "\n{} {} empty folders found",
+                    "Summary:".bold(),
+                    empty_dirs.len()
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -9,6 +9,8 @@ use colored::*;
 use serde::{Deserialize, Serialize};
 
 use crate::cli::ProfileAction;
+use crate::organizer::{ConflictStrategy, OrganizeMode};
+use crate::scanner::{parse_date, parse_size, ScanOptions, SizeUnits};
 
 /// A saved profile with organize command options
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +47,70 @@ pub struct ProfileOptions {
     pub ignore: Vec<String>,
 }
 
+impl ProfileOptions {
+    /// The organize mode these options select (by type unless another is set)
+    pub fn mode(&self) -> OrganizeMode {
+        if self.by_date {
+            OrganizeMode::ByDate
+        } else if self.by_extension {
+            OrganizeMode::ByExtension
+        } else if self.by_camera {
+            OrganizeMode::ByCamera
+        } else if self.by_date_taken {
+            OrganizeMode::ByDateTaken
+        } else if self.by_artist {
+            OrganizeMode::ByArtist
+        } else if self.by_album {
+            OrganizeMode::ByAlbum
+        } else {
+            OrganizeMode::ByType
+        }
+    }
+
+    /// How these options resolve name conflicts (rename unless another is set)
+    pub fn conflict_strategy(&self) -> ConflictStrategy {
+        match self.on_conflict.as_str() {
+            "skip" => ConflictStrategy::Skip,
+            "overwrite" => ConflictStrategy::Overwrite,
+            "ask" => ConflictStrategy::Ask,
+            _ => ConflictStrategy::Rename,
+        }
+    }
+
+    /// Scanner settings for the saved filters and ignore patterns
+    pub fn scan_options(&self) -> Result<ScanOptions> {
+        let size = |s: &Option<String>| {
+            s.as_deref()
+                .map(parse_size)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!(e))
+        };
+        let date = |s: &Option<String>| {
+            s.as_deref()
+                .map(parse_date)
+                .transpose()
+                .map_err(|e| anyhow::anyhow!(e))
+        };
+
+        Ok(ScanOptions {
+            include_hidden: false,
+            max_depth: if self.recursive { None } else { Some(1) },
+            follow_symlinks: false,
+            ignore_patterns: self.ignore.clone(),
+            min_size: size(&self.min_size)?,
+            max_size: size(&self.max_size)?,
+            after_date: date(&self.after)?,
+            before_date: date(&self.before)?,
+            name_startswith: self.startswith.clone(),
+            name_endswith: self.endswith.clone(),
+            name_contains: self.contains.clone(),
+            regex_pattern: self.regex.clone(),
+            mime_filter: self.mime.clone(),
+            ..Default::default()
+        })
+    }
+}
+
 impl Profile {
     /// Get profiles directory
     fn profiles_dir() -> Result<PathBuf> {
@@ -117,7 +183,7 @@ impl Profile {
 }
 
 /// Run profile command
-pub fn run(action: ProfileAction) -> Result<()> {
+pub fn run(action: ProfileAction, units: SizeUnits) -> Result<()> {
     match action {
         ProfileAction::Save {
             name,
@@ -217,7 +283,7 @@ pub fn run(action: ProfileAction) -> Result<()> {
 
             println!("{} Running profile '{}'...", "→".cyan(), name.bold());
 
-            run_profile(&profile, !dry_run)?;
+            run_profile(&profile, !dry_run, units)?;
 
             Ok(())
         }
@@ -269,66 +335,15 @@ fn get_mode_name(options: &ProfileOptions) -> &str {
     }
 }
 
-fn run_profile(profile: &Profile, execute: bool) -> Result<()> {
-    use crate::organizer::{
-        execute_moves, plan_moves, preview_moves, print_results, ConflictStrategy, OrganizeMode,
-    };
-    use crate::scanner::{parse_date, parse_size, scan_directory, ScanOptions};
-
-    let mode = if profile.options.by_date {
-        OrganizeMode::ByDate
-    } else if profile.options.by_extension {
-        OrganizeMode::ByExtension
-    } else if profile.options.by_camera {
-        OrganizeMode::ByCamera
-    } else if profile.options.by_date_taken {
-        OrganizeMode::ByDateTaken
-    } else if profile.options.by_artist {
-        OrganizeMode::ByArtist
-    } else if profile.options.by_album {
-        OrganizeMode::ByAlbum
-    } else {
-        OrganizeMode::ByType
-    };
-
-    let conflict_strategy = match profile.options.on_conflict.as_str() {
-        "skip" => ConflictStrategy::Skip,
-        "overwrite" => ConflictStrategy::Overwrite,
-        "ask" => ConflictStrategy::Ask,
-        _ => ConflictStrategy::Rename,
-    };
-
-    let min_size = profile
-        .options
-        .min_size
-        .as_ref()
-        .map(|s| parse_size(s))
-        .transpose()
-        .map_err(|e| anyhow::anyhow!(e))?;
-
-    let max_size = profile
-        .options
-        .max_size
-        .as_ref()
-        .map(|s| parse_size(s))
-        .transpose()
-        .map_err(|e| anyhow::anyhow!(e))?;
-
-    let after_date = profile
-        .options
-        .after
-        .as_ref()
-        .map(|s| parse_date(s))
-        .transpose()
-        .map_err(|e| anyhow::anyhow!(e))?;
-
-    let before_date = profile
-        .options
-        .before
-        .as_ref()
-        .map(|s| parse_date(s))
-        .transpose()
-        .map_err(|e| anyhow::anyhow!(e))?;
+fn run_profile(profile: &Profile, execute: bool, units: SizeUnits) -> Result<()> {
+    use crate::organizer::{execute_moves, plan_moves, preview_moves, print_results};
+    use crate::scanner::scan_directory;
+
+    let mode = profile.options.mode();
+
+    let conflict_strategy = profile.options.conflict_strategy();
+
+    let options = profile.options.scan_options()?;
 
     for path in &profile.paths {
         let canonical = path
@@ -337,26 +352,6 @@ fn run_profile(profile: &Profile, execute: bool) -> Result<()> {
 
         println!("  {} {}", "Scanning".dimmed(), canonical.display());
 
-        let options = ScanOptions {
-            include_hidden: false,
-            max_depth: if profile.options.recursive {
-                None
-            } else {
-                Some(1)
-            },
-            follow_symlinks: false,
-            ignore_patterns: profile.options.ignore.clone(),
-            min_size,
-            max_size,
-            after_date,
-            before_date,
-            name_startswith: profile.options.startswith.clone(),
-            name_endswith: profile.options.endswith.clone(),
-            name_contains: profile.options.contains.clone(),
-            regex_pattern: profile.options.regex.clone(),
-            mime_filter: profile.options.mime.clone(),
-        };
-
         let files = scan_directory(&canonical, &options)?;
         let moves = plan_moves(&files, &canonical, mode);
 
@@ -368,9 +363,9 @@ fn run_profile(profile: &Profile, execute: bool) -> Result<()> {
         if execute {
             let cmd_name = format!("profile {}", profile.name);
             let result = execute_moves(&moves, &cmd_name, conflict_strategy)?;
-            print_results(&result);
+            print_results(&result, units);
         } else {
-            preview_moves(&moves, &canonical);
+            preview_moves(&moves, &canonical, units);
         }
     }
 
@@ -5,11 +5,18 @@ use std::path::PathBuf;
 use anyhow::{Context, Result};
 use colored::*;
 
+use crate::cleaner::execute_clean;
 use crate::cli::QuickAction;
+use crate::config::Config as NeatConfig;
+use crate::filters::is_screenshot_name;
 use crate::organizer::{
-    execute_moves, plan_moves, preview_moves, print_results, ConflictStrategy, OrganizeMode,
+    execute_moves, plan_moves, plan_moves_with_fallbacks, plan_moves_with_template, preview_moves,
+    print_results, ConflictStrategy, ExtensionCase, OrganizeMode,
 };
-use crate::scanner::{scan_directory, ScanOptions};
+use crate::scanner::{scan_directory, FileInfo, ScanOptions, SizeUnits};
+
+/// Where `quick screenshots` files each screenshot, relative to the Desktop
+const SCREENSHOTS_TEMPLATE: &str = "Screenshots/{year}/{month}";
 
 /// Expand ~ to home directory
 fn expand_home(path: &std::path::Path) -> PathBuf {
@@ -23,16 +30,25 @@ fn expand_home(path: &std::path::Path) -> PathBuf {
 }
 
 /// Run a quick action
-pub fn run(action: QuickAction) -> Result<()> {
+pub fn run(action: QuickAction, units: SizeUnits) -> Result<()> {
     match action {
         QuickAction::Downloads { dry_run } => {
             let path = dirs::download_dir().context("Could not find Downloads directory")?;
-            organize_by_type(&path, !dry_run, "downloads")
+            organize_by_type(&path, !dry_run, "downloads", units)
         }
 
         QuickAction::Desktop { dry_run } => {
             let path = dirs::desktop_dir().context("Could not find Desktop directory")?;
-            organize_by_type(&path, !dry_run, "desktop")
+            organize_by_type(&path, !dry_run, "desktop", units)
+        }
+
+        QuickAction::Screenshots { downloads, dry_run } => {
+            let desktop = dirs::desktop_dir().context("Could not find Desktop directory")?;
+            let mut sources = vec![desktop.clone()];
+            if downloads {
+                sources.push(dirs::download_dir().context("Could not find Downloads directory")?);
+            }
+            sweep_screenshots(&desktop, &sources, !dry_run, units)
         }
 
         QuickAction::Photos { path, dry_run } => {
@@ -40,7 +56,7 @@ pub fn run(action: QuickAction) -> Result<()> {
             let canonical = expanded
                 .canonicalize()
                 .with_context(|| format!("Path does not exist: {:?}", expanded))?;
-            organize_photos(&canonical, !dry_run)
+            organize_photos(&canonical, !dry_run, units)
         }
 
         QuickAction::Music { path, dry_run } => {
@@ -48,22 +64,28 @@ pub fn run(action: QuickAction) -> Result<()> {
             let canonical = expanded
                 .canonicalize()
                 .with_context(|| format!("Path does not exist: {:?}", expanded))?;
-            organize_music(&canonical, !dry_run)
+            organize_music(&canonical, !dry_run, units)
         }
 
         QuickAction::Cleanup {
             days,
             trash,
             dry_run,
+            yes,
         } => {
             let path = dirs::download_dir().context("Could not find Downloads directory")?;
-            cleanup_old_files(&path, days, trash, !dry_run)
+            cleanup_old_files(&path, days, trash, !dry_run, yes, units)
         }
     }
 }
 
 /// Organize files by type
-fn organize_by_type(path: &std::path::Path, execute: bool, name: &str) -> Result<()> {
+fn organize_by_type(
+    path: &std::path::Path,
+    execute: bool,
+    name: &str,
+    units: SizeUnits,
+) -> Result<()> {
     println!(
         "{} Quick action: Organize {} by type",
         "→".cyan(),
@@ -100,16 +122,63 @@ fn organize_by_type(path: &std::path::Path, execute: bool, name: &str) -> Result
 
     if execute {
         let result = execute_moves(&moves, &format!("quick {}", name), ConflictStrategy::Rename)?;
-        print_results(&result);
+        print_results(&result, units);
     } else {
-        preview_moves(&moves, path);
+        preview_moves(&moves, path, units);
+    }
+
+    Ok(())
+}
+
+/// Move screenshots found in `sources` into `Screenshots/{year}/{month}` under `base`
+fn sweep_screenshots(
+    base: &std::path::Path,
+    sources: &[PathBuf],
+    execute: bool,
+    units: SizeUnits,
+) -> Result<()> {
+    println!(
+        "{} Quick action: Sweep screenshots into dated folders",
+        "→".cyan()
+    );
+    for source in sources {
+        println!("  Path: {}", source.display().to_string().dimmed());
+    }
+    println!();
+
+    let options = ScanOptions {
+        max_depth: Some(1),
+        ..Default::default()
+    };
+
+    let mut files = Vec::new();
+    for source in sources {
+        files.extend(
+            scan_directory(source, &options)?
+                .into_iter()
+                .filter(|file| is_screenshot_name(&file.name)),
+        );
+    }
+
+    if files.is_empty() {
+        println!("{}", "No screenshots found.".yellow());
+        return Ok(());
+    }
+
+    let moves = plan_moves_with_template(&files, base, SCREENSHOTS_TEMPLATE);
+
+    if execute {
+        let result = execute_moves(&moves, "quick screenshots", ConflictStrategy::Rename)?;
+        print_results(&result, units);
+    } else {
+        preview_moves(&moves, base, units);
     }
 
     Ok(())
 }
 
 /// Organize photos by date taken
-fn organize_photos(path: &std::path::Path, execute: bool) -> Result<()> {
+fn organize_photos(path: &std::path::Path, execute: bool, units: SizeUnits) -> Result<()> {
     println!("{} Quick action: Organize photos by date taken", "→".cyan());
     println!("  Path: {}", path.display().to_string().dimmed());
     println!();
@@ -136,16 +205,16 @@ fn organize_photos(path: &std::path::Path, execute: bool) -> Result<()> {
 
     if execute {
         let result = execute_moves(&moves, "quick photos", ConflictStrategy::Rename)?;
-        print_results(&result);
+        print_results(&result, units);
     } else {
-        preview_moves(&moves, path);
+        preview_moves(&moves, path, units);
     }
 
     Ok(())
 }
 
 /// Organize music by album
-fn organize_music(path: &std::path::Path, execute: bool) -> Result<()> {
+fn organize_music(path: &std::path::Path, execute: bool, units: SizeUnits) -> Result<()> {
     println!(
         "{} Quick action: Organize music by artist/album",
         "→".cyan()
@@ -166,7 +235,16 @@ fn organize_music(path: &std::path::Path, execute: bool) -> Result<()> {
     };
 
     let files = scan_directory(path, &options)?;
-    let moves = plan_moves(&files, path, OrganizeMode::ByAlbum);
+    let fallback_folders = NeatConfig::load_default_or_warn()
+        .map(|config| config.settings.fallback_folders)
+        .unwrap_or_default();
+    let moves = plan_moves_with_fallbacks(
+        &files,
+        path,
+        OrganizeMode::ByAlbum,
+        ExtensionCase::default(),
+        &fallback_folders,
+    );
 
     if moves.is_empty() {
         println!("{}", "All music is already organized.".green());
@@ -175,9 +253,9 @@ fn organize_music(path: &std::path::Path, execute: bool) -> Result<()> {
 
     if execute {
         let result = execute_moves(&moves, "quick music", ConflictStrategy::Rename)?;
-        print_results(&result);
+        print_results(&result, units);
     } else {
-        preview_moves(&moves, path);
+        preview_moves(&moves, path, units);
     }
 
     Ok(())
@@ -189,6 +267,8 @@ fn cleanup_old_files(
     days: u32,
     use_trash: bool,
     execute: bool,
+    yes: bool,
+    units: SizeUnits,
 ) -> Result<()> {
     use std::time::{Duration, SystemTime};
 
@@ -236,14 +316,8 @@ fn cleanup_old_files(
     println!();
 
     if execute {
-        let action = if use_trash { "Move to trash" } else { "Delete" };
-        println!(
-            "{} {} {} files? (this action is not undoable)",
-            "⚠".yellow(),
-            action,
-            files.len()
-        );
-        println!("  Use the 'clean' command with --trash for safer deletion.");
+        let old_files: Vec<&FileInfo> = files.iter().collect();
+        execute_clean(&old_files, yes, use_trash, None, None, units)?;
     } else {
         println!(
             "{} Run without {} to execute this cleanup.",
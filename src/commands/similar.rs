@@ -1,4 +1,4 @@
-//! Similar images command handler
+//! Similar images and videos command handler
 
 use std::fs;
 use std::path::Path;
@@ -8,25 +8,40 @@ use colored::*;
 use dialoguer::Confirm;
 
 use crate::duplicates;
-use crate::scanner::{scan_directory, ScanOptions};
+use crate::filters::{filter_by_date_taken, TakenDateFilter};
+use crate::scanner::{scan_directory, ScanOptions, SizeUnits};
+use crate::video;
 
-/// Find visually similar images using perceptual hashing
+/// Find visually similar images (or videos) using perceptual hashing
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     path: &Path,
     threshold: u32,
+    video: bool,
     delete: bool,
     dry_run: bool,
     execute: bool,
     use_trash: bool,
+    threads: Option<usize>,
+    taken_after: Option<String>,
+    taken_before: Option<String>,
+    taken_only: bool,
+    units: SizeUnits,
 ) -> Result<()> {
     let canonical_path = path
         .canonicalize()
         .with_context(|| format!("Path does not exist: {:?}", path))?;
 
+    let taken_filter =
+        TakenDateFilter::from_args(taken_after.as_deref(), taken_before.as_deref(), taken_only)
+            .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let noun = if video { "videos" } else { "images" };
     println!(
-        "{} Scanning {} for similar images (threshold: {})...",
+        "{} Scanning {} for similar {} (threshold: {})...",
         "→".cyan(),
         canonical_path.display().to_string().bold(),
+        noun,
         threshold
     );
 
@@ -42,10 +57,16 @@ pub fn run(
         ..Default::default()
     };
 
-    let files = scan_directory(&canonical_path, &options)?;
-    let similar = duplicates::find_similar_images(&files, threshold)?;
+    let files = filter_by_date_taken(scan_directory(&canonical_path, &options)?, &taken_filter);
+    let similar = duplicates::with_thread_limit(threads, || {
+        if video {
+            video::find_similar_videos(&files, threshold)
+        } else {
+            duplicates::find_similar_images(&files, threshold)
+        }
+    })??;
 
-    duplicates::display_similar_images(&similar);
+    duplicates::display_similar(&similar, noun, units);
 
     // Delete similar images if requested
     if delete && execute && !dry_run {
@@ -65,9 +86,10 @@ pub fn run(
 
         let confirm = Confirm::new()
             .with_prompt(format!(
-                "Are you sure you want to {} {} similar images?",
+                "Are you sure you want to {} {} similar {}?",
                 action,
-                files_to_remove.len()
+                files_to_remove.len(),
+                noun
             ))
             .default(false)
             .interact()?;
@@ -100,10 +122,11 @@ pub fn run(
                 "Deleted"
             };
             println!(
-                "\n{} {} {} similar images",
+                "\n{} {} {} similar {}",
                 "✓".green(),
                 action_past,
-                deleted.to_string().green()
+                deleted.to_string().green(),
+                noun
             );
         }
     }
@@ -0,0 +1,69 @@
+//! Size command handler
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use colored::*;
+
+use crate::scanner::{scan_directory, sizes_by_top_level, total_size, ScanOptions, SizeUnits};
+
+/// Show the biggest files and folders directly under a directory
+pub fn run(path: &Path, top: usize, units: SizeUnits) -> Result<()> {
+    let canonical_path = path
+        .canonicalize()
+        .with_context(|| format!("Path does not exist: {:?}", path))?;
+
+    println!(
+        "{} Measuring {}...\n",
+        "→".cyan(),
+        canonical_path.display().to_string().bold()
+    );
+
+    let options = ScanOptions {
+        max_depth: None,
+        ..Default::default()
+    };
+
+    let files = scan_directory(&canonical_path, &options)?;
+
+    if files.is_empty() {
+        println!("{}", "No files found.".yellow());
+        return Ok(());
+    }
+
+    let entries = sizes_by_top_level(&files, &canonical_path);
+    let grand_total = total_size(&files);
+    let largest = entries.first().map(|e| e.size).unwrap_or(0).max(1);
+
+    println!("{}", "Largest Entries:".bold());
+    println!("{}", "─".repeat(60));
+    for entry in entries.iter().take(top) {
+        let bar_len = (entry.size as f64 / largest as f64 * 30.0) as usize;
+        let bar = "█".repeat(bar_len);
+        let name = if entry.is_dir {
+            format!("{}/", entry.name).cyan()
+        } else {
+            entry.name.normal()
+        };
+        println!(
+            "  {:>10}  {:<30} {}",
+            units.format(entry.size).yellow(),
+            name,
+            bar.green()
+        );
+    }
+
+    if entries.len() > top {
+        println!("  {} ... and {} more", "→".dimmed(), entries.len() - top);
+    }
+
+    println!("\n{}", "─".repeat(60));
+    println!(
+        "{}: {} files, {}",
+        "Total".bold(),
+        files.len().to_string().cyan(),
+        units.format(grand_total).cyan()
+    );
+
+    Ok(())
+}
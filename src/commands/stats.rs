@@ -1,22 +1,35 @@
 //! Stats command handler
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
 use std::path::Path;
 
 use anyhow::{Context, Result};
 use colored::*;
 
 use crate::classifier::Classifier;
-use crate::export;
-use crate::scanner::{format_size, scan_directory, total_size, ScanOptions};
+use crate::config::{Config as NeatConfig, Threshold};
+use crate::duplicates::find_duplicates;
+use crate::export::{self, Format, ThresholdBreach};
+use crate::scanner::{scan_directory, total_size, FileInfo, ScanOptions, SizeUnits};
+
+/// Show statistics about a directory, optionally compared with an earlier JSON snapshot
+pub fn run(
+    path: &Path,
+    format: Format,
+    compare: Option<&Path>,
+    depth: Option<usize>,
+    with_duplicates: bool,
+    units: SizeUnits,
+) -> Result<()> {
+    // Read the snapshot first so a bad file fails before the scan
+    let snapshot = compare.map(load_snapshot).transpose()?;
 
-/// Show statistics about a directory
-pub fn run(path: &Path, json: bool) -> Result<()> {
     let canonical_path = path
         .canonicalize()
         .with_context(|| format!("Path does not exist: {:?}", path))?;
 
-    if !json {
+    if !format.is_machine() {
         println!(
             "{} Analyzing {}...\n",
             "→".cyan(),
@@ -26,7 +39,7 @@ pub fn run(path: &Path, json: bool) -> Result<()> {
 
     let options = ScanOptions {
         include_hidden: false,
-        max_depth: None,
+        max_depth: depth,
         follow_symlinks: false,
         ignore_patterns: Vec::new(),
         min_size: None,
@@ -39,46 +52,59 @@ pub fn run(path: &Path, json: bool) -> Result<()> {
     let files = scan_directory(&canonical_path, &options)?;
 
     if files.is_empty() {
-        if json {
-            println!("{{\"total_files\": 0, \"total_size\": 0, \"categories\": []}}");
-        } else {
-            println!("{}", "No files found.".yellow());
+        match format {
+            Format::Json => println!(
+                "{{\"total_files\": 0, \"total_size\": 0, \"categories\": [], \"threshold_breaches\": []}}"
+            ),
+            Format::Csv => println!("category,count,size"),
+            Format::Table => println!("{}", "No files found.".yellow()),
         }
         return Ok(());
     }
 
-    let classifier = Classifier::new();
+    let classifier = Classifier::configured();
 
     // Group by category
     let mut by_category: HashMap<String, (usize, u64)> = HashMap::new();
     for file in &files {
-        let category = classifier.classify(file.extension.as_deref());
-        let entry = by_category
-            .entry(category.folder_name().to_string())
-            .or_insert((0, 0));
+        let category = classifier.category_name(file.extension.as_deref());
+        let entry = by_category.entry(category.to_string()).or_insert((0, 0));
         entry.0 += 1;
         entry.1 += file.size;
     }
 
     // Sort by count
     let mut categories: Vec<_> = by_category.into_iter().collect();
-    categories.sort_by(|a, b| b.1 .0.cmp(&a.1 .0));
+    categories.sort_by_key(|c| std::cmp::Reverse(c.1 .0));
+    let category_stats: Vec<export::CategoryStats> = categories
+        .iter()
+        .map(|(name, (count, size))| export::CategoryStats {
+            name: name.clone(),
+            count: *count,
+            size: *size,
+        })
+        .collect();
+
+    // Hashing every file is slow, so only when asked for
+    let wasted = if with_duplicates {
+        Some(duplicate_wasted_space(&files)?)
+    } else {
+        None
+    };
 
-    // Handle JSON export
-    if json {
+    // Handle JSON/CSV export
+    if format.is_machine() {
+        let thresholds = NeatConfig::load_default_or_warn()
+            .map(|config| config.thresholds)
+            .unwrap_or_default();
         let stats = export::ExportStats {
             total_files: files.len(),
             total_size: total_size(&files),
-            categories: categories
-                .iter()
-                .map(|(name, (count, size))| export::CategoryStats {
-                    name: name.clone(),
-                    count: *count,
-                    size: *size,
-                })
-                .collect(),
+            categories: category_stats,
+            threshold_breaches: evaluate_thresholds(&categories, &thresholds),
+            duplicate_wasted_space: wasted,
         };
-        export::export_stats_json(&stats, &mut std::io::stdout())?;
+        export::export_stats(&stats, format, &mut std::io::stdout())?;
         return Ok(());
     }
 
@@ -91,27 +117,27 @@ pub fn run(path: &Path, json: bool) -> Result<()> {
             "  {:12} {:>5} files {:>10}  {}",
             category.cyan(),
             count,
-            format_size(*size).dimmed(),
+            units.format(*size).dimmed(),
             bar.green()
         );
     }
 
     // Top 10 largest files
     let mut sorted_files = files.clone();
-    sorted_files.sort_by(|a, b| b.size.cmp(&a.size));
+    sorted_files.sort_by_key(|f| std::cmp::Reverse(f.size));
 
     println!("\n{}", "Largest Files:".bold());
     println!("{}", "─".repeat(50));
     for file in sorted_files.iter().take(10) {
         println!(
             "  {:>10}  {}",
-            format_size(file.size).yellow(),
+            units.format(file.size).yellow(),
             file.name.dimmed()
         );
     }
 
     // Top 10 oldest files
-    sorted_files.sort_by(|a, b| a.modified.cmp(&b.modified));
+    sorted_files.sort_by_key(|f| f.modified);
 
     println!("\n{}", "Oldest Files:".bold());
     println!("{}", "─".repeat(50));
@@ -140,8 +166,273 @@ pub fn run(path: &Path, json: bool) -> Result<()> {
         "{}: {} files, {}",
         "Total".bold(),
         files.len().to_string().cyan(),
-        format_size(total_size(&files)).cyan()
+        units.format(total_size(&files)).cyan()
     );
+    if let Some(wasted) = wasted {
+        println!(
+            "{}: {} in duplicate copies",
+            "Recoverable".bold(),
+            units.format(wasted).yellow()
+        );
+    }
+
+    if let Some(snapshot) = snapshot {
+        print_changes(
+            &snapshot,
+            files.len(),
+            total_size(&files),
+            &category_stats,
+            units,
+        );
+    }
 
     Ok(())
 }
+
+/// Bytes taken up by extra copies of duplicate files
+fn duplicate_wasted_space(files: &[FileInfo]) -> Result<u64> {
+    let groups = find_duplicates(files)?;
+    Ok(groups.iter().map(|g| g.wasted_space()).sum())
+}
+
+/// Change in one category between a snapshot and now
+#[derive(Debug, PartialEq)]
+struct CategoryDelta {
+    name: String,
+    /// Files added (positive) or removed (negative)
+    count: i64,
+    /// Bytes added (positive) or removed (negative)
+    size: i64,
+}
+
+/// Read a stats snapshot written by `stats --format json`
+fn load_snapshot(path: &Path) -> Result<export::ExportStats> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read snapshot: {:?}", path))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Not a `neatcli stats --format json` snapshot: {:?}", path))
+}
+
+/// Per-category changes from `before` to `after`, including categories that
+/// appeared or disappeared. Unchanged categories are left out; the largest size
+/// changes come first.
+fn diff_categories(
+    before: &[export::CategoryStats],
+    after: &[export::CategoryStats],
+) -> Vec<CategoryDelta> {
+    let mut totals: BTreeMap<&str, (i64, i64)> = BTreeMap::new();
+    for category in before {
+        let entry = totals.entry(&category.name).or_default();
+        entry.0 -= category.count as i64;
+        entry.1 -= category.size as i64;
+    }
+    for category in after {
+        let entry = totals.entry(&category.name).or_default();
+        entry.0 += category.count as i64;
+        entry.1 += category.size as i64;
+    }
+
+    let mut deltas: Vec<CategoryDelta> = totals
+        .into_iter()
+        .filter(|(_, (count, size))| *count != 0 || *size != 0)
+        .map(|(name, (count, size))| CategoryDelta {
+            name: name.to_string(),
+            count,
+            size,
+        })
+        .collect();
+    deltas.sort_by_key(|d| std::cmp::Reverse(d.size.unsigned_abs()));
+    deltas
+}
+
+/// Print how the directory changed since `snapshot`
+fn print_changes(
+    snapshot: &export::ExportStats,
+    total_files: usize,
+    total_bytes: u64,
+    categories: &[export::CategoryStats],
+    units: SizeUnits,
+) {
+    println!("\n{}", "Changes Since Snapshot:".bold());
+    println!("{}", "─".repeat(50));
+
+    let deltas = diff_categories(&snapshot.categories, categories);
+    if deltas.is_empty() {
+        println!("  {}", "No changes.".green());
+    }
+    for delta in &deltas {
+        println!(
+            "  {:12} {:>6} files {:>12}",
+            delta.name.cyan(),
+            signed(delta.count, delta.count.unsigned_abs().to_string()),
+            signed(delta.size, units.format(delta.size.unsigned_abs())),
+        );
+    }
+
+    println!("\n{}", "─".repeat(50));
+    let count_change = total_files as i64 - snapshot.total_files as i64;
+    let size_change = total_bytes as i64 - snapshot.total_size as i64;
+    println!(
+        "{}: {} files, {}",
+        "Change".bold(),
+        signed(count_change, count_change.unsigned_abs().to_string()),
+        signed(size_change, units.format(size_change.unsigned_abs()))
+    );
+}
+
+/// `magnitude` with the sign of `value`, green for growth and red for shrinkage
+fn signed(value: i64, magnitude: String) -> ColoredString {
+    match value.signum() {
+        1 => format!("+{}", magnitude).green(),
+        -1 => format!("-{}", magnitude).red(),
+        _ => magnitude.normal(),
+    }
+}
+
+/// List every configured limit a category goes over, in category order.
+/// Category names in the config are matched case-insensitively.
+fn evaluate_thresholds(
+    categories: &[(String, (usize, u64))],
+    thresholds: &BTreeMap<String, Threshold>,
+) -> Vec<ThresholdBreach> {
+    let mut breaches = Vec::new();
+    for (name, (count, size)) in categories {
+        let Some(threshold) = thresholds
+            .iter()
+            .find(|(category, _)| category.eq_ignore_ascii_case(name))
+            .map(|(_, threshold)| threshold)
+        else {
+            continue;
+        };
+
+        if let Some(limit) = threshold.max_size.filter(|limit| size > limit) {
+            breaches.push(ThresholdBreach {
+                category: name.clone(),
+                metric: "size",
+                limit,
+                actual: *size,
+            });
+        }
+        if let Some(limit) = threshold.max_count.filter(|limit| count > limit) {
+            breaches.push(ThresholdBreach {
+                category: name.clone(),
+                metric: "count",
+                limit: limit as u64,
+                actual: *count as u64,
+            });
+        }
+    }
+    breaches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_categories() {
+        let stats = |entries: &[(&str, usize, u64)]| -> Vec<export::CategoryStats> {
+            entries
+                .iter()
+                .map(|(name, count, size)| export::CategoryStats {
+                    name: name.to_string(),
+                    count: *count,
+                    size: *size,
+                })
+                .collect()
+        };
+        let last_week = stats(&[
+            ("Images", 10, 5000),
+            ("Documents", 4, 800),
+            ("Archives", 2, 3000),
+            ("Code", 7, 70),
+        ]);
+        let today = stats(&[
+            ("Images", 13, 9000),
+            ("Documents", 3, 600),
+            ("Videos", 1, 2000),
+            ("Code", 7, 70),
+        ]);
+
+        let deltas = diff_categories(&last_week, &today);
+
+        let delta = |name: &str, count, size| CategoryDelta {
+            name: name.to_string(),
+            count,
+            size,
+        };
+        assert_eq!(
+            deltas,
+            vec![
+                delta("Images", 3, 4000),
+                delta("Archives", -2, -3000),
+                delta("Videos", 1, 2000),
+                delta("Documents", -1, -200),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_thresholds() {
+        let categories = vec![
+            ("Images".to_string(), (12, 4000)),
+            ("Videos".to_string(), (2, 9000)),
+            ("Documents".to_string(), (50, 100)),
+        ];
+        let mut thresholds = BTreeMap::new();
+        thresholds.insert(
+            "images".to_string(),
+            Threshold {
+                max_size: Some(5000),
+                max_count: Some(10),
+            },
+        );
+        thresholds.insert(
+            "Videos".to_string(),
+            Threshold {
+                max_size: Some(8000),
+                max_count: Some(2),
+            },
+        );
+
+        let breaches = evaluate_thresholds(&categories, &thresholds);
+        assert_eq!(
+            breaches,
+            vec![
+                ThresholdBreach {
+                    category: "Images".to_string(),
+                    metric: "count",
+                    limit: 10,
+                    actual: 12,
+                },
+                ThresholdBreach {
+                    category: "Videos".to_string(),
+                    metric: "size",
+                    limit: 8000,
+                    actual: 9000,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_wasted_space_matches_groups() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, content) in [
+            ("a.txt", "same content"),
+            ("b.txt", "same content"),
+            ("c.txt", "same content"),
+            ("d.bin", "other bytes!!"),
+            ("e.bin", "other bytes!!"),
+            ("f.txt", "unique"),
+        ] {
+            fs::write(dir.path().join(name), content).unwrap();
+        }
+        let files = scan_directory(dir.path(), &ScanOptions::default()).unwrap();
+
+        let groups = find_duplicates(&files).unwrap();
+        let expected: u64 = groups.iter().map(|g| g.wasted_space()).sum();
+        assert_eq!(duplicate_wasted_space(&files).unwrap(), expected);
+        assert_eq!(expected, 2 * 12 + 13);
+    }
+}
@@ -0,0 +1,76 @@
+//! Tidy-names command handler
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use colored::*;
+
+use crate::organizer::{execute_moves, plan_tidy_names, print_results, ConflictStrategy};
+use crate::scanner::{scan_directory, ScanOptions, SizeUnits};
+
+/// Rename files to trimmed, NFC-normalized names without zero-width characters
+pub fn run(
+    path: &Path,
+    recursive: bool,
+    dry_run: bool,
+    execute: bool,
+    summary_only: bool,
+    units: SizeUnits,
+) -> Result<()> {
+    let canonical_path = path
+        .canonicalize()
+        .with_context(|| format!("Path does not exist: {:?}", path))?;
+
+    println!(
+        "{} Scanning {} for untidy file names...",
+        "→".cyan(),
+        canonical_path.display().to_string().bold()
+    );
+
+    let options = ScanOptions {
+        max_depth: if recursive { None } else { Some(1) },
+        ..Default::default()
+    };
+
+    let files = scan_directory(&canonical_path, &options)?;
+    let renames = plan_tidy_names(&files);
+
+    if renames.is_empty() {
+        println!("{}", "All file names are already tidy.".green());
+        return Ok(());
+    }
+
+    if execute && !dry_run {
+        // Two names can tidy to the same result; keep both with a numeric suffix
+        let result = execute_moves(&renames, "tidy-names", ConflictStrategy::Rename)?;
+        print_results(&result, units);
+        return Ok(());
+    }
+
+    if !summary_only {
+        println!("\n{}", "Renames:".bold().cyan());
+        for mv in &renames {
+            let from = mv.from.strip_prefix(&canonical_path).unwrap_or(&mv.from);
+            let to = mv.to.file_name().unwrap_or_default().to_string_lossy();
+            println!(
+                "  {:?} {} {:?}",
+                from.display().to_string(),
+                "→".dimmed(),
+                to
+            );
+        }
+    }
+
+    println!(
+        "\n{} {} files would be renamed",
+        "Summary:".bold(),
+        renames.len()
+    );
+    println!(
+        "\n{} Use {} to rename these files.",
+        "⚠".yellow(),
+        "--execute".yellow()
+    );
+
+    Ok(())
+}
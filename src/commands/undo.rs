@@ -1,14 +1,16 @@
 //! Undo command handler
 
 use std::fs;
+use std::path::Path;
 
 use anyhow::Result;
 use colored::*;
 
-use crate::logger::{History, OperationType};
+use crate::logger::{content_hash, FileOperation, History, OperationBatch, OperationType};
+use crate::organizer::move_file;
 
-/// Undo the last operation
-pub fn run() -> Result<()> {
+/// Undo the last operation, or with `dry_run` only show what undoing it would do
+pub fn run(dry_run: bool) -> Result<()> {
     let mut history = History::load()?;
 
     if history.is_empty() {
@@ -16,70 +18,445 @@ pub fn run() -> Result<()> {
         return Ok(());
     }
 
+    if dry_run {
+        let batch = history.batches.last().unwrap();
+        println!(
+            "{} Undoing '{}' would ({} operations):",
+            "→".cyan(),
+            batch.display_name().bold(),
+            batch.operations.len()
+        );
+        preview_batch(batch);
+        return Ok(());
+    }
+
     let batch = history.pop_last().unwrap();
 
     println!(
         "{} Undoing '{}' ({} operations)...",
         "→".cyan(),
-        batch.command.bold(),
+        batch.display_name().bold(),
         batch.operations.len()
     );
 
+    let (undone, errors) = undo_batch(&batch, restore_from_trash);
+
+    if undone > 0 {
+        history.save()?;
+        println!(
+            "\n{} Restored {} files",
+            "✓".green(),
+            undone.to_string().green()
+        );
+    }
+
+    if errors > 0 {
+        println!(
+            "{} {} operations could not be undone",
+            "⚠".yellow(),
+            errors.to_string().yellow()
+        );
+    }
+
+    Ok(())
+}
+
+/// What undoing a single logged operation involves
+#[derive(Debug, PartialEq)]
+enum UndoStep<'a> {
+    /// Move the file at `to` back to `from`
+    Restore { from: &'a Path, to: &'a Path },
+    /// The moved file was replaced since, so it stays where it is
+    Changed(&'a Path),
+    /// The moved file is gone; there is nothing to restore
+    Missing,
+    /// Remove a symlink left behind by a move, before reversing the move
+    RemoveSymlink(&'a Path),
+    /// Remove a link created next to a file that never moved
+    RemoveLink(&'a Path),
+    /// Remove a folder created to hold links, if it's empty
+    RemoveCreatedDir(&'a Path),
+    /// Put a trashed file back
+    RestoreTrash(&'a Path),
+    /// Recreate a folder removed after it was emptied
+    RecreateDir(&'a Path),
+    /// A permanently deleted file, which can't come back
+    Unrecoverable(&'a Path),
+}
+
+/// Decide how to reverse one operation, given the filesystem as it is now
+fn plan_undo(op: &FileOperation) -> UndoStep<'_> {
+    match op.operation_type {
+        OperationType::Move => {
+            if op.hash.is_some() && op.to.exists() && content_hash(&op.to) != op.hash {
+                UndoStep::Changed(&op.to)
+            } else if op.to.exists() {
+                UndoStep::Restore {
+                    from: &op.from,
+                    to: &op.to,
+                }
+            } else {
+                UndoStep::Missing
+            }
+        }
+        OperationType::Symlink => UndoStep::RemoveSymlink(&op.from),
+        OperationType::Link => UndoStep::RemoveLink(&op.from),
+        OperationType::CreateDir => UndoStep::RemoveCreatedDir(&op.from),
+        OperationType::Trash => UndoStep::RestoreTrash(&op.from),
+        OperationType::RemoveDir => UndoStep::RecreateDir(&op.from),
+        OperationType::Delete => UndoStep::Unrecoverable(&op.from),
+    }
+}
+
+/// Print what undoing a batch would do, newest operation first, without touching anything
+fn preview_batch(batch: &OperationBatch) {
+    let mut restorable = 0;
+    let mut unrecoverable = 0;
+
+    for op in batch.operations.iter().rev() {
+        match plan_undo(op) {
+            UndoStep::Restore { from, to } => {
+                restorable += 1;
+                println!(
+                    "  {} {} → {}",
+                    "restore".green(),
+                    to.display(),
+                    from.display()
+                );
+            }
+            UndoStep::Changed(path) => {
+                unrecoverable += 1;
+                println!(
+                    "  {} {} (changed since it was moved)",
+                    "keep".yellow(),
+                    path.display()
+                );
+            }
+            UndoStep::Missing => {}
+            UndoStep::RemoveSymlink(path) => {
+                println!("  {} {}", "unlink".cyan(), path.display());
+            }
+            UndoStep::RemoveLink(path) => {
+                restorable += 1;
+                println!("  {} {}", "unlink".cyan(), path.display());
+            }
+            UndoStep::RemoveCreatedDir(path) => {
+                println!("  {} {} (if empty)", "rmdir".cyan(), path.display());
+            }
+            UndoStep::RestoreTrash(path) => {
+                restorable += 1;
+                println!("  {} {}", "untrash".green(), path.display());
+            }
+            UndoStep::RecreateDir(path) => {
+                restorable += 1;
+                println!("  {} {}", "mkdir".green(), path.display());
+            }
+            UndoStep::Unrecoverable(path) => {
+                unrecoverable += 1;
+                println!(
+                    "  {} {} (deleted, can't be restored)",
+                    "lost".red(),
+                    path.display()
+                );
+            }
+        }
+    }
+
+    println!(
+        "\n{} {} would be restored, {} can't be undone",
+        "ℹ".blue(),
+        restorable,
+        unrecoverable
+    );
+    println!("  {} Run {} to do it", "ℹ".blue(), "neatcli undo".yellow());
+}
+
+/// Reverse every operation in a batch, newest first, putting trashed files back
+/// with `restore_trashed`.
+/// Returns the number of restored files and the number of failures.
+fn undo_batch(
+    batch: &OperationBatch,
+    restore_trashed: impl Fn(&Path) -> Result<()>,
+) -> (usize, usize) {
     let mut undone = 0;
     let mut errors = 0;
 
     for op in batch.operations.iter().rev() {
-        match op.operation_type {
-            OperationType::Move => {
-                // Reverse the move
-                if op.to.exists() {
-                    // Create parent directory if needed
-                    if let Some(parent) = op.from.parent() {
-                        fs::create_dir_all(parent).ok();
-                    }
+        match plan_undo(op) {
+            UndoStep::Restore { from, to } => {
+                // Create parent directory if needed
+                if let Some(parent) = from.parent() {
+                    fs::create_dir_all(parent).ok();
+                }
 
-                    match fs::rename(&op.to, &op.from) {
-                        Ok(_) => undone += 1,
-                        Err(e) => {
-                            errors += 1;
-                            eprintln!(
-                                "{} Failed to restore {}: {}",
-                                "✗".red(),
-                                op.from.display(),
-                                e
-                            );
-                        }
+                match move_file(to, from) {
+                    Ok(_) => undone += 1,
+                    Err(e) => {
+                        errors += 1;
+                        eprintln!("{} Failed to restore {}: {}", "✗".red(), from.display(), e);
                     }
                 }
             }
-            OperationType::Delete => {
+            UndoStep::Changed(path) => {
+                errors += 1;
+                eprintln!(
+                    "{} Changed since it was moved, leaving in place: {}",
+                    "⚠".yellow(),
+                    path.display()
+                );
+            }
+            UndoStep::Missing => {}
+            UndoStep::RemoveSymlink(path) => {
+                // Remove the symlink left at the old path so the move can be reversed
+                if !remove_symlink(path) {
+                    errors += 1;
+                }
+            }
+            UndoStep::RemoveLink(path) => {
+                // The original never moved; only the link pointing at it goes
+                if remove_symlink(path) {
+                    undone += 1;
+                } else {
+                    errors += 1;
+                }
+            }
+            UndoStep::RemoveCreatedDir(path) => match fs::remove_dir(path) {
+                Ok(_) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(_) => eprintln!(
+                    "{} Folder not empty, leaving in place: {}",
+                    "⚠".yellow(),
+                    path.display()
+                ),
+            },
+            UndoStep::RestoreTrash(path) => match restore_trashed(path) {
+                Ok(_) => undone += 1,
+                Err(e) => {
+                    errors += 1;
+                    eprintln!(
+                        "{} Failed to restore {} from trash: {}",
+                        "✗".red(),
+                        path.display(),
+                        e
+                    );
+                }
+            },
+            UndoStep::RecreateDir(path) => match fs::create_dir_all(path) {
+                Ok(_) => undone += 1,
+                Err(e) => {
+                    errors += 1;
+                    eprintln!(
+                        "{} Failed to recreate folder {}: {}",
+                        "✗".red(),
+                        path.display(),
+                        e
+                    );
+                }
+            },
+            UndoStep::Unrecoverable(path) => {
                 // Cannot undo deletes
                 eprintln!(
                     "{} Cannot restore deleted file: {}",
                     "⚠".yellow(),
-                    op.from.display()
+                    path.display()
                 );
                 errors += 1;
             }
         }
     }
 
-    if undone > 0 {
-        history.save()?;
-        println!(
-            "\n{} Restored {} files",
-            "✓".green(),
-            undone.to_string().green()
-        );
+    (undone, errors)
+}
+
+/// Remove the symlink at `path`, leaving anything that is no longer a symlink alone.
+/// Returns false (after reporting why) if it is still there.
+fn remove_symlink(path: &Path) -> bool {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_symlink() => match fs::remove_file(path) {
+            Ok(_) => true,
+            Err(e) => {
+                eprintln!(
+                    "{} Failed to remove symlink {}: {}",
+                    "✗".red(),
+                    path.display(),
+                    e
+                );
+                false
+            }
+        },
+        Ok(_) => {
+            eprintln!(
+                "{} Not a symlink anymore, leaving in place: {}",
+                "⚠".yellow(),
+                path.display()
+            );
+            false
+        }
+        Err(_) => true, // Already gone
     }
+}
 
-    if errors > 0 {
-        println!(
-            "{} {} operations could not be undone",
-            "⚠".yellow(),
-            errors.to_string().yellow()
+/// Put a trashed file back at its original path (most recent trash entry wins)
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+fn restore_from_trash(path: &Path) -> Result<()> {
+    use trash::os_limited::{list, restore_all};
+
+    let item = list()
+        .map_err(|e| anyhow::anyhow!("{}", e))?
+        .into_iter()
+        .filter(|item| item.original_path() == path)
+        .max_by_key(|item| item.time_deleted)
+        .ok_or_else(|| anyhow::anyhow!("not found in trash"))?;
+
+    restore_all([item]).map_err(|e| anyhow::anyhow!("{}", e))
+}
+
+/// The trash crate cannot list or restore items on this platform
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+fn restore_from_trash(_path: &Path) -> Result<()> {
+    anyhow::bail!("restoring from trash is not supported on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_undo_restores_trashed_file() {
+        // A stand-in trash, so the test never touches the real one
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trashed.txt");
+        let restore = |trashed: &Path| -> Result<()> {
+            fs::write(trashed, "keep me")?;
+            Ok(())
+        };
+
+        let batch = OperationBatch {
+            timestamp: chrono::Utc::now(),
+            command: "clean --trash".to_string(),
+            label: None,
+            operations: vec![FileOperation {
+                from: path.clone(),
+                to: std::path::PathBuf::new(),
+                operation_type: OperationType::Trash,
+                hash: None,
+            }],
+        };
+
+        assert_eq!(undo_batch(&batch, restore), (1, 0));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "keep me");
+
+        let missing = |_: &Path| -> Result<()> { anyhow::bail!("not found in trash") };
+        assert_eq!(undo_batch(&batch, missing), (0, 1));
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn test_restore_from_trash_round_trip() {
+        // The freedesktop trash lives under XDG_DATA_HOME; keep it in the temp folder
+        let dir = tempfile::tempdir().unwrap();
+        std::env::set_var("XDG_DATA_HOME", dir.path().join("data"));
+        let path = dir.path().join("report.txt");
+        fs::write(&path, "keep me").unwrap();
+
+        trash::delete(&path).unwrap();
+        assert!(!path.exists());
+
+        restore_from_trash(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "keep me");
+        assert!(restore_from_trash(&path).is_err());
+    }
+
+    fn moved_file_batch(from: &Path, to: &Path) -> OperationBatch {
+        OperationBatch {
+            timestamp: chrono::Utc::now(),
+            command: "organize --by-type".to_string(),
+            label: None,
+            operations: vec![FileOperation {
+                from: from.to_path_buf(),
+                to: to.to_path_buf(),
+                operation_type: OperationType::Move,
+                hash: content_hash(to),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_undo_restores_unchanged_move() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("notes.txt");
+        let to = dir.path().join("notes-moved.txt");
+        fs::write(&to, "original").unwrap();
+
+        let batch = moved_file_batch(&from, &to);
+        assert!(batch.operations[0].hash.is_some());
+
+        assert_eq!(undo_batch(&batch, restore_from_trash), (1, 0));
+        assert_eq!(fs::read_to_string(&from).unwrap(), "original");
+    }
+
+    #[test]
+    fn test_plan_undo_leaves_filesystem_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("notes.txt");
+        let to = dir.path().join("Documents").join("notes.txt");
+        fs::create_dir_all(to.parent().unwrap()).unwrap();
+        fs::write(&to, "original").unwrap();
+        let mut batch = moved_file_batch(&from, &to);
+        let deleted = dir.path().join("old.log");
+        batch.operations.push(FileOperation {
+            from: deleted.clone(),
+            to: std::path::PathBuf::new(),
+            operation_type: OperationType::Delete,
+            hash: None,
+        });
+
+        let steps: Vec<UndoStep> = batch.operations.iter().rev().map(plan_undo).collect();
+        assert_eq!(
+            steps,
+            vec![
+                UndoStep::Unrecoverable(&deleted),
+                UndoStep::Restore {
+                    from: &from,
+                    to: &to
+                },
+            ]
         );
+
+        preview_batch(&batch);
+        assert!(!from.exists());
+        assert_eq!(fs::read_to_string(&to).unwrap(), "original");
     }
 
-    Ok(())
+    #[test]
+    fn test_undo_leaves_replaced_file_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("notes.txt");
+        let to = dir.path().join("notes-moved.txt");
+        fs::write(&to, "original").unwrap();
+        let batch = moved_file_batch(&from, &to);
+
+        // Something else now lives at the destination
+        fs::write(&to, "a different file").unwrap();
+
+        assert_eq!(undo_batch(&batch, restore_from_trash), (0, 1));
+        assert!(!from.exists());
+        assert_eq!(fs::read_to_string(&to).unwrap(), "a different file");
+    }
 }
@@ -2,36 +2,101 @@
 
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use colored::*;
 
+use crate::commands::profile::Profile;
 use crate::config::Config as NeatConfig;
-use crate::organizer::OrganizeMode;
+use crate::organizer::{ConflictStrategy, OrganizeMode};
+use crate::scanner::ScanOptions;
 use crate::watcher;
 
 /// Watch a directory and auto-organize new files
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     path: &Path,
     _by_type: bool,
     by_date: bool,
     by_extension: bool,
     config_path: Option<std::path::PathBuf>,
+    config_reload: bool,
     auto: bool,
+    profile: Option<&str>,
+    debounce: Option<&str>,
+    stats_interval: Option<&str>,
 ) -> Result<()> {
-    // Determine mode
-    let mode = if by_date {
-        OrganizeMode::ByDate
+    let debounce = debounce
+        .map(watcher::parse_interval)
+        .transpose()
+        .context("Invalid --debounce")?
+        .unwrap_or(watcher::DEFAULT_DEBOUNCE);
+    let stats_interval = stats_interval
+        .map(watcher::parse_interval)
+        .transpose()
+        .context("Invalid --stats-interval")?;
+    if stats_interval.is_some_and(|interval| interval.is_zero()) {
+        bail!("--stats-interval must be longer than zero");
+    }
+
+    // A profile brings its own mode, filters, ignore patterns and conflict handling
+    let (mode, filter, strategy) = if let Some(name) = profile {
+        let profile = Profile::load(name)?;
+        if profile.options.recursive {
+            bail!(
+                "Profile '{}' is recursive, but watch only organizes files directly in the folder; save a profile without --recursive to watch with it",
+                profile.name
+            );
+        }
+        println!("{} Using profile '{}'", "→".cyan(), profile.name.bold());
+        (
+            profile.options.mode(),
+            profile.options.scan_options()?,
+            profile.options.conflict_strategy(),
+        )
+    } else if by_date {
+        (
+            OrganizeMode::ByDate,
+            ScanOptions::default(),
+            ConflictStrategy::Rename,
+        )
     } else if by_extension {
-        OrganizeMode::ByExtension
+        (
+            OrganizeMode::ByExtension,
+            ScanOptions::default(),
+            ConflictStrategy::Rename,
+        )
     } else {
-        OrganizeMode::ByType // Default
+        (
+            OrganizeMode::ByType,
+            ScanOptions::default(),
+            ConflictStrategy::Rename,
+        ) // Default
     };
 
     // Load config if specified
-    let config = if let Some(cfg_path) = config_path {
-        Some(NeatConfig::load(&cfg_path)?)
+    let config = if let Some(ref cfg_path) = config_path {
+        Some(NeatConfig::load(cfg_path)?)
     } else {
         NeatConfig::load_default()?
     };
 
-    watcher::watch_directory(path, mode, config.as_ref(), auto)
+    // Reload from wherever the config came from (or would come from)
+    let reload_path = if config_reload {
+        let reload_path = config_path.or_else(NeatConfig::default_path);
+        Some(reload_path.context("Could not find home directory for the default config")?)
+    } else {
+        None
+    };
+
+    watcher::watch_directory(
+        path,
+        mode,
+        &filter,
+        strategy,
+        config,
+        reload_path.as_deref(),
+        auto,
+        debounce,
+        stats_interval,
+    )
 }
@@ -1,12 +1,19 @@
 //! Configuration and custom rules handling
 
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use chrono::{Datelike, Utc};
+use colored::*;
 use glob::Pattern;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::scanner::parse_size;
+
+/// Per-directory config, merged over the global one when that directory is organized
+pub const LOCAL_CONFIG_FILE: &str = ".neat.toml";
 
 /// Configuration file structure
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -18,6 +25,14 @@ pub struct Config {
     /// Default settings
     #[serde(default)]
     pub settings: Settings,
+
+    /// Per-category limits reported by `stats --json`, keyed by category name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub thresholds: BTreeMap<String, Threshold>,
+
+    /// Custom categories (name to extensions) that replace the built-in ones
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub categories: BTreeMap<String, Vec<String>>,
 }
 
 /// Default settings
@@ -34,6 +49,14 @@ pub struct Settings {
     /// Default organize mode
     #[serde(default = "default_organize_mode")]
     pub default_organize_mode: String,
+
+    /// Folder names used when a file has no metadata for the organize mode
+    #[serde(default)]
+    pub fallback_folders: FallbackFolders,
+
+    /// Suffix for renamed conflicts, e.g. " ({n})" (default "_{n}")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collision_suffix: Option<String>,
 }
 
 fn default_organize_mode() -> String {
@@ -46,10 +69,73 @@ impl Default for Settings {
             include_hidden: false,
             follow_symlinks: false,
             default_organize_mode: default_organize_mode(),
+            fallback_folders: FallbackFolders::default(),
+            collision_suffix: None,
         }
     }
 }
 
+/// Folder names for files missing the metadata an organize mode groups by
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FallbackFolders {
+    /// Images without a camera make or model (`--by-camera`)
+    pub camera: String,
+    /// Audio files without an artist (`--by-artist`, `--by-album`)
+    pub artist: String,
+    /// Audio files without an album (`--by-album`)
+    pub album: String,
+}
+
+impl Default for FallbackFolders {
+    fn default() -> Self {
+        FallbackFolders {
+            camera: "Unknown Camera".to_string(),
+            artist: "Unknown Artist".to_string(),
+            album: "Unknown Album".to_string(),
+        }
+    }
+}
+
+/// Size and count limits for one file category
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Threshold {
+    /// Largest total size in bytes; accepts sizes like "10GB" in the config
+    #[serde(
+        default,
+        deserialize_with = "deserialize_size",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub max_size: Option<u64>,
+
+    /// Largest number of files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_count: Option<usize>,
+}
+
+/// Read a size given either as a number of bytes or a string like "500MB"
+fn deserialize_size<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Size {
+        Bytes(u64),
+        Text(String),
+    }
+
+    match Size::deserialize(deserializer)? {
+        Size::Bytes(bytes) => Ok(Some(bytes)),
+        Size::Text(text) => parse_size(&text)
+            .map(Some)
+            .map_err(serde::de::Error::custom),
+    }
+}
+
+/// Placeholders a rule's destination template may use
+pub const RULE_VARIABLES: &[&str] = &["year", "month", "day", "ext"];
+
 /// A custom organization rule
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Rule {
@@ -73,6 +159,14 @@ pub struct Rule {
 }
 
 impl Rule {
+    /// Check that the pattern is a valid glob and the destination only uses known
+    /// placeholders
+    pub fn validate(&self) -> Result<()> {
+        Pattern::new(&self.pattern)
+            .with_context(|| format!("Invalid glob pattern: {}", self.pattern))?;
+        validate_destination(&self.destination)
+    }
+
     /// Check if a filename matches this rule's pattern
     pub fn matches(&self, filename: &str) -> bool {
         Pattern::new(&self.pattern)
@@ -95,6 +189,67 @@ impl Rule {
     }
 }
 
+/// Tell the user (on stderr) that their config, or part of it, is being ignored and why
+pub fn warn_ignored_config(error: &anyhow::Error) {
+    eprintln!(
+        "{} Ignoring invalid config, using defaults: {:#}",
+        "⚠".yellow(),
+        error
+    );
+}
+
+/// Read a config file as a raw TOML table, for merging
+fn read_table(path: &Path) -> Result<toml::Table> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {:?}", path))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse config file: {:?}", path))
+}
+
+/// Merge `overlay` into `base`: tables are merged recursively and any other value
+/// in `overlay` replaces the one in `base`
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Check a destination template for unbalanced braces and unknown placeholders
+pub fn validate_destination(template: &str) -> Result<()> {
+    if template.trim().is_empty() {
+        bail!("Destination cannot be empty");
+    }
+    let mut rest = template;
+    while let Some(open) = rest.find(['{', '}']) {
+        if rest[open..].starts_with('}') {
+            bail!("Unmatched '}}' in destination: {}", template);
+        }
+        let Some(len) = rest[open + 1..].find('}') else {
+            bail!("Unclosed '{{' in destination: {}", template);
+        };
+        let name = &rest[open + 1..open + 1 + len];
+        if !RULE_VARIABLES.contains(&name) {
+            bail!(
+                "Unknown variable {{{}}} in destination (available: {})",
+                name,
+                RULE_VARIABLES
+                    .iter()
+                    .map(|v| format!("{{{}}}", v))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        rest = &rest[open + len + 2..];
+    }
+    Ok(())
+}
+
 impl Config {
     /// Load config from a TOML file
     pub fn load(path: &Path) -> Result<Self> {
@@ -107,10 +262,60 @@ impl Config {
         Ok(config)
     }
 
+    /// Write config to a TOML file, creating parent directories as needed
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self).context("Failed to serialize config")?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write config file: {:?}", path))?;
+
+        Ok(())
+    }
+
+    /// Append `rules` to the config file at `path` as `[[rules]]` tables, creating
+    /// the file if needed. The rest of the file, comments included, is left as is.
+    pub fn append_rules(path: &Path, rules: &[Rule]) -> Result<()> {
+        #[derive(Serialize)]
+        struct Rules<'a> {
+            rules: &'a [Rule],
+        }
+
+        let mut content = if path.exists() {
+            fs::read_to_string(path)
+                .with_context(|| format!("Failed to read config file: {:?}", path))?
+        } else {
+            String::new()
+        };
+        if !content.is_empty() {
+            if !content.ends_with('\n') {
+                content.push('\n');
+            }
+            content.push('\n');
+        }
+        content.push_str(&toml::to_string(&Rules { rules }).context("Failed to serialize rules")?);
+
+        // e.g. a file with an inline `rules = [...]` can't take more [[rules]] tables
+        toml::from_str::<Config>(&content)
+            .with_context(|| format!("Failed to add rules to config file: {:?}", path))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content).with_context(|| format!("Failed to write config file: {:?}", path))
+    }
+
+    /// Default config location (~/.neat/config.toml), whether or not it exists
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".neat").join("config.toml"))
+    }
+
     /// Load config from default location (~/.neat/config.toml)
     pub fn load_default() -> Result<Option<Self>> {
-        if let Some(home) = dirs::home_dir() {
-            let config_path = home.join(".neat").join("config.toml");
+        if let Some(config_path) = Self::default_path() {
             if config_path.exists() {
                 return Ok(Some(Self::load(&config_path)?));
             }
@@ -118,23 +323,66 @@ impl Config {
         Ok(None)
     }
 
+    /// Load config from the default location, reporting a config that can't be read
+    /// or parsed and carrying on without it (for commands that only need parts of it)
+    pub fn load_default_or_warn() -> Option<Self> {
+        // Several parts of one command may ask; the problem is reported once
+        static WARNED: std::sync::Once = std::sync::Once::new();
+        Self::load_default().unwrap_or_else(|e| {
+            WARNED.call_once(|| warn_ignored_config(&e));
+            None
+        })
+    }
+
+    /// Load `<dir>/.neat.toml` merged over the global config (None if `dir` has none).
+    /// Local settings replace global ones key by key. Only the local file's rules are
+    /// kept, so a `.neat.toml` without rules doesn't start routing by global ones.
+    pub fn load_local(dir: &Path) -> Result<Option<Self>> {
+        let local_path = dir.join(LOCAL_CONFIG_FILE);
+        if !local_path.is_file() {
+            return Ok(None);
+        }
+
+        let mut merged = match Self::default_path().filter(|path| path.exists()) {
+            Some(global_path) => read_table(&global_path)?,
+            None => toml::Table::new(),
+        };
+        merged.remove("rules");
+        merge_tables(&mut merged, read_table(&local_path)?);
+
+        let config = toml::Value::Table(merged)
+            .try_into()
+            .with_context(|| format!("Failed to parse config file: {:?}", local_path))?;
+        Ok(Some(config))
+    }
+
     /// Get rules sorted by priority (highest first)
     pub fn get_sorted_rules(&self) -> Vec<&Rule> {
         let mut rules: Vec<_> = self.rules.iter().collect();
-        rules.sort_by(|a, b| b.priority.cmp(&a.priority));
+        rules.sort_by_key(|r| std::cmp::Reverse(r.priority));
         rules
     }
 
     /// Find matching rule for a filename
     pub fn find_matching_rule(&self, filename: &str) -> Option<&Rule> {
-        self.get_sorted_rules()
+        let rule = self
+            .get_sorted_rules()
             .into_iter()
-            .find(|rule| rule.matches(filename))
+            .find(|rule| rule.matches(filename));
+        if let Some(rule) = rule {
+            log::debug!(
+                "rule matched file={:?} rule={:?} pattern={:?}",
+                filename,
+                rule.name,
+                rule.pattern
+            );
+        }
+        rule
     }
 
-    /// Create a sample config file
-    pub fn create_sample(path: &Path) -> Result<()> {
-        let sample = Config {
+    /// The sample config written by `config init`
+    pub fn sample() -> Self {
+        Config {
             rules: vec![
                 Rule {
                     name: "Invoices".to_string(),
@@ -159,19 +407,24 @@ impl Config {
                 },
             ],
             settings: Settings::default(),
-        };
-
-        let content =
-            toml::to_string_pretty(&sample).context("Failed to serialize sample config")?;
-
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
+            thresholds: BTreeMap::new(),
+            categories: BTreeMap::new(),
         }
+    }
 
-        fs::write(path, content)
-            .with_context(|| format!("Failed to write config file: {:?}", path))?;
+    /// Create a sample config file
+    pub fn create_sample(path: &Path) -> Result<()> {
+        Self::sample().save(path)
+    }
 
-        Ok(())
+    /// The sample rules whose names aren't already taken, to merge in with
+    /// `append_rules`
+    pub fn missing_sample_rules(&self) -> Vec<Rule> {
+        Self::sample()
+            .rules
+            .into_iter()
+            .filter(|rule| self.rules.iter().all(|existing| existing.name != rule.name))
+            .collect()
     }
 }
 
@@ -281,6 +534,8 @@ mod tests {
                 },
             ],
             settings: Settings::default(),
+            thresholds: BTreeMap::new(),
+            categories: BTreeMap::new(),
         };
 
         let sorted = config.get_sorted_rules();
@@ -309,6 +564,8 @@ mod tests {
                 },
             ],
             settings: Settings::default(),
+            thresholds: BTreeMap::new(),
+            categories: BTreeMap::new(),
         };
 
         // PDF should match the PDF rule (higher priority)
@@ -333,12 +590,74 @@ mod tests {
                 post_action: None,
             }],
             settings: Settings::default(),
+            thresholds: BTreeMap::new(),
+            categories: BTreeMap::new(),
         };
 
         let result = config.find_matching_rule("image.png");
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_save_round_trips() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("nested").join("config.toml");
+
+        let mut config = Config::sample();
+        config.settings.include_hidden = true;
+        config
+            .categories
+            .insert("Comics".to_string(), vec!["cbz".to_string()]);
+        config.save(&path).unwrap();
+
+        let loaded = Config::load(&path).unwrap();
+        assert_eq!(loaded.rules.len(), config.rules.len());
+        assert_eq!(loaded.rules[0].name, "Invoices");
+        assert_eq!(loaded.rules[0].post_action, config.rules[0].post_action);
+        assert!(loaded.settings.include_hidden);
+        assert_eq!(loaded.categories["Comics"], vec!["cbz".to_string()]);
+    }
+
+    #[test]
+    fn test_missing_sample_rules_skips_existing_names() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let mut config = Config {
+            rules: vec![Rule {
+                name: "Invoices".to_string(),
+                pattern: "bill-*.pdf".to_string(),
+                destination: "Bills".to_string(),
+                priority: 3,
+                post_action: None,
+            }],
+            ..Default::default()
+        };
+
+        config.save(&path).unwrap();
+
+        let added = config.missing_sample_rules();
+        let names: Vec<_> = added.iter().map(|rule| rule.name.as_str()).collect();
+        assert_eq!(names, vec!["Screenshots", "Downloads by Month"]);
+        Config::append_rules(&path, &added).unwrap();
+        config = Config::load(&path).unwrap();
+        assert_eq!(config.rules.len(), 3);
+        // The user's own rule keeps its settings
+        assert_eq!(config.rules[0].pattern, "bill-*.pdf");
+
+        // Merging again adds nothing
+        assert!(config.missing_sample_rules().is_empty());
+    }
+
+    #[test]
+    fn test_validate_destination() {
+        assert!(validate_destination("Documents/{year}/{month}").is_ok());
+        assert!(validate_destination("Files/{ext}").is_ok());
+        assert!(validate_destination("Archive/{decade}").is_err());
+        assert!(validate_destination("Archive/{year").is_err());
+        assert!(validate_destination("Archive/year}").is_err());
+        assert!(validate_destination("  ").is_err());
+    }
+
     #[test]
     fn test_settings_default() {
         let settings = Settings::default();
@@ -346,4 +665,98 @@ mod tests {
         assert!(!settings.follow_symlinks);
         assert_eq!(settings.default_organize_mode, "by-type");
     }
+
+    #[test]
+    fn test_fallback_folders_override() {
+        let config: Config = toml::from_str(
+            r#"
+            [settings.fallback_folders]
+            camera = "Appareil inconnu"
+            "#,
+        )
+        .unwrap();
+
+        let folders = config.settings.fallback_folders;
+        assert_eq!(folders.camera, "Appareil inconnu");
+        assert_eq!(folders.artist, FallbackFolders::default().artist);
+        assert_eq!(folders.album, FallbackFolders::default().album);
+    }
+
+    #[test]
+    fn test_thresholds_parse_sizes() {
+        let config: Config = toml::from_str(
+            r#"
+            [thresholds.Videos]
+            max_size = "10GB"
+
+            [thresholds.Images]
+            max_size = 2048
+            max_count = 500
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.thresholds["Videos"],
+            Threshold {
+                max_size: Some(10 * 1024 * 1024 * 1024),
+                max_count: None,
+            }
+        );
+        assert_eq!(config.thresholds["Images"].max_size, Some(2048));
+        assert_eq!(config.thresholds["Images"].max_count, Some(500));
+
+        assert!(toml::from_str::<Config>("[thresholds.Videos]\nmax_size = \"lots\"").is_err());
+    }
+
+    #[test]
+    fn test_merge_tables_local_wins() {
+        // Arrays such as `rules` are replaced whole rather than combined
+        let mut global: toml::Table = toml::from_str(
+            r#"
+            [[rules]]
+            name = "Reports"
+            pattern = "*.pdf"
+            destination = "Global"
+
+            [[rules]]
+            name = "Archives"
+            pattern = "*.zip"
+            destination = "Archives"
+
+            [settings]
+            default_organize_mode = "by-date"
+
+            [settings.fallback_folders]
+            camera = "No Camera"
+            artist = "No Artist"
+            "#,
+        )
+        .unwrap();
+        let local: toml::Table = toml::from_str(
+            r#"
+            [[rules]]
+            name = "Reports"
+            pattern = "*.pdf"
+            destination = "Projects/Reports"
+
+            [settings.fallback_folders]
+            camera = "Sans appareil"
+            "#,
+        )
+        .unwrap();
+
+        merge_tables(&mut global, local);
+        let config: Config = toml::Value::Table(global).try_into().unwrap();
+
+        let rules: Vec<_> = config
+            .rules
+            .iter()
+            .map(|r| (r.name.as_str(), r.destination.as_str()))
+            .collect();
+        assert_eq!(rules, vec![("Reports", "Projects/Reports")]);
+        assert_eq!(config.settings.default_organize_mode, "by-date");
+        assert_eq!(config.settings.fallback_folders.camera, "Sans appareil");
+        assert_eq!(config.settings.fallback_folders.artist, "No Artist");
+    }
 }
@@ -0,0 +1,96 @@
+//! Reading entries of zip archives, so loose files can be matched against them
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use xxhash_rust::xxh3::Xxh3;
+use zip::ZipArchive;
+
+use crate::scanner::FileInfo;
+
+/// A file stored inside an archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    /// The archive on disk
+    pub archive: PathBuf,
+    /// Path of the entry inside the archive
+    pub name: String,
+    /// Uncompressed size in bytes
+    pub size: u64,
+}
+
+/// Whether neat can look inside this file
+pub fn is_archive_supported(file: &FileInfo) -> bool {
+    file.extension.as_deref() == Some("zip")
+}
+
+/// List the files (not folders) stored in a zip archive
+pub fn zip_entries(path: &Path) -> Result<Vec<ArchiveEntry>> {
+    let mut archive = open_zip(path)?;
+    let mut entries = Vec::new();
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i)?;
+        if entry.is_file() {
+            entries.push(ArchiveEntry {
+                archive: path.to_path_buf(),
+                name: entry.name().to_string(),
+                size: entry.size(),
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// Hash the uncompressed contents of the named entries of one archive, in order
+pub fn hash_zip_entries(path: &Path, names: &[&str]) -> Result<Vec<u64>> {
+    let mut archive = open_zip(path)?;
+    names
+        .iter()
+        .map(|name| {
+            let entry = archive
+                .by_name(name)
+                .with_context(|| format!("Failed to read {} in {}", name, path.display()))?;
+            hash_reader(entry)
+        })
+        .collect()
+}
+
+/// Give `read` the uncompressed contents of one entry of an archive
+pub fn read_zip_entry<T>(
+    path: &Path,
+    name: &str,
+    read: impl FnOnce(&mut dyn Read) -> Result<T>,
+) -> Result<T> {
+    let mut archive = open_zip(path)?;
+    let mut entry = archive
+        .by_name(name)
+        .with_context(|| format!("Failed to read {} in {}", name, path.display()))?;
+    read(&mut entry)
+}
+
+/// Hash a file on disk the same way as archive entries
+pub fn hash_file(path: &Path) -> Result<u64> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    hash_reader(file)
+}
+
+fn open_zip(path: &Path) -> Result<ZipArchive<BufReader<File>>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    ZipArchive::new(BufReader::new(file))
+        .with_context(|| format!("Not a readable zip archive: {}", path.display()))
+}
+
+fn hash_reader<R: Read>(mut reader: R) -> Result<u64> {
+    let mut hasher = Xxh3::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(hasher.digest())
+}
@@ -1,6 +1,11 @@
 //! File classifier - categorize files by extension
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+use anyhow::{bail, Result};
+
+use crate::config::{warn_ignored_config, Config};
 
 /// File category
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -31,7 +36,6 @@ impl Category {
     }
 
     /// Get all categories
-    #[allow(dead_code)]
     pub fn all() -> &'static [Category] {
         &[
             Category::Images,
@@ -47,10 +51,22 @@ impl Category {
 }
 
 /// Classifier for file extensions
+#[derive(Debug, Clone)]
 pub struct Classifier {
     extension_map: HashMap<String, Category>,
+    /// Category scheme from the config, replacing the built-in categories for naming
+    custom: Option<CustomCategories>,
+}
+
+/// User-defined category names and the extensions in each
+#[derive(Debug, Clone)]
+struct CustomCategories {
+    names: Vec<String>,
+    by_extension: HashMap<String, usize>,
 }
 
+static CONFIGURED: OnceLock<Classifier> = OnceLock::new();
+
 impl Default for Classifier {
     fn default() -> Self {
         Self::new()
@@ -110,7 +126,63 @@ impl Classifier {
             map.insert(ext.to_string(), Category::Data);
         }
 
-        Classifier { extension_map: map }
+        Classifier {
+            extension_map: map,
+            custom: None,
+        }
+    }
+
+    /// Build a classifier whose categories are exactly `categories` (name to
+    /// extensions); files with other extensions are `Other`.
+    /// `classify` keeps returning the built-in categories.
+    pub fn from_categories(categories: &BTreeMap<String, Vec<String>>) -> Result<Self> {
+        let mut names = Vec::new();
+        let mut by_extension = HashMap::new();
+
+        for (name, extensions) in categories {
+            if name.trim().is_empty() || name.contains(['/', '\\']) {
+                bail!("Invalid category name '{}'", name);
+            }
+            for ext in extensions {
+                let ext = ext.trim().trim_start_matches('.').to_lowercase();
+                if let Some(&other) = by_extension.get(&ext) {
+                    bail!(
+                        "Extension '{}' is listed in both '{}' and '{}' categories",
+                        ext,
+                        names[other],
+                        name
+                    );
+                }
+                by_extension.insert(ext, names.len());
+            }
+            names.push(name.clone());
+        }
+
+        Ok(Classifier {
+            custom: Some(CustomCategories {
+                names,
+                by_extension,
+            }),
+            ..Self::new()
+        })
+    }
+
+    /// The classifier for the config's `[categories]`, or the built-in one. Loaded on
+    /// first use; an unreadable config or invalid categories are reported and the
+    /// built-in categories are used instead.
+    pub fn configured() -> &'static Classifier {
+        CONFIGURED.get_or_init(|| {
+            let categories = Config::load_default_or_warn()
+                .map(|config| config.categories)
+                .unwrap_or_default();
+            if categories.is_empty() {
+                return Classifier::new();
+            }
+            Classifier::from_categories(&categories).unwrap_or_else(|e| {
+                warn_ignored_config(&e);
+                Classifier::new()
+            })
+        })
     }
 
     /// Classify a file by its extension
@@ -125,6 +197,32 @@ impl Classifier {
         }
     }
 
+    /// Name of the category (and folder) a file with this extension belongs to
+    pub fn category_name(&self, extension: Option<&str>) -> &str {
+        match &self.custom {
+            Some(custom) => extension
+                .and_then(|ext| custom.by_extension.get(&ext.to_lowercase()))
+                .map(|&i| custom.names[i].as_str())
+                .unwrap_or(Category::Other.folder_name()),
+            None => self.classify(extension).folder_name(),
+        }
+    }
+
+    /// Every category name files can be sorted into, including `Other`
+    pub fn category_names(&self) -> Vec<&str> {
+        match &self.custom {
+            Some(custom) => {
+                let mut names: Vec<&str> = custom.names.iter().map(String::as_str).collect();
+                let other = Category::Other.folder_name();
+                if !names.contains(&other) {
+                    names.push(other);
+                }
+                names
+            }
+            None => Category::all().iter().map(|c| c.folder_name()).collect(),
+        }
+    }
+
     /// Get the category for a file extension
     #[allow(dead_code)]
     pub fn get_category(&self, extension: &str) -> Category {
@@ -223,6 +321,43 @@ mod tests {
         assert_eq!(classifier.classify(Some("JSON")), Category::Data);
     }
 
+    #[test]
+    fn test_custom_categories_replace_builtin() {
+        let mut categories = BTreeMap::new();
+        categories.insert(
+            "Work".to_string(),
+            vec!["docx".to_string(), ".XLSX".to_string(), "pdf".to_string()],
+        );
+        categories.insert(
+            "Media".to_string(),
+            vec!["jpg".to_string(), "mp4".to_string(), "mp3".to_string()],
+        );
+
+        let classifier = Classifier::from_categories(&categories).unwrap();
+        assert_eq!(classifier.category_name(Some("PDF")), "Work");
+        assert_eq!(classifier.category_name(Some("xlsx")), "Work");
+        assert_eq!(classifier.category_name(Some("mp4")), "Media");
+        // Built-in categories no longer apply
+        assert_eq!(classifier.category_name(Some("png")), "Other");
+        assert_eq!(classifier.category_name(None), "Other");
+        assert_eq!(classifier.category_names(), ["Media", "Work", "Other"]);
+    }
+
+    #[test]
+    fn test_custom_categories_reject_shared_extension() {
+        let mut categories = BTreeMap::new();
+        categories.insert("Personal".to_string(), vec!["pdf".to_string()]);
+        categories.insert("Work".to_string(), vec!["pdf".to_string()]);
+        assert!(Classifier::from_categories(&categories).is_err());
+    }
+
+    #[test]
+    fn test_builtin_category_names() {
+        let classifier = Classifier::new();
+        assert_eq!(classifier.category_name(Some("jpg")), "Images");
+        assert_eq!(classifier.category_names().len(), 8);
+    }
+
     #[test]
     fn test_category_folder_names() {
         assert_eq!(Category::Images.folder_name(), "Images");
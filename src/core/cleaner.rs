@@ -1,18 +1,22 @@
 //! Clean old files from directories
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 use anyhow::{bail, Result};
 use colored::*;
-use dialoguer::Confirm;
-use indicatif::{ProgressBar, ProgressStyle};
+use dialoguer::{Confirm, Input};
+use walkdir::WalkDir;
 
+use crate::duplicates::find_duplicates;
 use crate::logger::Logger;
-use crate::scanner::{format_size, FileInfo};
+use crate::progress::progress_bar;
+use crate::scanner::{FileInfo, SizeUnits};
+use crate::throttle::Throttle;
 
-/// Parse a duration string (e.g., "30d", "7d", "1w")
+/// Parse a duration string (e.g., "30d", "7d", "1w", "12h")
 pub fn parse_duration(s: &str) -> Result<Duration> {
     let s = s.trim().to_lowercase();
 
@@ -56,8 +60,118 @@ pub fn find_old_files(files: &[FileInfo], max_age: Duration) -> Vec<&FileInfo> {
     files.iter().filter(|f| f.modified < cutoff).collect()
 }
 
+/// Size thresholds for `clean --larger-than` / `--smaller-than` (both exclusive)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SizeLimits {
+    pub larger_than: Option<u64>,
+    pub smaller_than: Option<u64>,
+}
+
+impl SizeLimits {
+    /// Whether any threshold is set
+    pub fn is_set(&self) -> bool {
+        self.larger_than.is_some() || self.smaller_than.is_some()
+    }
+
+    /// Whether `file` is over `larger_than` and under `smaller_than` (when set)
+    pub fn matches(&self, file: &FileInfo) -> bool {
+        self.larger_than.is_none_or(|min| file.size > min)
+            && self.smaller_than.is_none_or(|max| file.size < max)
+    }
+
+    /// Human-readable thresholds, e.g. "larger than 1.00 GB"
+    pub fn describe(&self, units: SizeUnits) -> String {
+        let mut parts = Vec::new();
+        if let Some(min) = self.larger_than {
+            parts.push(format!("larger than {}", units.format(min)));
+        }
+        if let Some(max) = self.smaller_than {
+            parts.push(format!("smaller than {}", units.format(max)));
+        }
+        parts.join(" and ")
+    }
+}
+
+/// Files matching the `limits` size thresholds
+pub fn find_by_size(files: &[FileInfo], limits: SizeLimits) -> Vec<&FileInfo> {
+    files.iter().filter(|f| limits.matches(f)).collect()
+}
+
+/// Everything but the `keep` most recently modified files, per folder when `per_dir`
+/// is set (otherwise across all of `files`). Ties on mtime are broken by path.
+pub fn find_all_but_newest(files: &[FileInfo], keep: usize, per_dir: bool) -> Vec<&FileInfo> {
+    let mut groups: BTreeMap<Option<&Path>, Vec<&FileInfo>> = BTreeMap::new();
+    for file in files {
+        let key = if per_dir { file.path.parent() } else { None };
+        groups.entry(key).or_default().push(file);
+    }
+
+    groups
+        .into_values()
+        .flat_map(|mut group| {
+            group.sort_by(|a, b| {
+                b.modified
+                    .cmp(&a.modified)
+                    .then_with(|| a.path.cmp(&b.path))
+            });
+            group.into_iter().skip(keep)
+        })
+        .collect()
+}
+
+/// Old files and redundant duplicate copies found in a single scan
+pub struct OldAndDuplicates<'a> {
+    pub old: Vec<&'a FileInfo>,
+    /// Extra copies to remove; one copy of every duplicate group is kept
+    pub duplicates: Vec<&'a FileInfo>,
+}
+
+impl OldAndDuplicates<'_> {
+    /// Every file to remove (the two lists never overlap)
+    pub fn all(&self) -> Vec<&FileInfo> {
+        self.old.iter().chain(&self.duplicates).copied().collect()
+    }
+}
+
+/// Find old files and duplicate copies in the same scanned files.
+/// The copy kept from each duplicate group is one that isn't old, when there is one.
+pub fn find_old_and_duplicates(
+    files: &[FileInfo],
+    max_age: Option<Duration>,
+) -> Result<OldAndDuplicates<'_>> {
+    let old = max_age
+        .map(|age| find_old_files(files, age))
+        .unwrap_or_default();
+    let old_paths: HashSet<&Path> = old.iter().map(|f| f.path.as_path()).collect();
+    let by_path: HashMap<&Path, &FileInfo> = files.iter().map(|f| (f.path.as_path(), f)).collect();
+
+    let mut duplicates = Vec::new();
+    for group in find_duplicates(files)? {
+        let keep = group
+            .files
+            .iter()
+            .position(|f| !old_paths.contains(f.path.as_path()))
+            .unwrap_or(0);
+        for (i, copy) in group.files.iter().enumerate() {
+            if i == keep || old_paths.contains(copy.path.as_path()) {
+                continue;
+            }
+            if let Some(file) = by_path.get(copy.path.as_path()) {
+                duplicates.push(*file);
+            }
+        }
+    }
+
+    Ok(OldAndDuplicates { old, duplicates })
+}
+
+/// Find zero-byte files
+pub fn find_empty_files(files: &[FileInfo]) -> Vec<&FileInfo> {
+    files.iter().filter(|f| f.size == 0).collect()
+}
+
 /// Preview files to be cleaned
-pub fn preview_clean(files: &[&FileInfo], duration_str: &str) {
+pub fn preview_clean(files: &[&FileInfo], duration_str: &str, units: SizeUnits) {
     if files.is_empty() {
         println!(
             "{} No files older than {} found.",
@@ -67,8 +181,6 @@ pub fn preview_clean(files: &[&FileInfo], duration_str: &str) {
         return;
     }
 
-    let total_size: u64 = files.iter().map(|f| f.size).sum();
-
     println!(
         "\n{}",
         format!("Files older than {}:", duration_str)
@@ -93,17 +205,32 @@ pub fn preview_clean(files: &[&FileInfo], duration_str: &str) {
             "  {} {} ({}, {})",
             "○".yellow(),
             file.path.display(),
-            format_size(file.size).dimmed(),
+            units.format(file.size).dimmed(),
             age.dimmed()
         );
     }
 
     println!("\n{}", "─".repeat(60));
+    print_clean_summary(files, duration_str, units);
+}
+
+/// Print only the totals for files to be cleaned (no per-file listing)
+pub fn print_clean_summary(files: &[&FileInfo], duration_str: &str, units: SizeUnits) {
+    if files.is_empty() {
+        println!(
+            "{} No files older than {} found.",
+            "✓".green(),
+            duration_str.cyan()
+        );
+        return;
+    }
+
+    let total_size: u64 = files.iter().map(|f| f.size).sum();
     println!(
         "\n{}: {} files ({}) would be deleted",
         "Summary".bold(),
         files.len().to_string().yellow(),
-        format_size(total_size).red()
+        units.format(total_size).red()
     );
     println!(
         "\n{} Use {} to delete these files.",
@@ -112,8 +239,40 @@ pub fn preview_clean(files: &[&FileInfo], duration_str: &str) {
     );
 }
 
-/// Execute file deletion with confirmation
-pub fn execute_clean(files: &[&FileInfo], force: bool, use_trash: bool) -> Result<(usize, u64)> {
+/// Word that must be typed to go ahead with a clean over `--confirm-threshold`
+const CONFIRM_WORD: &str = "DELETE";
+
+/// When `total` bytes is over `threshold`, have the user type `CONFIRM_WORD` via
+/// `ask`. True if no extra confirmation was needed or the word was typed exactly.
+pub fn confirm_large_clean(
+    total: u64,
+    threshold: Option<u64>,
+    action: &str,
+    units: SizeUnits,
+    ask: &mut dyn FnMut(&str) -> Result<String>,
+) -> Result<bool> {
+    if threshold.is_none_or(|limit| total <= limit) {
+        return Ok(true);
+    }
+    let answer = ask(&format!(
+        "You're about to {} {}. Type {} to proceed",
+        action.to_lowercase(),
+        units.format(total),
+        CONFIRM_WORD
+    ))?;
+    Ok(answer.trim() == CONFIRM_WORD)
+}
+
+/// Execute file deletion with confirmation, at most `throttle` deletions per second.
+/// Removing more than `confirm_threshold` bytes also needs a typed confirmation.
+pub fn execute_clean(
+    files: &[&FileInfo],
+    force: bool,
+    use_trash: bool,
+    throttle: Option<f64>,
+    confirm_threshold: Option<u64>,
+    units: SizeUnits,
+) -> Result<(usize, u64)> {
     if files.is_empty() {
         return Ok((0, 0));
     }
@@ -127,7 +286,7 @@ pub fn execute_clean(files: &[&FileInfo], force: bool, use_trash: bool) -> Resul
                 "{} {} files ({})?",
                 action,
                 files.len(),
-                format_size(files.iter().map(|f| f.size).sum())
+                units.format(files.iter().map(|f| f.size).sum())
             ))
             .default(false)
             .interact()?;
@@ -136,27 +295,41 @@ pub fn execute_clean(files: &[&FileInfo], force: bool, use_trash: bool) -> Resul
             println!("{}", "Operation cancelled.".yellow());
             return Ok((0, 0));
         }
+
+        let total = files.iter().map(|f| f.size).sum();
+        let mut ask = |prompt: &str| -> Result<String> {
+            Ok(Input::<String>::new()
+                .with_prompt(prompt)
+                .allow_empty(true)
+                .interact_text()?)
+        };
+        if !confirm_large_clean(total, confirm_threshold, action, units, &mut ask)? {
+            println!("{}", "Operation cancelled.".yellow());
+            return Ok((0, 0));
+        }
     }
 
-    let pb = ProgressBar::new(files.len() as u64);
-    let template = if use_trash {
-        "{spinner:.green} Moving to trash [{bar:40.yellow/white}] {pos}/{len}"
+    let (label, template) = if use_trash {
+        (
+            "Moving to trash",
+            "{spinner:.green} Moving to trash [{bar:40.yellow/white}] {pos}/{len}",
+        )
     } else {
-        "{spinner:.green} Deleting [{bar:40.red/white}] {pos}/{len}"
+        (
+            "Deleting",
+            "{spinner:.green} Deleting [{bar:40.red/white}] {pos}/{len}",
+        )
     };
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template(template)
-            .unwrap()
-            .progress_chars("█▓░"),
-    );
+    let pb = progress_bar(files.len() as u64, label, template);
 
     let mut deleted = 0;
     let mut total_size = 0u64;
     let mut logger = Logger::new(if use_trash { "clean --trash" } else { "clean" });
+    let mut throttle = Throttle::new(throttle);
 
     for file in files {
         pb.inc(1);
+        throttle.wait();
         let result = if use_trash {
             trash::delete(&file.path).map_err(|e| anyhow::anyhow!("{}", e))
         } else {
@@ -165,11 +338,26 @@ pub fn execute_clean(files: &[&FileInfo], force: bool, use_trash: bool) -> Resul
 
         match result {
             Ok(_) => {
+                log::info!(
+                    "{} path={:?} size={}",
+                    if use_trash { "trashed" } else { "deleted" },
+                    file.path,
+                    file.size
+                );
                 deleted += 1;
                 total_size += file.size;
-                logger.log_delete(file.path.clone());
+                if use_trash {
+                    logger.log_trash(file.path.clone());
+                } else {
+                    logger.log_delete(file.path.clone());
+                }
             }
             Err(e) => {
+                log::warn!(
+                    "remove failed path={:?} error={:?}",
+                    file.path,
+                    e.to_string()
+                );
                 eprintln!(
                     "{} Failed to {} {}: {}",
                     "✗".red(),
@@ -194,54 +382,155 @@ pub fn execute_clean(files: &[&FileInfo], force: bool, use_trash: bool) -> Resul
         "✓".green(),
         action_past,
         deleted.to_string().green(),
-        format_size(total_size).green()
+        units.format(total_size).green()
     );
 
     Ok((deleted, total_size))
 }
 
+/// OS-generated files that `clean --empty-folders --remove-junk` doesn't count as
+/// content: a folder holding only these is removed along with them
+pub const JUNK_FILES: [&str; 3] = [".DS_Store", "Thumbs.db", "desktop.ini"];
+
 /// Find empty directories
-pub fn find_empty_dirs(path: &Path) -> Result<Vec<std::path::PathBuf>> {
+///
+/// `max_depth` bounds the recursion the same way `ScanOptions::max_depth` bounds
+/// file scanning (None = unlimited). Directories below the limit are not inspected,
+/// so their parents are never reported as empty.
+pub fn find_empty_dirs(path: &Path, max_depth: Option<usize>) -> Result<Vec<std::path::PathBuf>> {
+    find_empty_dirs_ignoring(path, max_depth, &[])
+}
+
+/// Find directories that are empty apart from files named in `junk`
+/// (matched case-insensitively). Remove them with `remove_empty_dir`.
+pub fn find_empty_dirs_ignoring(
+    path: &Path,
+    max_depth: Option<usize>,
+    junk: &[&str],
+) -> Result<Vec<std::path::PathBuf>> {
     let mut empty_dirs = Vec::new();
-    find_empty_dirs_recursive(path, &mut empty_dirs)?;
+    find_empty_dirs_recursive(path, 0, max_depth, junk, &mut empty_dirs)?;
     Ok(empty_dirs)
 }
 
 fn find_empty_dirs_recursive(
     path: &Path,
+    depth: usize,
+    max_depth: Option<usize>,
+    junk: &[&str],
     empty_dirs: &mut Vec<std::path::PathBuf>,
 ) -> Result<bool> {
     if !path.is_dir() {
         return Ok(false);
     }
 
-    let entries: Vec<_> = fs::read_dir(path)?.filter_map(|e| e.ok()).collect();
-
-    if entries.is_empty() {
-        empty_dirs.push(path.to_path_buf());
-        return Ok(true);
+    if max_depth.is_some_and(|max| depth > max) {
+        return Ok(false);
     }
 
-    let entries_count = entries.len();
+    let entries: Vec<_> = fs::read_dir(path)?.filter_map(|e| e.ok()).collect();
+
     let mut all_empty = true;
     for entry in &entries {
         let entry_path = entry.path();
         if entry_path.is_dir() {
-            if !find_empty_dirs_recursive(&entry_path, empty_dirs)? {
+            if !find_empty_dirs_recursive(&entry_path, depth + 1, max_depth, junk, empty_dirs)? {
                 all_empty = false;
             }
-        } else {
+        } else if !is_junk(&entry_path, junk) {
             all_empty = false;
         }
     }
 
-    if all_empty && entries_count > 0 {
+    if all_empty {
         empty_dirs.push(path.to_path_buf());
     }
 
     Ok(all_empty)
 }
 
+/// Whether the file name of `path` is one of `junk` (case-insensitive)
+fn is_junk(path: &Path, junk: &[&str]) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    junk.iter().any(|j| name.eq_ignore_ascii_case(j))
+}
+
+/// Remove a directory found by `find_empty_dirs_ignoring`, deleting the `junk`
+/// files in it first
+pub fn remove_empty_dir(dir: &Path, junk: &[&str]) -> std::io::Result<()> {
+    if !junk.is_empty() {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_file() && is_junk(&path, junk) {
+                fs::remove_file(&path)?;
+            }
+        }
+    }
+    fs::remove_dir(dir)
+}
+
+/// Find symlinks whose target no longer exists
+///
+/// Links are never followed, and hidden entries are included since dead
+/// dotfile links are the most common kind.
+pub fn find_broken_symlinks(path: &Path, max_depth: Option<usize>) -> Vec<PathBuf> {
+    let mut walker = WalkDir::new(path).follow_links(false);
+    if let Some(depth) = max_depth {
+        walker = walker.max_depth(depth);
+    }
+
+    walker
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path_is_symlink() && !entry.path().exists())
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// Remove broken symlinks with confirmation
+pub fn execute_remove_symlinks(links: &[PathBuf], force: bool) -> Result<usize> {
+    if links.is_empty() {
+        return Ok(0);
+    }
+
+    if !force {
+        let confirmed = Confirm::new()
+            .with_prompt(format!("Remove {} broken symlinks?", links.len()))
+            .default(false)
+            .interact()?;
+
+        if !confirmed {
+            println!("{}", "Operation cancelled.".yellow());
+            return Ok(0);
+        }
+    }
+
+    let mut removed = 0;
+    let mut logger = Logger::new("clean --broken-symlinks");
+
+    for link in links {
+        match fs::remove_file(link) {
+            Ok(_) => {
+                removed += 1;
+                logger.log_delete(link.clone());
+            }
+            Err(e) => {
+                eprintln!("{} Failed to remove {}: {}", "✗".red(), link.display(), e);
+            }
+        }
+    }
+
+    logger.save()?;
+
+    println!(
+        "\n{} Removed {} broken symlinks",
+        "✓".green(),
+        removed.to_string().green()
+    );
+
+    Ok(removed)
+}
+
 /// Format age as human-readable string
 fn format_age(duration: Duration) -> String {
     let secs = duration.as_secs();
@@ -261,6 +550,195 @@ mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_confirm_large_clean_requires_typed_word_over_threshold() {
+        let mut prompts = Vec::new();
+        let ask = |answer: &'static str| {
+            move |prompt: &str| -> Result<String> {
+                assert!(prompt.contains("Type DELETE"));
+                Ok(answer.to_string())
+            }
+        };
+        assert!(confirm_large_clean(
+            2000,
+            Some(1000),
+            "Delete",
+            SizeUnits::Binary,
+            &mut ask("DELETE")
+        )
+        .unwrap());
+        assert!(confirm_large_clean(
+            2000,
+            Some(1000),
+            "Delete",
+            SizeUnits::Binary,
+            &mut ask(" DELETE\n")
+        )
+        .unwrap());
+        assert!(!confirm_large_clean(
+            2000,
+            Some(1000),
+            "Delete",
+            SizeUnits::Binary,
+            &mut ask("delete")
+        )
+        .unwrap());
+        assert!(
+            !confirm_large_clean(2000, Some(1000), "Delete", SizeUnits::Binary, &mut ask("y"))
+                .unwrap()
+        );
+
+        // At or under the threshold, or without one, nothing more is asked
+        let mut never = |prompt: &str| -> Result<String> {
+            prompts.push(prompt.to_string());
+            Ok(String::new())
+        };
+        assert!(
+            confirm_large_clean(1000, Some(1000), "Delete", SizeUnits::Binary, &mut never).unwrap()
+        );
+        assert!(confirm_large_clean(5000, None, "Delete", SizeUnits::Binary, &mut never).unwrap());
+        assert!(prompts.is_empty());
+    }
+
+    #[test]
+    fn test_size_limits_describe_in_requested_units() {
+        let limits = SizeLimits {
+            larger_than: Some(1500),
+            smaller_than: None,
+        };
+        assert_eq!(limits.describe(SizeUnits::Binary), "larger than 1.46 KB");
+        assert_eq!(limits.describe(SizeUnits::Si), "larger than 1.50 kB");
+        assert_eq!(limits.describe(SizeUnits::Iec), "larger than 1.46 KiB");
+    }
+
+    #[test]
+    fn test_find_by_size_selects_over_threshold() {
+        use crate::scanner::{scan_directory, ScanOptions};
+
+        let dir = tempdir().unwrap();
+        for (name, size) in [("tiny.tmp", 10), ("small.txt", 1000), ("big.iso", 5000)] {
+            fs::write(dir.path().join(name), vec![0u8; size]).unwrap();
+        }
+        let files = scan_directory(dir.path(), &ScanOptions::default()).unwrap();
+        let names = |selected: Vec<&FileInfo>| {
+            let mut names: Vec<String> = selected.iter().map(|f| f.name.clone()).collect();
+            names.sort();
+            names
+        };
+
+        let larger = SizeLimits {
+            larger_than: Some(1000),
+            ..Default::default()
+        };
+        assert_eq!(names(find_by_size(&files, larger)), ["big.iso"]);
+
+        let smaller = SizeLimits {
+            smaller_than: Some(1000),
+            ..Default::default()
+        };
+        assert_eq!(names(find_by_size(&files, smaller)), ["tiny.tmp"]);
+
+        let between = SizeLimits {
+            larger_than: Some(10),
+            smaller_than: Some(5000),
+        };
+        assert_eq!(names(find_by_size(&files, between)), ["small.txt"]);
+    }
+
+    #[test]
+    fn test_find_all_but_newest_keeps_three() {
+        use crate::scanner::{scan_directory, ScanOptions};
+
+        let dir = tempdir().unwrap();
+        let now = SystemTime::now();
+        for day in 1..=5u64 {
+            let path = dir.path().join(format!("app-{}.log", day));
+            fs::write(&path, "log").unwrap();
+            let mtime = now - Duration::from_secs(day * 86400);
+            filetime::set_file_mtime(&path, filetime::FileTime::from_system_time(mtime)).unwrap();
+        }
+
+        let files = scan_directory(dir.path(), &ScanOptions::default()).unwrap();
+        let mut names: Vec<&str> = find_all_but_newest(&files, 3, true)
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        names.sort();
+
+        assert_eq!(names, vec!["app-4.log", "app-5.log"]);
+    }
+
+    #[test]
+    fn test_find_all_but_newest_per_dir_groups_by_folder() {
+        let dir = tempdir().unwrap();
+        let now = SystemTime::now();
+        for (i, sub) in ["a", "a", "b", "b"].iter().enumerate() {
+            let folder = dir.path().join(sub);
+            fs::create_dir_all(&folder).unwrap();
+            let path = folder.join(format!("{}.log", i));
+            fs::write(&path, "log").unwrap();
+            let mtime = now - Duration::from_secs(i as u64 * 86400);
+            filetime::set_file_mtime(&path, filetime::FileTime::from_system_time(mtime)).unwrap();
+        }
+        let files = crate::scanner::scan_directory(dir.path(), &Default::default()).unwrap();
+
+        // One file kept per folder, versus one kept overall
+        assert_eq!(find_all_but_newest(&files, 1, true).len(), 2);
+        assert_eq!(find_all_but_newest(&files, 1, false).len(), 3);
+        assert!(find_all_but_newest(&files, 5, true).is_empty());
+    }
+
+    #[test]
+    fn test_find_old_and_duplicates_from_one_scan() {
+        use crate::scanner::{scan_directory, ScanOptions};
+
+        let dir = tempdir().unwrap();
+        let old_path = dir.path().join("old.log");
+        fs::write(&old_path, "ancient").unwrap();
+        let two_months_ago = SystemTime::now() - Duration::from_secs(60 * 86400);
+        filetime::set_file_mtime(
+            &old_path,
+            filetime::FileTime::from_system_time(two_months_ago),
+        )
+        .unwrap();
+        fs::write(dir.path().join("photo.jpg"), "same bytes").unwrap();
+        fs::write(dir.path().join("photo copy.jpg"), "same bytes").unwrap();
+
+        let files = scan_directory(dir.path(), &ScanOptions::default()).unwrap();
+        let found = find_old_and_duplicates(&files, Some(Duration::from_secs(30 * 86400))).unwrap();
+
+        assert_eq!(found.old.len(), 1);
+        assert_eq!(found.old[0].name, "old.log");
+        assert_eq!(found.duplicates.len(), 1);
+        assert!(found.duplicates[0].name.starts_with("photo"));
+        assert_eq!(found.all().len(), 2);
+    }
+
+    #[test]
+    fn test_find_old_and_duplicates_keeps_a_recent_copy() {
+        use crate::scanner::{scan_directory, ScanOptions};
+
+        let dir = tempdir().unwrap();
+        let old_copy = dir.path().join("a-old.txt");
+        fs::write(&old_copy, "shared").unwrap();
+        fs::write(dir.path().join("b-new.txt"), "shared").unwrap();
+        fs::write(dir.path().join("c-new.txt"), "shared").unwrap();
+        let two_months_ago = SystemTime::now() - Duration::from_secs(60 * 86400);
+        filetime::set_file_mtime(
+            &old_copy,
+            filetime::FileTime::from_system_time(two_months_ago),
+        )
+        .unwrap();
+
+        let files = scan_directory(dir.path(), &ScanOptions::default()).unwrap();
+        let found = find_old_and_duplicates(&files, Some(Duration::from_secs(30 * 86400))).unwrap();
+
+        // The old copy goes as old, one recent copy survives, the other is a duplicate
+        assert_eq!(found.old.len(), 1);
+        assert_eq!(found.duplicates.len(), 1);
+        assert_ne!(found.duplicates[0].path, old_copy);
+    }
+
     #[test]
     fn test_parse_duration_days() {
         let d = parse_duration("30d").unwrap();
@@ -279,6 +757,13 @@ mod tests {
         assert_eq!(d, Duration::from_secs(24 * 3600));
     }
 
+    #[test]
+    fn test_parse_duration_rejects_ambiguous_units() {
+        // "6m" could mean months; ages for clean are never taken in minutes
+        assert!(parse_duration("6m").is_err());
+        assert!(parse_duration("10s").is_err());
+    }
+
     #[test]
     fn test_parse_duration_no_unit_defaults_to_days() {
         let d = parse_duration("5").unwrap();
@@ -322,6 +807,7 @@ mod tests {
                 path: std::path::PathBuf::from("/tmp/old.txt"),
                 size: 100,
                 extension: Some("txt".to_string()),
+                extension_raw: Some("txt".to_string()),
                 modified: old_time,
                 created: None,
             },
@@ -330,6 +816,7 @@ mod tests {
                 path: std::path::PathBuf::from("/tmp/new.txt"),
                 size: 100,
                 extension: Some("txt".to_string()),
+                extension_raw: Some("txt".to_string()),
                 modified: new_time,
                 created: None,
             },
@@ -340,13 +827,48 @@ mod tests {
         assert_eq!(old_files[0].name, "old.txt");
     }
 
+    #[test]
+    fn test_find_empty_files_selects_only_zero_byte() {
+        let dir = tempdir().unwrap();
+        fs::File::create(dir.path().join("empty.txt")).unwrap();
+        fs::File::create(dir.path().join("also_empty.log")).unwrap();
+        fs::write(dir.path().join("data.txt"), "content").unwrap();
+
+        let files =
+            crate::scanner::scan_directory(dir.path(), &crate::scanner::ScanOptions::default())
+                .unwrap();
+        let mut empty: Vec<_> = find_empty_files(&files)
+            .iter()
+            .map(|f| f.name.clone())
+            .collect();
+        empty.sort();
+
+        assert_eq!(empty, vec!["also_empty.log", "empty.txt"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_broken_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("target.txt");
+        fs::write(&target, "content").unwrap();
+        symlink(&target, dir.path().join("good_link")).unwrap();
+        symlink(dir.path().join("missing.txt"), dir.path().join("dead_link")).unwrap();
+
+        let broken = find_broken_symlinks(dir.path(), None);
+
+        assert_eq!(broken, vec![dir.path().join("dead_link")]);
+    }
+
     #[test]
     fn test_find_empty_dirs_empty_directory() {
         let dir = tempdir().unwrap();
         let empty_dir = dir.path().join("empty");
         fs::create_dir(&empty_dir).unwrap();
 
-        let result = find_empty_dirs(dir.path()).unwrap();
+        let result = find_empty_dirs(dir.path(), None).unwrap();
         assert!(result.contains(&empty_dir));
     }
 
@@ -357,7 +879,7 @@ mod tests {
         fs::create_dir(&non_empty).unwrap();
         fs::write(non_empty.join("file.txt"), "content").unwrap();
 
-        let result = find_empty_dirs(dir.path()).unwrap();
+        let result = find_empty_dirs(dir.path(), None).unwrap();
         assert!(!result.contains(&non_empty));
     }
 
@@ -368,12 +890,56 @@ mod tests {
         let child = parent.join("child");
         fs::create_dir_all(&child).unwrap();
 
-        let result = find_empty_dirs(dir.path()).unwrap();
+        let result = find_empty_dirs(dir.path(), None).unwrap();
         // Both child and parent should be marked empty
         assert!(result.contains(&child));
         assert!(result.contains(&parent));
     }
 
+    #[test]
+    fn test_find_empty_dirs_junk_only_folder() {
+        let dir = tempdir().unwrap();
+        let junk_only = dir.path().join("old-photos");
+        let with_file = dir.path().join("keep");
+        fs::create_dir_all(junk_only.join("nested")).unwrap();
+        fs::create_dir(&with_file).unwrap();
+        fs::write(junk_only.join(".DS_Store"), "junk").unwrap();
+        fs::write(junk_only.join("nested").join("Thumbs.db"), "junk").unwrap();
+        fs::write(with_file.join(".DS_Store"), "junk").unwrap();
+        fs::write(with_file.join("photo.jpg"), "photo").unwrap();
+
+        // Without a junk set, .DS_Store counts as content
+        let plain = find_empty_dirs(dir.path(), None).unwrap();
+        assert!(!plain.contains(&junk_only));
+
+        let result = find_empty_dirs_ignoring(dir.path(), None, &JUNK_FILES).unwrap();
+        assert!(result.contains(&junk_only.join("nested")));
+        assert!(result.contains(&junk_only));
+        assert!(!result.contains(&with_file));
+
+        // Children come first, so each folder is down to junk when removed
+        for empty in result.iter().filter(|d| *d != dir.path()) {
+            remove_empty_dir(empty, &JUNK_FILES).unwrap();
+        }
+        assert!(!junk_only.exists());
+        assert!(with_file.join(".DS_Store").exists());
+    }
+
+    #[test]
+    fn test_find_empty_dirs_respects_max_depth() {
+        let dir = tempdir().unwrap();
+        let top = dir.path().join("top");
+        let nested = dir.path().join("a").join("b");
+        fs::create_dir(&top).unwrap();
+        fs::create_dir_all(&nested).unwrap();
+
+        let result = find_empty_dirs(dir.path(), Some(1)).unwrap();
+        assert!(result.contains(&top));
+        // "a/b" is below the limit, so neither it nor "a" is reported
+        assert!(!result.contains(&nested));
+        assert!(!result.contains(&dir.path().join("a")));
+    }
+
     #[test]
     fn test_format_age_minutes() {
         let age = format_age(Duration::from_secs(1800)); // 30 minutes
@@ -458,4 +1024,3 @@ mod tests {
         assert_eq!(age, "1d ago");
     }
 }
-
@@ -1,19 +1,22 @@
 //! Duplicate detection using direct byte comparison (faster than hashing)
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::{BufReader, Read};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use colored::*;
-use indicatif::{ProgressBar, ProgressStyle};
 use memmap2::Mmap;
 use rayon::prelude::*;
 use xxhash_rust::xxh3::xxh3_64;
 
-use crate::scanner::{format_size, FileInfo};
+use crate::archive::{
+    hash_file, hash_zip_entries, is_archive_supported, read_zip_entry, zip_entries, ArchiveEntry,
+};
+use crate::progress::progress_bar;
+use crate::scanner::{FileInfo, SizeUnits};
 
 /// A group of duplicate files
 #[derive(Debug)]
@@ -33,17 +36,104 @@ impl DuplicateGroup {
             0
         }
     }
+
+    /// Move the most recently modified file to the front, where it is kept.
+    /// Ties keep their current order, so the first of them stays in front.
+    pub fn keep_newest(&mut self) {
+        if let Some(newest) = self
+            .files
+            .iter()
+            .enumerate()
+            .max_by(|(i, a), (j, b)| a.modified.cmp(&b.modified).then(j.cmp(i)))
+            .map(|(i, _)| i)
+        {
+            let keeper = self.files.remove(newest);
+            self.files.insert(0, keeper);
+        }
+    }
+
+    /// Number of distinct `roots` the files of this group are under
+    pub fn roots_spanned(&self, roots: &[PathBuf]) -> usize {
+        let spanned: HashSet<usize> = self
+            .files
+            .iter()
+            .filter_map(|f| roots.iter().position(|root| f.path.starts_with(root)))
+            .collect();
+        spanned.len()
+    }
+
+    /// Move the first file under `root` to the front, where it is kept
+    pub fn keep_in(&mut self, root: &Path) {
+        if let Some(i) = self.files.iter().position(|f| f.path.starts_with(root)) {
+            let keeper = self.files.remove(i);
+            self.files.insert(0, keeper);
+        }
+    }
+
+    /// The files to delete so only copies under `root` remain; nothing if the
+    /// group has no copy under `root` to keep
+    pub fn removable_outside(&self, root: &Path) -> Vec<&FileInfo> {
+        if !self.files.iter().any(|f| f.path.starts_with(root)) {
+            return Vec::new();
+        }
+        self.files
+            .iter()
+            .filter(|f| !f.path.starts_with(root))
+            .collect()
+    }
+}
+
+/// Which file of each duplicate group `duplicates --merge` keeps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// The most recently modified copy
+    Newest,
 }
 
 /// Chunk size for comparing large files (64KB)
 const COMPARE_CHUNK_SIZE: usize = 64 * 1024;
+/// Window size for streaming comparison of files over `MMAP_THRESHOLD` (4MB),
+/// so memory stays flat however big the files are
+const LARGE_COMPARE_CHUNK_SIZE: usize = 4 * 1024 * 1024;
 /// Threshold for using memory-mapped files (files larger than this use mmap)
 const MMAP_THRESHOLD: u64 = 64 * 1024; // 64KB
 
+/// Run `f` inside a dedicated rayon pool of `threads` workers, so the parallel
+/// hashing loops it triggers are capped (None = rayon's default global pool)
+pub fn with_thread_limit<T, F>(threads: Option<usize>, f: F) -> Result<T>
+where
+    F: FnOnce() -> T + Send,
+    T: Send,
+{
+    match threads {
+        Some(n) => {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()
+                .context("Failed to build thread pool")?;
+            Ok(pool.install(f))
+        }
+        None => Ok(f()),
+    }
+}
+
 /// Find duplicate files by content using hybrid hash + direct compare
 pub fn find_duplicates(files: &[FileInfo]) -> Result<Vec<DuplicateGroup>> {
+    let mut duplicates = Vec::new();
+    for_each_duplicate_group(files, |group| duplicates.push(group))?;
+    Ok(duplicates)
+}
+
+/// Find duplicate files and hand each group to `on_group` as soon as it is confirmed
+///
+/// Groups are not collected, so memory stays flat on very large trees. The callback
+/// may be invoked from rayon worker threads (one at a time).
+pub fn for_each_duplicate_group<F>(files: &[FileInfo], on_group: F) -> Result<()>
+where
+    F: FnMut(DuplicateGroup) + Send,
+{
     if files.is_empty() {
-        return Ok(Vec::new());
+        return Ok(());
     }
 
     // Step 1: Group by size (files with different sizes can't be duplicates)
@@ -54,23 +144,24 @@ pub fn find_duplicates(files: &[FileInfo]) -> Result<Vec<DuplicateGroup>> {
         }
     }
 
-    // Filter to only groups with potential duplicates (same size)
+    // Filter to only groups with potential duplicates (same size). Hard links to
+    // the same file share storage, so only one path per file is compared.
     let potential_dups: Vec<Vec<&FileInfo>> = by_size
         .into_values()
         .filter(|group| group.len() > 1)
+        .map(collapse_links)
+        .filter(|group| group.len() > 1)
         .collect();
 
     if potential_dups.is_empty() {
-        return Ok(Vec::new());
+        return Ok(());
     }
 
     let total_files: usize = potential_dups.iter().map(|g| g.len()).sum();
-    let pb = ProgressBar::new(total_files as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} Hashing files [{bar:40.cyan/blue}] {pos}/{len} ({per_sec})")
-            .unwrap()
-            .progress_chars("█▓░"),
+    let pb = progress_bar(
+        total_files as u64,
+        "Hashing files",
+        "{spinner:.green} Hashing files [{bar:40.cyan/blue}] {pos}/{len} ({per_sec})",
     );
 
     // Step 2: Quick hash first 4KB to group files (O(n) instead of O(n²))
@@ -95,20 +186,50 @@ pub fn find_duplicates(files: &[FileInfo]) -> Result<Vec<DuplicateGroup>> {
         .collect();
 
     if candidates.is_empty() {
-        return Ok(Vec::new());
+        return Ok(());
     }
 
     // Step 4: Direct compare within each candidate group (small groups, fast)
-    let duplicates: Mutex<Vec<DuplicateGroup>> = Mutex::new(Vec::new());
+    let on_group = Mutex::new(on_group);
 
     candidates.par_iter().for_each(|group| {
         if let Ok(groups) = find_duplicates_in_group(group) {
-            let mut dups = duplicates.lock().unwrap();
-            dups.extend(groups);
+            let mut on_group = on_group.lock().unwrap();
+            for dup in groups {
+                on_group(dup);
+            }
         }
     });
 
-    Ok(duplicates.into_inner().unwrap())
+    Ok(())
+}
+
+/// Keep one path per underlying file, dropping hard links (and followed symlinks)
+/// that resolve to a file already in the group
+fn collapse_links(files: Vec<&FileInfo>) -> Vec<&FileInfo> {
+    let mut seen = HashSet::new();
+    files
+        .into_iter()
+        .filter(|file| match file_identity(&file.path) {
+            Some(id) => seen.insert(id),
+            None => true,
+        })
+        .collect()
+}
+
+/// Device and inode number identifying the file behind `path`
+#[cfg(unix)]
+pub fn file_identity(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// File identity is not available on this platform, so links are compared as
+/// regular files
+#[cfg(not(unix))]
+pub fn file_identity(_path: &Path) -> Option<(u64, u64)> {
+    None
 }
 
 /// Quick hash of first 4KB for fast grouping
@@ -153,7 +274,7 @@ fn find_duplicates_in_group(files: &[&FileInfo]) -> Result<Vec<DuplicateGroup>>
 
     // For larger groups, compare pairs
     let mut groups: Vec<Vec<FileInfo>> = Vec::new();
-    let mut processed: std::collections::HashSet<usize> = std::collections::HashSet::new();
+    let mut processed: HashSet<usize> = HashSet::new();
 
     for i in 0..files.len() {
         if processed.contains(&i) {
@@ -191,8 +312,8 @@ fn find_duplicates_in_group(files: &[&FileInfo]) -> Result<Vec<DuplicateGroup>>
     Ok(result)
 }
 
-/// Compare two files for equality using memory-mapped access (very fast)
-fn files_are_equal(path1: &Path, path2: &Path) -> Result<bool> {
+/// Compare two files for equality, streaming them in fixed-size windows
+pub fn files_are_equal(path1: &Path, path2: &Path) -> Result<bool> {
     let file1 = File::open(path1)?;
     let file2 = File::open(path2)?;
 
@@ -209,38 +330,59 @@ fn files_are_equal(path1: &Path, path2: &Path) -> Result<bool> {
         return Ok(true);
     }
 
-    // Use memory-mapped files for large files (much faster)
+    // Large files are compared in bounded windows on the heap rather than mapped
+    // whole, which for two multi-GB files would pull both into memory at once
     if size1 > MMAP_THRESHOLD {
-        // Safety: we're only reading, files are opened read-only
-        let mmap1 = unsafe { Mmap::map(&file1)? };
-        let mmap2 = unsafe { Mmap::map(&file2)? };
-
-        // Direct byte comparison - very fast due to mmap
-        return Ok(mmap1[..] == mmap2[..]);
+        let mut buf1 = vec![0u8; LARGE_COMPARE_CHUNK_SIZE];
+        let mut buf2 = vec![0u8; LARGE_COMPARE_CHUNK_SIZE];
+        return readers_equal(file1, file2, &mut buf1, &mut buf2);
     }
 
     // For small files, use buffered reading
-    let mut reader1 = BufReader::new(file1);
-    let mut reader2 = BufReader::new(file2);
     let mut buf1 = [0u8; COMPARE_CHUNK_SIZE];
     let mut buf2 = [0u8; COMPARE_CHUNK_SIZE];
+    readers_equal(
+        BufReader::new(file1),
+        BufReader::new(file2),
+        &mut buf1,
+        &mut buf2,
+    )
+}
 
+/// Compare two readers window by window, never holding more than the two buffers
+fn readers_equal<R1: Read, R2: Read>(
+    mut reader1: R1,
+    mut reader2: R2,
+    buf1: &mut [u8],
+    buf2: &mut [u8],
+) -> Result<bool> {
     loop {
-        let n1 = reader1.read(&mut buf1)?;
-        let n2 = reader2.read(&mut buf2)?;
+        let n1 = read_window(&mut reader1, buf1)?;
+        let n2 = read_window(&mut reader2, buf2)?;
 
-        if n1 != n2 {
+        if n1 != n2 || buf1[..n1] != buf2[..n2] {
             return Ok(false);
         }
 
-        if n1 == 0 {
+        if n1 < buf1.len() {
             return Ok(true);
         }
+    }
+}
 
-        if buf1[..n1] != buf2[..n2] {
-            return Ok(false);
+/// Fill `buf` as far as the reader allows; a short count means end of file.
+/// A single `read` may return less than asked, which must not count as a mismatch.
+fn read_window<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
         }
     }
+    Ok(filled)
 }
 
 /// Quick hash for display purposes (not for comparison)
@@ -264,15 +406,12 @@ fn quick_hash(path: &Path) -> Result<String> {
 }
 
 /// Display duplicate groups
-pub fn display_duplicates(groups: &[DuplicateGroup]) {
+pub fn display_duplicates(groups: &[DuplicateGroup], units: SizeUnits) {
     if groups.is_empty() {
         println!("{}", "No duplicate files found.".green());
         return;
     }
 
-    let total_wasted: u64 = groups.iter().map(|g| g.wasted_space()).sum();
-    let total_count: usize = groups.iter().map(|g| g.files.len() - 1).sum();
-
     println!("\n{}", "Duplicate Files Found:".bold().yellow());
     println!("{}", "─".repeat(60));
 
@@ -285,7 +424,7 @@ pub fn display_duplicates(groups: &[DuplicateGroup]) {
         println!(
             "\n  {} ({}) - {} copies:",
             format!("Group {}", i + 1).cyan().bold(),
-            format_size(group.size).dimmed(),
+            units.format(group.size).dimmed(),
             group.files.len()
         );
 
@@ -300,6 +439,223 @@ pub fn display_duplicates(groups: &[DuplicateGroup]) {
     }
 
     println!("\n{}", "─".repeat(60));
+    print_duplicates_summary(groups, units);
+}
+
+/// A loose file whose contents are also stored inside an archive
+#[derive(Debug)]
+pub struct ArchiveDuplicate {
+    pub file: FileInfo,
+    pub entry: ArchiveEntry,
+}
+
+/// Find loose files that duplicate entries of the zip archives among `files`
+///
+/// Entries are matched by size first, so only entries with a same-sized loose
+/// file are decompressed and hashed, and a matching hash is confirmed by comparing
+/// the bytes. Archives that can't be read are skipped.
+pub fn find_archive_duplicates(files: &[FileInfo]) -> Result<Vec<ArchiveDuplicate>> {
+    let mut by_size: HashMap<u64, Vec<&FileInfo>> = HashMap::new();
+    for file in files {
+        if file.size > 0 {
+            by_size.entry(file.size).or_default().push(file);
+        }
+    }
+
+    let mut loose_hashes: HashMap<&Path, Option<u64>> = HashMap::new();
+    let mut found = Vec::new();
+    for archive in files.iter().filter(|f| is_archive_supported(f)) {
+        let entries = match zip_entries(&archive.path) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::debug!("skip archive {}: {:#}", archive.path.display(), e);
+                continue;
+            }
+        };
+        let candidates: Vec<ArchiveEntry> = entries
+            .into_iter()
+            .filter(|entry| entry.size > 0 && by_size.contains_key(&entry.size))
+            .collect();
+        if candidates.is_empty() {
+            continue;
+        }
+
+        let names: Vec<&str> = candidates.iter().map(|e| e.name.as_str()).collect();
+        let hashes = match hash_zip_entries(&archive.path, &names) {
+            Ok(hashes) => hashes,
+            Err(e) => {
+                log::debug!("skip archive {}: {:#}", archive.path.display(), e);
+                continue;
+            }
+        };
+
+        for (entry, hash) in candidates.iter().zip(hashes) {
+            for file in &by_size[&entry.size] {
+                if file.path == archive.path {
+                    continue;
+                }
+                let loose = *loose_hashes
+                    .entry(&file.path)
+                    .or_insert_with(|| hash_file(&file.path).ok());
+                if loose == Some(hash) && entry_equals_file(entry, &file.path) {
+                    found.push(ArchiveDuplicate {
+                        file: (*file).clone(),
+                        entry: entry.clone(),
+                    });
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Whether an archive entry holds exactly the bytes of the file at `path`
+fn entry_equals_file(entry: &ArchiveEntry, path: &Path) -> bool {
+    let compared = read_zip_entry(&entry.archive, &entry.name, |stored| {
+        let loose = File::open(path)?;
+        let mut buf1 = [0u8; COMPARE_CHUNK_SIZE];
+        let mut buf2 = [0u8; COMPARE_CHUNK_SIZE];
+        readers_equal(stored, BufReader::new(loose), &mut buf1, &mut buf2)
+    });
+    compared.unwrap_or_else(|e| {
+        log::debug!(
+            "skip {} in {}: {:#}",
+            entry.name,
+            entry.archive.display(),
+            e
+        );
+        false
+    })
+}
+
+/// Display loose files that are also stored in an archive
+pub fn display_archive_duplicates(found: &[ArchiveDuplicate], units: SizeUnits) {
+    println!("\n{}", "Also Stored in Archives:".bold().yellow());
+    println!("{}", "─".repeat(60));
+    if found.is_empty() {
+        println!("  {}", "No loose files are duplicated in archives.".green());
+        return;
+    }
+
+    for dup in found {
+        println!(
+            "  {} {} ({})",
+            "○".yellow(),
+            dup.file.path.display(),
+            units.format(dup.file.size).dimmed()
+        );
+        println!(
+            "      {} {} → {}",
+            "in".dimmed(),
+            dup.entry.archive.display(),
+            dup.entry.name
+        );
+    }
+
+    let total: u64 = loose_archive_copies(found).iter().map(|f| f.size).sum();
+    println!(
+        "\n{}: {} loose files ({}) already stored in archives",
+        "Archived".bold(),
+        loose_archive_copies(found).len().to_string().yellow(),
+        units.format(total).yellow()
+    );
+}
+
+/// Each loose file with an archived copy, once, even if several archives hold it
+pub fn loose_archive_copies(found: &[ArchiveDuplicate]) -> Vec<&FileInfo> {
+    let mut seen = HashSet::new();
+    found
+        .iter()
+        .map(|dup| &dup.file)
+        .filter(|file| seen.insert(&file.path))
+        .collect()
+}
+
+/// Space that removing duplicates would free in one folder
+#[derive(Debug, PartialEq)]
+pub struct DirWaste {
+    pub dir: PathBuf,
+    /// Duplicate copies in this folder (the kept first file of a group doesn't count)
+    pub files: usize,
+    pub wasted: u64,
+}
+
+/// Total the wasted space of duplicate groups per folder, charging each removable
+/// copy to the folder it is in. Sorted by wasted space, largest first.
+pub fn wasted_by_dir(groups: &[DuplicateGroup]) -> Vec<DirWaste> {
+    let mut by_dir: HashMap<&Path, (usize, u64)> = HashMap::new();
+    for group in groups {
+        for file in group.files.iter().skip(1) {
+            let dir = file.path.parent().unwrap_or(Path::new(""));
+            let entry = by_dir.entry(dir).or_default();
+            entry.0 += 1;
+            entry.1 += group.size;
+        }
+    }
+
+    let mut entries: Vec<DirWaste> = by_dir
+        .into_iter()
+        .map(|(dir, (files, wasted))| DirWaste {
+            dir: dir.to_path_buf(),
+            files,
+            wasted,
+        })
+        .collect();
+    entries.sort_by(|a, b| b.wasted.cmp(&a.wasted).then_with(|| a.dir.cmp(&b.dir)));
+    entries
+}
+
+/// Display wasted space per folder instead of listing groups; folders are shown
+/// relative to `base` when given
+pub fn display_wasted_by_dir(groups: &[DuplicateGroup], base: Option<&Path>, units: SizeUnits) {
+    if groups.is_empty() {
+        println!("{}", "No duplicate files found.".green());
+        return;
+    }
+
+    let entries = wasted_by_dir(groups);
+    let largest = entries.first().map(|e| e.wasted).unwrap_or(0).max(1);
+
+    println!("\n{}", "Wasted Space by Folder:".bold().yellow());
+    println!("{}", "─".repeat(60));
+    for entry in entries.iter().take(20) {
+        let bar_len = (entry.wasted as f64 / largest as f64 * 20.0) as usize;
+        let dir = base
+            .and_then(|base| entry.dir.strip_prefix(base).ok())
+            .map(|rel| {
+                if rel.as_os_str().is_empty() {
+                    ".".to_string()
+                } else {
+                    rel.display().to_string()
+                }
+            })
+            .unwrap_or_else(|| entry.dir.display().to_string());
+        println!(
+            "  {:>10}  {:>5} files  {:<30} {}",
+            units.format(entry.wasted).red(),
+            entry.files,
+            format!("{}/", dir).cyan(),
+            "█".repeat(bar_len).yellow()
+        );
+    }
+    if entries.len() > 20 {
+        println!("  {} ... and {} more", "→".dimmed(), entries.len() - 20);
+    }
+
+    println!("\n{}", "─".repeat(60));
+    print_duplicates_summary(groups, units);
+}
+
+/// Print only the totals for duplicate groups (no per-file listing)
+pub fn print_duplicates_summary(groups: &[DuplicateGroup], units: SizeUnits) {
+    if groups.is_empty() {
+        println!("{}", "No duplicate files found.".green());
+        return;
+    }
+
+    let total_wasted: u64 = groups.iter().map(|g| g.wasted_space()).sum();
+    let total_count: usize = groups.iter().map(|g| g.files.len() - 1).sum();
+
     println!(
         "\n{}: {} duplicate files in {} groups",
         "Summary".bold(),
@@ -309,7 +665,7 @@ pub fn display_duplicates(groups: &[DuplicateGroup]) {
     println!(
         "{}: {} could be recovered by removing duplicates",
         "Wasted space".bold(),
-        format_size(total_wasted).red()
+        units.format(total_wasted).red()
     );
     println!(
         "\n{} Use {} to remove duplicates (keeps first file in each group).",
@@ -318,7 +674,7 @@ pub fn display_duplicates(groups: &[DuplicateGroup]) {
     );
 }
 
-/// A group of visually similar images
+/// A group of visually similar images or videos
 #[derive(Debug)]
 pub struct SimilarGroup {
     /// Representative file (first in group)
@@ -348,7 +704,6 @@ fn is_image_supported(path: &std::path::Path) -> bool {
 }
 
 /// Find visually similar images using perceptual hashing
-#[allow(clippy::needless_range_loop)]
 pub fn find_similar_images(files: &[FileInfo], threshold: u32) -> Result<Vec<SimilarGroup>> {
     use image_hasher::{HashAlg, HasherConfig};
 
@@ -368,14 +723,10 @@ pub fn find_similar_images(files: &[FileInfo], threshold: u32) -> Result<Vec<Sim
         images.len()
     );
 
-    let pb = ProgressBar::new(images.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template(
-                "{spinner:.green} Hashing images [{bar:40.cyan/blue}] {pos}/{len} ({per_sec})",
-            )
-            .unwrap()
-            .progress_chars("█▓░"),
+    let pb = progress_bar(
+        images.len() as u64,
+        "Hashing images",
+        "{spinner:.green} Hashing images [{bar:40.cyan/blue}] {pos}/{len} ({per_sec})",
     );
 
     // Configure hasher with DCT algorithm (good for finding similar images)
@@ -398,16 +749,31 @@ pub fn find_similar_images(files: &[FileInfo], threshold: u32) -> Result<Vec<Sim
 
     pb.finish_and_clear();
 
-    // Find similar images
-    let mut groups: Vec<SimilarGroup> = Vec::new();
-    let mut used: std::collections::HashSet<usize> = std::collections::HashSet::new();
-
     println!(
         "  {} Comparing {} image pairs...",
         "→".cyan(),
         images.len() * (images.len() - 1) / 2
     );
 
+    Ok(group_similar(&hashes, threshold, |a, b| a.dist(b)))
+}
+
+/// Cluster files whose hashes are within `threshold` of each other.
+///
+/// Each group is led by the first file that has any match; files without a hash
+/// are never grouped, and a file joins at most one group.
+#[allow(clippy::needless_range_loop)]
+pub fn group_similar<H, D>(
+    hashes: &[(&FileInfo, Option<H>)],
+    threshold: u32,
+    distance: D,
+) -> Vec<SimilarGroup>
+where
+    D: Fn(&H, &H) -> u32,
+{
+    let mut groups: Vec<SimilarGroup> = Vec::new();
+    let mut used: std::collections::HashSet<usize> = std::collections::HashSet::new();
+
     for i in 0..hashes.len() {
         if used.contains(&i) {
             continue;
@@ -432,7 +798,7 @@ pub fn find_similar_images(files: &[FileInfo], threshold: u32) -> Result<Vec<Sim
                 None => continue,
             };
 
-            let distance = hash_i.dist(hash_j);
+            let distance = distance(hash_i, hash_j);
 
             if distance <= threshold {
                 similar.push(((*file_j).clone(), distance));
@@ -449,25 +815,27 @@ pub fn find_similar_images(files: &[FileInfo], threshold: u32) -> Result<Vec<Sim
         }
     }
 
-    Ok(groups)
+    groups
 }
 
-/// Display similar image groups
-pub fn display_similar_images(groups: &[SimilarGroup]) {
+/// Display similar file groups; `noun` is what the files are ("images", "videos")
+pub fn display_similar(groups: &[SimilarGroup], noun: &str, units: SizeUnits) {
     if groups.is_empty() {
-        println!("{}", "No similar images found.".green());
+        println!("{}", format!("No similar {} found.", noun).green());
         return;
     }
 
     let total_similar: usize = groups.iter().map(|g| g.similar.len()).sum();
     let total_space: u64 = groups.iter().map(|g| g.similar_space()).sum();
 
-    println!("\n{}", "Similar Images Found:".bold().yellow());
+    let mut title = noun.to_string();
+    title[..1].make_ascii_uppercase();
+    println!("\n{}", format!("Similar {} Found:", title).bold().yellow());
     println!("{}", "─".repeat(60));
 
     for (i, group) in groups.iter().enumerate() {
         if i >= 10 {
-            println!("\n... and {} more similar image groups", groups.len() - 10);
+            println!("\n... and {} more similar groups", groups.len() - 10);
             break;
         }
 
@@ -482,7 +850,7 @@ pub fn display_similar_images(groups: &[SimilarGroup]) {
             "    {} {} ({})",
             "●".green(),
             group.representative.path.display(),
-            format_size(group.representative.size).dimmed()
+            units.format(group.representative.size).dimmed()
         );
 
         // Show similar files
@@ -491,7 +859,7 @@ pub fn display_similar_images(groups: &[SimilarGroup]) {
                 "    {} {} ({}, {}% similar)",
                 "○".yellow(),
                 file.path.display(),
-                format_size(file.size).dimmed(),
+                units.format(file.size).dimmed(),
                 100 - (distance * 100 / 256).min(100)
             );
         }
@@ -499,15 +867,17 @@ pub fn display_similar_images(groups: &[SimilarGroup]) {
 
     println!("\n{}", "─".repeat(60));
     println!(
-        "\n{}: {} similar images in {} groups",
+        "\n{}: {} similar {} in {} groups",
         "Summary".bold(),
         total_similar.to_string().yellow(),
+        noun,
         groups.len().to_string().cyan()
     );
     println!(
-        "{}: {} used by similar images",
+        "{}: {} used by similar {}",
         "Space".bold(),
-        format_size(total_space).yellow()
+        units.format(total_space).yellow(),
+        noun
     );
     println!(
         "\n{} Lower threshold = more strict matching (default: 10)",
@@ -518,16 +888,17 @@ pub fn display_similar_images(groups: &[SimilarGroup]) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::fs::File;
+    use std::fs::{self, File};
     use std::io::Write;
     use std::path::PathBuf;
-    use std::time::SystemTime;
+    use std::time::{Duration, SystemTime};
     use tempfile::tempdir;
 
     fn make_file_info(path: PathBuf, size: u64) -> FileInfo {
         FileInfo {
             name: path.file_name().unwrap().to_string_lossy().to_string(),
             extension: path.extension().map(|e| e.to_string_lossy().to_string()),
+            extension_raw: path.extension().map(|e| e.to_string_lossy().to_string()),
             path,
             size,
             modified: SystemTime::now(),
@@ -535,6 +906,202 @@ mod tests {
         }
     }
 
+    fn write_zip(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_find_archive_duplicates_matches_loose_file() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("report.pdf"), "quarterly numbers").unwrap();
+        fs::write(dir.path().join("notes.txt"), "not archived").unwrap();
+        write_zip(
+            &dir.path().join("backup.zip"),
+            &[
+                ("docs/report.pdf", "quarterly numbers"),
+                ("docs/other.txt", "something else"),
+            ],
+        );
+        let files: Vec<FileInfo> = ["report.pdf", "notes.txt", "backup.zip"]
+            .iter()
+            .map(|n| FileInfo::from_path(&dir.path().join(n)).unwrap())
+            .collect();
+
+        let found = find_archive_duplicates(&files).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].file.name, "report.pdf");
+        assert_eq!(found[0].entry.archive, dir.path().join("backup.zip"));
+        assert_eq!(found[0].entry.name, "docs/report.pdf");
+    }
+
+    #[test]
+    fn test_entry_equals_file_compares_bytes() {
+        // Stands in for a hash collision: the hash check alone isn't trusted
+        let dir = tempdir().unwrap();
+        let archive = dir.path().join("backup.zip");
+        write_zip(&archive, &[("report.pdf", "quarterly numbers")]);
+        fs::write(dir.path().join("same.pdf"), "quarterly numbers").unwrap();
+        fs::write(dir.path().join("other.pdf"), "quarterly NUMBERS").unwrap();
+        let entry = ArchiveEntry {
+            archive,
+            name: "report.pdf".to_string(),
+            size: 17,
+        };
+
+        assert!(entry_equals_file(&entry, &dir.path().join("same.pdf")));
+        assert!(!entry_equals_file(&entry, &dir.path().join("other.pdf")));
+        assert!(!entry_equals_file(&entry, &dir.path().join("missing.pdf")));
+    }
+
+    #[test]
+    fn test_loose_archive_copies_lists_each_file_once() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "same").unwrap();
+        write_zip(&dir.path().join("one.zip"), &[("a.txt", "same")]);
+        write_zip(&dir.path().join("two.zip"), &[("copy.txt", "same")]);
+        let files: Vec<FileInfo> = ["a.txt", "one.zip", "two.zip"]
+            .iter()
+            .map(|n| FileInfo::from_path(&dir.path().join(n)).unwrap())
+            .collect();
+
+        let found = find_archive_duplicates(&files).unwrap();
+        assert_eq!(found.len(), 2);
+        assert_eq!(loose_archive_copies(&found).len(), 1);
+    }
+
+    #[test]
+    fn test_keep_newest_moves_latest_modified_first() {
+        let base = SystemTime::UNIX_EPOCH;
+        let file = |name: &str, secs: u64| FileInfo {
+            modified: base + Duration::from_secs(secs),
+            ..make_file_info(PathBuf::from(name), 100)
+        };
+        let mut group = DuplicateGroup {
+            hash: "abc".to_string(),
+            files: vec![
+                file("/home/report.docx", 100),
+                file("/work/report.docx", 300),
+                file("/backup/report.docx", 200),
+                file("/usb/report.docx", 300),
+            ],
+            size: 100,
+        };
+
+        group.keep_newest();
+
+        let names: Vec<_> = group.files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            names,
+            [
+                PathBuf::from("/work/report.docx"),
+                PathBuf::from("/home/report.docx"),
+                PathBuf::from("/backup/report.docx"),
+                PathBuf::from("/usb/report.docx"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_duplicates_across_roots() {
+        let downloads = tempdir().unwrap();
+        let archive = tempdir().unwrap();
+        let roots = vec![downloads.path().to_path_buf(), archive.path().to_path_buf()];
+
+        // Same file in both trees, plus a pair that only exists within Downloads
+        fs::write(downloads.path().join("report.pdf"), "quarterly report").unwrap();
+        fs::write(archive.path().join("report-2024.pdf"), "quarterly report").unwrap();
+        fs::write(downloads.path().join("a.txt"), "local copy").unwrap();
+        fs::write(downloads.path().join("b.txt"), "local copy").unwrap();
+
+        let files: Vec<FileInfo> = [
+            downloads.path().join("report.pdf"),
+            downloads.path().join("a.txt"),
+            downloads.path().join("b.txt"),
+            archive.path().join("report-2024.pdf"),
+        ]
+        .iter()
+        .map(|p| FileInfo::from_path(p).unwrap())
+        .collect();
+
+        let mut groups = find_duplicates(&files).unwrap();
+        assert_eq!(groups.len(), 2);
+        groups.retain(|g| g.roots_spanned(&roots) >= 2);
+        assert_eq!(groups.len(), 1);
+
+        // Keeping the Archive copy removes only the one in Downloads
+        let group = &mut groups[0];
+        group.keep_in(archive.path());
+        assert_eq!(group.files[0].name, "report-2024.pdf");
+        let removable = group.removable_outside(archive.path());
+        assert_eq!(removable.len(), 1);
+        assert_eq!(removable[0].name, "report.pdf");
+
+        // A root with no copy in the group never loses the last ones
+        let elsewhere = tempdir().unwrap();
+        assert!(group.removable_outside(elsewhere.path()).is_empty());
+    }
+
+    #[test]
+    fn test_wasted_by_dir_charges_removable_copies() {
+        let file = |path: &str, size: u64| make_file_info(PathBuf::from(path), size);
+        let groups = vec![
+            DuplicateGroup {
+                hash: "a".to_string(),
+                files: vec![
+                    file("/keep/movie.mkv", 700),
+                    file("/downloads/movie.mkv", 700),
+                    file("/downloads/old/movie.mkv", 700),
+                ],
+                size: 700,
+            },
+            DuplicateGroup {
+                hash: "b".to_string(),
+                files: vec![
+                    file("/downloads/photo.jpg", 5),
+                    file("/backup/photo.jpg", 5),
+                ],
+                size: 5,
+            },
+            DuplicateGroup {
+                hash: "c".to_string(),
+                files: vec![file("/keep/song.mp3", 40), file("/downloads/song.mp3", 40)],
+                size: 40,
+            },
+        ];
+
+        let entries = wasted_by_dir(&groups);
+
+        // The kept first file of each group is never charged, so /keep doesn't appear
+        assert_eq!(
+            entries,
+            vec![
+                DirWaste {
+                    dir: PathBuf::from("/downloads"),
+                    files: 2,
+                    wasted: 740,
+                },
+                DirWaste {
+                    dir: PathBuf::from("/downloads/old"),
+                    files: 1,
+                    wasted: 700,
+                },
+                DirWaste {
+                    dir: PathBuf::from("/backup"),
+                    files: 1,
+                    wasted: 5,
+                },
+            ]
+        );
+        let total: u64 = entries.iter().map(|e| e.wasted).sum();
+        assert_eq!(total, groups.iter().map(|g| g.wasted_space()).sum::<u64>());
+    }
+
     #[test]
     fn test_wasted_space_single_file() {
         let group = DuplicateGroup {
@@ -643,6 +1210,166 @@ mod tests {
         assert!(result.is_empty()); // Empty files are skipped
     }
 
+    #[test]
+    fn test_for_each_duplicate_group_streams_groups() {
+        let dir = tempdir().unwrap();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            fs::write(dir.path().join(name), "same content").unwrap();
+        }
+        fs::write(dir.path().join("d.txt"), "other").unwrap();
+
+        let files: Vec<FileInfo> = ["a.txt", "b.txt", "c.txt", "d.txt"]
+            .iter()
+            .map(|n| FileInfo::from_path(&dir.path().join(n)).unwrap())
+            .collect();
+
+        let mut seen = Vec::new();
+        for_each_duplicate_group(&files, |group| seen.push(group.files.len())).unwrap();
+        assert_eq!(seen, vec![3]);
+    }
+
+    #[test]
+    fn test_find_duplicates_single_thread_matches_default() {
+        let dir = tempdir().unwrap();
+        for i in 0..6 {
+            fs::write(dir.path().join(format!("a{}.txt", i)), "alpha").unwrap();
+            fs::write(dir.path().join(format!("b{}.txt", i)), "bravo!").unwrap();
+            fs::write(
+                dir.path().join(format!("u{}.txt", i)),
+                format!("unique {}", i),
+            )
+            .unwrap();
+        }
+        let files = crate::scanner::scan_directory(dir.path(), &Default::default()).unwrap();
+
+        let sorted_groups = |groups: Vec<DuplicateGroup>| {
+            let mut groups: Vec<Vec<PathBuf>> = groups
+                .into_iter()
+                .map(|g| {
+                    let mut paths: Vec<PathBuf> = g.files.into_iter().map(|f| f.path).collect();
+                    paths.sort();
+                    paths
+                })
+                .collect();
+            groups.sort();
+            groups
+        };
+
+        let default = sorted_groups(find_duplicates(&files).unwrap());
+        let single = sorted_groups(
+            with_thread_limit(Some(1), || find_duplicates(&files))
+                .unwrap()
+                .unwrap(),
+        );
+
+        assert_eq!(default.len(), 2);
+        assert_eq!(default, single);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_duplicates_ignores_hard_links() {
+        let dir = tempdir().unwrap();
+        let original = dir.path().join("a.txt");
+        let link = dir.path().join("b.txt");
+        fs::write(&original, "shared storage").unwrap();
+        fs::hard_link(&original, &link).unwrap();
+
+        let files = vec![
+            FileInfo::from_path(&original).unwrap(),
+            FileInfo::from_path(&link).unwrap(),
+        ];
+        let result = find_duplicates(&files).unwrap();
+        let wasted: u64 = result.iter().map(|g| g.wasted_space()).sum();
+        assert!(result.is_empty());
+        assert_eq!(wasted, 0);
+
+        // A real copy is still a duplicate, counted once against the linked pair
+        let copy = dir.path().join("c.txt");
+        fs::write(&copy, "shared storage").unwrap();
+        let mut files = files;
+        files.push(FileInfo::from_path(&copy).unwrap());
+        let result = find_duplicates(&files).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].files.len(), 2);
+        assert_eq!(result[0].wasted_space(), 14);
+    }
+
+    /// Reader that records the largest single read it was asked for
+    struct TrackingReader<R> {
+        inner: R,
+        max_request: usize,
+    }
+
+    impl<R: Read> Read for TrackingReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.max_request = self.max_request.max(buf.len());
+            self.inner.read(buf)
+        }
+    }
+
+    #[test]
+    fn test_files_are_equal_large_files() {
+        let dir = tempdir().unwrap();
+        // Several windows plus a partial one, well over the mmap threshold
+        let len = LARGE_COMPARE_CHUNK_SIZE * 2 + 12345;
+        let content: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+
+        let a = dir.path().join("a.bin");
+        let b = dir.path().join("b.bin");
+        let c = dir.path().join("c.bin");
+        fs::write(&a, &content).unwrap();
+        fs::write(&b, &content).unwrap();
+        let mut differing = content.clone();
+        differing[len - 1] ^= 0xFF;
+        fs::write(&c, &differing).unwrap();
+
+        assert!(files_are_equal(&a, &b).unwrap());
+        assert!(!files_are_equal(&a, &c).unwrap());
+
+        // Memory stays bounded: no read ever asks for more than one window
+        let mut buf1 = vec![0u8; LARGE_COMPARE_CHUNK_SIZE];
+        let mut buf2 = vec![0u8; LARGE_COMPARE_CHUNK_SIZE];
+        let mut reader1 = TrackingReader {
+            inner: File::open(&a).unwrap(),
+            max_request: 0,
+        };
+        let mut reader2 = TrackingReader {
+            inner: File::open(&b).unwrap(),
+            max_request: 0,
+        };
+        assert!(readers_equal(&mut reader1, &mut reader2, &mut buf1, &mut buf2).unwrap());
+        assert!(reader1.max_request <= LARGE_COMPARE_CHUNK_SIZE);
+        assert!(reader2.max_request <= LARGE_COMPARE_CHUNK_SIZE);
+    }
+
+    #[test]
+    fn test_readers_equal_tolerates_short_reads() {
+        // One side hands back a byte at a time; contents are still equal
+        let data = b"the same bytes, read in different sized pieces".to_vec();
+        let mut buf1 = [0u8; 16];
+        let mut buf2 = [0u8; 16];
+        assert!(readers_equal(OneByteReader(&data[..]), &data[..], &mut buf1, &mut buf2).unwrap());
+        assert!(!readers_equal(
+            OneByteReader(&data[..]),
+            &data[..data.len() - 1],
+            &mut buf1,
+            &mut buf2
+        )
+        .unwrap());
+    }
+
+    struct OneByteReader<'a>(&'a [u8]);
+
+    impl Read for OneByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(1).min(self.0.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
     #[test]
     fn test_hash_file() {
         let dir = tempdir().unwrap();
@@ -1,7 +1,12 @@
 //! File filters - name, regex, MIME type filtering
 
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
 use std::path::Path;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use crate::metadata::{is_exif_supported, ImageMetadata};
+use crate::scanner::{parse_date, FileInfo};
 
 /// Name-based file filters
 #[derive(Debug, Default, Clone)]
@@ -78,41 +83,305 @@ impl NameFilter {
     }
 }
 
+/// Filter photos by their EXIF date taken
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TakenDateFilter {
+    /// Date taken must be on or after this time
+    pub after: Option<SystemTime>,
+    /// Date taken must be before this time (from --taken-before, the start of the
+    /// following day, so the whole given day is included)
+    pub before: Option<SystemTime>,
+    /// Exclude files without an EXIF date instead of using their modification time
+    pub taken_only: bool,
+}
+
+impl TakenDateFilter {
+    /// Build a filter from YYYY-MM-DD (or YYYY/MM/DD) date arguments, both of which
+    /// include the day they name
+    pub fn from_args(
+        after: Option<&str>,
+        before: Option<&str>,
+        taken_only: bool,
+    ) -> Result<Self, String> {
+        const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+        Ok(Self {
+            after: after.map(parse_date).transpose()?,
+            before: before.map(parse_date).transpose()?.map(|day| day + DAY),
+            taken_only,
+        })
+    }
+
+    /// Check if any date bound is set (EXIF is only read when it is)
+    pub fn is_active(&self) -> bool {
+        self.after.is_some() || self.before.is_some()
+    }
+
+    /// Check a file's date taken, falling back to `modified` when it has none
+    pub fn matches(&self, taken: Option<SystemTime>, modified: SystemTime) -> bool {
+        let date = match (taken, self.taken_only) {
+            (Some(taken), _) => taken,
+            (None, false) => modified,
+            (None, true) => return false,
+        };
+
+        if self.after.is_some_and(|after| date < after) {
+            return false;
+        }
+        if self.before.is_some_and(|before| date >= before) {
+            return false;
+        }
+        true
+    }
+}
+
+/// Keep files whose EXIF date taken passes `filter`, reading EXIF only for
+/// supported image formats
+pub fn filter_by_date_taken(files: Vec<FileInfo>, filter: &TakenDateFilter) -> Vec<FileInfo> {
+    if !filter.is_active() {
+        return files;
+    }
+
+    files
+        .into_iter()
+        .filter(|file| {
+            let taken = if is_exif_supported(&file.path) {
+                ImageMetadata::from_path(&file.path).and_then(|m| m.date_taken_time())
+            } else {
+                None
+            };
+            filter.matches(taken, file.modified)
+        })
+        .collect()
+}
+
+/// Filter images by pixel dimensions
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DimensionFilter {
+    pub min_width: Option<u32>,
+    pub min_height: Option<u32>,
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+    /// Exclude files whose dimensions can't be read instead of keeping them
+    pub images_only: bool,
+}
+
+impl DimensionFilter {
+    /// Check if any bound is set (images are only opened when one is)
+    pub fn is_active(&self) -> bool {
+        self.min_width.is_some()
+            || self.min_height.is_some()
+            || self.max_width.is_some()
+            || self.max_height.is_some()
+    }
+
+    /// Check a file's `(width, height)`, or None for files that aren't images
+    pub fn matches(&self, dimensions: Option<(u32, u32)>) -> bool {
+        let Some((width, height)) = dimensions else {
+            return !self.images_only;
+        };
+
+        self.min_width.is_none_or(|min| width >= min)
+            && self.min_height.is_none_or(|min| height >= min)
+            && self.max_width.is_none_or(|max| width <= max)
+            && self.max_height.is_none_or(|max| height <= max)
+    }
+}
+
+/// Read an image's dimensions from its header, or None if it isn't a readable image
+pub fn image_dimensions(path: &Path) -> Option<(u32, u32)> {
+    image::ImageFormat::from_path(path).ok()?;
+    image::image_dimensions(path).ok()
+}
+
+/// Keep files whose dimensions pass `filter`, decoding headers only for image formats
+pub fn filter_by_dimensions(files: Vec<FileInfo>, filter: &DimensionFilter) -> Vec<FileInfo> {
+    if !filter.is_active() {
+        return files;
+    }
+
+    files
+        .into_iter()
+        .filter(|file| filter.matches(image_dimensions(&file.path)))
+        .collect()
+}
+
 /// Check if a filename matches a regex pattern
-pub fn matches_regex(filename: &str, pattern: &str) -> Result<bool, regex::Error> {
-    let re = Regex::new(pattern)?;
+pub fn matches_regex(
+    filename: &str,
+    pattern: &str,
+    case_insensitive: bool,
+) -> Result<bool, regex::Error> {
+    let re = RegexBuilder::new(pattern)
+        .case_insensitive(case_insensitive)
+        .build()?;
     Ok(re.is_match(filename))
 }
 
-/// Check if a file matches a MIME type filter
-/// Supports wildcards like "image/*", "application/pdf"
-pub fn matches_mime(path: &Path, mime_filter: &str) -> bool {
-    let guess = mime_guess::from_path(path);
+/// Extract named capture groups from a filename
+/// Returns None if the regex does not match
+pub fn regex_captures(filename: &str, re: &Regex) -> Option<Vec<(String, String)>> {
+    let caps = re.captures(filename)?;
+    Some(
+        re.capture_names()
+            .flatten()
+            .filter_map(|name| {
+                caps.name(name)
+                    .map(|m| (name.to_string(), m.as_str().to_string()))
+            })
+            .collect(),
+    )
+}
 
-    if let Some(mime) = guess.first() {
-        let mime_str = mime.to_string();
+/// Screenshot names as saved by macOS (`Screenshot 2024-03-05 at 10.22.11.png`, or
+/// `Screen Shot ...` before Mojave), Windows (`Screenshot (12).png`,
+/// `Screenshot 2024-03-05 102211.png`) and Linux (GNOME `Screenshot from 2024-03-05 10-22-11.png`,
+/// KDE `Screenshot_20240305_102211.png`)
+static SCREENSHOT_NAME: OnceLock<Regex> = OnceLock::new();
 
-        // Handle wildcard patterns like "image/*"
-        if let Some(prefix) = mime_filter.strip_suffix("/*") {
-            return mime_str.starts_with(prefix);
-        }
+/// Check if a filename looks like an image saved by a platform screenshot tool
+pub fn is_screenshot_name(filename: &str) -> bool {
+    SCREENSHOT_NAME
+        .get_or_init(|| {
+            Regex::new(r"(?i)^screen ?shot(?:[ _-].*)?\.(?:png|jpe?g|heic|webp|bmp|gif|tiff?)$")
+                .expect("valid screenshot regex")
+        })
+        .is_match(filename)
+}
+
+/// Check if a file matches a MIME type filter, guessing the type from the extension
+/// (or the content, for files without a recognized extension)
+pub fn matches_mime(path: &Path, mime_filter: &str) -> bool {
+    get_mime_type(path).is_some_and(|mime| mime_matches(&mime, mime_filter))
+}
+
+/// Check if a file matches a MIME type filter, preferring the type its content sniffs as
+pub fn matches_detected_mime(path: &Path, mime_filter: &str) -> bool {
+    detect_mime(path).is_some_and(|mime| mime_matches(&mime, mime_filter))
+}
 
-        // Exact match
-        mime_str == mime_filter
-    } else {
-        false
+/// Compare a MIME type against a filter like "image/png" or "image/*"
+fn mime_matches(mime: &str, mime_filter: &str) -> bool {
+    match mime_filter.strip_suffix("/*") {
+        Some(prefix) => mime.split('/').next() == Some(prefix),
+        None => mime == mime_filter,
     }
 }
 
-/// Get MIME type for a file path
+/// Get MIME type for a file path from its extension, sniffing the content when
+/// the extension is missing or unknown
 pub fn get_mime_type(path: &Path) -> Option<String> {
-    mime_guess::from_path(path).first().map(|m| m.to_string())
+    mime_guess::from_path(path)
+        .first()
+        .map(|m| m.to_string())
+        .or_else(|| sniff_mime(path).map(str::to_string))
+}
+
+/// Get MIME type for a file, trusting its content over its extension
+pub fn detect_mime(path: &Path) -> Option<String> {
+    sniff_mime(path)
+        .map(str::to_string)
+        .or_else(|| mime_guess::from_path(path).first().map(|m| m.to_string()))
+}
+
+/// Identify common file formats by their leading magic bytes
+pub fn sniff_mime(path: &Path) -> Option<&'static str> {
+    let mut header = [0u8; 32];
+    let mut file = std::fs::File::open(path).ok()?;
+    let len = std::io::Read::read(&mut file, &mut header).ok()?;
+    sniff_bytes(&header[..len])
+}
+
+fn sniff_bytes(bytes: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"\xFF\xD8\xFF", "image/jpeg"),
+        (b"\x89PNG\r\n\x1A\n", "image/png"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"BM", "image/bmp"),
+        (b"II*\0", "image/tiff"),
+        (b"MM\0*", "image/tiff"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1F\x8B", "application/gzip"),
+        (b"7z\xBC\xAF\x27\x1C", "application/x-7z-compressed"),
+        (b"Rar!\x1A\x07", "application/vnd.rar"),
+        (b"ID3", "audio/mpeg"),
+        (b"fLaC", "audio/flac"),
+        (b"OggS", "audio/ogg"),
+        (b"\x1A\x45\xDF\xA3", "video/x-matroska"),
+    ];
+
+    if let Some((_, mime)) = SIGNATURES.iter().find(|(sig, _)| bytes.starts_with(sig)) {
+        return Some(mime);
+    }
+
+    // RIFF containers name their format at offset 8
+    if bytes.starts_with(b"RIFF") && bytes.len() >= 12 {
+        return match &bytes[8..12] {
+            b"WEBP" => Some("image/webp"),
+            b"WAVE" => Some("audio/wav"),
+            b"AVI " => Some("video/x-msvideo"),
+            _ => None,
+        };
+    }
+
+    // ISO media files carry a brand after "ftyp" at offset 4
+    if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        return match &bytes[8..12] {
+            b"heic" | b"heix" | b"mif1" | b"msf1" => Some("image/heic"),
+            b"avif" | b"avis" => Some("image/avif"),
+            b"M4A " | b"M4B " => Some("audio/mp4"),
+            b"qt  " => Some("video/quicktime"),
+            b"isom" | b"iso2" | b"iso4" | b"iso5" | b"iso6" | b"mp41" | b"mp42" | b"avc1"
+            | b"dash" | b"M4V " | b"MSNV" => Some("video/mp4"),
+            b"3gp4" | b"3gp5" | b"3gp6" => Some("video/3gpp"),
+            _ => None,
+        };
+    }
+
+    None
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_screenshot_name_macos() {
+        assert!(is_screenshot_name("Screenshot 2024-03-05 at 10.22.11.png"));
+        assert!(is_screenshot_name(
+            "Screen Shot 2019-11-02 at 9.41.07 PM.png"
+        ));
+        assert!(is_screenshot_name("Screenshot 2024-03-05 at 10.22.11.heic"));
+    }
+
+    #[test]
+    fn test_is_screenshot_name_windows() {
+        assert!(is_screenshot_name("Screenshot (12).png"));
+        assert!(is_screenshot_name("Screenshot 2024-03-05 102211.png"));
+        assert!(is_screenshot_name("screenshot.JPG"));
+    }
+
+    #[test]
+    fn test_is_screenshot_name_linux() {
+        assert!(is_screenshot_name(
+            "Screenshot from 2024-03-05 10-22-11.png"
+        ));
+        assert!(is_screenshot_name(
+            "Screenshot From 2024-03-05 10-22-11.png"
+        ));
+        assert!(is_screenshot_name("Screenshot_20240305_102211.png"));
+    }
+
+    #[test]
+    fn test_is_screenshot_name_rejects_lookalikes() {
+        assert!(!is_screenshot_name("Screenshots.zip"));
+        assert!(!is_screenshot_name("Screenshot 2024-03-05.pdf"));
+        assert!(!is_screenshot_name("screenshotter.png"));
+        assert!(!is_screenshot_name("my Screenshot.png"));
+        assert!(!is_screenshot_name("IMG_0001.png"));
+    }
+
     #[test]
     fn test_name_filter_startswith() {
         let filter = NameFilter {
@@ -166,10 +435,135 @@ mod tests {
         assert!(!filter.matches("photo_2024.jpg"));
     }
 
+    fn taken_on(year: i32, month: u32, day: u32) -> SystemTime {
+        let meta = ImageMetadata {
+            date_taken: Some(format!("{:04}:{:02}:{:02} 12:00:00", year, month, day)),
+            ..Default::default()
+        };
+        meta.date_taken_time().unwrap()
+    }
+
+    #[test]
+    fn test_taken_date_filter_range() {
+        let filter = TakenDateFilter {
+            after: Some(taken_on(2024, 1, 1)),
+            before: Some(taken_on(2024, 12, 31)),
+            taken_only: false,
+        };
+        let mtime = taken_on(2020, 1, 1);
+
+        assert!(filter.matches(Some(taken_on(2024, 6, 15)), mtime));
+        assert!(!filter.matches(Some(taken_on(2023, 6, 15)), mtime));
+        assert!(!filter.matches(Some(taken_on(2025, 1, 2)), mtime));
+    }
+
+    #[test]
+    fn test_taken_date_filter_includes_before_day() {
+        let filter = TakenDateFilter::from_args(None, Some("2024-12-31"), false).unwrap();
+        let mtime = taken_on(2020, 1, 1);
+
+        assert!(filter.matches(Some(taken_on(2024, 12, 31)), mtime));
+        assert!(!filter.matches(Some(taken_on(2025, 1, 1)), mtime));
+    }
+
+    #[test]
+    fn test_taken_date_filter_fallback_to_mtime() {
+        let filter = TakenDateFilter {
+            after: Some(taken_on(2024, 1, 1)),
+            ..Default::default()
+        };
+
+        assert!(filter.matches(None, taken_on(2024, 3, 1)));
+        assert!(!filter.matches(None, taken_on(2023, 3, 1)));
+
+        let strict = TakenDateFilter {
+            taken_only: true,
+            ..filter
+        };
+        assert!(!strict.matches(None, taken_on(2024, 3, 1)));
+    }
+
+    #[test]
+    fn test_filter_by_date_taken_inactive_keeps_all() {
+        let file = FileInfo {
+            path: "/photos/a.jpg".into(),
+            name: "a.jpg".to_string(),
+            extension: Some("jpg".to_string()),
+            extension_raw: Some("jpg".to_string()),
+            size: 1,
+            modified: SystemTime::UNIX_EPOCH,
+            created: None,
+        };
+        let kept = filter_by_date_taken(vec![file], &TakenDateFilter::default());
+        assert_eq!(kept.len(), 1);
+    }
+
+    fn png_file(dir: &Path, name: &str, width: u32, height: u32) -> FileInfo {
+        let path = dir.join(name);
+        image::RgbImage::new(width, height).save(&path).unwrap();
+        FileInfo::from_path(&path).unwrap()
+    }
+
+    #[test]
+    fn test_filter_by_dimensions() {
+        let dir = tempfile::tempdir().unwrap();
+        let thumb = png_file(dir.path(), "thumb.png", 64, 48);
+        let photo = png_file(dir.path(), "photo.png", 640, 480);
+        let filter = DimensionFilter {
+            min_width: Some(200),
+            min_height: Some(200),
+            ..Default::default()
+        };
+
+        let kept = filter_by_dimensions(vec![thumb.clone(), photo.clone()], &filter);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].name, "photo.png");
+
+        let filter = DimensionFilter {
+            max_width: Some(199),
+            ..Default::default()
+        };
+        let kept = filter_by_dimensions(vec![thumb, photo], &filter);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].name, "thumb.png");
+    }
+
+    #[test]
+    fn test_dimension_filter_non_images() {
+        let filter = DimensionFilter {
+            min_width: Some(200),
+            ..Default::default()
+        };
+        assert!(filter.matches(None));
+
+        let strict = DimensionFilter {
+            images_only: true,
+            ..filter
+        };
+        assert!(!strict.matches(None));
+        assert!(strict.matches(Some((200, 10))));
+    }
+
     #[test]
     fn test_regex_matches() {
-        assert!(matches_regex("IMG_0001.jpg", r"^IMG_\d{4}").unwrap());
-        assert!(!matches_regex("photo.jpg", r"^IMG_\d{4}").unwrap());
+        assert!(matches_regex("IMG_0001.jpg", r"^IMG_\d{4}", false).unwrap());
+        assert!(!matches_regex("photo.jpg", r"^IMG_\d{4}", false).unwrap());
+        assert!(matches_regex("img_0001.jpg", r"^IMG_\d{4}", true).unwrap());
+        assert!(!matches_regex("img_0001.jpg", r"^IMG_\d{4}", false).unwrap());
+    }
+
+    #[test]
+    fn test_regex_captures_named_groups() {
+        let re = Regex::new(r"(?P<proj>\w+?)_(?P<num>\d+)\.log").unwrap();
+        let caps = regex_captures("backend_42.log", &re).unwrap();
+        assert_eq!(
+            caps,
+            vec![
+                ("proj".to_string(), "backend".to_string()),
+                ("num".to_string(), "42".to_string()),
+            ]
+        );
+        assert!(regex_captures("notes.txt", &re).is_none());
     }
 
     #[test]
@@ -188,6 +582,42 @@ mod tests {
         assert!(!matches_mime(path, "image/jpeg"));
     }
 
+    const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1A\n\0\0\0\rIHDR";
+
+    #[test]
+    fn test_detect_mime_sniffs_renamed_image() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("holiday.txt");
+        std::fs::write(&path, PNG_HEADER).unwrap();
+
+        // The extension says text, the content says PNG
+        assert_eq!(get_mime_type(&path), Some("text/plain".to_string()));
+        assert_eq!(detect_mime(&path), Some("image/png".to_string()));
+        assert!(!matches_mime(&path, "image/*"));
+        assert!(matches_detected_mime(&path, "image/*"));
+    }
+
+    #[test]
+    fn test_get_mime_type_sniffs_extensionless_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("IMG_0042");
+        std::fs::write(&path, b"\xFF\xD8\xFF\xE0\0\x10JFIF").unwrap();
+        assert_eq!(get_mime_type(&path), Some("image/jpeg".to_string()));
+
+        let unknown = dir.path().join("notes");
+        std::fs::write(&unknown, "plain words").unwrap();
+        assert_eq!(get_mime_type(&unknown), None);
+    }
+
+    #[test]
+    fn test_sniff_bytes_containers() {
+        assert_eq!(sniff_bytes(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff_bytes(b"\0\0\0\x18ftypheic"), Some("image/heic"));
+        assert_eq!(sniff_bytes(b"\0\0\0\x18ftypisom"), Some("video/mp4"));
+        assert_eq!(sniff_bytes(b"\0\0\0\x18ftypcrx "), None);
+        assert_eq!(sniff_bytes(b"hello"), None);
+    }
+
     #[test]
     fn test_mime_filter_wildcard() {
         let path = Path::new("test.jpg");
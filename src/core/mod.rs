@@ -1,5 +1,6 @@
 //! Core logic modules for neatcli
 
+pub mod archive;
 pub mod classifier;
 pub mod cleaner;
 pub mod content;
@@ -8,3 +9,4 @@ pub mod filters;
 pub mod organizer;
 pub mod scanner;
 pub mod template;
+pub mod video;
@@ -1,21 +1,37 @@
 //! Organizer - move files to organized locations
 
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 use anyhow::{Context, Result};
 use chrono::{Datelike, TimeZone, Utc};
 use colored::*;
-use indicatif::{ProgressBar, ProgressStyle};
-
-use crate::classifier::Classifier;
+use indicatif::ProgressBar;
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::archive::hash_file;
+use crate::classifier::{Category, Classifier};
+use crate::cleaner::find_empty_dirs;
+use crate::config::{Config, FallbackFolders};
+use crate::duplicates::{file_identity, files_are_equal};
 use crate::logger::Logger;
 use crate::metadata::{is_audio_supported, is_exif_supported, AudioMetadata, ImageMetadata};
-use crate::scanner::{format_size, FileInfo};
+use crate::progress::progress_bar;
+use crate::scanner::{first_letter, size_bucket, FileInfo, SizeUnits};
+use crate::throttle::Throttle;
+
+/// Folder that `organize --include-empty-dirs` moves empty folders into
+pub const EMPTY_FOLDER: &str = "Empty";
+
+/// Progress bar style shared by move and copy batches
+const MOVE_PROGRESS_TEMPLATE: &str =
+    "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})";
 
 /// Organization mode
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[allow(clippy::enum_variant_names)]
 pub enum OrganizeMode {
     ByType,
@@ -25,6 +41,66 @@ pub enum OrganizeMode {
     ByDateTaken,
     ByArtist,
     ByAlbum,
+    BySizeBucket,
+    /// `A/`, `B/`, ... by the first letter of the name (`#` for anything else)
+    ByFirstLetter,
+    /// Per file: images by date taken, audio by album, the rest by type
+    Smart,
+}
+
+impl OrganizeMode {
+    /// Human-readable name, as in "organizing by type"
+    pub fn name(&self) -> &'static str {
+        match self {
+            OrganizeMode::ByType => "type",
+            OrganizeMode::ByDate => "date",
+            OrganizeMode::ByExtension => "extension",
+            OrganizeMode::ByCamera => "camera",
+            OrganizeMode::ByDateTaken => "date taken",
+            OrganizeMode::ByArtist => "artist",
+            OrganizeMode::ByAlbum => "album",
+            OrganizeMode::BySizeBucket => "size",
+            OrganizeMode::ByFirstLetter => "alpha",
+            OrganizeMode::Smart => "smart",
+        }
+    }
+}
+
+/// Why a planned move goes where it does (shown by `organize --explain`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoveReason {
+    /// A config rule with this name matched the file
+    Rule(String),
+    /// The organize mode chose the folder
+    Mode(OrganizeMode),
+    /// The mode's fallback folder, for files without the metadata it groups by
+    ModeFallback(OrganizeMode),
+    /// A --template or --preset
+    Template,
+    /// Named captures from --name-regex filled the template
+    NameRegex,
+    /// The file name didn't match --name-regex, so it went to the fallback folder
+    NameRegexFallback,
+    /// Merged into Other by --group-small
+    GroupSmall,
+    /// Mirrored by --keep-structure
+    KeepStructure,
+}
+
+impl std::fmt::Display for MoveReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let by = |mode: &OrganizeMode| mode.name().replace(' ', "-");
+        match self {
+            MoveReason::Rule(name) => write!(f, "rule:{}", name),
+            MoveReason::Mode(mode) => write!(f, "mode:by-{}", by(mode)),
+            MoveReason::ModeFallback(mode) => write!(f, "mode:by-{} (fallback)", by(mode)),
+            MoveReason::Template => write!(f, "template"),
+            MoveReason::NameRegex => write!(f, "name-regex"),
+            MoveReason::NameRegexFallback => write!(f, "name-regex (fallback)"),
+            MoveReason::GroupSmall => write!(f, "group-small"),
+            MoveReason::KeepStructure => write!(f, "keep-structure"),
+        }
+    }
 }
 
 /// Strategy for handling file conflicts
@@ -45,8 +121,139 @@ pub enum ConflictStrategy {
     Backup,
 }
 
+/// Suffix added to a file's stem when `ConflictStrategy::Rename` needs a new name.
+/// `{n}` is the attempt number; `{date}` (YYYYMMDD) and `{time}` (HHMMSS) are the
+/// local time of the rename.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CollisionSuffix(Cow<'static, str>);
+
+/// The suffix used unless `--collision-suffix` or the config sets one
+static DEFAULT_COLLISION_SUFFIX: CollisionSuffix = CollisionSuffix(Cow::Borrowed("_{n}"));
+
+/// Placeholders a collision suffix may use
+const COLLISION_PLACEHOLDERS: [&str; 3] = ["n", "date", "time"];
+
+impl Default for CollisionSuffix {
+    fn default() -> Self {
+        DEFAULT_COLLISION_SUFFIX.clone()
+    }
+}
+
+impl CollisionSuffix {
+    /// Check a suffix pattern such as `" ({n})"` or `"-{date}"`
+    pub fn parse(pattern: &str) -> Result<Self> {
+        if pattern.is_empty() {
+            anyhow::bail!("Collision suffix cannot be empty");
+        }
+        if pattern.contains(['/', '\\']) {
+            anyhow::bail!(
+                "Collision suffix cannot contain path separators: {}",
+                pattern
+            );
+        }
+        let mut rest = pattern;
+        while let Some(open) = rest.find('{') {
+            let Some(len) = rest[open..].find('}') else {
+                anyhow::bail!("Unclosed '{{' in collision suffix: {}", pattern);
+            };
+            let name = &rest[open + 1..open + len];
+            if !COLLISION_PLACEHOLDERS.contains(&name) {
+                anyhow::bail!(
+                    "Unknown placeholder {{{}}} in collision suffix (use {{n}}, {{date}} or {{time}})",
+                    name
+                );
+            }
+            rest = &rest[open + len + 1..];
+        }
+        Ok(CollisionSuffix(Cow::Owned(pattern.to_string())))
+    }
+
+    /// The file name for the `attempt`-th rename (starting at 1). Patterns without
+    /// `{n}` fall back to `_1`, `_2`, ... after their first attempt.
+    fn file_name(
+        &self,
+        stem: &str,
+        extension: &str,
+        attempt: usize,
+        now: chrono::DateTime<chrono::Local>,
+    ) -> String {
+        let suffix = self
+            .0
+            .replace("{n}", &attempt.to_string())
+            .replace("{date}", &now.format("%Y%m%d").to_string())
+            .replace("{time}", &now.format("%H%M%S").to_string());
+        if self.0.contains("{n}") || attempt == 1 {
+            format!("{}{}{}", stem, suffix, extension)
+        } else {
+            format!("{}{}_{}{}", stem, suffix, attempt - 1, extension)
+        }
+    }
+}
+
+/// Kind of link `organize --link` creates at each destination instead of moving
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LinkKind {
+    /// A symbolic link pointing at the original file
+    Symlink,
+}
+
+/// Letter case for folder names in extension-based organization
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum ExtensionCase {
+    /// Uppercase folder names (`PY/`)
+    #[default]
+    Upper,
+    /// Lowercase folder names (`py/`)
+    Lower,
+    /// Keep the extension exactly as it appears in the file name
+    Keep,
+}
+
+/// Extra behavior for executing a batch of moves
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MoveOptions<'a> {
+    /// Leave a symlink at each old path pointing to the new location
+    pub leave_symlink: bool,
+    /// When a file is byte-identical to one already moved into the same folder,
+    /// hard-link it to that copy instead of moving it, so the content is stored once
+    pub link_duplicates: bool,
+    /// Abort on the first error instead of recording it and continuing
+    pub fail_fast: bool,
+    /// Give copies the source's modified and accessed times
+    pub preserve_times: bool,
+    /// Apply every move or none of them (see `execute_moves_atomic`)
+    pub atomic: bool,
+    /// After moving, remove folders under this path left empty (not the path itself)
+    pub delete_empty_in: Option<&'a Path>,
+    /// After moving, move folders under the first path left empty (not the path
+    /// itself) into the second, keeping their path relative to the first
+    pub collect_empty_in: Option<(&'a Path, &'a Path)>,
+    /// After moving, remove each of these folders, and the folders inside it, if
+    /// nothing is left in them
+    pub remove_if_empty: &'a [PathBuf],
+    /// Refuse moves whose destination resolves outside this folder (e.g. a template
+    /// rendering `../`), recording them as errors instead
+    pub confine_to: Option<&'a Path>,
+    /// Perform at most this many operations per second (None = unlimited);
+    /// not applied to atomic batches
+    pub throttle: Option<f64>,
+    /// Suffix for destinations renamed by `ConflictStrategy::Rename` (None = `_{n}`)
+    pub collision_suffix: Option<&'a CollisionSuffix>,
+}
+
+impl MoveOptions<'_> {
+    /// The suffix renamed conflicts get
+    pub fn suffix(&self) -> &CollisionSuffix {
+        self.collision_suffix.unwrap_or(&DEFAULT_COLLISION_SUFFIX)
+    }
+}
+
+/// Number of completed moves between history checkpoints, bounding how much an
+/// interrupted run (e.g. Ctrl+C) can leave unrecorded
+const CHECKPOINT_INTERVAL: usize = 100;
+
 /// A planned file move
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlannedMove {
     pub from: PathBuf,
     pub to: PathBuf,
@@ -54,29 +261,157 @@ pub struct PlannedMove {
 }
 
 /// Result of organizing
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize)]
 pub struct OrganizeResult {
     pub moved: usize,
     pub copied: usize,
+    pub linked: usize,
     pub skipped: usize,
     pub errors: Vec<String>,
     pub total_size: u64,
     pub deduplicated: usize,
     pub backed_up: usize,
+    pub removed_dirs: usize,
+    pub moved_dirs: usize,
+    /// Files that were actually moved, copied or linked, with their final destination
+    #[serde(skip)]
+    pub done: Vec<PlannedMove>,
+}
+
+impl OrganizeResult {
+    /// Add another run's counts and errors to these
+    pub fn merge(&mut self, other: OrganizeResult) {
+        self.moved += other.moved;
+        self.copied += other.copied;
+        self.linked += other.linked;
+        self.skipped += other.skipped;
+        self.errors.extend(other.errors);
+        self.total_size += other.total_size;
+        self.deduplicated += other.deduplicated;
+        self.backed_up += other.backed_up;
+        self.removed_dirs += other.removed_dirs;
+        self.moved_dirs += other.moved_dirs;
+        self.done.extend(other.done);
+    }
 }
 
 /// Plan file moves based on the organization mode
 pub fn plan_moves(files: &[FileInfo], base_path: &Path, mode: OrganizeMode) -> Vec<PlannedMove> {
-    let classifier = Classifier::new();
+    plan_moves_with_ext_case(files, base_path, mode, ExtensionCase::default())
+}
+
+/// Plan file moves, using `ext_case` for extension folder names
+pub fn plan_moves_with_ext_case(
+    files: &[FileInfo],
+    base_path: &Path,
+    mode: OrganizeMode,
+    ext_case: ExtensionCase,
+) -> Vec<PlannedMove> {
+    plan_moves_with_fallbacks(
+        files,
+        base_path,
+        mode,
+        ext_case,
+        &FallbackFolders::default(),
+    )
+}
+
+/// Plan file moves, naming folders for files without metadata from `fallbacks`
+pub fn plan_moves_with_fallbacks(
+    files: &[FileInfo],
+    base_path: &Path,
+    mode: OrganizeMode,
+    ext_case: ExtensionCase,
+    fallbacks: &FallbackFolders,
+) -> Vec<PlannedMove> {
+    let classifier = Classifier::configured();
     let mut moves = Vec::new();
 
     for file in files {
-        let destination = match mode {
-            OrganizeMode::ByType => {
-                let category = classifier.classify(file.extension.as_deref());
-                base_path.join(category.folder_name()).join(&file.name)
+        let Some(destination) =
+            plan_destination(file, base_path, mode, ext_case, fallbacks, classifier)
+        else {
+            log::debug!("skip path={:?} reason=already-sorted", file.path);
+            continue;
+        };
+
+        // Skip if file is already in the right place
+        if file.path != destination {
+            log::debug!(
+                "planned from={:?} to={:?} mode={}",
+                file.path,
+                destination,
+                mode.name()
+            );
+            moves.push(PlannedMove {
+                from: file.path.clone(),
+                to: destination,
+                size: file.size,
+            });
+        }
+    }
+
+    moves
+}
+
+/// Where `mode` puts `file`, or None if the mode doesn't apply to it
+/// (e.g. non-audio files when organizing by artist)
+fn plan_destination(
+    file: &FileInfo,
+    base_path: &Path,
+    mode: OrganizeMode,
+    ext_case: ExtensionCase,
+    fallbacks: &FallbackFolders,
+    classifier: &Classifier,
+) -> Option<PathBuf> {
+    if in_mode_folder(file, base_path, mode) {
+        return None;
+    }
+
+    let destination = match mode {
+        OrganizeMode::ByType => {
+            let category = classifier.category_name(file.extension.as_deref());
+            base_path.join(category).join(&file.name)
+        }
+        OrganizeMode::ByDate => {
+            let datetime = file
+                .modified
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| Utc.timestamp_opt(d.as_secs() as i64, 0).unwrap())
+                .unwrap_or_else(|_| Utc::now());
+
+            let year = datetime.year().to_string();
+            let month = format!("{:02}", datetime.month());
+
+            base_path.join(year).join(month).join(&file.name)
+        }
+        OrganizeMode::ByExtension => {
+            let folder = match &file.extension_raw {
+                Some(ext) => match ext_case {
+                    ExtensionCase::Upper => ext.to_uppercase(),
+                    ExtensionCase::Lower => ext.to_lowercase(),
+                    ExtensionCase::Keep => ext.clone(),
+                },
+                None => "NO_EXTENSION".to_string(),
+            };
+            base_path.join(folder).join(&file.name)
+        }
+        OrganizeMode::ByCamera => {
+            // Only process files with EXIF support
+            if !is_exif_supported(&file.path) {
+                return None;
             }
-            OrganizeMode::ByDate => {
+
+            let folder = ImageMetadata::from_path(&file.path)
+                .and_then(|m| m.camera_folder_name())
+                .unwrap_or_else(|| fallbacks.camera.clone());
+
+            base_path.join(folder).join(&file.name)
+        }
+        OrganizeMode::ByDateTaken => {
+            // Only process files with EXIF support
+            if !is_exif_supported(&file.path) {
+                // Fallback to file modified date for non-EXIF files
                 let datetime = file
                     .modified
                     .duration_since(std::time::UNIX_EPOCH)
@@ -85,96 +420,293 @@ pub fn plan_moves(files: &[FileInfo], base_path: &Path, mode: OrganizeMode) -> V
 
                 let year = datetime.year().to_string();
                 let month = format!("{:02}", datetime.month());
-
                 base_path.join(year).join(month).join(&file.name)
-            }
-            OrganizeMode::ByExtension => {
-                let ext = file.extension.as_deref().unwrap_or("no_extension");
-                base_path.join(ext.to_uppercase()).join(&file.name)
-            }
-            OrganizeMode::ByCamera => {
-                // Only process files with EXIF support
-                if !is_exif_supported(&file.path) {
-                    continue;
-                }
-
+            } else {
                 let folder = ImageMetadata::from_path(&file.path)
-                    .and_then(|m| m.camera_folder_name())
-                    .unwrap_or_else(|| "Unknown Camera".to_string());
+                    .and_then(|m| m.date_taken_folder())
+                    .unwrap_or_else(|| {
+                        // Fallback to file modified date
+                        let datetime = file
+                            .modified
+                            .duration_since(std::time::UNIX_EPOCH)
+                            .map(|d| Utc.timestamp_opt(d.as_secs() as i64, 0).unwrap())
+                            .unwrap_or_else(|_| Utc::now());
+                        format!("{}/{:02}", datetime.year(), datetime.month())
+                    });
 
                 base_path.join(folder).join(&file.name)
             }
-            OrganizeMode::ByDateTaken => {
-                // Only process files with EXIF support
-                if !is_exif_supported(&file.path) {
-                    // Fallback to file modified date for non-EXIF files
-                    let datetime = file
-                        .modified
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .map(|d| Utc.timestamp_opt(d.as_secs() as i64, 0).unwrap())
-                        .unwrap_or_else(|_| Utc::now());
-
-                    let year = datetime.year().to_string();
-                    let month = format!("{:02}", datetime.month());
-                    base_path.join(year).join(month).join(&file.name)
-                } else {
-                    let folder = ImageMetadata::from_path(&file.path)
-                        .and_then(|m| m.date_taken_folder())
-                        .unwrap_or_else(|| {
-                            // Fallback to file modified date
-                            let datetime = file
-                                .modified
-                                .duration_since(std::time::UNIX_EPOCH)
-                                .map(|d| Utc.timestamp_opt(d.as_secs() as i64, 0).unwrap())
-                                .unwrap_or_else(|_| Utc::now());
-                            format!("{}/{:02}", datetime.year(), datetime.month())
-                        });
-
-                    base_path.join(folder).join(&file.name)
-                }
+        }
+        OrganizeMode::ByArtist => {
+            // Only process audio files
+            if !is_audio_supported(&file.path) {
+                return None;
             }
-            OrganizeMode::ByArtist => {
-                // Only process audio files
-                if !is_audio_supported(&file.path) {
-                    continue;
-                }
 
-                let folder = AudioMetadata::from_path(&file.path)
-                    .and_then(|m| m.artist_folder_name())
-                    .unwrap_or_else(|| "Unknown Artist".to_string());
+            let folder = AudioMetadata::from_path(&file.path)
+                .and_then(|m| m.artist_folder_name())
+                .unwrap_or_else(|| fallbacks.artist.clone());
 
-                base_path.join(folder).join(&file.name)
+            base_path.join(folder).join(&file.name)
+        }
+        OrganizeMode::ByAlbum => {
+            // Only process audio files
+            if !is_audio_supported(&file.path) {
+                return None;
             }
-            OrganizeMode::ByAlbum => {
-                // Only process audio files
-                if !is_audio_supported(&file.path) {
-                    continue;
-                }
 
-                let meta = AudioMetadata::from_path(&file.path);
-                let artist = meta
-                    .as_ref()
-                    .and_then(|m| m.artist_folder_name())
-                    .unwrap_or_else(|| "Unknown Artist".to_string());
-                let album = meta
-                    .and_then(|m| m.album_folder_name())
-                    .unwrap_or_else(|| "Unknown Album".to_string());
+            let meta = AudioMetadata::from_path(&file.path);
+            let artist = meta
+                .as_ref()
+                .and_then(|m| m.artist_folder_name())
+                .unwrap_or_else(|| fallbacks.artist.clone());
+            let album = meta
+                .and_then(|m| m.album_folder_name())
+                .unwrap_or_else(|| fallbacks.album.clone());
 
-                base_path.join(artist).join(album).join(&file.name)
+            base_path.join(artist).join(album).join(&file.name)
+        }
+        OrganizeMode::BySizeBucket => base_path.join(size_bucket(file.size)).join(&file.name),
+        OrganizeMode::ByFirstLetter => base_path.join(first_letter(&file.name)).join(&file.name),
+        OrganizeMode::Smart => {
+            let mode = smart_mode(file, classifier);
+            return plan_destination(file, base_path, mode, ext_case, fallbacks, classifier);
+        }
+    };
+
+    Some(destination)
+}
+
+/// Whether `file` already sits in a folder of the kind `mode` creates, directly under
+/// `base_path`. Recursive re-runs leave such files alone even when their destination
+/// would now differ, e.g. a date folder after the file was edited, or an extension
+/// folder in another case.
+fn in_mode_folder(file: &FileInfo, base_path: &Path, mode: OrganizeMode) -> bool {
+    let Some(folder) = file
+        .path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(base_path).ok())
+    else {
+        return false;
+    };
+    let parts: Vec<&str> = folder
+        .components()
+        .filter_map(|c| c.as_os_str().to_str())
+        .collect();
+
+    match mode {
+        OrganizeMode::ByDate | OrganizeMode::ByDateTaken => match parts.as_slice() {
+            [year, month] => {
+                year.len() == 4
+                    && year.chars().all(|c| c.is_ascii_digit())
+                    && month.len() == 2
+                    && month.parse::<u32>().is_ok_and(|m| (1..=12).contains(&m))
+            }
+            _ => false,
+        },
+        OrganizeMode::ByExtension => match (parts.as_slice(), &file.extension_raw) {
+            ([folder], Some(ext)) => folder.eq_ignore_ascii_case(ext),
+            ([folder], None) => *folder == "NO_EXTENSION",
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// The mode `OrganizeMode::Smart` uses for a file: photos by date taken,
+/// music by artist/album, everything else by type
+pub fn smart_mode(file: &FileInfo, classifier: &Classifier) -> OrganizeMode {
+    match classifier.classify(file.extension.as_deref()) {
+        Category::Images => OrganizeMode::ByDateTaken,
+        Category::Audio if is_audio_supported(&file.path) => OrganizeMode::ByAlbum,
+        _ => OrganizeMode::ByType,
+    }
+}
+
+/// Redirect moves into folders that would end up with fewer than `min_files`
+/// files to `Other/`. Files already in a folder count toward its total.
+pub fn group_small_folders(
+    moves: Vec<PlannedMove>,
+    base_path: &Path,
+    min_files: usize,
+) -> Vec<PlannedMove> {
+    let other = base_path.join("Other");
+
+    let mut incoming: HashMap<PathBuf, usize> = HashMap::new();
+    for mv in &moves {
+        if let Some(folder) = mv.to.parent() {
+            *incoming.entry(folder.to_path_buf()).or_default() += 1;
+        }
+    }
+
+    let small: HashSet<PathBuf> = incoming
+        .into_iter()
+        .filter(|(folder, count)| {
+            let existing = fs::read_dir(folder).map(|d| d.count()).unwrap_or(0);
+            *folder != other && count + existing < min_files
+        })
+        .map(|(folder, _)| folder)
+        .collect();
+
+    moves
+        .into_iter()
+        .filter_map(|mut mv| {
+            if mv.to.parent().is_some_and(|folder| small.contains(folder)) {
+                mv.to = other.join(mv.to.file_name()?);
+            }
+            // A file already in Other doesn't need to move
+            (mv.from != mv.to).then_some(mv)
+        })
+        .collect()
+}
+
+/// Plan moves like `plan_moves_with_fallbacks`, except that files matching a config
+/// rule go to the highest-priority rule's destination
+pub fn plan_moves_with_rules(
+    files: &[FileInfo],
+    base_path: &Path,
+    mode: OrganizeMode,
+    ext_case: ExtensionCase,
+    fallbacks: &FallbackFolders,
+    config: &Config,
+) -> Vec<PlannedMove> {
+    files
+        .iter()
+        .flat_map(|file| match config.find_matching_rule(&file.name) {
+            Some(rule) => {
+                let to = rule.get_destination(base_path, &file.name, file.extension.as_deref());
+                let planned = PlannedMove {
+                    from: file.path.clone(),
+                    to,
+                    size: file.size,
+                };
+                // A file already at its rule's destination doesn't need to move
+                (planned.from != planned.to)
+                    .then_some(planned)
+                    .into_iter()
+                    .collect()
             }
+            None => plan_moves_with_fallbacks(
+                std::slice::from_ref(file),
+                base_path,
+                mode,
+                ext_case,
+                fallbacks,
+            ),
+        })
+        .collect()
+}
+
+/// Plan where a single file goes: the highest-priority matching config rule wins,
+/// otherwise `mode` decides. Returns None if the file is already in place.
+pub fn plan_move_with_rules(
+    file: &FileInfo,
+    base_path: &Path,
+    mode: OrganizeMode,
+    config: Option<&Config>,
+) -> Option<(PlannedMove, MoveReason)> {
+    if let Some(rule) = config.and_then(|cfg| cfg.find_matching_rule(&file.name)) {
+        let planned = PlannedMove {
+            from: file.path.clone(),
+            to: rule.get_destination(base_path, &file.name, file.extension.as_deref()),
+            size: file.size,
         };
+        return Some((planned, MoveReason::Rule(rule.name.clone())));
+    }
 
-        // Skip if file is already in the right place
-        if file.path != destination {
-            moves.push(PlannedMove {
+    plan_moves(std::slice::from_ref(file), base_path, mode)
+        .pop()
+        .map(|planned| (planned, MoveReason::Mode(mode)))
+}
+
+/// Plan moves that recreate each file's path relative to `source_root` under `output_dir`
+pub fn plan_moves_mirrored(
+    files: &[FileInfo],
+    source_root: &Path,
+    output_dir: &Path,
+) -> Vec<PlannedMove> {
+    files
+        .iter()
+        .filter_map(|file| {
+            let relative = file.path.strip_prefix(source_root).ok()?;
+            let destination = output_dir.join(relative);
+            (destination != file.path).then(|| PlannedMove {
                 from: file.path.clone(),
                 to: destination,
                 size: file.size,
-            });
+            })
+        })
+        .collect()
+}
+
+/// Characters that render as nothing but make otherwise equal names differ
+const ZERO_WIDTH_CHARS: [char; 5] = ['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'];
+
+/// Clean up a file name: NFC-normalize it, drop zero-width characters and trim
+/// whitespace around both the stem and the extension
+pub fn tidy_file_name(name: &str) -> String {
+    let name: String = name
+        .nfc()
+        .filter(|c| !ZERO_WIDTH_CHARS.contains(c))
+        .collect();
+    let tidy = match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.trim().is_empty() && !ext.trim().is_empty() => {
+            format!("{}.{}", stem.trim(), ext.trim())
         }
+        _ => name.trim().to_string(),
+    };
+    // A name made only of whitespace can't be tidied into anything useful
+    if tidy.is_empty() {
+        name
+    } else {
+        tidy
     }
+}
 
-    moves
+/// Plan in-place renames for files whose names change when tidied
+pub fn plan_tidy_names(files: &[FileInfo]) -> Vec<PlannedMove> {
+    files
+        .iter()
+        .filter_map(|file| {
+            let tidy = tidy_file_name(&file.name);
+            (tidy != file.name).then(|| PlannedMove {
+                from: file.path.clone(),
+                to: file.path.with_file_name(tidy),
+                size: file.size,
+            })
+        })
+        .collect()
+}
+
+/// Folders directly under `base` named like a category (as `--by-type` creates them)
+pub fn category_folders(base: &Path, names: &[&str]) -> Result<Vec<PathBuf>> {
+    let mut folders: Vec<PathBuf> = fs::read_dir(base)
+        .with_context(|| format!("Failed to read {}", base.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                && !path.is_symlink()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| names.contains(&name))
+        })
+        .collect();
+    folders.sort();
+    Ok(folders)
+}
+
+/// Plan moves that bring files back up to `base`, keeping their names
+pub fn plan_flatten(files: &[FileInfo], base: &Path) -> Vec<PlannedMove> {
+    files
+        .iter()
+        .map(|file| PlannedMove {
+            from: file.path.clone(),
+            to: base.join(&file.name),
+            size: file.size,
+        })
+        .collect()
 }
 
 /// Plan moves using a custom template
@@ -183,28 +715,59 @@ pub fn plan_moves_with_template(
     base_path: &Path,
     template: &str,
 ) -> Vec<PlannedMove> {
-    use crate::classifier::Classifier;
     use crate::template::TemplateEngine;
 
-    let classifier = Classifier::new();
+    let classifier = Classifier::configured();
     let mut moves = Vec::new();
 
     for file in files {
         // Create template engine with file variables
-        let engine = TemplateEngine::from_file(file, &classifier);
+        let engine = TemplateEngine::from_file(file, classifier);
+        let destination = render_destination(&engine, template, file, base_path);
+
+        // Skip if file is already in the right place
+        if file.path != destination {
+            moves.push(PlannedMove {
+                from: file.path.clone(),
+                to: destination,
+                size: file.size,
+            });
+        }
+    }
+
+    moves
+}
 
-        // Render the destination path from template
-        let dest_relative = engine.render(template);
+/// Plan moves using a template fed by the named capture groups of `name_regex`
+///
+/// Files whose names don't match go to `fallback_folder`, or are skipped if none is given.
+pub fn plan_moves_with_name_regex(
+    files: &[FileInfo],
+    base_path: &Path,
+    template: &str,
+    name_regex: &regex::Regex,
+    fallback_folder: Option<&str>,
+) -> Vec<PlannedMove> {
+    use crate::filters::regex_captures;
+    use crate::template::TemplateEngine;
 
-        // Build full destination: base_path + rendered template + extension
-        let ext = file
-            .extension
-            .as_ref()
-            .map(|e| format!(".{}", e))
-            .unwrap_or_default();
+    let classifier = Classifier::configured();
+    let mut moves = Vec::new();
 
-        let destination = base_path.join(&dest_relative).with_extension("");
-        let destination = PathBuf::from(format!("{}{}", destination.display(), ext));
+    for file in files {
+        let destination = match regex_captures(&file.name, name_regex) {
+            Some(captures) => {
+                let mut engine = TemplateEngine::from_file(file, classifier);
+                for (name, value) in captures {
+                    engine.set(name, value);
+                }
+                render_destination(&engine, template, file, base_path)
+            }
+            None => match fallback_folder {
+                Some(folder) => base_path.join(folder).join(&file.name),
+                None => continue,
+            },
+        };
 
         // Skip if file is already in the right place
         if file.path != destination {
@@ -219,30 +782,127 @@ pub fn plan_moves_with_template(
     moves
 }
 
-/// Preview planned moves (dry-run)
-pub fn preview_moves(moves: &[PlannedMove], base_path: &Path) {
-    if moves.is_empty() {
-        println!("{}", "No files to move.".yellow());
-        return;
+/// Default template for `--name-regex`: one folder per named group, in order
+pub fn default_name_regex_template(name_regex: &regex::Regex) -> String {
+    let mut parts: Vec<String> = name_regex
+        .capture_names()
+        .flatten()
+        .map(|name| format!("{{{}}}", name))
+        .collect();
+    parts.push("{filename}".to_string());
+    parts.join("/")
+}
+
+/// Render a template into a full destination path
+///
+/// A template that mentions `{filename}` or `{name}` controls the whole leaf, so files
+/// can be renamed while they move (e.g. `{year}{month}_{filename}`); the file's extension
+/// is re-attached unless the template already ends with it (a file without one doesn't
+/// get `.unknown` from `.{ext}`). Any other template only
+/// names folders and the original file name is kept.
+fn render_destination(
+    engine: &crate::template::TemplateEngine,
+    template: &str,
+    file: &FileInfo,
+    base_path: &Path,
+) -> PathBuf {
+    let dest_relative = engine.render(template);
+
+    if !template_names_file(template) {
+        return base_path.join(dest_relative).join(&file.name);
     }
 
-    println!("\n{}", "Preview:".bold().cyan());
-    println!("{}", "─".repeat(60));
+    let ext = match file.extension_raw {
+        Some(ref e) => format!(".{}", e),
+        // `{ext}` renders as "unknown" here; don't give the file that extension
+        None => {
+            let leaf = dest_relative.strip_suffix(".unknown");
+            return base_path.join(leaf.unwrap_or(&dest_relative));
+        }
+    };
+
+    // Avoid doubling an extension the template already rendered (e.g. `.{ext}`)
+    let leaf_has_ext = dest_relative.len() > ext.len()
+        && dest_relative.is_char_boundary(dest_relative.len() - ext.len())
+        && dest_relative[dest_relative.len() - ext.len()..].eq_ignore_ascii_case(&ext);
+    let stem = if leaf_has_ext {
+        &dest_relative[..dest_relative.len() - ext.len()]
+    } else {
+        dest_relative.as_str()
+    };
+
+    base_path.join(format!("{}{}", stem, ext))
+}
+
+/// Check whether a template renders the file name itself, not just its folders
+fn template_names_file(template: &str) -> bool {
+    ["{filename", "{name"].iter().any(|var| {
+        template.match_indices(var).any(|(i, _)| {
+            matches!(
+                template[i + var.len()..].chars().next(),
+                Some('}') | Some('|')
+            )
+        })
+    })
+}
+
+/// Write planned moves to `path` as JSON so they can be reviewed and applied later
+pub fn save_plan(moves: &[PlannedMove], path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(moves).context("Failed to serialize plan")?;
+    fs::write(path, json + "\n").with_context(|| format!("Failed to write plan: {:?}", path))
+}
+
+/// Read planned moves written by `save_plan`
+pub fn load_plan(path: &Path) -> Result<Vec<PlannedMove>> {
+    let json =
+        fs::read_to_string(path).with_context(|| format!("Failed to read plan: {:?}", path))?;
+    serde_json::from_str(&json).with_context(|| format!("Failed to parse plan: {:?}", path))
+}
+
+/// Check that every source in a plan still exists with the size it was planned with.
+/// Returns one message per stale entry.
+pub fn validate_plan(moves: &[PlannedMove]) -> Vec<String> {
+    moves
+        .iter()
+        .filter_map(|mv| match fs::metadata(&mv.from) {
+            Ok(meta) if !meta.is_file() => Some(format!("{}: not a file", mv.from.display())),
+            Ok(meta) if meta.len() != mv.size => Some(format!(
+                "{}: size changed ({} planned, {} now)",
+                mv.from.display(),
+                mv.size,
+                meta.len()
+            )),
+            Ok(_) => None,
+            Err(_) => Some(format!("{}: no longer exists", mv.from.display())),
+        })
+        .collect()
+}
 
-    // Group by destination folder
-    let mut by_folder: HashMap<PathBuf, Vec<&PlannedMove>> = HashMap::new();
+/// Group planned moves by destination folder, sorted by folder
+fn group_by_folder<'a>(
+    moves: &'a [PlannedMove],
+    base_path: &Path,
+) -> BTreeMap<PathBuf, Vec<&'a PlannedMove>> {
+    let mut by_folder: BTreeMap<PathBuf, Vec<&PlannedMove>> = BTreeMap::new();
     for mv in moves {
         let folder = mv.to.parent().unwrap_or(base_path).to_path_buf();
         by_folder.entry(folder).or_default().push(mv);
     }
+    by_folder
+}
 
-    // Sort folders
-    let mut folders: Vec<_> = by_folder.keys().collect();
-    folders.sort();
+/// Preview planned moves (dry-run)
+pub fn preview_moves(moves: &[PlannedMove], base_path: &Path, units: SizeUnits) {
+    if moves.is_empty() {
+        println!("{}", "No files to move.".yellow());
+        return;
+    }
 
-    for folder in folders {
-        let files = &by_folder[folder];
-        let folder_name = folder.strip_prefix(base_path).unwrap_or(folder);
+    println!("\n{}", "Preview:".bold().cyan());
+    println!("{}", "─".repeat(60));
+
+    for (folder, files) in group_by_folder(moves, base_path) {
+        let folder_name = folder.strip_prefix(base_path).unwrap_or(&folder);
         println!(
             "\n  {} ({} files)",
             folder_name.display().to_string().green().bold(),
@@ -260,73 +920,335 @@ pub fn preview_moves(moves: &[PlannedMove], base_path: &Path) {
         }
     }
 
-    let total_size: u64 = moves.iter().map(|m| m.size).sum();
     println!("\n{}", "─".repeat(60));
-    println!(
-        "\n{}: {} files to move ({})",
-        "Summary".bold(),
-        moves.len().to_string().cyan(),
-        format_size(total_size).cyan()
-    );
-    println!(
-        "\n{} Use {} to execute these changes.",
-        "ℹ".blue(),
-        "--execute".yellow()
-    );
+    print_moves_summary(moves, units);
 }
 
-/// Execute planned moves
-pub fn execute_moves(
+/// Preview planned moves one per line, each with the reason for its destination
+pub fn preview_moves_explained(
     moves: &[PlannedMove],
-    command_name: &str,
-    strategy: ConflictStrategy,
-) -> Result<OrganizeResult> {
+    reasons: &[MoveReason],
+    base_path: &Path,
+    units: SizeUnits,
+) {
     if moves.is_empty() {
-        return Ok(OrganizeResult::default());
+        println!("{}", "No files to move.".yellow());
+        return;
     }
 
-    let pb = ProgressBar::new(moves.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template(
-                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
-            )
-            .unwrap()
-            .progress_chars("█▓░"),
-    );
+    println!("\n{}", "Preview:".bold().cyan());
+    println!("{}", "─".repeat(60));
 
-    let mut result = OrganizeResult::default();
-    let mut logger = Logger::new(command_name);
+    for (mv, reason) in moves.iter().zip(reasons) {
+        let from_name = mv.from.file_name().unwrap_or_default().to_string_lossy();
+        let folder = mv.to.parent().unwrap_or(base_path);
+        let folder = folder.strip_prefix(base_path).unwrap_or(folder);
+        println!(
+            "  {} {} {} {}",
+            from_name,
+            "→".dimmed(),
+            format!("{}/", folder.display()).green(),
+            format!("[{}]", reason).dimmed()
+        );
+    }
 
-    for mv in moves {
-        pb.inc(1);
+    println!("\n{}", "─".repeat(60));
+    print_moves_summary(moves, units);
+}
 
-        // Create parent directory if needed
-        if let Some(parent) = mv.to.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent)
-                    .with_context(|| format!("Failed to create directory: {:?}", parent))?;
-            }
-        }
+/// Render planned moves as diff-style line pairs (`- old` / `+ new`), sorted by
+/// source path so the output is stable. Paths are shown relative to `base_path`.
+pub fn moves_diff(moves: &[PlannedMove], base_path: &Path) -> String {
+    let relative = |path: &Path| {
+        path.strip_prefix(base_path)
+            .unwrap_or(path)
+            .display()
+            .to_string()
+    };
 
-        // Handle name conflicts based on strategy
-        let final_dest = match resolve_conflict_with_strategy(&mv.to, strategy, &pb) {
-            Some(dest) => dest,
-            None => {
-                // Skip was chosen
-                result.skipped += 1;
-                continue;
-            }
-        };
+    let mut sorted: Vec<&PlannedMove> = moves.iter().collect();
+    sorted.sort_by(|a, b| a.from.cmp(&b.from));
 
-        // Move the file
-        match fs::rename(&mv.from, &final_dest) {
-            Ok(_) => {
-                result.moved += 1;
+    let mut diff = String::new();
+    for mv in sorted {
+        diff.push_str(&format!(
+            "- {}\n+ {}\n",
+            relative(&mv.from),
+            relative(&mv.to)
+        ));
+    }
+    diff
+}
+
+/// The moves with destinations renamed (`_1`, `_2`, ...) the way `--on-conflict rename`
+/// will rename them, for existing files and for earlier moves to the same path
+pub fn with_renamed_conflicts(moves: &[PlannedMove], suffix: &CollisionSuffix) -> Vec<PlannedMove> {
+    let mut claimed = HashSet::new();
+    moves
+        .iter()
+        .map(|mv| {
+            let to = resolve_conflict_excluding(&mv.to, &claimed, suffix);
+            claimed.insert(to.clone());
+            PlannedMove { to, ..mv.clone() }
+        })
+        .collect()
+}
+
+/// A planned move whose destination is already taken
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collision {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    /// Taken by a file already on disk, rather than by an earlier move in the plan
+    pub existing: bool,
+}
+
+/// Find the moves that would hit a name conflict when executed: their destination
+/// already exists, or an earlier move in the plan goes to the same path.
+/// Moves are simulated in order, so an existing file only stops counting once an
+/// earlier move has taken it away.
+pub fn find_collisions(moves: &[PlannedMove]) -> Vec<Collision> {
+    find_collisions_with(moves, |path| path.exists())
+}
+
+fn find_collisions_with<F>(moves: &[PlannedMove], exists: F) -> Vec<Collision>
+where
+    F: Fn(&Path) -> bool,
+{
+    let mut vacated: HashSet<&Path> = HashSet::new();
+    let mut claimed: HashSet<&Path> = HashSet::new();
+
+    moves
+        .iter()
+        .filter_map(|mv| {
+            let existing = !vacated.contains(mv.to.as_path()) && exists(&mv.to);
+            let first_claim = claimed.insert(mv.to.as_path());
+            // Every move goes ahead (renamed on conflict), freeing its source path
+            vacated.insert(mv.from.as_path());
+            (existing || !first_claim).then(|| Collision {
+                from: mv.from.clone(),
+                to: mv.to.clone(),
+                existing,
+            })
+        })
+        .collect()
+}
+
+/// Print how many planned moves would collide, grouped by destination folder
+pub fn print_collision_report(moves: &[PlannedMove], collisions: &[Collision], base_path: &Path) {
+    println!("\n{}", "Conflicts:".bold().cyan());
+    println!("{}", "─".repeat(60));
+
+    if collisions.is_empty() {
+        println!("  {} No destination conflicts", "✓".green());
+        return;
+    }
+
+    let mut by_folder: BTreeMap<&Path, (usize, usize)> = BTreeMap::new();
+    for collision in collisions {
+        let folder = collision.to.parent().unwrap_or(base_path);
+        let entry = by_folder.entry(folder).or_default();
+        if collision.existing {
+            entry.0 += 1;
+        } else {
+            entry.1 += 1;
+        }
+    }
+
+    for (folder, (existing, planned)) in &by_folder {
+        let folder_name = folder.strip_prefix(base_path).unwrap_or(folder);
+        println!(
+            "  {:30} {:>5} ({} already exist, {} within the plan)",
+            folder_name.display().to_string().yellow(),
+            existing + planned,
+            existing,
+            planned
+        );
+    }
+
+    println!("{}", "─".repeat(60));
+    println!(
+        "{} of {} moves would collide; many collisions often mean the wrong organize mode",
+        collisions.len().to_string().yellow().bold(),
+        moves.len()
+    );
+}
+
+/// Print only the totals for planned moves (no per-folder listing)
+pub fn print_moves_summary(moves: &[PlannedMove], units: SizeUnits) {
+    if moves.is_empty() {
+        println!("{}", "No files to move.".yellow());
+        return;
+    }
+
+    let total_size: u64 = moves.iter().map(|m| m.size).sum();
+    println!(
+        "\n{}: {} files to move ({})",
+        "Summary".bold(),
+        moves.len().to_string().cyan(),
+        units.format(total_size).cyan()
+    );
+    println!(
+        "\n{} Use {} to execute these changes.",
+        "ℹ".blue(),
+        "--execute".yellow()
+    );
+}
+
+/// Execute planned moves
+pub fn execute_moves(
+    moves: &[PlannedMove],
+    command_name: &str,
+    strategy: ConflictStrategy,
+) -> Result<OrganizeResult> {
+    execute_moves_with_options(moves, command_name, strategy, &MoveOptions::default())
+}
+
+/// Execute planned moves with extra behavior from `options`
+pub fn execute_moves_with_options(
+    moves: &[PlannedMove],
+    command_name: &str,
+    strategy: ConflictStrategy,
+    options: &MoveOptions,
+) -> Result<OrganizeResult> {
+    let (moves, escaped) = reject_escaping_moves(moves, options.confine_to);
+    let mut result = OrganizeResult {
+        skipped: escaped.len(),
+        errors: escaped,
+        ..Default::default()
+    };
+    if moves.is_empty() {
+        return Ok(result);
+    }
+    if options.atomic {
+        let mut atomic = execute_moves_atomic(&moves, command_name, strategy, options)?;
+        atomic.skipped += result.skipped;
+        atomic.errors.splice(0..0, result.errors);
+        return Ok(atomic);
+    }
+
+    let pb = progress_bar(moves.len() as u64, "Moving", MOVE_PROGRESS_TEMPLATE);
+
+    let mut logger = Logger::new(command_name);
+    let mut created_dirs = HashSet::new();
+    let mut throttle = Throttle::new(options.throttle);
+
+    // Only files sharing a size with another move can be identical, so only those are hashed
+    let shared_sizes = if options.link_duplicates {
+        shared_sizes(&moves)
+    } else {
+        HashSet::new()
+    };
+    let mut placed: HashMap<ContentKey, PathBuf> = HashMap::new();
+    let mut moved_from = Vec::new();
+
+    for mv in moves.iter() {
+        pb.inc(1);
+        throttle.wait();
+
+        // Create parent directory if needed
+        if let Err(e) = ensure_parent_dir(&mv.to, &mut created_dirs) {
+            if options.fail_fast {
+                return abort_batch(&pb, logger, e);
+            }
+            result.skipped += 1;
+            result
+                .errors
+                .push(format!("{}: {:#}", mv.from.display(), e));
+            continue;
+        }
+
+        // Handle name conflicts based on strategy; a destination that is the source
+        // itself under a folded name is not a conflict
+        let final_dest = if is_folded_name(&mv.from, &mv.to) {
+            mv.to.clone()
+        } else {
+            match resolve_conflict_with_strategy(&mv.to, strategy, options.suffix(), &pb) {
+                Some(dest) => dest,
+                None => {
+                    // Skip was chosen
+                    log::debug!("skip from={:?} reason=conflict", mv.from);
+                    result.skipped += 1;
+                    continue;
+                }
+            }
+        };
+
+        let key = if shared_sizes.contains(&mv.size) {
+            content_key(mv, &final_dest)
+        } else {
+            None
+        };
+        let linked = match key.as_ref().and_then(|key| placed.get(key)) {
+            Some(original) => match link_in_place(original, &mv.from, &final_dest) {
+                Ok(_) => true,
+                Err(e) => {
+                    // e.g. a hash collision or no hard links here; fall back to a plain move
+                    log::warn!(
+                        "hard link failed from={:?} to={:?} error={:?}",
+                        original,
+                        final_dest,
+                        e.to_string()
+                    );
+                    false
+                }
+            },
+            None => false,
+        };
+
+        // Move the file
+        let moved = if linked {
+            Ok(())
+        } else {
+            move_file(&mv.from, &final_dest)
+        };
+        match moved {
+            Ok(_) => {
+                if linked {
+                    log::info!("linked duplicate from={:?} to={:?}", mv.from, final_dest);
+                    result.deduplicated += 1;
+                } else {
+                    log::info!("moved from={:?} to={:?}", mv.from, final_dest);
+                    if let Some(key) = key {
+                        placed.insert(key, final_dest.clone());
+                    }
+                }
+                result.moved += 1;
                 result.total_size += mv.size;
-                logger.log_move(mv.from.clone(), final_dest);
+                result.done.push(PlannedMove {
+                    to: final_dest.clone(),
+                    ..mv.clone()
+                });
+                moved_from.push(mv.from.clone());
+                logger.log_move(mv.from.clone(), final_dest.clone());
+
+                if options.leave_symlink {
+                    match create_symlink(&final_dest, &mv.from) {
+                        Ok(_) => logger.log_symlink(mv.from.clone(), final_dest),
+                        Err(e) => result.errors.push(format!(
+                            "{}: moved, but could not leave symlink: {}",
+                            mv.from.display(),
+                            e
+                        )),
+                    }
+                }
+
+                if result.moved.is_multiple_of(CHECKPOINT_INTERVAL) {
+                    logger.checkpoint()?;
+                }
             }
             Err(e) => {
+                log::warn!(
+                    "move failed from={:?} to={:?} error={:?}",
+                    mv.from,
+                    final_dest,
+                    e.to_string()
+                );
+                if options.fail_fast {
+                    let e = anyhow::Error::from(e)
+                        .context(format!("Failed to move {}", mv.from.display()));
+                    return abort_batch(&pb, logger, e);
+                }
                 result.skipped += 1;
                 result.errors.push(format!("{}: {}", mv.from.display(), e));
             }
@@ -334,63 +1256,482 @@ pub fn execute_moves(
     }
 
     pb.finish_and_clear();
+    if let Some(root) = options.delete_empty_in {
+        remove_vacated_dirs(&moved_from, root, &mut result, &mut logger);
+    }
+    if let Some((root, bucket)) = options.collect_empty_in {
+        collect_empty_dirs(
+            &moved_from,
+            root,
+            bucket,
+            options.suffix(),
+            &mut result,
+            &mut logger,
+        );
+    }
+    for dir in options.remove_if_empty {
+        remove_empty_dirs(dir, &mut result, &mut logger);
+    }
+    logger.save()?;
+
+    Ok(result)
+}
+
+/// Destination folder, size and content hash of a moved file
+type ContentKey = (PathBuf, u64, u64);
+
+/// Sizes that more than one move in the batch has
+fn shared_sizes(moves: &[PlannedMove]) -> HashSet<u64> {
+    let mut seen = HashSet::new();
+    moves
+        .iter()
+        .filter(|mv| !seen.insert(mv.size))
+        .map(|mv| mv.size)
+        .collect()
+}
+
+/// Key under which a file moving to `dest` is matched against identical files
+/// already placed in the same folder (None if it can't be read)
+fn content_key(mv: &PlannedMove, dest: &Path) -> Option<ContentKey> {
+    let folder = dest.parent()?.to_path_buf();
+    let hash = hash_file(&mv.from).ok()?;
+    Some((folder, mv.size, hash))
+}
+
+/// Put `from` at `dest` as a hard link to `original`, then remove `from`. The two
+/// are compared byte for byte first, since a matching hash alone doesn't prove
+/// they're identical. Undoing it like a move restores `from` with the same content.
+fn link_in_place(original: &Path, from: &Path, dest: &Path) -> Result<()> {
+    if !files_are_equal(original, from)? {
+        anyhow::bail!("contents differ from {}", original.display());
+    }
+    fs::hard_link(original, dest)?;
+    if let Err(e) = fs::remove_file(from) {
+        let _ = fs::remove_file(dest);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Split off moves whose destination resolves outside `root`, returning the rest
+/// and an error message for each rejected move
+fn reject_escaping_moves<'a>(
+    moves: &'a [PlannedMove],
+    root: Option<&Path>,
+) -> (Cow<'a, [PlannedMove]>, Vec<String>) {
+    let Some(root) = root else {
+        return (Cow::Borrowed(moves), Vec::new());
+    };
+    let root = resolve_path(root);
+
+    let mut kept = Vec::with_capacity(moves.len());
+    let mut errors = Vec::new();
+    for mv in moves {
+        if resolve_path(&mv.to).starts_with(&root) {
+            kept.push(mv.clone());
+        } else {
+            errors.push(format!(
+                "{}: destination {} is outside {}, refusing to move it",
+                mv.from.display(),
+                mv.to.display(),
+                root.display()
+            ));
+        }
+    }
+    (Cow::Owned(kept), errors)
+}
+
+/// Resolve `.`, `..` and symlinks in a path that may not exist yet: each existing
+/// prefix is canonicalized, and `..` steps up from wherever that prefix really is
+fn resolve_path(path: &Path) -> PathBuf {
+    let mut resolved = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            other => {
+                resolved.push(other);
+                if let Ok(real) = resolved.canonicalize() {
+                    resolved = real;
+                }
+            }
+        }
+    }
+    resolved
+}
+
+/// The folders under `root` (not `root` itself) that moving files out of `sources`
+/// emptied: each source's folder and its parents, when nothing is left in them but
+/// other such folders. Deepest first. Folders that were already empty, and anything
+/// inside a hidden folder, are never included.
+fn vacated_dirs(sources: &[PathBuf], root: &Path) -> Vec<PathBuf> {
+    let hidden = |dir: &Path| {
+        dir.file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+    };
+    let mut candidates: Vec<PathBuf> = sources
+        .iter()
+        .filter_map(|source| source.parent())
+        .filter(|dir| dir.starts_with(root))
+        .filter(|dir| !dir.ancestors().take_while(|d| *d != root).any(hidden))
+        .flat_map(|dir| dir.ancestors().take_while(|d| *d != root))
+        .map(Path::to_path_buf)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    candidates.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+
+    // Children come before their parents, so a parent holding only vacated folders counts too
+    let mut vacated = HashSet::new();
+    let mut ordered = Vec::new();
+    for dir in candidates {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        if entries
+            .into_iter()
+            .all(|entry| entry.is_ok_and(|entry| vacated.contains(&entry.path())))
+        {
+            vacated.insert(dir.clone());
+            ordered.push(dir);
+        }
+    }
+    ordered
+}
+
+/// Remove the folders under `root` that this batch's moves left empty (see
+/// `vacated_dirs`), logging each so `undo` can recreate it
+fn remove_vacated_dirs(
+    sources: &[PathBuf],
+    root: &Path,
+    result: &mut OrganizeResult,
+    logger: &mut Logger,
+) {
+    for dir in vacated_dirs(sources, root) {
+        match fs::remove_dir(&dir) {
+            Ok(_) => {
+                result.removed_dirs += 1;
+                logger.log_remove_dir(dir);
+            }
+            Err(e) => result.errors.push(format!("{}: {}", dir.display(), e)),
+        }
+    }
+}
+
+/// Remove `root` and the folders under it that have nothing left in them, logging
+/// each so `undo` can recreate it
+fn remove_empty_dirs(root: &Path, result: &mut OrganizeResult, logger: &mut Logger) {
+    let empty_dirs = match find_empty_dirs(root, None) {
+        Ok(dirs) => dirs,
+        Err(e) => {
+            result.errors.push(format!(
+                "{}: could not look for empty folders: {}",
+                root.display(),
+                e
+            ));
+            return;
+        }
+    };
+
+    // Children are listed before their parents, so each folder is empty when removed
+    for dir in empty_dirs {
+        match fs::remove_dir(&dir) {
+            Ok(_) => {
+                result.removed_dirs += 1;
+                logger.log_remove_dir(dir);
+            }
+            Err(e) => result.errors.push(format!("{}: {}", dir.display(), e)),
+        }
+    }
+}
+
+/// Move the folders under `root` that this batch's moves left empty (see
+/// `vacated_dirs`) into `bucket`, at the same path relative to `root`. Only the
+/// outermost such folder of a branch is moved, taking its empty subfolders with it;
+/// each is logged as a move so `undo` puts it back. A folder already in the bucket
+/// is kept by renaming the newcomer with `suffix`.
+fn collect_empty_dirs(
+    sources: &[PathBuf],
+    root: &Path,
+    bucket: &Path,
+    suffix: &CollisionSuffix,
+    result: &mut OrganizeResult,
+    logger: &mut Logger,
+) {
+    let empty_dirs = vacated_dirs(sources, root);
+    let empty: HashSet<&Path> = empty_dirs.iter().map(PathBuf::as_path).collect();
+
+    for dir in &empty_dirs {
+        let nested = dir
+            .parent()
+            .is_some_and(|parent| parent != root && empty.contains(parent));
+        if nested || dir.starts_with(bucket) || bucket.starts_with(dir) {
+            continue;
+        }
+        let Ok(relative) = dir.strip_prefix(root) else {
+            continue;
+        };
+
+        let dest = resolve_conflict(&bucket.join(relative), suffix);
+        let moved = match dest.parent() {
+            Some(parent) => fs::create_dir_all(parent).and_then(|_| fs::rename(dir, &dest)),
+            None => fs::rename(dir, &dest),
+        };
+        match moved {
+            Ok(_) => {
+                log::info!("moved empty folder from={:?} to={:?}", dir, dest);
+                result.moved_dirs += 1;
+                logger.log_move(dir.clone(), dest);
+            }
+            Err(e) => result.errors.push(format!("{}: {}", dir.display(), e)),
+        }
+    }
+}
+
+/// Execute planned moves all-or-nothing
+///
+/// 1. Every destination is resolved up front (only `Skip` and `Rename` are allowed,
+///    since overwriting can't be undone).
+/// 2. Every file is moved into a hidden staging folder under the closest folder
+///    shared by all sources, so staging never crosses filesystems.
+/// 3. Staged files are moved to their destinations.
+///
+/// If any step fails, files already staged or placed are moved back to where they
+/// started, folders created for the run are removed if empty, and nothing is written
+/// to history. History is only recorded once every move has succeeded, after which
+/// folders under `options.delete_empty_in` left empty are removed. Of the other
+/// options, only `collision_suffix` applies.
+pub fn execute_moves_atomic(
+    moves: &[PlannedMove],
+    command_name: &str,
+    strategy: ConflictStrategy,
+    options: &MoveOptions,
+) -> Result<OrganizeResult> {
+    if !matches!(strategy, ConflictStrategy::Skip | ConflictStrategy::Rename) {
+        anyhow::bail!("Atomic moves only support the 'skip' and 'rename' conflict strategies");
+    }
+    if moves.is_empty() {
+        return Ok(OrganizeResult::default());
+    }
+
+    let mut result = OrganizeResult::default();
+
+    // Resolve destinations against the disk and each other before touching anything
+    let mut claimed = HashSet::new();
+    let mut planned = Vec::new();
+    for mv in moves {
+        let conflict = claimed.contains(&mv.to) || mv.to.exists();
+        if conflict && strategy == ConflictStrategy::Skip {
+            result.skipped += 1;
+            continue;
+        }
+        let dest = resolve_conflict_excluding(&mv.to, &claimed, options.suffix());
+        claimed.insert(dest.clone());
+        planned.push((mv, dest));
+    }
+
+    let root = common_ancestor(planned.iter().map(|(mv, _)| mv.from.as_path()))
+        .context("Sources share no common folder to stage in")?;
+    let staging = root.join(format!(".neat-staging-{}", std::process::id()));
+    fs::create_dir(&staging)
+        .with_context(|| format!("Failed to create staging folder: {:?}", staging))?;
+
+    let pb = progress_bar(planned.len() as u64 * 2, "Moving", MOVE_PROGRESS_TEMPLATE);
+
+    // Where each file currently is, so a failure can put it back
+    let mut locations: Vec<PathBuf> = Vec::with_capacity(planned.len());
+    let mut created_dirs = Vec::new();
+
+    let outcome = (|| -> Result<()> {
+        for (i, (mv, _)) in planned.iter().enumerate() {
+            let staged = staging.join(i.to_string());
+            move_file(&mv.from, &staged)
+                .with_context(|| format!("Failed to stage {}", mv.from.display()))?;
+            locations.push(staged);
+            pb.inc(1);
+        }
+
+        for (i, (_, dest)) in planned.iter().enumerate() {
+            if let Some(parent) = dest.parent() {
+                create_dirs_recording(parent, &mut created_dirs)?;
+            }
+            move_file(&locations[i], dest)
+                .with_context(|| format!("Failed to move into place {}", dest.display()))?;
+            locations[i] = dest.clone();
+            pb.inc(1);
+        }
+        Ok(())
+    })();
+
+    pb.finish_and_clear();
+
+    if let Err(e) = outcome {
+        let mut stranded = Vec::new();
+        for (location, (mv, _)) in locations.iter().zip(&planned).rev() {
+            if move_file(location, &mv.from).is_err() {
+                stranded.push(location.display().to_string());
+            }
+        }
+        for dir in created_dirs.iter().rev() {
+            let _ = fs::remove_dir(dir);
+        }
+        if stranded.is_empty() {
+            let _ = fs::remove_dir(&staging);
+            return Err(e.context(
+                "Atomic organize failed; all files were returned to their original locations",
+            ));
+        }
+        return Err(e.context(format!(
+            "Atomic organize failed and {} files could not be returned: {}",
+            stranded.len(),
+            stranded.join(", ")
+        )));
+    }
+
+    fs::remove_dir(&staging)
+        .with_context(|| format!("Failed to remove staging folder: {:?}", staging))?;
+
+    let mut logger = Logger::new(command_name);
+    for (mv, dest) in &planned {
+        result.moved += 1;
+        result.total_size += mv.size;
+        result.done.push(PlannedMove {
+            to: dest.clone(),
+            ..(*mv).clone()
+        });
+        logger.log_move(mv.from.clone(), dest.clone());
+    }
+    if let Some(root) = options.delete_empty_in {
+        let moved_from: Vec<PathBuf> = planned.iter().map(|(mv, _)| mv.from.clone()).collect();
+        remove_vacated_dirs(&moved_from, root, &mut result, &mut logger);
+    }
     logger.save()?;
 
     Ok(result)
 }
 
+/// Create `dir` and any missing ancestors, recording each one created (outermost first)
+fn create_dirs_recording(dir: &Path, created: &mut Vec<PathBuf>) -> Result<()> {
+    if dir.is_dir() {
+        return Ok(());
+    }
+    if let Some(parent) = dir.parent() {
+        create_dirs_recording(parent, created)?;
+    }
+    fs::create_dir(dir).with_context(|| format!("Failed to create directory: {:?}", dir))?;
+    created.push(dir.to_path_buf());
+    Ok(())
+}
+
+/// The deepest folder containing every path
+pub fn common_ancestor<'a>(paths: impl IntoIterator<Item = &'a Path>) -> Option<PathBuf> {
+    let mut common: Option<PathBuf> = None;
+    for path in paths {
+        let dir = path.parent()?;
+        common = Some(match common {
+            None => dir.to_path_buf(),
+            Some(current) => current
+                .components()
+                .zip(dir.components())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+    common.filter(|c| !c.as_os_str().is_empty())
+}
+
 /// Execute planned copies (copy instead of move)
 pub fn execute_copies(
     moves: &[PlannedMove],
     command_name: &str,
     strategy: ConflictStrategy,
 ) -> Result<OrganizeResult> {
+    execute_copies_with_options(moves, command_name, strategy, &MoveOptions::default())
+}
+
+/// Execute planned copies with extra behavior from `options` (`leave_symlink` is ignored)
+pub fn execute_copies_with_options(
+    moves: &[PlannedMove],
+    command_name: &str,
+    strategy: ConflictStrategy,
+    options: &MoveOptions,
+) -> Result<OrganizeResult> {
+    let (moves, escaped) = reject_escaping_moves(moves, options.confine_to);
+    let mut result = OrganizeResult {
+        skipped: escaped.len(),
+        errors: escaped,
+        ..Default::default()
+    };
     if moves.is_empty() {
-        return Ok(OrganizeResult::default());
+        return Ok(result);
     }
 
-    let pb = ProgressBar::new(moves.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template(
-                "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
-            )
-            .unwrap()
-            .progress_chars("█▓░"),
-    );
+    let pb = progress_bar(moves.len() as u64, "Copying", MOVE_PROGRESS_TEMPLATE);
 
-    let mut result = OrganizeResult::default();
     let mut logger = Logger::new(command_name);
+    let mut created_dirs = HashSet::new();
+    let mut throttle = Throttle::new(options.throttle);
 
-    for mv in moves {
+    for mv in moves.iter() {
         pb.inc(1);
+        throttle.wait();
 
         // Create parent directory if needed
-        if let Some(parent) = mv.to.parent() {
-            if !parent.exists() {
-                fs::create_dir_all(parent)
-                    .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+        if let Err(e) = ensure_parent_dir(&mv.to, &mut created_dirs) {
+            if options.fail_fast {
+                return abort_batch(&pb, logger, e);
             }
+            result.skipped += 1;
+            result
+                .errors
+                .push(format!("{}: {:#}", mv.from.display(), e));
+            continue;
         }
 
         // Handle name conflicts based on strategy
-        let final_dest = match resolve_conflict_with_strategy(&mv.to, strategy, &pb) {
-            Some(dest) => dest,
-            None => {
-                // Skip was chosen
-                result.skipped += 1;
-                continue;
-            }
-        };
+        let final_dest =
+            match resolve_conflict_with_strategy(&mv.to, strategy, options.suffix(), &pb) {
+                Some(dest) => dest,
+                None => {
+                    // Skip was chosen
+                    result.skipped += 1;
+                    continue;
+                }
+            };
 
         // Copy the file instead of moving
         match fs::copy(&mv.from, &final_dest) {
             Ok(_) => {
                 result.moved += 1; // reusing 'moved' for 'copied' count
                 result.total_size += mv.size;
+                result.done.push(PlannedMove {
+                    to: final_dest.clone(),
+                    ..mv.clone()
+                });
+                if options.preserve_times {
+                    if let Err(e) = copy_file_times(&mv.from, &final_dest) {
+                        result.errors.push(format!(
+                            "{}: copied but timestamps not preserved: {}",
+                            mv.from.display(),
+                            e
+                        ));
+                    }
+                }
                 logger.log_move(mv.from.clone(), final_dest);
+
+                if result.moved.is_multiple_of(CHECKPOINT_INTERVAL) {
+                    logger.checkpoint()?;
+                }
             }
             Err(e) => {
+                if options.fail_fast {
+                    let e = anyhow::Error::from(e)
+                        .context(format!("Failed to copy {}", mv.from.display()));
+                    return abort_batch(&pb, logger, e);
+                }
                 result.skipped += 1;
                 result.errors.push(format!("{}: {}", mv.from.display(), e));
             }
@@ -403,33 +1744,251 @@ pub fn execute_copies(
     Ok(result)
 }
 
-/// Resolve filename conflicts with a specific strategy
-/// Returns None if the file should be skipped
-fn resolve_conflict_with_strategy(
-    path: &Path,
+/// Create a link at each destination pointing back at the original, building an
+/// organized view while every file stays where it is
+///
+/// Links never replace existing files, whatever `strategy` says; a taken destination
+/// is only renamed (`Rename`) or skipped. Each link and each folder created for the
+/// view is logged so `undo` removes the whole tree. Only `fail_fast` and `confine_to`
+/// in `options` apply.
+pub fn execute_links_with_options(
+    moves: &[PlannedMove],
+    command_name: &str,
+    kind: LinkKind,
     strategy: ConflictStrategy,
-    pb: &ProgressBar,
-) -> Option<PathBuf> {
-    if !path.exists() {
-        return Some(path.to_path_buf());
+    options: &MoveOptions,
+) -> Result<OrganizeResult> {
+    let (moves, escaped) = reject_escaping_moves(moves, options.confine_to);
+    let mut result = OrganizeResult {
+        skipped: escaped.len(),
+        errors: escaped,
+        ..Default::default()
+    };
+    if moves.is_empty() {
+        return Ok(result);
     }
 
-    match strategy {
-        ConflictStrategy::Skip => {
-            // Don't move/copy, skip this file
-            None
-        }
-        ConflictStrategy::Overwrite => {
-            // Return the same path, fs::rename/fs::copy will overwrite
-            Some(path.to_path_buf())
+    let pb = progress_bar(moves.len() as u64, "Linking", MOVE_PROGRESS_TEMPLATE);
+
+    let mut logger = Logger::new(command_name);
+    let mut throttle = Throttle::new(options.throttle);
+
+    for mv in moves.iter() {
+        pb.inc(1);
+        throttle.wait();
+
+        // Record new folders so undo can take the view down again
+        let mut created_dirs = Vec::new();
+        let created = match mv.to.parent() {
+            Some(parent) => create_dirs_recording(parent, &mut created_dirs),
+            None => Ok(()),
+        };
+        for dir in created_dirs {
+            logger.log_create_dir(dir);
         }
-        ConflictStrategy::Rename => {
-            // Add suffix like _1, _2, etc.
-            Some(resolve_conflict(path))
+        if let Err(e) = created {
+            if options.fail_fast {
+                return abort_batch(&pb, logger, e);
+            }
+            result.skipped += 1;
+            result
+                .errors
+                .push(format!("{}: {:#}", mv.from.display(), e));
+            continue;
+        }
+
+        let exists = |path: &Path| fs::symlink_metadata(path).is_ok();
+        let final_dest = if !exists(&mv.to) {
+            mv.to.clone()
+        } else if strategy == ConflictStrategy::Rename {
+            resolve_conflict(&mv.to, options.suffix())
+        } else {
+            result.skipped += 1;
+            continue;
+        };
+
+        let linked = match kind {
+            LinkKind::Symlink => create_symlink(&mv.from, &final_dest),
+        };
+        match linked {
+            Ok(_) => {
+                result.linked += 1;
+                result.total_size += mv.size;
+                result.done.push(PlannedMove {
+                    to: final_dest.clone(),
+                    ..mv.clone()
+                });
+                logger.log_link(final_dest, mv.from.clone());
+
+                if result.linked.is_multiple_of(CHECKPOINT_INTERVAL) {
+                    logger.checkpoint()?;
+                }
+            }
+            Err(e) => {
+                let e = anyhow::Error::from(e).context(format!(
+                    "Failed to link {} at {}",
+                    mv.from.display(),
+                    final_dest.display()
+                ));
+                if options.fail_fast {
+                    return abort_batch(&pb, logger, e);
+                }
+                result.skipped += 1;
+                result.errors.push(format!("{:#}", e));
+            }
+        }
+    }
+
+    pb.finish_and_clear();
+    logger.save()?;
+
+    Ok(result)
+}
+
+/// Stop a batch at its first error, saving the operations completed so far
+/// so `undo` can still revert them
+fn abort_batch(pb: &ProgressBar, logger: Logger, error: anyhow::Error) -> Result<OrganizeResult> {
+    pb.finish_and_clear();
+    let done = logger.count();
+    logger.save()?;
+    Err(error.context(format!(
+        "Stopped at the first error ({} operations completed and recorded for undo)",
+        done
+    )))
+}
+
+/// Make sure the parent directory of `path` exists, checking each directory only once
+/// per batch. Returns true if the filesystem was consulted.
+fn ensure_parent_dir(path: &Path, created_dirs: &mut HashSet<PathBuf>) -> Result<bool> {
+    let parent = match path.parent() {
+        Some(parent) => parent,
+        None => return Ok(false),
+    };
+
+    if created_dirs.contains(parent) {
+        return Ok(false);
+    }
+
+    if !parent.exists() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {:?}", parent))?;
+    }
+    created_dirs.insert(parent.to_path_buf());
+
+    Ok(true)
+}
+
+/// Move a file with `fs::rename`, or, when `to` is on another filesystem (e.g. an
+/// --output-dir on another drive), copy it over and remove `from`
+pub fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == std::io::ErrorKind::CrossesDevices => copy_and_remove(from, to),
+        moved => moved,
+    }
+}
+
+/// Copy `from` to `to` with its times, flush the copy to disk and remove `from`.
+/// If any step fails, whatever was written to `to` is removed and `from` is left as it was.
+fn copy_and_remove(from: &Path, to: &Path) -> std::io::Result<()> {
+    let copied = fs::copy(from, to)
+        .and_then(|_| copy_file_times(from, to))
+        .and_then(|_| fs::OpenOptions::new().write(true).open(to)?.sync_all())
+        .and_then(|_| fs::remove_file(from));
+    if copied.is_err() {
+        let _ = fs::remove_file(to);
+    }
+    copied
+}
+
+/// Whether `to` only "exists" because the filesystem folds its name onto `from`
+/// (case- or normalization-insensitive volumes), so moving there renames in place
+fn is_folded_name(from: &Path, to: &Path) -> bool {
+    if from == to {
+        return false;
+    }
+    match (file_identity(from), file_identity(to)) {
+        (Some(a), Some(b)) if a == b => {}
+        _ => return false,
+    }
+    // A hard link under the exact name is a real entry, not a folded lookup
+    let (Some(parent), Some(name)) = (to.parent(), to.file_name()) else {
+        return false;
+    };
+    fs::read_dir(parent).is_ok_and(|entries| {
+        !entries
+            .filter_map(|entry| entry.ok())
+            .any(|entry| entry.file_name() == name)
+    })
+}
+
+/// Set the modified and accessed times of `dest` to those of `src`
+fn copy_file_times(src: &Path, dest: &Path) -> std::io::Result<()> {
+    let metadata = fs::metadata(src)?;
+    let mtime = filetime::FileTime::from_last_modification_time(&metadata);
+    let atime = filetime::FileTime::from_last_access_time(&metadata);
+    filetime::set_file_times(dest, atime, mtime)
+}
+
+/// Create a symlink at `link` pointing to `target`
+#[cfg(unix)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Create a symlink at `link` pointing to `target`
+/// (requires Developer Mode or administrator rights on Windows)
+#[cfg(windows)]
+fn create_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    const ERROR_PRIVILEGE_NOT_HELD: i32 = 1314;
+    std::os::windows::fs::symlink_file(target, link).map_err(|e| {
+        if e.raw_os_error() == Some(ERROR_PRIVILEGE_NOT_HELD) {
+            std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                "creating symlinks needs Developer Mode or administrator rights on Windows",
+            )
+        } else {
+            e
+        }
+    })
+}
+
+/// Symlinks are not supported on this platform
+#[cfg(not(any(unix, windows)))]
+fn create_symlink(_target: &Path, _link: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "symlinks are not supported on this platform",
+    ))
+}
+
+/// Resolve filename conflicts with a specific strategy
+/// Returns None if the file should be skipped
+fn resolve_conflict_with_strategy(
+    path: &Path,
+    strategy: ConflictStrategy,
+    suffix: &CollisionSuffix,
+    pb: &ProgressBar,
+) -> Option<PathBuf> {
+    if !path.exists() {
+        return Some(path.to_path_buf());
+    }
+
+    match strategy {
+        ConflictStrategy::Skip => {
+            // Don't move/copy, skip this file
+            None
+        }
+        ConflictStrategy::Overwrite => {
+            // Return the same path, fs::rename/fs::copy will overwrite
+            Some(path.to_path_buf())
+        }
+        ConflictStrategy::Rename => {
+            // Add suffix like _1, _2, etc.
+            Some(resolve_conflict(path, suffix))
         }
         ConflictStrategy::Ask => {
             // Pause progress bar and ask user
-            pb.suspend(|| ask_conflict_resolution(path))
+            pb.suspend(|| ask_conflict_resolution(path, suffix))
         }
         ConflictStrategy::Deduplicate => {
             // For Deduplicate, we'll return the path but handle dedup logic in execute_moves
@@ -445,228 +2004,1469 @@ fn resolve_conflict_with_strategy(
     }
 }
 
-/// Interactive conflict resolution
-fn ask_conflict_resolution(path: &Path) -> Option<PathBuf> {
-    use std::io::{self, Write};
+/// Interactive conflict resolution
+fn ask_conflict_resolution(path: &Path, suffix: &CollisionSuffix) -> Option<PathBuf> {
+    use std::io::{self, Write};
+
+    let filename = path.file_name().unwrap_or_default().to_string_lossy();
+
+    println!(
+        "\n{} File already exists: {}",
+        "⚠".yellow(),
+        filename.bold()
+    );
+    print!("  [s]kip, [o]verwrite, [r]ename? ");
+    io::stdout().flush().ok();
+
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input).is_err() {
+        return None; // Skip on error
+    }
+
+    match input.trim().to_lowercase().chars().next() {
+        Some('s') => None,
+        Some('o') => Some(path.to_path_buf()),
+        Some('r') => Some(resolve_conflict(path, suffix)),
+        _ => None, // Default to skip
+    }
+}
+
+/// Resolve filename conflicts by adding a suffix (see `CollisionSuffix`)
+fn resolve_conflict(path: &Path, suffix: &CollisionSuffix) -> PathBuf {
+    resolve_conflict_excluding(path, &HashSet::new(), suffix)
+}
+
+/// Resolve filename conflicts by adding a suffix, also avoiding `claimed`
+/// paths that earlier moves in the same batch will occupy
+fn resolve_conflict_excluding(
+    path: &Path,
+    claimed: &HashSet<PathBuf>,
+    suffix: &CollisionSuffix,
+) -> PathBuf {
+    let taken = |p: &Path| p.exists() || claimed.contains(p);
+    if !taken(path) {
+        return path.to_path_buf();
+    }
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let parent = path.parent().unwrap_or(Path::new("."));
+    let now = chrono::Local::now();
+
+    let mut attempt = 1;
+    loop {
+        let new_path = parent.join(suffix.file_name(&stem, &extension, attempt, now));
+        if !taken(&new_path) {
+            return new_path;
+        }
+        attempt += 1;
+    }
+}
+
+/// Backup a file to ~/.neat/versions/
+fn backup_file(path: &Path) -> anyhow::Result<PathBuf> {
+    use chrono::Local;
+
+    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("No home directory"))?;
+    let date = Local::now().format("%Y-%m-%d").to_string();
+    let backup_dir = home.join(".neat").join("versions").join(&date);
+
+    fs::create_dir_all(&backup_dir)?;
+
+    let filename = path.file_name().unwrap_or_default();
+    let backup_path = backup_dir.join(filename);
+
+    // If backup already exists, add suffix
+    let final_backup = if backup_path.exists() {
+        resolve_conflict(&backup_path, &DEFAULT_COLLISION_SUFFIX)
+    } else {
+        backup_path
+    };
+
+    fs::copy(path, &final_backup)?;
+    Ok(final_backup)
+}
+
+/// Print organize results
+pub fn print_results(result: &OrganizeResult, units: SizeUnits) {
+    println!("\n{}", "Results:".bold().green());
+    println!("{}", "─".repeat(40));
+
+    if result.moved > 0 {
+        println!(
+            "  {} {} files moved ({})",
+            "✓".green(),
+            result.moved.to_string().green(),
+            units.format(result.total_size).dimmed()
+        );
+    }
+
+    if result.copied > 0 {
+        println!(
+            "  {} {} files copied",
+            "✓".green(),
+            result.copied.to_string().green()
+        );
+    }
+
+    if result.linked > 0 {
+        println!(
+            "  {} {} files linked ({})",
+            "✓".green(),
+            result.linked.to_string().green(),
+            units.format(result.total_size).dimmed()
+        );
+    }
+
+    if result.deduplicated > 0 {
+        println!(
+            "  {} {} identical files hard-linked instead of stored twice",
+            "✓".cyan(),
+            result.deduplicated.to_string().cyan()
+        );
+    }
+
+    if result.backed_up > 0 {
+        println!(
+            "  {} {} files backed up to ~/.neat/versions/",
+            "↻".blue(),
+            result.backed_up.to_string().blue()
+        );
+    }
+
+    if result.removed_dirs > 0 {
+        println!(
+            "  {} {} empty folders removed",
+            "✓".cyan(),
+            result.removed_dirs.to_string().cyan()
+        );
+    }
+
+    if result.moved_dirs > 0 {
+        println!(
+            "  {} {} empty folders moved to {}/",
+            "✓".cyan(),
+            result.moved_dirs.to_string().cyan(),
+            EMPTY_FOLDER
+        );
+    }
+
+    if result.skipped > 0 {
+        println!(
+            "  {} {} files skipped",
+            "⚠".yellow(),
+            result.skipped.to_string().yellow()
+        );
+    }
+
+    if !result.errors.is_empty() {
+        println!("\n  {}", "Errors:".red());
+        for error in result.errors.iter().take(5) {
+            println!("    {} {}", "✗".red(), error);
+        }
+        if result.errors.len() > 5 {
+            println!("    ... and {} more errors", result.errors.len() - 5);
+        }
+    }
+}
+
+/// Append a timestamped summary of an executed run to the report file at `path`,
+/// creating it if missing. Only the files the run actually placed are listed
+pub fn append_report(
+    path: &Path,
+    command_name: &str,
+    base_path: &Path,
+    result: &OrganizeResult,
+    units: SizeUnits,
+) -> Result<()> {
+    use std::fmt::Write as _;
+    use std::io::Write as _;
+
+    let mut entry = String::new();
+    writeln!(
+        entry,
+        "=== {} | {} ===",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        command_name
+    )?;
+    writeln!(entry, "Path:      {}", base_path.display())?;
+    writeln!(
+        entry,
+        "Processed: {} files ({})",
+        result.done.len(),
+        units.format(result.total_size)
+    )?;
+    writeln!(entry, "Skipped:   {}", result.skipped)?;
+    writeln!(entry, "Errors:    {}", result.errors.len())?;
+    writeln!(entry, "Folders:")?;
+    for (folder, files) in group_by_folder(&result.done, base_path) {
+        let folder_name = folder.strip_prefix(base_path).unwrap_or(&folder);
+        let size: u64 = files.iter().map(|m| m.size).sum();
+        writeln!(
+            entry,
+            "  {} ({} files, {})",
+            folder_name.display(),
+            files.len(),
+            units.format(size)
+        )?;
+    }
+    writeln!(entry)?;
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open report file: {:?}", path))?;
+    file.write_all(entry.as_bytes())
+        .with_context(|| format!("Failed to write report file: {:?}", path))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    fn make_file_info(name: &str, ext: Option<&str>, size: u64) -> FileInfo {
+        FileInfo {
+            path: PathBuf::from(format!("/test/{}", name)),
+            name: name.to_string(),
+            extension: ext.map(|s| s.to_string()),
+            extension_raw: Path::new(name)
+                .extension()
+                .map(|e| e.to_string_lossy().to_string()),
+            size,
+            modified: SystemTime::now(),
+            created: None,
+        }
+    }
+
+    #[test]
+    fn test_plan_moves_by_type() {
+        let files = vec![
+            make_file_info("photo.jpg", Some("jpg"), 1000),
+            make_file_info("doc.pdf", Some("pdf"), 2000),
+            make_file_info("song.mp3", Some("mp3"), 3000),
+        ];
+
+        let base = Path::new("/base");
+        let moves = plan_moves(&files, base, OrganizeMode::ByType);
+
+        assert_eq!(moves.len(), 3);
+
+        // Check destinations contain correct category folders
+        assert!(moves[0].to.to_string_lossy().contains("Images"));
+        assert!(moves[1].to.to_string_lossy().contains("Documents"));
+        assert!(moves[2].to.to_string_lossy().contains("Audio"));
+    }
+
+    #[test]
+    fn test_plan_moves_by_extension() {
+        let files = vec![
+            make_file_info("a.txt", Some("txt"), 100),
+            make_file_info("b.txt", Some("txt"), 200),
+            make_file_info("c.py", Some("py"), 300),
+        ];
+
+        let base = Path::new("/base");
+        let moves = plan_moves(&files, base, OrganizeMode::ByExtension);
+
+        assert_eq!(moves.len(), 3);
+
+        // Check destinations use uppercase extension folders
+        assert_eq!(moves[0].to, PathBuf::from("/base/TXT/a.txt"));
+        assert_eq!(moves[1].to, PathBuf::from("/base/TXT/b.txt"));
+        assert_eq!(moves[2].to, PathBuf::from("/base/PY/c.py"));
+    }
+
+    #[test]
+    fn test_plan_moves_by_first_letter() {
+        let files = vec![
+            make_file_info("arial.ttf", Some("ttf"), 1),
+            make_file_info("Brush Script.otf", Some("otf"), 1),
+            make_file_info("3dumb.ttf", Some("ttf"), 1),
+            make_file_info("Ärger.epub", Some("epub"), 1),
+        ];
+
+        let moves = plan_moves(&files, Path::new("/base"), OrganizeMode::ByFirstLetter);
+
+        assert_eq!(moves[0].to, PathBuf::from("/base/A/arial.ttf"));
+        assert_eq!(moves[1].to, PathBuf::from("/base/B/Brush Script.otf"));
+        assert_eq!(moves[2].to, PathBuf::from("/base/#/3dumb.ttf"));
+        assert_eq!(moves[3].to, PathBuf::from("/base/Ä/Ärger.epub"));
+    }
+
+    #[test]
+    fn test_plan_moves_by_size_bucket() {
+        const MB: u64 = 1024 * 1024;
+        let files = vec![
+            make_file_info("notes.txt", Some("txt"), MB - 1),
+            make_file_info("scan.pdf", Some("pdf"), MB),
+            make_file_info("movie.mkv", Some("mkv"), 1024 * MB),
+        ];
+
+        let moves = plan_moves(&files, Path::new("/base"), OrganizeMode::BySizeBucket);
+
+        assert_eq!(moves[0].to, PathBuf::from("/base/Tiny/notes.txt"));
+        assert_eq!(moves[1].to, PathBuf::from("/base/Small/scan.pdf"));
+        assert_eq!(moves[2].to, PathBuf::from("/base/Huge/movie.mkv"));
+    }
+
+    #[test]
+    fn test_plan_moves_by_extension_lower() {
+        let files = vec![
+            make_file_info("c.py", Some("py"), 300),
+            make_file_info("IMG_0001.JPG", Some("jpg"), 400),
+        ];
+
+        let base = Path::new("/base");
+        let moves = plan_moves_with_ext_case(
+            &files,
+            base,
+            OrganizeMode::ByExtension,
+            ExtensionCase::Lower,
+        );
+
+        assert_eq!(moves[0].to, PathBuf::from("/base/py/c.py"));
+        assert_eq!(moves[1].to, PathBuf::from("/base/jpg/IMG_0001.JPG"));
+    }
+
+    #[test]
+    fn test_plan_moves_by_extension_keep() {
+        let files = vec![
+            make_file_info("c.py", Some("py"), 300),
+            make_file_info("IMG_0001.JPG", Some("jpg"), 400),
+            make_file_info("Notes.Md", Some("md"), 500),
+        ];
+
+        let base = Path::new("/base");
+        let moves =
+            plan_moves_with_ext_case(&files, base, OrganizeMode::ByExtension, ExtensionCase::Keep);
+
+        assert_eq!(moves[0].to, PathBuf::from("/base/py/c.py"));
+        assert_eq!(moves[1].to, PathBuf::from("/base/JPG/IMG_0001.JPG"));
+        assert_eq!(moves[2].to, PathBuf::from("/base/Md/Notes.Md"));
+    }
+
+    #[test]
+    fn test_plan_moves_no_extension_stable_across_cases() {
+        let files = vec![make_file_info("Makefile", None, 100)];
+        let base = Path::new("/base");
+
+        for ext_case in [
+            ExtensionCase::Upper,
+            ExtensionCase::Lower,
+            ExtensionCase::Keep,
+        ] {
+            let moves = plan_moves_with_ext_case(&files, base, OrganizeMode::ByExtension, ext_case);
+            assert_eq!(moves[0].to, PathBuf::from("/base/NO_EXTENSION/Makefile"));
+        }
+    }
+
+    #[test]
+    fn test_plan_moves_with_name_regex_captures() {
+        let files = vec![
+            make_file_info("backend_001.log", Some("log"), 100),
+            make_file_info("frontend_002.log", Some("log"), 100),
+        ];
+        let re = regex::Regex::new(r"^(?P<proj>[a-z]+)_\d+\.log$").unwrap();
+        let template = default_name_regex_template(&re);
+        assert_eq!(template, "{proj}/{filename}");
+
+        let moves = plan_moves_with_name_regex(&files, Path::new("/base"), &template, &re, None);
+
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[0].to, PathBuf::from("/base/backend/backend_001.log"));
+        assert_eq!(
+            moves[1].to,
+            PathBuf::from("/base/frontend/frontend_002.log")
+        );
+    }
+
+    #[test]
+    fn test_plan_moves_with_name_regex_fallback() {
+        let files = vec![
+            make_file_info("backend_001.log", Some("log"), 100),
+            make_file_info("notes.txt", Some("txt"), 100),
+        ];
+        let re = regex::Regex::new(r"^(?P<proj>[a-z]+)_\d+\.log$").unwrap();
+        let base = Path::new("/base");
+
+        let skipped = plan_moves_with_name_regex(&files, base, "{proj}/{filename}", &re, None);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].from, PathBuf::from("/test/backend_001.log"));
+
+        let with_fallback =
+            plan_moves_with_name_regex(&files, base, "{proj}/{filename}", &re, Some("Unmatched"));
+        assert_eq!(with_fallback.len(), 2);
+        assert_eq!(
+            with_fallback[1].to,
+            PathBuf::from("/base/Unmatched/notes.txt")
+        );
+    }
+
+    #[test]
+    fn test_ensure_parent_dir_checks_shared_dir_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("Images");
+        let mut created_dirs = HashSet::new();
+
+        assert!(ensure_parent_dir(&dest.join("a.jpg"), &mut created_dirs).unwrap());
+        assert!(!ensure_parent_dir(&dest.join("b.jpg"), &mut created_dirs).unwrap());
+        assert!(!ensure_parent_dir(&dest.join("c.jpg"), &mut created_dirs).unwrap());
+        assert!(dest.is_dir());
+        assert_eq!(created_dirs.len(), 1);
+    }
+
+    #[test]
+    fn test_execute_moves_shared_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let names = ["a.jpg", "b.jpg", "c.jpg"];
+        let moves: Vec<PlannedMove> = names
+            .iter()
+            .map(|name| {
+                let from = dir.path().join(name);
+                fs::write(&from, name).unwrap();
+                PlannedMove {
+                    from,
+                    to: dir.path().join("Images").join(name),
+                    size: name.len() as u64,
+                }
+            })
+            .collect();
+
+        let result = execute_moves(&moves, "test", ConflictStrategy::Rename).unwrap();
+
+        assert_eq!(result.moved, 3);
+        for name in names {
+            assert!(!dir.path().join(name).exists());
+            assert_eq!(
+                fs::read_to_string(dir.path().join("Images").join(name)).unwrap(),
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_append_report_accumulates() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = dir.path().join("report.log");
+        let result = OrganizeResult {
+            moved: 1,
+            total_size: 2048,
+            done: vec![PlannedMove {
+                from: dir.path().join("a.jpg"),
+                to: dir.path().join("Images").join("a.jpg"),
+                size: 2048,
+            }],
+            ..Default::default()
+        };
+
+        append_report(
+            &report,
+            "organize --by-type",
+            dir.path(),
+            &result,
+            SizeUnits::Binary,
+        )
+        .unwrap();
+        append_report(
+            &report,
+            "organize --by-type",
+            dir.path(),
+            &result,
+            SizeUnits::Binary,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&report).unwrap();
+        assert_eq!(content.matches("| organize --by-type ===").count(), 2);
+        assert!(content.contains("Processed: 1 files (2.00 KB)"));
+        assert!(content.contains("  Images (1 files, 2.00 KB)"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_is_folded_name_ignores_hard_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let nfd = dir.path().join("cafe\u{301}.txt");
+        let nfc = dir.path().join("caf\u{e9}.txt");
+        fs::write(&nfd, "x").unwrap();
+        assert!(!is_folded_name(&nfd, &nfc));
+
+        // Both names are real entries for the same file, so a rename would be a no-op
+        fs::hard_link(&nfd, &nfc).unwrap();
+        assert!(!is_folded_name(&nfd, &nfc));
+        assert!(!is_folded_name(&nfd, &nfd));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_copy_and_remove_cleans_up_failed_copy() {
+        let dir = tempfile::tempdir().unwrap();
+        let to = dir.path().join("mem");
+
+        // Reading this file fails after the destination has been created
+        let from = Path::new("/proc/self/mem");
+        assert!(copy_and_remove(from, &to).is_err());
+        assert!(!to.exists());
+    }
+
+    #[test]
+    fn test_append_report_lists_only_executed_moves() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.jpg"), "a").unwrap();
+        fs::write(dir.path().join("b.pdf"), "b").unwrap();
+        fs::create_dir(dir.path().join("Documents")).unwrap();
+        fs::write(dir.path().join("Documents").join("b.pdf"), "old").unwrap();
+        let moves = vec![
+            PlannedMove {
+                from: dir.path().join("a.jpg"),
+                to: dir.path().join("Images").join("a.jpg"),
+                size: 1,
+            },
+            PlannedMove {
+                from: dir.path().join("b.pdf"),
+                to: dir.path().join("Documents").join("b.pdf"),
+                size: 1,
+            },
+        ];
+        let result = execute_copies(&moves, "test", ConflictStrategy::Skip).unwrap();
+
+        let report = dir.path().join("report.log");
+        append_report(
+            &report,
+            "organize --by-type --copy",
+            dir.path(),
+            &result,
+            SizeUnits::Binary,
+        )
+        .unwrap();
+
+        let content = fs::read_to_string(&report).unwrap();
+        assert!(content.contains("Processed: 1 files"));
+        assert!(content.contains("Skipped:   1"));
+        assert!(content.contains("  Images (1 files"));
+        assert!(!content.contains("Documents"));
+    }
+
+    fn blocked_moves(dir: &Path) -> Vec<PlannedMove> {
+        // "Blocked" is a file, so Blocked/x/ can never be created
+        fs::write(dir.join("Blocked"), "file").unwrap();
+        fs::write(dir.join("a.txt"), "a").unwrap();
+        fs::write(dir.join("b.txt"), "b").unwrap();
+        vec![
+            PlannedMove {
+                from: dir.join("a.txt"),
+                to: dir.join("Blocked").join("x").join("a.txt"),
+                size: 1,
+            },
+            PlannedMove {
+                from: dir.join("b.txt"),
+                to: dir.join("Docs").join("b.txt"),
+                size: 1,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_execute_moves_records_uncreatable_dir_and_continues() {
+        let dir = tempfile::tempdir().unwrap();
+        let moves = blocked_moves(dir.path());
+
+        let result = execute_moves(&moves, "test", ConflictStrategy::Rename).unwrap();
+
+        assert_eq!(result.moved, 1);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.errors.len(), 1);
+        assert!(dir.path().join("a.txt").exists());
+        assert!(dir.path().join("Docs").join("b.txt").exists());
+    }
+
+    #[test]
+    fn test_fail_fast_after_checkpoints_records_one_batch() {
+        let dir = tempfile::tempdir().unwrap();
+        let neat = tempfile::tempdir().unwrap();
+        crate::logger::set_test_neat_dir(neat.path());
+
+        let done = CHECKPOINT_INTERVAL * 2 + 20;
+        let mut moves: Vec<PlannedMove> = (0..done)
+            .map(|i| {
+                let name = format!("{:03}.txt", i);
+                fs::write(dir.path().join(&name), "x").unwrap();
+                PlannedMove {
+                    from: dir.path().join(&name),
+                    to: dir.path().join("Docs").join(&name),
+                    size: 1,
+                }
+            })
+            .collect();
+        moves.extend(blocked_moves(dir.path()));
+        let options = MoveOptions {
+            fail_fast: true,
+            ..Default::default()
+        };
+
+        let result = execute_moves_with_options(&moves, "test", ConflictStrategy::Rename, &options);
+        assert!(result.is_err());
+
+        let history = crate::logger::History::load().unwrap();
+        assert_eq!(history.batches.len(), 1);
+        assert_eq!(history.batches[0].operations.len(), done);
+        let journals = fs::read_dir(neat.path().join("journal")).unwrap().count();
+        assert_eq!(journals, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_execute_moves_links_identical_files() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("Docs");
+        let mv = |name: &str, content: &str| {
+            fs::write(dir.path().join(name), content).unwrap();
+            PlannedMove {
+                from: dir.path().join(name),
+                to: docs.join(name),
+                size: content.len() as u64,
+            }
+        };
+        let moves = vec![
+            mv("a.txt", "same text"),
+            mv("b.txt", "same text"),
+            mv("c.txt", "diff text"),
+        ];
+        let options = MoveOptions {
+            link_duplicates: true,
+            ..Default::default()
+        };
+
+        let result =
+            execute_moves_with_options(&moves, "test", ConflictStrategy::Rename, &options).unwrap();
+
+        assert_eq!(result.moved, 3);
+        assert_eq!(result.deduplicated, 1);
+        assert!(!dir.path().join("b.txt").exists());
+        let inode = |name: &str| fs::metadata(docs.join(name)).unwrap().ino();
+        assert_eq!(inode("a.txt"), inode("b.txt"));
+        assert_ne!(inode("a.txt"), inode("c.txt"));
+        assert_eq!(fs::read_to_string(docs.join("b.txt")).unwrap(), "same text");
+    }
+
+    #[test]
+    fn test_link_in_place_refuses_different_content() {
+        // Stands in for a hash collision: same size, different bytes
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("a.txt");
+        let from = dir.path().join("b.txt");
+        let dest = dir.path().join("b_linked.txt");
+        fs::write(&original, "same size").unwrap();
+        fs::write(&from, "diff size").unwrap();
+
+        assert!(link_in_place(&original, &from, &dest).is_err());
+        assert_eq!(fs::read_to_string(&from).unwrap(), "diff size");
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_execute_moves_fail_fast_stops_at_uncreatable_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let moves = blocked_moves(dir.path());
+        let options = MoveOptions {
+            fail_fast: true,
+            ..Default::default()
+        };
+
+        let result = execute_moves_with_options(&moves, "test", ConflictStrategy::Rename, &options);
+
+        assert!(result.is_err());
+        assert!(dir.path().join("a.txt").exists());
+        assert!(dir.path().join("b.txt").exists());
+        assert!(!dir.path().join("Docs").exists());
+    }
+
+    #[test]
+    fn test_execute_moves_rejects_destinations_outside_base() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().join("inbox");
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("evil.txt"), "x").unwrap();
+        fs::write(base.join("fine.txt"), "x").unwrap();
+        let files = [
+            FileInfo::from_path(&base.join("evil.txt")).unwrap(),
+            FileInfo::from_path(&base.join("fine.txt")).unwrap(),
+        ];
+
+        let mut moves = plan_moves_with_template(&files[..1], &base, "../../{filename}");
+        moves.extend(plan_moves_with_template(&files[1..], &base, "Docs/../Docs"));
+        let options = MoveOptions {
+            confine_to: Some(&base),
+            ..Default::default()
+        };
+
+        let result =
+            execute_moves_with_options(&moves, "test", ConflictStrategy::Rename, &options).unwrap();
+
+        assert_eq!(result.moved, 1);
+        assert_eq!(result.skipped, 1);
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].contains("outside"));
+        assert!(base.join("evil.txt").exists());
+        assert!(!dir.path().parent().unwrap().join("evil.txt").exists());
+        assert!(base.join("Docs").join("fine.txt").exists());
+    }
+
+    #[test]
+    fn test_execute_moves_throttle_paces_moves() {
+        let dir = tempfile::tempdir().unwrap();
+        let moves: Vec<PlannedMove> = (0..4)
+            .map(|i| {
+                let from = dir.path().join(format!("{}.txt", i));
+                fs::write(&from, "x").unwrap();
+                PlannedMove {
+                    to: dir.path().join("Docs").join(format!("{}.txt", i)),
+                    from,
+                    size: 1,
+                }
+            })
+            .collect();
+        let options = MoveOptions {
+            throttle: Some(20.0),
+            ..Default::default()
+        };
+
+        let start = std::time::Instant::now();
+        let result =
+            execute_moves_with_options(&moves, "test", ConflictStrategy::Rename, &options).unwrap();
+
+        // 4 moves at 20/s: three 50ms gaps after the first
+        assert_eq!(result.moved, 4);
+        assert!(start.elapsed() >= std::time::Duration::from_millis(150));
+    }
+
+    fn tree_listing(dir: &Path) -> Vec<PathBuf> {
+        let mut entries: Vec<PathBuf> = walkdir::WalkDir::new(dir)
+            .into_iter()
+            .map(|e| e.unwrap().path().strip_prefix(dir).unwrap().to_path_buf())
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn test_execute_moves_atomic_rolls_back_on_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut moves = blocked_moves(dir.path());
+        // Put the failing move last so earlier moves have to be undone
+        moves.reverse();
+        let before = tree_listing(dir.path());
+        let options = MoveOptions {
+            atomic: true,
+            ..Default::default()
+        };
+
+        let result = execute_moves_with_options(&moves, "test", ConflictStrategy::Rename, &options);
+
+        let err = format!("{:#}", result.unwrap_err());
+        assert!(
+            err.contains("returned to their original locations"),
+            "{}",
+            err
+        );
+        assert_eq!(tree_listing(dir.path()), before);
+        assert_eq!(fs::read_to_string(dir.path().join("b.txt")).unwrap(), "b");
+    }
+
+    #[test]
+    fn test_execute_moves_atomic_success() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("Docs")).unwrap();
+        fs::write(dir.path().join("Docs").join("a.txt"), "existing").unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        fs::write(dir.path().join("b.txt"), "b").unwrap();
+        let moves: Vec<PlannedMove> = ["a.txt", "b.txt"]
+            .iter()
+            .map(|name| PlannedMove {
+                from: dir.path().join(name),
+                to: dir.path().join("Docs").join(name),
+                size: 1,
+            })
+            .collect();
+
+        let result = execute_moves_atomic(
+            &moves,
+            "test",
+            ConflictStrategy::Rename,
+            &MoveOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.moved, 2);
+        let docs = dir.path().join("Docs");
+        assert_eq!(fs::read_to_string(docs.join("a.txt")).unwrap(), "existing");
+        assert_eq!(fs::read_to_string(docs.join("a_1.txt")).unwrap(), "a");
+        assert_eq!(fs::read_to_string(docs.join("b.txt")).unwrap(), "b");
+        // The staging folder is gone
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_execute_moves_atomic_rejects_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let moves = blocked_moves(dir.path());
+        assert!(execute_moves_atomic(
+            &moves,
+            "test",
+            ConflictStrategy::Overwrite,
+            &MoveOptions::default()
+        )
+        .is_err());
+        assert!(dir.path().join("a.txt").exists());
+    }
+
+    #[test]
+    fn test_common_ancestor() {
+        let paths = [
+            Path::new("/base/a.txt"),
+            Path::new("/base/Docs/x/a.txt"),
+            Path::new("/base/Images/b.jpg"),
+        ];
+        assert_eq!(common_ancestor(paths), Some(PathBuf::from("/base")));
+    }
+
+    #[test]
+    fn test_execute_copies_preserve_times() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("photo.jpg");
+        fs::write(&from, "data").unwrap();
+        let old = filetime::FileTime::from_unix_time(946_684_800, 0); // 2000-01-01
+        filetime::set_file_times(&from, old, old).unwrap();
+
+        let moves = vec![PlannedMove {
+            from: from.clone(),
+            to: dir.path().join("Images").join("photo.jpg"),
+            size: 4,
+        }];
+        let options = MoveOptions {
+            preserve_times: true,
+            ..Default::default()
+        };
+
+        let result =
+            execute_copies_with_options(&moves, "test", ConflictStrategy::Rename, &options)
+                .unwrap();
+
+        assert_eq!(result.moved, 1);
+        assert!(result.errors.is_empty());
+        let copied = fs::metadata(dir.path().join("Images").join("photo.jpg")).unwrap();
+        let mtime = filetime::FileTime::from_last_modification_time(&copied);
+        assert!((mtime.unix_seconds() - old.unix_seconds()).abs() <= 1);
+    }
+
+    #[test]
+    fn test_plan_moves_no_extension() {
+        let files = vec![make_file_info("Makefile", None, 100)];
+
+        let base = Path::new("/base");
+        let moves = plan_moves(&files, base, OrganizeMode::ByExtension);
+
+        assert_eq!(moves.len(), 1);
+        assert!(moves[0].to.to_string_lossy().contains("NO_EXTENSION"));
+    }
+
+    #[test]
+    fn test_plan_moves_empty_files() {
+        let files: Vec<FileInfo> = vec![];
+        let base = Path::new("/base");
+        let moves = plan_moves(&files, base, OrganizeMode::ByType);
+        assert!(moves.is_empty());
+    }
 
-    let filename = path.file_name().unwrap_or_default().to_string_lossy();
+    #[test]
+    fn test_plan_moves_skip_already_organized() {
+        // File already in correct location
+        let files = vec![FileInfo {
+            path: PathBuf::from("/base/Images/photo.jpg"),
+            name: "photo.jpg".to_string(),
+            extension: Some("jpg".to_string()),
+            extension_raw: Some("jpg".to_string()),
+            size: 1000,
+            modified: SystemTime::now(),
+            created: None,
+        }];
 
-    println!(
-        "\n{} File already exists: {}",
-        "⚠".yellow(),
-        filename.bold()
-    );
-    print!("  [s]kip, [o]verwrite, [r]ename? ");
-    io::stdout().flush().ok();
+        let base = Path::new("/base");
+        let moves = plan_moves(&files, base, OrganizeMode::ByType);
 
-    let mut input = String::new();
-    if io::stdin().read_line(&mut input).is_err() {
-        return None; // Skip on error
+        // Should skip since already in correct place
+        assert!(moves.is_empty());
     }
 
-    match input.trim().to_lowercase().chars().next() {
-        Some('s') => None,
-        Some('o') => Some(path.to_path_buf()),
-        Some('r') => Some(resolve_conflict(path)),
-        _ => None, // Default to skip
+    #[test]
+    fn test_plan_moves_by_date_skips_files_in_date_folders() {
+        let mut in_date_folder = make_file_info("notes.txt", Some("txt"), 10);
+        in_date_folder.path = PathBuf::from("/base/2023/05/notes.txt");
+        let mut in_other_folder = make_file_info("todo.txt", Some("txt"), 10);
+        in_other_folder.path = PathBuf::from("/base/Docs/2023/05/todo.txt");
+        let mut bad_month = make_file_info("log.txt", Some("txt"), 10);
+        bad_month.path = PathBuf::from("/base/2023/13/log.txt");
+        let files = vec![in_date_folder, in_other_folder, bad_month];
+
+        // Modified now, so a fresh run would pick a different month for all three
+        let moves = plan_moves(&files, Path::new("/base"), OrganizeMode::ByDate);
+
+        let moved: Vec<&str> = moves
+            .iter()
+            .map(|m| m.from.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(moved, vec!["todo.txt", "log.txt"]);
     }
-}
 
-/// Resolve filename conflicts by adding a number suffix
-fn resolve_conflict(path: &Path) -> PathBuf {
-    if !path.exists() {
-        return path.to_path_buf();
-    }
+    #[test]
+    fn test_plan_moves_by_extension_skips_files_in_extension_folders() {
+        let mut lower = make_file_info("a.txt", Some("txt"), 10);
+        lower.path = PathBuf::from("/base/txt/a.txt");
+        let mut no_ext = make_file_info("Makefile", None, 10);
+        no_ext.path = PathBuf::from("/base/NO_EXTENSION/Makefile");
+        let mut misplaced = make_file_info("b.txt", Some("txt"), 10);
+        misplaced.path = PathBuf::from("/base/PY/b.txt");
+        let files = vec![lower, no_ext, misplaced];
 
-    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
-    let extension = path
-        .extension()
-        .map(|e| format!(".{}", e.to_string_lossy()))
-        .unwrap_or_default();
-    let parent = path.parent().unwrap_or(Path::new("."));
+        let moves = plan_moves(&files, Path::new("/base"), OrganizeMode::ByExtension);
 
-    let mut counter = 1;
-    loop {
-        let new_name = format!("{}_{}{}", stem, counter, extension);
-        let new_path = parent.join(new_name);
-        if !new_path.exists() {
-            return new_path;
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].to, PathBuf::from("/base/TXT/b.txt"));
+    }
+
+    #[test]
+    fn test_plan_moves_smart_picks_mode_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["photo.jpg", "song.mp3", "report.pdf"] {
+            fs::write(dir.path().join(name), "no metadata").unwrap();
         }
-        counter += 1;
+        let mut files: Vec<FileInfo> = ["photo.jpg", "song.mp3", "report.pdf"]
+            .iter()
+            .map(|name| FileInfo::from_path(&dir.path().join(name)).unwrap())
+            .collect();
+        let modified = Utc.with_ymd_and_hms(2023, 7, 14, 12, 0, 0).unwrap();
+        files[0].modified = modified.into();
+
+        let moves = plan_moves(&files, dir.path(), OrganizeMode::Smart);
+
+        // Without EXIF the photo falls back to its modified date
+        assert_eq!(moves[0].to, dir.path().join("2023/07/photo.jpg"));
+        assert_eq!(
+            moves[1].to,
+            dir.path().join("Unknown Artist/Unknown Album/song.mp3")
+        );
+        assert_eq!(moves[2].to, dir.path().join("Documents/report.pdf"));
     }
-}
 
-/// Backup a file to ~/.neat/versions/
-fn backup_file(path: &Path) -> anyhow::Result<PathBuf> {
-    use chrono::Local;
+    #[test]
+    fn test_plan_moves_camera_fallback_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        // No EXIF data, so the camera is unknown
+        let path = dir.path().join("photo.jpg");
+        fs::write(&path, "not really a jpeg").unwrap();
+        let files = vec![FileInfo::from_path(&path).unwrap()];
+        let fallbacks = FallbackFolders {
+            camera: "Appareil inconnu".to_string(),
+            ..Default::default()
+        };
 
-    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("No home directory"))?;
-    let date = Local::now().format("%Y-%m-%d").to_string();
-    let backup_dir = home.join(".neat").join("versions").join(&date);
+        let moves = plan_moves_with_fallbacks(
+            &files,
+            dir.path(),
+            OrganizeMode::ByCamera,
+            ExtensionCase::default(),
+            &fallbacks,
+        );
+        assert_eq!(
+            moves[0].to,
+            dir.path().join("Appareil inconnu").join("photo.jpg")
+        );
 
-    fs::create_dir_all(&backup_dir)?;
+        let moves = plan_moves(&files, dir.path(), OrganizeMode::ByCamera);
+        assert_eq!(
+            moves[0].to,
+            dir.path().join("Unknown Camera").join("photo.jpg")
+        );
+    }
 
-    let filename = path.file_name().unwrap_or_default();
-    let backup_path = backup_dir.join(filename);
+    #[test]
+    fn test_group_small_folders_redirects_singletons() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        let planned = |name: &str, folder: &str| PlannedMove {
+            from: base.join(name),
+            to: base.join(folder).join(name),
+            size: 1,
+        };
+        let moves = vec![
+            planned("a.jpg", "Images"),
+            planned("b.jpg", "Images"),
+            planned("c.jpg", "Images"),
+            planned("disk.iso", "Archives"),
+        ];
 
-    // If backup already exists, add suffix
-    let final_backup = if backup_path.exists() {
-        resolve_conflict(&backup_path)
-    } else {
-        backup_path
-    };
+        let moves = group_small_folders(moves, base, 2);
 
-    fs::copy(path, &final_backup)?;
-    Ok(final_backup)
-}
+        assert_eq!(moves.len(), 4);
+        assert_eq!(moves[0].to, base.join("Images").join("a.jpg"));
+        assert_eq!(moves[3].to, base.join("Other").join("disk.iso"));
+    }
 
-/// Print organize results
-pub fn print_results(result: &OrganizeResult) {
-    println!("\n{}", "Results:".bold().green());
-    println!("{}", "─".repeat(40));
+    #[test]
+    fn test_group_small_folders_counts_existing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        fs::create_dir(base.join("Archives")).unwrap();
+        fs::write(base.join("Archives").join("old.zip"), "zip").unwrap();
+        let moves = vec![PlannedMove {
+            from: base.join("disk.iso"),
+            to: base.join("Archives").join("disk.iso"),
+            size: 1,
+        }];
 
-    if result.moved > 0 {
-        println!(
-            "  {} {} files moved ({})",
-            "✓".green(),
-            result.moved.to_string().green(),
-            format_size(result.total_size).dimmed()
-        );
+        let moves = group_small_folders(moves, base, 2);
+
+        assert_eq!(moves[0].to, base.join("Archives").join("disk.iso"));
     }
 
-    if result.copied > 0 {
-        println!(
-            "  {} {} files copied",
-            "✓".green(),
-            result.copied.to_string().green()
-        );
+    fn file_modified_on(dir: &Path, name: &str, unix_time: i64) -> FileInfo {
+        let path = dir.join(name);
+        fs::write(&path, name).unwrap();
+        let mtime = filetime::FileTime::from_unix_time(unix_time, 0);
+        filetime::set_file_mtime(&path, mtime).unwrap();
+        FileInfo::from_path(&path).unwrap()
     }
 
-    if result.deduplicated > 0 {
-        println!(
-            "  {} {} duplicate files removed",
-            "✓".cyan(),
-            result.deduplicated.to_string().cyan()
+    #[test]
+    fn test_plan_moves_with_template_renames_file() {
+        let dir = tempfile::tempdir().unwrap();
+        // 2024-06-15 12:00 UTC, the same day in every timezone
+        let files = vec![file_modified_on(dir.path(), "IMG_0001.jpg", 1_718_452_800)];
+
+        let moves = plan_moves_with_template(&files, dir.path(), "Photos/{year}{month}_{filename}");
+
+        assert_eq!(
+            moves[0].to,
+            dir.path().join("Photos").join("202406_IMG_0001.jpg")
         );
     }
 
-    if result.backed_up > 0 {
-        println!(
-            "  {} {} files backed up to ~/.neat/versions/",
-            "↻".blue(),
-            result.backed_up.to_string().blue()
+    #[test]
+    fn test_moves_diff_with_renamed_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        fs::create_dir_all(base.join("Documents")).unwrap();
+        fs::write(base.join("Documents/report.pdf"), "already here").unwrap();
+
+        let mv = |from: &str, to: &str| PlannedMove {
+            from: base.join(from),
+            to: base.join(to),
+            size: 1,
+        };
+        let moves = vec![
+            mv("inbox/report.pdf", "Documents/report.pdf"),
+            mv("b/notes.txt", "Documents/notes.txt"),
+            mv("a/notes.txt", "Documents/notes.txt"),
+            mv("photo.jpg", "Images/photo.jpg"),
+        ];
+
+        assert_eq!(
+            moves_diff(
+                &with_renamed_conflicts(&moves, &CollisionSuffix::default()),
+                base
+            ),
+            "- a/notes.txt\n\
+             + Documents/notes_1.txt\n\
+             - b/notes.txt\n\
+             + Documents/notes.txt\n\
+             - inbox/report.pdf\n\
+             + Documents/report_1.pdf\n\
+             - photo.jpg\n\
+             + Images/photo.jpg\n"
         );
     }
 
-    if result.skipped > 0 {
-        println!(
-            "  {} {} files skipped",
-            "⚠".yellow(),
-            result.skipped.to_string().yellow()
+    #[test]
+    fn test_find_collisions() {
+        let mv = |from: &str, to: &str| PlannedMove {
+            from: PathBuf::from(from),
+            to: PathBuf::from(to),
+            size: 1,
+        };
+        let moves = vec![
+            mv("/in/a/notes.txt", "/out/Documents/notes.txt"),
+            mv("/in/b/notes.txt", "/out/Documents/notes.txt"),
+            mv("/in/photo.jpg", "/out/Images/photo.jpg"),
+            mv("/in/logo.png", "/out/Images/logo.png"),
+            // Lands on a file that is itself moved away first
+            mv("/out/Images/old.gif", "/out/Images/Old/old.gif"),
+            mv("/in/old.gif", "/out/Images/old.gif"),
+            // Lands on a file that is only moved away afterwards
+            mv("/in/cat.gif", "/out/Images/cat.gif"),
+            mv("/out/Images/cat.gif", "/out/Images/Old/cat.gif"),
+        ];
+        let on_disk = [
+            Path::new("/out/Images/logo.png"),
+            Path::new("/out/Images/old.gif"),
+            Path::new("/out/Images/cat.gif"),
+        ];
+
+        let collisions = find_collisions_with(&moves, |path| on_disk.contains(&path));
+
+        assert_eq!(
+            collisions,
+            vec![
+                Collision {
+                    from: PathBuf::from("/in/b/notes.txt"),
+                    to: PathBuf::from("/out/Documents/notes.txt"),
+                    existing: false,
+                },
+                Collision {
+                    from: PathBuf::from("/in/logo.png"),
+                    to: PathBuf::from("/out/Images/logo.png"),
+                    existing: true,
+                },
+                Collision {
+                    from: PathBuf::from("/in/cat.gif"),
+                    to: PathBuf::from("/out/Images/cat.gif"),
+                    existing: true,
+                },
+            ]
         );
     }
 
-    if !result.errors.is_empty() {
-        println!("\n  {}", "Errors:".red());
-        for error in result.errors.iter().take(5) {
-            println!("    {} {}", "✗".red(), error);
-        }
-        if result.errors.len() > 5 {
-            println!("    ... and {} more errors", result.errors.len() - 5);
+    #[test]
+    fn test_plan_moves_with_template_keeps_extension_case() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = vec![file_modified_on(dir.path(), "IMG_0001.JPG", 1_718_452_800)];
+
+        for template in ["Photos/{filename}", "Photos/{filename}.{ext}"] {
+            let moves = plan_moves_with_template(&files, dir.path(), template);
+            assert_eq!(moves[0].to, dir.path().join("Photos").join("IMG_0001.JPG"));
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::time::SystemTime;
+    #[test]
+    fn test_plan_moves_with_template_leaf_variants() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = vec![file_modified_on(
+            dir.path(),
+            "v1.2 notes.txt",
+            1_718_452_800,
+        )];
+        let dest = |template: &str| {
+            plan_moves_with_template(&files, dir.path(), template)[0]
+                .to
+                .clone()
+        };
 
-    fn make_file_info(name: &str, ext: Option<&str>, size: u64) -> FileInfo {
-        FileInfo {
-            path: PathBuf::from(format!("/test/{}", name)),
-            name: name.to_string(),
-            extension: ext.map(|s| s.to_string()),
-            size,
-            modified: SystemTime::now(),
-            created: None,
-        }
+        // Folder-only templates keep the original name
+        assert_eq!(
+            dest("Archive/{year}"),
+            dir.path()
+                .join("Archive")
+                .join("2024")
+                .join("v1.2 notes.txt")
+        );
+        // An explicit extension isn't doubled, and dots in the name survive
+        assert_eq!(
+            dest("{year}_{filename}.{ext}"),
+            dir.path().join("2024_v1.2 notes.txt")
+        );
+        assert_eq!(
+            dest("x/{name}"),
+            dir.path().join("x").join("v1.2 notes.txt")
+        );
+        assert_eq!(dest("{filename|upper}"), dir.path().join("V1.2 NOTES.txt"));
     }
 
     #[test]
-    fn test_plan_moves_by_type() {
+    fn test_plan_moves_with_template_extensionless_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = vec![file_modified_on(dir.path(), "Makefile", 1_718_452_800)];
+        let dest = |template: &str| {
+            plan_moves_with_template(&files, dir.path(), template)[0]
+                .to
+                .clone()
+        };
+
+        assert_eq!(
+            dest("{category}/{filename}.{ext}"),
+            dir.path().join("Other").join("Makefile")
+        );
+        assert_eq!(dest("{year}_{filename}"), dir.path().join("2024_Makefile"));
+    }
+
+    #[test]
+    fn test_renamed_files_sharing_a_name_do_not_collide() {
+        let dir = tempfile::tempdir().unwrap();
         let files = vec![
-            make_file_info("photo.jpg", Some("jpg"), 1000),
-            make_file_info("doc.pdf", Some("pdf"), 2000),
-            make_file_info("song.mp3", Some("mp3"), 3000),
+            file_modified_on(dir.path(), "My Photo.jpg", 1_718_452_800),
+            file_modified_on(dir.path(), "my-photo.jpg", 1_718_452_800),
         ];
 
-        let base = Path::new("/base");
-        let moves = plan_moves(&files, base, OrganizeMode::ByType);
+        // Both render to Photos/2024_my_photo.jpg
+        let moves = plan_moves_with_template(&files, dir.path(), "Photos/{year}_{filename|snake}");
+        assert_eq!(moves[0].to, moves[1].to);
 
-        assert_eq!(moves.len(), 3);
+        let result = execute_moves(&moves, "test", ConflictStrategy::Rename).unwrap();
 
-        // Check destinations contain correct category folders
-        assert!(moves[0].to.to_string_lossy().contains("Images"));
-        assert!(moves[1].to.to_string_lossy().contains("Documents"));
-        assert!(moves[2].to.to_string_lossy().contains("Audio"));
+        assert_eq!(result.moved, 2);
+        let photos = dir.path().join("Photos");
+        assert!(photos.join("2024_my_photo.jpg").exists());
+        assert!(photos.join("2024_my_photo_1.jpg").exists());
     }
 
     #[test]
-    fn test_plan_moves_by_extension() {
-        let files = vec![
-            make_file_info("a.txt", Some("txt"), 100),
-            make_file_info("b.txt", Some("txt"), 200),
-            make_file_info("c.py", Some("py"), 300),
-        ];
+    fn test_plan_move_with_rules_explains_reason() {
+        let config: Config = toml::from_str(
+            r#"
+[[rules]]
+name = "Invoices"
+pattern = "*invoice*.pdf"
+destination = "Documents/Invoices"
+"#,
+        )
+        .unwrap();
+        let base = Path::new("/test");
+
+        let invoice = make_file_info("march-invoice.pdf", Some("pdf"), 10);
+        let (planned, reason) =
+            plan_move_with_rules(&invoice, base, OrganizeMode::ByType, Some(&config)).unwrap();
+        assert_eq!(reason, MoveReason::Rule("Invoices".to_string()));
+        assert_eq!(reason.to_string(), "rule:Invoices");
+        assert_eq!(
+            planned.to,
+            PathBuf::from("/test/Documents/Invoices/march-invoice.pdf")
+        );
 
-        let base = Path::new("/base");
-        let moves = plan_moves(&files, base, OrganizeMode::ByExtension);
+        let photo = make_file_info("beach.jpg", Some("jpg"), 10);
+        let (planned, reason) =
+            plan_move_with_rules(&photo, base, OrganizeMode::ByType, Some(&config)).unwrap();
+        assert_eq!(reason, MoveReason::Mode(OrganizeMode::ByType));
+        assert_eq!(reason.to_string(), "mode:by-type");
+        assert_eq!(planned.to, PathBuf::from("/test/Images/beach.jpg"));
+    }
 
-        assert_eq!(moves.len(), 3);
+    #[test]
+    fn test_move_reason_display() {
+        assert_eq!(
+            MoveReason::ModeFallback(OrganizeMode::ByDateTaken).to_string(),
+            "mode:by-date-taken (fallback)"
+        );
+        assert_eq!(
+            MoveReason::NameRegexFallback.to_string(),
+            "name-regex (fallback)"
+        );
+        assert_eq!(MoveReason::GroupSmall.to_string(), "group-small");
+    }
 
-        // Check destinations use uppercase extension folders
-        assert!(moves[0].to.to_string_lossy().contains("TXT"));
-        assert!(moves[1].to.to_string_lossy().contains("TXT"));
-        assert!(moves[2].to.to_string_lossy().contains("PY"));
+    #[test]
+    fn test_plan_moves_mirrored() {
+        let mut nested = make_file_info("song.mp3", Some("mp3"), 5);
+        nested.path = PathBuf::from("/test/music/2024/song.mp3");
+        let files = vec![make_file_info("top.txt", Some("txt"), 1), nested];
+
+        let moves = plan_moves_mirrored(&files, Path::new("/test"), Path::new("/out"));
+        assert_eq!(moves.len(), 2);
+        assert_eq!(moves[0].to, PathBuf::from("/out/top.txt"));
+        assert_eq!(moves[1].to, PathBuf::from("/out/music/2024/song.mp3"));
+
+        // Mirroring onto itself moves nothing
+        assert!(plan_moves_mirrored(&files, Path::new("/test"), Path::new("/test")).is_empty());
     }
 
     #[test]
-    fn test_plan_moves_no_extension() {
-        let files = vec![make_file_info("Makefile", None, 100)];
+    fn test_tidy_file_name_trims_whitespace() {
+        assert_eq!(tidy_file_name("report .pdf"), "report.pdf");
+        assert_eq!(tidy_file_name("  notes.txt  "), "notes.txt");
+        assert_eq!(tidy_file_name("README "), "README");
+        assert_eq!(tidy_file_name("my photo.jpg"), "my photo.jpg");
+        assert_eq!(tidy_file_name("   "), "   ");
+    }
 
-        let base = Path::new("/base");
-        let moves = plan_moves(&files, base, OrganizeMode::ByExtension);
+    #[test]
+    fn test_tidy_file_name_normalizes_unicode() {
+        // "café" with a combining acute accent (NFD) becomes the precomposed é (NFC)
+        let nfd = "cafe\u{0301}.txt";
+        assert_eq!(tidy_file_name(nfd), "caf\u{00E9}.txt");
+        assert_eq!(tidy_file_name("caf\u{00E9}.txt"), "caf\u{00E9}.txt");
+        assert_eq!(tidy_file_name("in\u{200B}voice.pdf"), "invoice.pdf");
+    }
+
+    #[test]
+    fn test_plan_tidy_names_renames_in_place() {
+        let files = vec![
+            make_file_info("clean.txt", Some("txt"), 1),
+            make_file_info("messy .txt", Some("txt"), 2),
+        ];
 
+        let moves = plan_tidy_names(&files);
         assert_eq!(moves.len(), 1);
-        assert!(moves[0].to.to_string_lossy().contains("NO_EXTENSION"));
+        assert_eq!(moves[0].from, PathBuf::from("/test/messy .txt"));
+        assert_eq!(moves[0].to, PathBuf::from("/test/messy.txt"));
     }
 
     #[test]
-    fn test_plan_moves_empty_files() {
-        let files: Vec<FileInfo> = vec![];
-        let base = Path::new("/base");
-        let moves = plan_moves(&files, base, OrganizeMode::ByType);
-        assert!(moves.is_empty());
+    fn test_flatten_moves_category_files_up_and_removes_folders() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        fs::create_dir_all(base.join("Images").join("2024")).unwrap();
+        fs::create_dir_all(base.join("Documents")).unwrap();
+        fs::create_dir_all(base.join("Projects")).unwrap();
+        fs::write(base.join("Images").join("2024").join("a.jpg"), "a").unwrap();
+        fs::write(base.join("Documents").join("a.jpg"), "b").unwrap();
+        fs::write(base.join("Projects").join("keep.txt"), "c").unwrap();
+
+        let folders = category_folders(base, &["Images", "Documents", "Other"]).unwrap();
+        assert_eq!(folders, [base.join("Documents"), base.join("Images")]);
+
+        let files: Vec<FileInfo> = [
+            base.join("Documents").join("a.jpg"),
+            base.join("Images").join("2024").join("a.jpg"),
+        ]
+        .iter()
+        .map(|p| FileInfo::from_path(p).unwrap())
+        .collect();
+        let moves = plan_flatten(&files, base);
+        assert!(moves.iter().all(|mv| mv.to == base.join("a.jpg")));
+
+        let options = MoveOptions {
+            remove_if_empty: &folders,
+            ..Default::default()
+        };
+        let result =
+            execute_moves_with_options(&moves, "test", ConflictStrategy::Rename, &options).unwrap();
+        assert_eq!(result.moved, 2);
+        assert_eq!(result.removed_dirs, 3);
+        assert_eq!(fs::read_to_string(base.join("a.jpg")).unwrap(), "b");
+        assert_eq!(fs::read_to_string(base.join("a_1.jpg")).unwrap(), "a");
+        assert!(!base.join("Images").exists());
+        assert!(!base.join("Documents").exists());
+        assert!(base.join("Projects").join("keep.txt").exists());
     }
 
     #[test]
-    fn test_plan_moves_skip_already_organized() {
-        // File already in correct location
-        let files = vec![FileInfo {
-            path: PathBuf::from("/base/Images/photo.jpg"),
-            name: "photo.jpg".to_string(),
-            extension: Some("jpg".to_string()),
-            size: 1000,
-            modified: SystemTime::now(),
-            created: None,
+    fn test_execute_moves_collects_empty_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        fs::create_dir_all(base.join("old").join("2019")).unwrap();
+        fs::create_dir_all(base.join(".git").join("refs")).unwrap();
+        fs::create_dir_all(base.join("drafts")).unwrap();
+        fs::create_dir_all(base.join("keep")).unwrap();
+        fs::write(base.join("drafts").join("a.txt"), "a").unwrap();
+        fs::write(base.join("keep").join("b.txt"), "b").unwrap();
+
+        let moves = vec![PlannedMove {
+            from: base.join("drafts").join("a.txt"),
+            to: base.join("Documents").join("a.txt"),
+            size: 1,
         }];
+        let bucket = base.join(EMPTY_FOLDER);
+        let options = MoveOptions {
+            collect_empty_in: Some((base, &bucket)),
+            ..Default::default()
+        };
+        let result =
+            execute_moves_with_options(&moves, "test", ConflictStrategy::Rename, &options).unwrap();
+
+        // Only the folder emptied by the move goes; folders empty already stay put
+        assert_eq!(result.moved, 1);
+        assert_eq!(result.moved_dirs, 1);
+        assert!(bucket.join("drafts").is_dir());
+        assert!(!base.join("drafts").exists());
+        assert!(base.join("old").join("2019").is_dir());
+        assert!(base.join(".git").join("refs").is_dir());
+        assert!(base.join("keep").join("b.txt").exists());
+        assert!(base.join("Documents").join("a.txt").exists());
+    }
 
-        let base = Path::new("/base");
-        let moves = plan_moves(&files, base, OrganizeMode::ByType);
+    #[test]
+    fn test_collected_empty_dir_uses_collision_suffix() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        let bucket = base.join(EMPTY_FOLDER);
+        fs::create_dir_all(bucket.join("drafts")).unwrap();
+        fs::create_dir_all(base.join("drafts")).unwrap();
+        fs::write(base.join("drafts").join("a.txt"), "a").unwrap();
+
+        let moves = vec![PlannedMove {
+            from: base.join("drafts").join("a.txt"),
+            to: base.join("Documents").join("a.txt"),
+            size: 1,
+        }];
+        let suffix = CollisionSuffix::parse(" ({n})").unwrap();
+        let options = MoveOptions {
+            collect_empty_in: Some((base, &bucket)),
+            collision_suffix: Some(&suffix),
+            ..Default::default()
+        };
+        execute_moves_with_options(&moves, "test", ConflictStrategy::Rename, &options).unwrap();
 
-        // Should skip since already in correct place
-        assert!(moves.is_empty());
+        assert!(bucket.join("drafts (1)").is_dir());
+        assert!(!base.join("drafts").exists());
+    }
+
+    #[test]
+    fn test_plan_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let plan = dir.path().join("plan.json");
+        let moves = vec![
+            PlannedMove {
+                from: dir.path().join("a.jpg"),
+                to: dir.path().join("Images").join("a.jpg"),
+                size: 10,
+            },
+            PlannedMove {
+                from: dir.path().join("b b.pdf"),
+                to: dir.path().join("Documents").join("b b.pdf"),
+                size: 0,
+            },
+        ];
+
+        save_plan(&moves, &plan).unwrap();
+
+        assert_eq!(load_plan(&plan).unwrap(), moves);
+    }
+
+    #[test]
+    fn test_load_plan_rejects_garbage() {
+        let dir = tempfile::tempdir().unwrap();
+        let plan = dir.path().join("plan.json");
+        fs::write(&plan, "{not json").unwrap();
+        assert!(load_plan(&plan).is_err());
+        assert!(load_plan(&dir.path().join("missing.json")).is_err());
+    }
+
+    #[test]
+    fn test_validate_plan() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("same.txt"), "12345").unwrap();
+        fs::write(dir.path().join("grown.txt"), "123456789").unwrap();
+        let planned = |name: &str| PlannedMove {
+            from: dir.path().join(name),
+            to: dir.path().join("Documents").join(name),
+            size: 5,
+        };
+
+        let errors = validate_plan(&[
+            planned("same.txt"),
+            planned("grown.txt"),
+            planned("gone.txt"),
+        ]);
+
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].contains("grown.txt: size changed (5 planned, 9 now)"));
+        assert!(errors[1].contains("gone.txt: no longer exists"));
     }
 
     #[test]
@@ -678,11 +3478,54 @@ mod tests {
         assert_eq!(result.total_size, 0);
     }
 
+    #[test]
+    fn test_collision_suffix_numbered_forms() {
+        let now = chrono::Local::now();
+        let default = CollisionSuffix::default();
+        assert_eq!(default.file_name("report", ".pdf", 1, now), "report_1.pdf");
+        assert_eq!(default.file_name("report", ".pdf", 2, now), "report_2.pdf");
+
+        let mac = CollisionSuffix::parse(" ({n})").unwrap();
+        assert_eq!(mac.file_name("report", ".pdf", 1, now), "report (1).pdf");
+        assert_eq!(mac.file_name("Makefile", "", 3, now), "Makefile (3)");
+    }
+
+    #[test]
+    fn test_collision_suffix_dated_form() {
+        let now = chrono::Local
+            .with_ymd_and_hms(2024, 6, 15, 9, 5, 7)
+            .unwrap();
+        let dated = CollisionSuffix::parse("-{date}").unwrap();
+        assert_eq!(
+            dated.file_name("report", ".pdf", 1, now),
+            "report-20240615.pdf"
+        );
+        // A second clash the same day still gets a unique name
+        assert_eq!(
+            dated.file_name("report", ".pdf", 2, now),
+            "report-20240615_1.pdf"
+        );
+
+        let stamped = CollisionSuffix::parse("_{date}_{time}").unwrap();
+        assert_eq!(
+            stamped.file_name("report", ".pdf", 1, now),
+            "report_20240615_090507.pdf"
+        );
+    }
+
+    #[test]
+    fn test_collision_suffix_rejects_bad_patterns() {
+        assert!(CollisionSuffix::parse("").is_err());
+        assert!(CollisionSuffix::parse("/{n}").is_err());
+        assert!(CollisionSuffix::parse("_{count}").is_err());
+        assert!(CollisionSuffix::parse("_{n").is_err());
+    }
+
     #[test]
     fn test_resolve_conflict_no_conflict() {
         // Non-existent path should return as-is
         let path = Path::new("/nonexistent/file.txt");
-        let result = resolve_conflict(path);
+        let result = resolve_conflict(path, &CollisionSuffix::default());
         assert_eq!(result, path);
     }
 }
@@ -3,9 +3,11 @@
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use std::time::SystemTime;
 
 use anyhow::{Context, Result};
+use rayon::prelude::*;
 use walkdir::WalkDir;
 
 /// Information about a scanned file
@@ -13,7 +15,10 @@ use walkdir::WalkDir;
 pub struct FileInfo {
     pub path: PathBuf,
     pub name: String,
+    /// Lowercased extension, for matching and classification
     pub extension: Option<String>,
+    /// Extension as it appears on disk (e.g. `JPG`), for display and folder names
+    pub extension_raw: Option<String>,
     pub size: u64,
     pub modified: SystemTime,
     #[allow(dead_code)]
@@ -31,17 +36,38 @@ impl FileInfo {
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_default();
 
-        let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+        let extension_raw = path.extension().map(|e| e.to_string_lossy().to_string());
+        let extension = extension_raw.as_ref().map(|e| e.to_lowercase());
 
         Ok(FileInfo {
             path: path.to_path_buf(),
             name,
             extension,
+            extension_raw,
             size: metadata.len(),
             modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
             created: metadata.created().ok(),
         })
     }
+
+    /// In-memory FileInfo for tests, named after `path` without touching the disk
+    #[cfg(test)]
+    pub fn fixture(path: impl AsRef<Path>, size: u64) -> Self {
+        let path = path.as_ref();
+        let extension_raw = path.extension().map(|e| e.to_string_lossy().to_string());
+        FileInfo {
+            path: path.to_path_buf(),
+            name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            extension: extension_raw.as_ref().map(|e| e.to_lowercase()),
+            extension_raw,
+            size,
+            modified: SystemTime::now(),
+            created: None,
+        }
+    }
 }
 
 /// Scanner configuration
@@ -71,8 +97,106 @@ pub struct ScanOptions {
     pub name_contains: Option<String>,
     /// Regex pattern to match filename
     pub regex_pattern: Option<String>,
+    /// Match name, regex and extension filters with exact case
+    pub case_sensitive: bool,
     /// MIME type filter (e.g., "image/*", "application/pdf")
     pub mime_filter: Option<String>,
+    /// Match `mime_filter` against the type sniffed from file contents, not the extension
+    pub sniff_mime: bool,
+    /// Only include files with one of these extensions (empty = all)
+    pub only_extensions: Vec<String>,
+    /// Never include files with one of these extensions
+    pub exclude_extensions: Vec<String>,
+    /// Include OS junk files listed in `DEFAULT_IGNORES` (skipped by default)
+    pub no_default_ignores: bool,
+}
+
+impl ScanOptions {
+    /// Compile `ignore_patterns`, expanding braces. Patterns that aren't valid globs are dropped.
+    pub fn ignore_globs(&self) -> Result<Vec<glob::Pattern>> {
+        let mut expanded_patterns = Vec::new();
+        for pattern in &self.ignore_patterns {
+            expanded_patterns.extend(
+                expand_braces(pattern)
+                    .with_context(|| format!("Invalid ignore pattern: {}", pattern))?,
+            );
+        }
+        Ok(expanded_patterns
+            .iter()
+            .filter_map(|p| glob::Pattern::new(p).ok())
+            .collect())
+    }
+
+    /// Whether `file` passes the extension, size, date, name, regex and MIME filters
+    /// (hidden files, default ignores and `ignore_patterns` are checked while walking)
+    pub fn matches(&self, file: &FileInfo) -> bool {
+        let extension = if self.case_sensitive {
+            file.extension_raw.as_deref()
+        } else {
+            file.extension.as_deref()
+        };
+        if !extension_allowed(
+            extension,
+            &self.only_extensions,
+            &self.exclude_extensions,
+            self.case_sensitive,
+        ) {
+            return false;
+        }
+        if self.min_size.is_some_and(|min| file.size < min)
+            || self.max_size.is_some_and(|max| file.size > max)
+        {
+            return false;
+        }
+        if self.after_date.is_some_and(|after| file.modified < after)
+            || self
+                .before_date
+                .is_some_and(|before| file.modified > before)
+        {
+            return false;
+        }
+
+        use crate::core::filters::NameFilter;
+        let filter = NameFilter {
+            startswith: self.name_startswith.clone(),
+            endswith: self.name_endswith.clone(),
+            contains: self.name_contains.clone(),
+            case_insensitive: !self.case_sensitive,
+        };
+        if !filter.is_empty() && !filter.matches(&file.name) {
+            return false;
+        }
+
+        if let Some(ref pattern) = self.regex_pattern {
+            if !crate::core::filters::matches_regex(&file.name, pattern, !self.case_sensitive)
+                .unwrap_or_default()
+            {
+                return false;
+            }
+        }
+
+        match self.mime_filter {
+            Some(ref mime_filter) if self.sniff_mime => {
+                crate::core::filters::matches_detected_mime(&file.path, mime_filter)
+            }
+            Some(ref mime_filter) => crate::core::filters::matches_mime(&file.path, mime_filter),
+            None => true,
+        }
+    }
+}
+
+/// OS-generated junk skipped by every scan unless `no_default_ignores` is set.
+/// A directory in this list is skipped with everything inside it.
+pub const DEFAULT_IGNORES: [&str; 4] = [".DS_Store", "Thumbs.db", "__MACOSX", "desktop.ini"];
+
+/// Whether any component of `relative` is a default-ignored name (case-insensitive)
+fn is_default_junk(relative: &Path) -> bool {
+    relative.components().any(|component| {
+        let name = component.as_os_str().to_string_lossy();
+        DEFAULT_IGNORES
+            .iter()
+            .any(|junk| name.eq_ignore_ascii_case(junk))
+    })
 }
 
 /// Load ignore patterns from .neatignore file in the given directory
@@ -82,21 +206,142 @@ pub fn load_ignore_patterns(dir: &Path) -> Vec<String> {
         return Vec::new();
     }
 
-    let file = match File::open(&ignore_file) {
-        Ok(f) => f,
-        Err(_) => return Vec::new(),
-    };
+    load_list_file(&ignore_file).unwrap_or_default()
+}
+
+/// Read one entry per line, skipping blank lines and `#` comments
+pub fn load_list_file(path: &Path) -> Result<Vec<String>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
 
-    BufReader::new(file)
+    Ok(BufReader::new(file)
         .lines()
-        .map_while(Result::ok)
+        .map_while(std::result::Result::ok)
         .map(|line| line.trim().to_string())
         .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect())
+}
+
+/// Expand shell-style braces in a glob pattern, e.g. `*.{jpg,png}` becomes
+/// `*.jpg` and `*.png`. Braces may nest; characters inside `[...]` are literal.
+pub fn expand_braces(pattern: &str) -> Result<Vec<String>> {
+    let chars: Vec<char> = pattern.chars().collect();
+
+    // Find the first top-level brace group and the commas that split it
+    let mut open = None;
+    let mut commas = Vec::new();
+    let mut depth = 0;
+    let mut in_class = false;
+    for (i, &c) in chars.iter().enumerate() {
+        match c {
+            '[' if !in_class => in_class = true,
+            ']' if in_class => in_class = false,
+            _ if in_class => {}
+            '{' => {
+                if depth == 0 {
+                    open = Some(i);
+                }
+                depth += 1;
+            }
+            '}' => {
+                if depth == 0 {
+                    anyhow::bail!("Unmatched '}}' in pattern: {}", pattern);
+                }
+                depth -= 1;
+                if depth == 0 {
+                    let start = open.unwrap_or_default();
+                    let prefix: String = chars[..start].iter().collect();
+                    let suffix: String = chars[i + 1..].iter().collect();
+
+                    let mut bounds = vec![start];
+                    bounds.append(&mut commas);
+                    bounds.push(i);
+
+                    let mut expanded = Vec::new();
+                    for pair in bounds.windows(2) {
+                        let alternative: String = chars[pair[0] + 1..pair[1]].iter().collect();
+                        let candidate = format!("{}{}{}", prefix, alternative, suffix);
+                        expanded.extend(expand_braces(&candidate)?);
+                    }
+                    return Ok(expanded);
+                }
+            }
+            ',' if depth == 1 => commas.push(i),
+            _ => {}
+        }
+    }
+
+    if depth > 0 {
+        anyhow::bail!("Unmatched '{{' in pattern: {}", pattern);
+    }
+
+    Ok(vec![pattern.to_string()])
+}
+
+/// Compile a glob pattern, expanding braces into one glob per alternative
+pub fn compile_glob(pattern: &str) -> Result<Vec<glob::Pattern>> {
+    expand_braces(pattern)?
+        .iter()
+        .map(|p| glob::Pattern::new(p).with_context(|| format!("Invalid glob pattern: {}", p)))
         .collect()
 }
 
+/// Check whether a path's file name or full path matches any of the patterns
+pub fn matches_glob(patterns: &[glob::Pattern], path: &Path) -> bool {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    let file_path = path.to_string_lossy();
+    patterns
+        .iter()
+        .any(|pattern| pattern.matches(&file_name) || pattern.matches(&file_path))
+}
+
+/// Check an extension against allow and deny lists, ignoring leading dots (and case
+/// unless `case_sensitive`). Files without an extension never match the allow list.
+pub fn extension_allowed(
+    extension: Option<&str>,
+    only: &[String],
+    exclude: &[String],
+    case_sensitive: bool,
+) -> bool {
+    let listed = |list: &[String], ext: &str| {
+        list.iter().any(|e| {
+            let e = e.trim().trim_start_matches('.');
+            if case_sensitive {
+                e == ext
+            } else {
+                e.eq_ignore_ascii_case(ext)
+            }
+        })
+    };
+
+    match extension {
+        Some(ext) => (only.is_empty() || listed(only, ext)) && !listed(exclude, ext),
+        None => only.is_empty(),
+    }
+}
+
+static FAST_SCAN: OnceLock<bool> = OnceLock::new();
+
+/// Read file metadata in parallel in every scan for the rest of the process
+/// (`--fast-scan`; first call wins)
+pub fn set_fast_scan(enabled: bool) {
+    let _ = FAST_SCAN.set(enabled);
+}
+
 /// Scan a directory and return file information
 pub fn scan_directory(path: &Path, options: &ScanOptions) -> Result<Vec<FileInfo>> {
+    scan_directory_with(path, options, FAST_SCAN.get().copied().unwrap_or(false))
+}
+
+/// Scan a directory, reading metadata and applying filters on all cores when
+/// `parallel` is set. Both ways return the same files in the same order.
+fn scan_directory_with(
+    path: &Path,
+    options: &ScanOptions,
+    parallel: bool,
+) -> Result<Vec<FileInfo>> {
     if !path.exists() {
         anyhow::bail!("Path does not exist: {:?}", path);
     }
@@ -105,12 +350,7 @@ pub fn scan_directory(path: &Path, options: &ScanOptions) -> Result<Vec<FileInfo
         anyhow::bail!("Not a directory: {:?}", path);
     }
 
-    // Compile ignore patterns
-    let ignore_patterns: Vec<glob::Pattern> = options
-        .ignore_patterns
-        .iter()
-        .filter_map(|p| glob::Pattern::new(p).ok())
-        .collect();
+    let ignore_patterns = options.ignore_globs()?;
 
     let mut walker = WalkDir::new(path).follow_links(options.follow_symlinks);
 
@@ -118,7 +358,9 @@ pub fn scan_directory(path: &Path, options: &ScanOptions) -> Result<Vec<FileInfo
         walker = walker.max_depth(depth);
     }
 
-    let files: Vec<FileInfo> = walker
+    // The walk itself only reads directories; the per-file metadata reads and
+    // filters after it are what a parallel scan spreads out
+    let paths: Vec<PathBuf> = walker
         .into_iter()
         .filter_map(|entry| entry.ok())
         .filter(|entry| entry.file_type().is_file())
@@ -130,99 +372,153 @@ pub fn scan_directory(path: &Path, options: &ScanOptions) -> Result<Vec<FileInfo
             }
         })
         .filter(|entry| {
-            // Check if file matches any ignore pattern
-            let file_name = entry.file_name().to_string_lossy();
-            let file_path = entry.path().to_string_lossy();
-            !ignore_patterns
-                .iter()
-                .any(|pattern| pattern.matches(&file_name) || pattern.matches(&file_path))
-        })
-        .filter_map(|entry| FileInfo::from_path(entry.path()).ok())
-        // Apply size filters
-        .filter(|file| {
-            if let Some(min) = options.min_size {
-                if file.size < min {
-                    return false;
-                }
-            }
-            if let Some(max) = options.max_size {
-                if file.size > max {
-                    return false;
-                }
-            }
-            true
+            options.no_default_ignores
+                || !is_default_junk(entry.path().strip_prefix(path).unwrap_or(entry.path()))
         })
-        // Apply date filters
-        .filter(|file| {
-            if let Some(after) = options.after_date {
-                if file.modified < after {
-                    return false;
-                }
-            }
-            if let Some(before) = options.before_date {
-                if file.modified > before {
-                    return false;
-                }
-            }
-            true
-        })
-        // Apply name filters
-        .filter(|file| {
-            use crate::core::filters::NameFilter;
-            let filter = NameFilter {
-                startswith: options.name_startswith.clone(),
-                endswith: options.name_endswith.clone(),
-                contains: options.name_contains.clone(),
-                case_insensitive: true,
-            };
-            if filter.is_empty() {
-                return true;
-            }
-            filter.matches(&file.name)
-        })
-        // Apply regex filter
-        .filter(|file| {
-            if let Some(ref pattern) = options.regex_pattern {
-                crate::core::filters::matches_regex(&file.name, pattern).unwrap_or_default()
-            } else {
-                true
-            }
-        })
-        // Apply MIME filter
-        .filter(|file| {
-            if let Some(ref mime_filter) = options.mime_filter {
-                crate::core::filters::matches_mime(&file.path, mime_filter)
-            } else {
-                true
+        // Check if file matches any ignore pattern
+        .filter(|entry| {
+            let ignored = matches_glob(&ignore_patterns, entry.path());
+            if ignored {
+                log::debug!("skip path={:?} reason=ignore-pattern", entry.path());
             }
+            !ignored
         })
+        .map(walkdir::DirEntry::into_path)
         .collect();
 
+    let files: Vec<FileInfo> = if parallel {
+        paths
+            .par_iter()
+            .filter_map(|path| scan_file(path, options))
+            .collect()
+    } else {
+        paths
+            .iter()
+            .filter_map(|path| scan_file(path, options))
+            .collect()
+    };
+
+    log::info!("scanned root={:?} files={}", path, files.len());
     Ok(files)
 }
 
+/// Read one walked file's metadata and keep it if it passes the filters
+fn scan_file(path: &Path, options: &ScanOptions) -> Option<FileInfo> {
+    let file = FileInfo::from_path(path).ok()?;
+    if !options.matches(&file) {
+        log::debug!("skip path={:?} reason=filters", file.path);
+        return None;
+    }
+    Some(file)
+}
+
 /// Count total size of files
 pub fn total_size(files: &[FileInfo]) -> u64 {
     files.iter().map(|f| f.size).sum()
 }
 
-/// Format bytes into human-readable string
-pub fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
+/// Disk usage of one top-level entry under a scanned directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeEntry {
+    pub name: String,
+    pub is_dir: bool,
+    pub size: u64,
+    pub file_count: usize,
+}
 
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
+/// Sum file sizes per immediate child of `base` (subdirectories are summed
+/// recursively, standalone files count on their own), largest first
+pub fn sizes_by_top_level(files: &[FileInfo], base: &Path) -> Vec<SizeEntry> {
+    let mut entries: std::collections::HashMap<String, SizeEntry> =
+        std::collections::HashMap::new();
+
+    for file in files {
+        let relative = match file.path.strip_prefix(base) {
+            Ok(r) => r,
+            Err(_) => continue,
+        };
+        let mut components = relative.components();
+        let first = match components.next() {
+            Some(c) => c.as_os_str().to_string_lossy().to_string(),
+            None => continue,
+        };
+        let is_dir = components.next().is_some();
+
+        let entry = entries.entry(first.clone()).or_insert(SizeEntry {
+            name: first,
+            is_dir,
+            size: 0,
+            file_count: 0,
+        });
+        entry.size += file.size;
+        entry.file_count += 1;
+    }
+
+    let mut entries: Vec<SizeEntry> = entries.into_values().collect();
+    entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(&b.name)));
+    entries
+}
+
+/// How sizes are scaled and labeled for display (`--units`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SizeUnits {
+    /// Powers of 1024 labeled KB/MB/GB (the historical output)
+    #[default]
+    Binary,
+    /// Powers of 1000 labeled kB/MB/GB
+    Si,
+    /// Powers of 1024 labeled KiB/MiB/GiB
+    Iec,
+}
+
+impl SizeUnits {
+    /// Format `bytes` in these units, e.g. `1.50 KB`
+    pub fn format(self, bytes: u64) -> String {
+        let (base, labels) = match self {
+            SizeUnits::Binary => (1024u64, ["KB", "MB", "GB"]),
+            SizeUnits::Si => (1000, ["kB", "MB", "GB"]),
+            SizeUnits::Iec => (1024, ["KiB", "MiB", "GiB"]),
+        };
+
+        let mut unit = base.pow(labels.len() as u32);
+        for label in labels.iter().rev() {
+            if bytes >= unit {
+                return format!("{:.2} {}", bytes as f64 / unit as f64, label);
+            }
+            unit /= base;
+        }
         format!("{} B", bytes)
     }
 }
 
+/// Upper bounds (exclusive) of the size buckets, in bytes, with their folder names.
+/// Anything at or above the last bound is `SIZE_BUCKET_HUGE`.
+pub const SIZE_BUCKETS: [(u64, &str); 3] = [
+    (1024 * 1024, "Tiny"),
+    (100 * 1024 * 1024, "Small"),
+    (1024 * 1024 * 1024, "Large"),
+];
+
+/// Bucket for files at or above the last `SIZE_BUCKETS` bound
+pub const SIZE_BUCKET_HUGE: &str = "Huge";
+
+/// Coarse size bucket for `bytes`: Tiny (<1MB), Small (<100MB), Large (<1GB), Huge (>=1GB)
+pub fn size_bucket(bytes: u64) -> &'static str {
+    SIZE_BUCKETS
+        .iter()
+        .find(|(limit, _)| bytes < *limit)
+        .map_or(SIZE_BUCKET_HUGE, |(_, name)| name)
+}
+
+/// Folder for a name's first character: the letter uppercased, or `#` when the
+/// name starts with anything but a letter
+pub fn first_letter(name: &str) -> String {
+    match name.chars().next() {
+        Some(c) if c.is_alphabetic() => c.to_uppercase().collect(),
+        _ => "#".to_string(),
+    }
+}
+
 /// Parse a human-readable size string to bytes
 /// Examples: "10MB", "1.5GB", "500KB", "1024", "100B"
 pub fn parse_size(s: &str) -> Result<u64, String> {
@@ -303,30 +599,299 @@ mod tests {
     use std::io::Write;
     use tempfile::tempdir;
 
+    #[test]
+    fn test_sizes_by_top_level() {
+        let dir = tempdir().unwrap();
+        let base = dir.path();
+        fs::create_dir_all(base.join("videos/2024")).unwrap();
+        fs::create_dir_all(base.join("docs")).unwrap();
+        fs::write(base.join("videos/a.mp4"), vec![0u8; 3000]).unwrap();
+        fs::write(base.join("videos/2024/b.mp4"), vec![0u8; 2000]).unwrap();
+        fs::write(base.join("docs/notes.txt"), vec![0u8; 100]).unwrap();
+        fs::write(base.join("big.iso"), vec![0u8; 4000]).unwrap();
+
+        let files = scan_directory(base, &ScanOptions::default()).unwrap();
+        let entries = sizes_by_top_level(&files, base);
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].name, "videos");
+        assert!(entries[0].is_dir);
+        assert_eq!(entries[0].size, 5000);
+        assert_eq!(entries[0].file_count, 2);
+        assert_eq!(entries[1].name, "big.iso");
+        assert!(!entries[1].is_dir);
+        assert_eq!(entries[1].size, 4000);
+        assert_eq!(entries[2].name, "docs");
+        assert_eq!(entries[2].size, 100);
+    }
+
+    #[test]
+    fn test_expand_braces() {
+        assert_eq!(
+            expand_braces("*.{jpg,png}").unwrap(),
+            vec!["*.jpg", "*.png"]
+        );
+        assert_eq!(
+            expand_braces("{a,b{c,d}}.txt").unwrap(),
+            vec!["a.txt", "bc.txt", "bd.txt"]
+        );
+        assert_eq!(expand_braces("[{]*").unwrap(), vec!["[{]*"]);
+    }
+
+    #[test]
+    fn test_expand_braces_literal_unchanged() {
+        assert_eq!(expand_braces("*.tmp").unwrap(), vec!["*.tmp"]);
+    }
+
+    #[test]
+    fn test_expand_braces_malformed() {
+        assert!(expand_braces("*.{jpg,png").is_err());
+        assert!(expand_braces("*.jpg}").is_err());
+    }
+
+    #[test]
+    fn test_compile_glob_matches_name_or_path() {
+        let patterns = compile_glob("*.{jpg,png}").unwrap();
+        assert!(matches_glob(&patterns, Path::new("/photos/a.jpg")));
+        assert!(matches_glob(&patterns, Path::new("b.png")));
+        assert!(!matches_glob(&patterns, Path::new("/photos/c.gif")));
+
+        assert!(compile_glob("[").is_err());
+    }
+
+    #[test]
+    fn test_load_list_file_skips_blanks_and_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("exts.txt");
+        fs::write(&path, "# raw formats\ncr2\n\n  .NEF  \n# done\n").unwrap();
+
+        let exts = load_list_file(&path).unwrap();
+        assert_eq!(exts, vec!["cr2", ".NEF"]);
+        assert!(extension_allowed(Some("nef"), &exts, &[], false));
+        assert!(!extension_allowed(Some("jpg"), &exts, &[], false));
+
+        assert!(load_list_file(&dir.path().join("missing.txt")).is_err());
+    }
+
+    #[test]
+    fn test_extension_allowed_only_list() {
+        let only = vec!["jpg".to_string(), ".PNG".to_string()];
+        assert!(extension_allowed(Some("jpg"), &only, &[], false));
+        assert!(extension_allowed(Some("png"), &only, &[], false));
+        assert!(!extension_allowed(Some("gif"), &only, &[], false));
+        assert!(!extension_allowed(None, &only, &[], false));
+    }
+
+    #[test]
+    fn test_extension_allowed_exclude_list() {
+        let exclude = vec!["tmp".to_string(), "Part".to_string()];
+        assert!(!extension_allowed(Some("tmp"), &[], &exclude, false));
+        assert!(!extension_allowed(Some("part"), &[], &exclude, false));
+        assert!(extension_allowed(Some("jpg"), &[], &exclude, false));
+        assert!(extension_allowed(None, &[], &exclude, false));
+        assert!(extension_allowed(None, &[], &[], false));
+    }
+
+    #[test]
+    fn test_scan_directory_extension_lists() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.JPG", "b.png", "c.tmp", "Makefile"] {
+            File::create(dir.path().join(name)).unwrap();
+        }
+        let scan = |only: &[&str], exclude: &[&str]| {
+            let options = ScanOptions {
+                only_extensions: only.iter().map(|e| e.to_string()).collect(),
+                exclude_extensions: exclude.iter().map(|e| e.to_string()).collect(),
+                ..Default::default()
+            };
+            let mut names: Vec<String> = scan_directory(dir.path(), &options)
+                .unwrap()
+                .into_iter()
+                .map(|f| f.name)
+                .collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(scan(&["jpg", "png"], &[]), vec!["a.JPG", "b.png"]);
+        assert_eq!(scan(&[], &["tmp"]), vec!["Makefile", "a.JPG", "b.png"]);
+        assert_eq!(scan(&["jpg", "png"], &["png"]), vec!["a.JPG"]);
+    }
+
+    #[test]
+    fn test_scan_directory_case_sensitive_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["Foo.txt", "foo.TXT", "bar.txt"] {
+            File::create(dir.path().join(name)).unwrap();
+        }
+        let scan = |options: ScanOptions| {
+            let mut names: Vec<String> = scan_directory(dir.path(), &options)
+                .unwrap()
+                .into_iter()
+                .map(|f| f.name)
+                .collect();
+            names.sort();
+            names
+        };
+        let contains = |case_sensitive| ScanOptions {
+            name_contains: Some("Foo".to_string()),
+            case_sensitive,
+            ..Default::default()
+        };
+        let regex = |case_sensitive| ScanOptions {
+            regex_pattern: Some("^foo".to_string()),
+            case_sensitive,
+            ..Default::default()
+        };
+        let only_ext = |case_sensitive| ScanOptions {
+            only_extensions: vec!["txt".to_string()],
+            case_sensitive,
+            ..Default::default()
+        };
+
+        assert_eq!(scan(contains(false)), vec!["Foo.txt", "foo.TXT"]);
+        assert_eq!(scan(contains(true)), vec!["Foo.txt"]);
+        assert_eq!(scan(regex(false)), vec!["Foo.txt", "foo.TXT"]);
+        assert_eq!(scan(regex(true)), vec!["foo.TXT"]);
+        assert_eq!(scan(only_ext(false)), vec!["Foo.txt", "bar.txt", "foo.TXT"]);
+        assert_eq!(scan(only_ext(true)), vec!["Foo.txt", "bar.txt"]);
+    }
+
+    #[test]
+    fn test_parallel_scan_matches_sequential() {
+        let dir = tempfile::tempdir().unwrap();
+        for sub in ["", "docs", "docs/old", "photos", ".cache", "__MACOSX"] {
+            let folder = dir.path().join(sub);
+            fs::create_dir_all(&folder).unwrap();
+            for i in 0..25 {
+                let ext = ["txt", "jpg", "pdf", "tmp"][i % 4];
+                fs::write(folder.join(format!("f{}.{}", i, ext)), "x".repeat(i)).unwrap();
+            }
+            fs::write(folder.join(".hidden"), "h").unwrap();
+        }
+        let file_set = |options: &ScanOptions, parallel: bool| {
+            let mut paths: Vec<(PathBuf, u64)> = scan_directory_with(dir.path(), options, parallel)
+                .unwrap()
+                .into_iter()
+                .map(|f| (f.path, f.size))
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        let filtered = ScanOptions {
+            ignore_patterns: vec!["*.tmp".to_string()],
+            min_size: Some(3),
+            max_depth: Some(2),
+            name_contains: Some("1".to_string()),
+            ..Default::default()
+        };
+        for options in [ScanOptions::default(), filtered] {
+            let sequential = file_set(&options, false);
+            assert!(!sequential.is_empty());
+            assert_eq!(file_set(&options, true), sequential);
+        }
+    }
+
+    #[test]
+    fn test_scan_directory_ignore_braces() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["a.jpg", "b.png", "c.txt"] {
+            File::create(dir.path().join(name)).unwrap();
+        }
+
+        let options = ScanOptions {
+            ignore_patterns: vec!["*.{jpg,png}".to_string()],
+            ..Default::default()
+        };
+        let files = scan_directory(dir.path(), &options).unwrap();
+
+        let names: Vec<_> = files.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["c.txt"]);
+
+        let options = ScanOptions {
+            ignore_patterns: vec!["*.{jpg".to_string()],
+            ..Default::default()
+        };
+        assert!(scan_directory(dir.path(), &options).is_err());
+    }
+
     #[test]
     fn test_format_size_bytes() {
-        assert_eq!(format_size(0), "0 B");
-        assert_eq!(format_size(500), "500 B");
-        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(SizeUnits::Binary.format(0), "0 B");
+        assert_eq!(SizeUnits::Binary.format(500), "500 B");
+        assert_eq!(SizeUnits::Binary.format(1023), "1023 B");
     }
 
     #[test]
     fn test_format_size_kb() {
-        assert_eq!(format_size(1024), "1.00 KB");
-        assert_eq!(format_size(1536), "1.50 KB");
-        assert_eq!(format_size(10240), "10.00 KB");
+        assert_eq!(SizeUnits::Binary.format(1024), "1.00 KB");
+        assert_eq!(SizeUnits::Binary.format(1536), "1.50 KB");
+        assert_eq!(SizeUnits::Binary.format(10240), "10.00 KB");
+    }
+
+    #[test]
+    fn test_first_letter() {
+        assert_eq!(first_letter("fonts.zip"), "F");
+        assert_eq!(first_letter("Dune.epub"), "D");
+        assert_eq!(first_letter("1984.epub"), "#");
+        assert_eq!(first_letter("_draft.txt"), "#");
+        assert_eq!(first_letter(".hidden"), "#");
+        assert_eq!(first_letter("émile.pdf"), "É");
+        assert_eq!(first_letter("ωmega.txt"), "Ω");
+        assert_eq!(first_letter("東京.jpg"), "東");
+        assert_eq!(first_letter(""), "#");
+    }
+
+    #[test]
+    fn test_size_bucket_boundaries() {
+        const MB: u64 = 1024 * 1024;
+        const GB: u64 = 1024 * MB;
+        assert_eq!(size_bucket(0), "Tiny");
+        assert_eq!(size_bucket(MB - 1), "Tiny");
+        assert_eq!(size_bucket(MB), "Small");
+        assert_eq!(size_bucket(100 * MB - 1), "Small");
+        assert_eq!(size_bucket(100 * MB), "Large");
+        assert_eq!(size_bucket(GB - 1), "Large");
+        assert_eq!(size_bucket(GB), "Huge");
+        assert_eq!(size_bucket(u64::MAX), "Huge");
     }
 
     #[test]
     fn test_format_size_mb() {
-        assert_eq!(format_size(1024 * 1024), "1.00 MB");
-        assert_eq!(format_size(1024 * 1024 * 5), "5.00 MB");
+        assert_eq!(SizeUnits::Binary.format(1024 * 1024), "1.00 MB");
+        assert_eq!(SizeUnits::Binary.format(1024 * 1024 * 5), "5.00 MB");
     }
 
     #[test]
     fn test_format_size_gb() {
-        assert_eq!(format_size(1024 * 1024 * 1024), "1.00 GB");
-        assert_eq!(format_size(1024 * 1024 * 1024 * 2), "2.00 GB");
+        assert_eq!(SizeUnits::Binary.format(1024 * 1024 * 1024), "1.00 GB");
+        assert_eq!(SizeUnits::Binary.format(1024 * 1024 * 1024 * 2), "2.00 GB");
+    }
+
+    #[test]
+    fn test_size_units_binary() {
+        assert_eq!(SizeUnits::Binary.format(999), "999 B");
+        assert_eq!(SizeUnits::Binary.format(1000), "1000 B");
+        assert_eq!(SizeUnits::Binary.format(1536), "1.50 KB");
+        assert_eq!(SizeUnits::Binary.format(1024 * 1024 * 1024), "1.00 GB");
+    }
+
+    #[test]
+    fn test_size_units_si() {
+        assert_eq!(SizeUnits::Si.format(999), "999 B");
+        assert_eq!(SizeUnits::Si.format(1000), "1.00 kB");
+        assert_eq!(SizeUnits::Si.format(1024), "1.02 kB");
+        assert_eq!(SizeUnits::Si.format(1_500_000), "1.50 MB");
+        assert_eq!(SizeUnits::Si.format(1024 * 1024 * 1024), "1.07 GB");
+    }
+
+    #[test]
+    fn test_size_units_iec() {
+        assert_eq!(SizeUnits::Iec.format(1023), "1023 B");
+        assert_eq!(SizeUnits::Iec.format(1536), "1.50 KiB");
+        assert_eq!(SizeUnits::Iec.format(5 * 1024 * 1024), "5.00 MiB");
+        assert_eq!(SizeUnits::Iec.format(2 * 1024 * 1024 * 1024), "2.00 GiB");
     }
 
     #[test]
@@ -336,6 +901,7 @@ mod tests {
                 path: PathBuf::from("/test/a.txt"),
                 name: "a.txt".to_string(),
                 extension: Some("txt".to_string()),
+                extension_raw: Some("txt".to_string()),
                 size: 100,
                 modified: SystemTime::now(),
                 created: None,
@@ -344,6 +910,7 @@ mod tests {
                 path: PathBuf::from("/test/b.txt"),
                 name: "b.txt".to_string(),
                 extension: Some("txt".to_string()),
+                extension_raw: Some("txt".to_string()),
                 size: 200,
                 modified: SystemTime::now(),
                 created: None,
@@ -399,6 +966,35 @@ mod tests {
         assert_eq!(result.len(), 2);
     }
 
+    #[test]
+    fn test_scan_directory_default_ignores() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("__MACOSX/photos")).unwrap();
+        File::create(dir.path().join("__MACOSX/photos/._beach.jpg")).unwrap();
+        File::create(dir.path().join("__MACOSX/notes.txt")).unwrap();
+        File::create(dir.path().join("Thumbs.db")).unwrap();
+        File::create(dir.path().join("desktop.ini")).unwrap();
+        File::create(dir.path().join(".DS_Store")).unwrap();
+        File::create(dir.path().join("beach.jpg")).unwrap();
+
+        // Junk is skipped even when hidden files are included
+        let options = ScanOptions {
+            include_hidden: true,
+            ..Default::default()
+        };
+        let result = scan_directory(dir.path(), &options).unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].name, "beach.jpg");
+
+        let options = ScanOptions {
+            include_hidden: true,
+            no_default_ignores: true,
+            ..Default::default()
+        };
+        let result = scan_directory(dir.path(), &options).unwrap();
+        assert_eq!(result.len(), 6);
+    }
+
     #[test]
     fn test_scan_directory_max_depth() {
         let dir = tempdir().unwrap();
@@ -445,6 +1041,17 @@ mod tests {
         assert_eq!(info.size, 7); // "content" = 7 bytes
     }
 
+    #[test]
+    fn test_file_info_keeps_raw_extension_case() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("IMG_0001.JPG");
+        File::create(&file_path).unwrap();
+
+        let info = FileInfo::from_path(&file_path).unwrap();
+        assert_eq!(info.extension.as_deref(), Some("jpg"));
+        assert_eq!(info.extension_raw.as_deref(), Some("JPG"));
+    }
+
     #[test]
     fn test_file_info_no_extension() {
         let dir = tempdir().unwrap();
@@ -454,6 +1061,7 @@ mod tests {
         let info = FileInfo::from_path(&file_path).unwrap();
         assert_eq!(info.name, "Makefile");
         assert_eq!(info.extension, None);
+        assert_eq!(info.extension_raw, None);
     }
 
     // ==================== parse_size tests ====================
@@ -467,10 +1075,7 @@ mod tests {
         );
         assert_eq!(parse_size("500KB").unwrap(), 500 * 1024);
         assert_eq!(parse_size("100B").unwrap(), 100);
-        assert_eq!(
-            parse_size("1TB").unwrap(),
-            1024_u64 * 1024 * 1024 * 1024
-        );
+        assert_eq!(parse_size("1TB").unwrap(), 1024_u64 * 1024 * 1024 * 1024);
     }
 
     #[test]
@@ -502,7 +1107,10 @@ mod tests {
 
     #[test]
     fn test_parse_size_float_values() {
-        assert_eq!(parse_size("0.5GB").unwrap(), (0.5 * 1024.0 * 1024.0 * 1024.0) as u64);
+        assert_eq!(
+            parse_size("0.5GB").unwrap(),
+            (0.5 * 1024.0 * 1024.0 * 1024.0) as u64
+        );
         assert_eq!(parse_size("2.5MB").unwrap(), (2.5 * 1024.0 * 1024.0) as u64);
     }
 
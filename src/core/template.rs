@@ -1,15 +1,20 @@
 //! Template engine for flexible file/folder naming
 //!
 //! Supports variables like: `{year}/{month}/{category}/{filename}`
+//! and transform filters like `{ext|lower}` or `{filename|snake|upper}`
 
 use std::collections::HashMap;
 use std::path::Path;
 
 use chrono::{Datelike, Local};
+use xxhash_rust::xxh3::xxh3_128;
 
 use crate::classifier::Classifier;
-use crate::scanner::FileInfo;
-use crate::utils::metadata::{is_audio_supported, is_exif_supported, AudioMetadata, ImageMetadata};
+use crate::filters::image_dimensions;
+use crate::scanner::{first_letter, size_bucket, FileInfo};
+use crate::utils::metadata::{
+    is_audio_supported, is_exif_supported, orientation_bucket, AudioMetadata, ImageMetadata,
+};
 
 /// Template engine for rendering destination paths
 pub struct TemplateEngine {
@@ -41,6 +46,12 @@ impl TemplateEngine {
                 .clone()
                 .unwrap_or_else(|| "unknown".to_string()),
         );
+        variables.insert(
+            "ext_raw".to_string(),
+            file.extension_raw
+                .clone()
+                .unwrap_or_else(|| "unknown".to_string()),
+        );
 
         // File size
         variables.insert("size".to_string(), file.size.to_string());
@@ -49,6 +60,11 @@ impl TemplateEngine {
             "size_mb".to_string(),
             (file.size / (1024 * 1024)).to_string(),
         );
+        variables.insert(
+            "size_bucket".to_string(),
+            size_bucket(file.size).to_string(),
+        );
+        variables.insert("first_letter".to_string(), first_letter(&file.name));
 
         // Modified date
         if let Ok(duration) = file.modified.duration_since(std::time::UNIX_EPOCH) {
@@ -69,14 +85,24 @@ impl TemplateEngine {
         variables.insert("now.day".to_string(), format!("{:02}", now.day()));
         variables.insert("now.date".to_string(), now.format("%Y-%m-%d").to_string());
 
+        // De-collision tokens, derived from the path so dry-runs match the real run
+        let uuid = path_uuid(&file.path);
+        variables.insert("short".to_string(), uuid[..8].to_string());
+        variables.insert("random".to_string(), uuid[..8].to_string());
+        variables.insert("uuid".to_string(), uuid);
+
         // Category
-        let category = classifier.classify(file.extension.as_deref());
-        variables.insert("category".to_string(), category.folder_name().to_string());
-        variables.insert("type".to_string(), category.folder_name().to_string());
+        let category = classifier.category_name(file.extension.as_deref());
+        variables.insert("category".to_string(), category.to_string());
+        variables.insert("type".to_string(), category.to_string());
 
         // Try to get EXIF metadata for images
+        let mut orientation = None;
+        let mut dimensions = None;
         if is_exif_supported(&file.path) {
             if let Some(meta) = ImageMetadata::from_path(&file.path) {
+                orientation = meta.orientation;
+                dimensions = meta.dimensions;
                 if let Some(camera) = meta.camera_folder_name() {
                     variables.insert("camera".to_string(), camera);
                 }
@@ -92,6 +118,13 @@ impl TemplateEngine {
             }
         }
 
+        // Photos without EXIF dimensions (e.g. PNGs) fall back to the image header
+        let dimensions = dimensions.or_else(|| image_dimensions(&file.path));
+        variables.insert(
+            "orientation".to_string(),
+            orientation_bucket(dimensions, orientation).to_string(),
+        );
+
         // Try to get audio metadata
         if is_audio_supported(&file.path) {
             if let Some(meta) = AudioMetadata::from_path(&file.path) {
@@ -114,7 +147,20 @@ impl TemplateEngine {
 
     /// Render a template string, replacing {variable} with values
     pub fn render(&self, template: &str) -> String {
-        let mut result = template.to_string();
+        // Resolve {variable|transform|...} first; unknown variables are left
+        // in place so the fallback below turns them into "Unknown"
+        let filter_re = regex::Regex::new(r"\{([^{}|]+)((?:\|[A-Za-z_]+)+)\}").unwrap();
+        let mut result = filter_re
+            .replace_all(template, |caps: &regex::Captures| {
+                match self.variables.get(&caps[1]) {
+                    Some(value) => caps[2]
+                        .split('|')
+                        .filter(|t| !t.is_empty())
+                        .fold(value.clone(), |acc, t| apply_transform(&acc, t)),
+                    None => caps[0].to_string(),
+                }
+            })
+            .to_string();
 
         for (key, value) in &self.variables {
             let pattern = format!("{{{}}}", key);
@@ -146,6 +192,48 @@ impl TemplateEngine {
     }
 }
 
+/// Apply a named transform filter to a value.
+/// Unknown transforms leave the value unchanged.
+fn apply_transform(value: &str, transform: &str) -> String {
+    match transform.to_lowercase().as_str() {
+        "upper" => value.to_uppercase(),
+        "lower" => value.to_lowercase(),
+        "snake" => value
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|part| !part.is_empty())
+            .map(|part| part.to_lowercase())
+            .collect::<Vec<_>>()
+            .join("_"),
+        "title" => value
+            .split(' ')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => {
+                        first.to_uppercase().collect::<String>() + &chars.as_str().to_lowercase()
+                    }
+                    None => String::new(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => value.to_string(),
+    }
+}
+
+/// Built-in preset names with their aliases, as accepted by `get_preset_template`
+pub const PRESETS: &[(&str, &[&str])] = &[
+    ("by-type", &["type"]),
+    ("by-date", &["date"]),
+    ("by-extension", &["extension", "ext"]),
+    ("by-camera", &["camera"]),
+    ("by-date-taken", &["date-taken"]),
+    ("by-artist", &["artist"]),
+    ("by-album", &["album"]),
+    ("photos", &[]),
+    ("music", &[]),
+];
+
 /// Built-in template presets
 pub fn get_preset_template(preset: &str) -> Option<&'static str> {
     match preset.to_lowercase().as_str() {
@@ -162,6 +250,23 @@ pub fn get_preset_template(preset: &str) -> Option<&'static str> {
     }
 }
 
+/// A v4-formatted UUID seeded from a hash of `path`, so the same file always gets
+/// the same token
+fn path_uuid(path: &Path) -> String {
+    let mut bytes = xxh3_128(path.to_string_lossy().as_bytes()).to_be_bytes();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40; // version 4
+    bytes[8] = (bytes[8] & 0x3f) | 0x80; // RFC 4122 variant
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,6 +284,81 @@ mod tests {
         assert_eq!(result, "2024/12/photo");
     }
 
+    #[test]
+    fn test_random_tokens_are_stable_per_path() {
+        let classifier = Classifier::new();
+        let file = |path: &str| FileInfo::fixture(path, 1);
+        let template = "{filename}_{short}/{uuid}";
+
+        let first = TemplateEngine::from_file(&file("/dump/a/IMG_0001.bin"), &classifier);
+        let again = TemplateEngine::from_file(&file("/dump/a/IMG_0001.bin"), &classifier);
+        let other = TemplateEngine::from_file(&file("/dump/b/IMG_0001.bin"), &classifier);
+        assert_eq!(first.render(template), again.render(template));
+        assert_ne!(first.render(template), other.render(template));
+
+        let rendered = first.render("{short}|{uuid}|{random}");
+        let parts: Vec<&str> = rendered.split('|').collect();
+        assert_eq!(parts[0].len(), 8);
+        assert_eq!(parts[0], parts[2]);
+        assert!(parts[1].starts_with(parts[0]));
+        assert_eq!(parts[1].len(), 36);
+        assert_eq!(&parts[1][14..15], "4");
+    }
+
+    #[test]
+    fn test_ext_raw_keeps_case() {
+        let file = FileInfo::fixture("/dump/IMG_0001.JPG", 1);
+        let engine = TemplateEngine::from_file(&file, &Classifier::new());
+        assert_eq!(engine.render("{ext}/{ext_raw}"), "jpg/JPG");
+    }
+
+    #[test]
+    fn test_size_bucket_variable() {
+        let file = |size: u64| FileInfo::fixture("/dump/disk.img", size);
+        let classifier = Classifier::new();
+        let render =
+            |size| TemplateEngine::from_file(&file(size), &classifier).render("{size_bucket}");
+        assert_eq!(render(1024 * 1024), "Small");
+        assert_eq!(render(1024 * 1024 * 1024), "Huge");
+    }
+
+    #[test]
+    fn test_first_letter_variable() {
+        let file = |name: &str| FileInfo::fixture(std::path::Path::new("/books").join(name), 1);
+        let classifier = Classifier::new();
+        let render = |name| {
+            TemplateEngine::from_file(&file(name), &classifier).render("{first_letter}/{name}")
+        };
+        assert_eq!(render("dune.epub"), "D/dune.epub");
+        assert_eq!(render("2001.epub"), "#/2001.epub");
+        assert_eq!(render("Öga.epub"), "Ö/Öga.epub");
+    }
+
+    #[test]
+    fn test_orientation_variable_from_image_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = |name: &str, width: u32, height: u32| {
+            let path = dir.path().join(name);
+            image::RgbImage::new(width, height).save(&path).unwrap();
+            FileInfo::from_path(&path).unwrap()
+        };
+        let classifier = Classifier::new();
+        let render = |file: FileInfo| {
+            TemplateEngine::from_file(&file, &classifier).render("Photos/{orientation}")
+        };
+
+        assert_eq!(render(file("tall.png", 4, 8)), "Photos/Portrait");
+        assert_eq!(render(file("wide.png", 8, 4)), "Photos/Landscape");
+        assert_eq!(render(file("even.png", 5, 5)), "Photos/Square");
+
+        let notes = dir.path().join("notes.txt");
+        std::fs::write(&notes, "not a photo").unwrap();
+        assert_eq!(
+            render(FileInfo::from_path(&notes).unwrap()),
+            "Photos/Unknown"
+        );
+    }
+
     #[test]
     fn test_render_missing_variable() {
         let vars = HashMap::new();
@@ -201,6 +381,54 @@ mod tests {
         assert!(result.contains("folder"));
     }
 
+    fn transform_engine() -> TemplateEngine {
+        let mut vars = HashMap::new();
+        vars.insert("ext".to_string(), "JPG".to_string());
+        vars.insert("filename".to_string(), "IMG 0001-Final".to_string());
+        vars.insert("artist".to_string(), "taylor SWIFT".to_string());
+        TemplateEngine::new(vars)
+    }
+
+    #[test]
+    fn test_render_transform_upper_lower() {
+        let engine = transform_engine();
+        assert_eq!(engine.render("{ext|lower}"), "jpg");
+        assert_eq!(engine.render("{ext|upper}"), "JPG");
+    }
+
+    #[test]
+    fn test_render_transform_snake() {
+        let engine = transform_engine();
+        assert_eq!(engine.render("{filename|snake}"), "img_0001_final");
+    }
+
+    #[test]
+    fn test_render_transform_title() {
+        let engine = transform_engine();
+        assert_eq!(engine.render("{artist|title}"), "Taylor Swift");
+    }
+
+    #[test]
+    fn test_render_transform_chain() {
+        let engine = transform_engine();
+        assert_eq!(
+            engine.render("{filename|snake|upper}.{ext|lower}"),
+            "IMG_0001_FINAL.jpg"
+        );
+    }
+
+    #[test]
+    fn test_render_transform_unknown_leaves_value() {
+        let engine = transform_engine();
+        assert_eq!(engine.render("{ext|reverse}"), "JPG");
+    }
+
+    #[test]
+    fn test_render_transform_missing_variable() {
+        let engine = transform_engine();
+        assert_eq!(engine.render("{album|lower}/{ext}"), "Unknown/JPG");
+    }
+
     #[test]
     fn test_preset_templates() {
         assert_eq!(
@@ -290,6 +518,16 @@ mod tests {
         assert_eq!(list.len(), 2);
     }
 
+    #[test]
+    fn test_preset_list_matches_templates() {
+        for (name, aliases) in PRESETS {
+            assert!(get_preset_template(name).is_some(), "{}", name);
+            for alias in *aliases {
+                assert_eq!(get_preset_template(alias), get_preset_template(name));
+            }
+        }
+    }
+
     #[test]
     fn test_preset_case_insensitive() {
         assert!(get_preset_template("BY-TYPE").is_some());
@@ -311,4 +549,3 @@ mod tests {
         assert!(get_preset_template("music").is_some());
     }
 }
-
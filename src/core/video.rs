@@ -0,0 +1,260 @@
+//! Similar video detection by perceptual hashing of sampled frames
+//!
+//! Frames are extracted with `ffmpeg`/`ffprobe`, which are only called when
+//! neatcli is built with the `video` feature.
+
+use std::path::Path;
+
+use anyhow::Result;
+use image_hasher::ImageHash;
+
+use crate::duplicates::{group_similar, SimilarGroup};
+use crate::scanner::FileInfo;
+
+/// Positions (as fractions of the duration) where frames are sampled, away from
+/// the start and end where intros, fades and black frames are common
+pub const SAMPLE_POINTS: [f64; 5] = [0.1, 0.3, 0.5, 0.7, 0.9];
+
+/// Check if a file is a video format that can be frame-sampled
+pub fn is_video_supported(path: &Path) -> bool {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase());
+
+    matches!(
+        ext.as_deref(),
+        Some("mp4")
+            | Some("mov")
+            | Some("mkv")
+            | Some("avi")
+            | Some("webm")
+            | Some("m4v")
+            | Some("wmv")
+            | Some("flv")
+            | Some("mpg")
+            | Some("mpeg")
+    )
+}
+
+/// Distance between two videos: the average hamming distance of frames taken at
+/// the same sample points. Videos with no frames to compare are never similar.
+pub fn frames_distance(a: &[ImageHash], b: &[ImageHash]) -> u32 {
+    let pairs = a.len().min(b.len());
+    if pairs == 0 {
+        return u32::MAX;
+    }
+    let total: u32 = a.iter().zip(b).map(|(x, y)| x.dist(y)).sum();
+    total / pairs as u32
+}
+
+/// Group videos whose sampled frame hashes are within `threshold` of each other
+pub fn group_similar_videos(
+    hashes: &[(&FileInfo, Option<Vec<ImageHash>>)],
+    threshold: u32,
+) -> Vec<SimilarGroup> {
+    group_similar(hashes, threshold, |a, b| frames_distance(a, b))
+}
+
+/// Find visually similar videos by hashing frames sampled from each one
+#[cfg(feature = "video")]
+pub fn find_similar_videos(files: &[FileInfo], threshold: u32) -> Result<Vec<SimilarGroup>> {
+    use colored::*;
+    use image_hasher::{HashAlg, HasherConfig};
+    use rayon::prelude::*;
+
+    use crate::progress::progress_bar;
+
+    let videos: Vec<&FileInfo> = files
+        .iter()
+        .filter(|f| is_video_supported(&f.path))
+        .collect();
+
+    if videos.len() < 2 {
+        return Ok(Vec::new());
+    }
+
+    ffmpeg::check_installed()?;
+
+    println!(
+        "  {} Sampling {} frames from each of {} videos (parallel)...",
+        "→".cyan(),
+        SAMPLE_POINTS.len(),
+        videos.len()
+    );
+
+    let pb = progress_bar(
+        videos.len() as u64,
+        "Hashing videos",
+        "{spinner:.green} Hashing videos [{bar:40.cyan/blue}] {pos}/{len} ({per_sec})",
+    );
+
+    // Same hasher as for still images, so thresholds mean the same thing
+    let hasher = HasherConfig::new()
+        .hash_alg(HashAlg::DoubleGradient)
+        .hash_size(16, 16)
+        .to_hasher();
+
+    let hashes: Vec<(&FileInfo, Option<Vec<ImageHash>>)> = videos
+        .par_iter()
+        .map(|file| {
+            pb.inc(1);
+            let frames = ffmpeg::sample_frames(&file.path, &SAMPLE_POINTS)
+                .ok()
+                .map(|frames| frames.iter().map(|f| hasher.hash_image(f)).collect());
+            (*file, frames)
+        })
+        .collect();
+
+    pb.finish_and_clear();
+
+    Ok(group_similar_videos(&hashes, threshold))
+}
+
+/// Without the `video` feature there is no frame extraction to fall back on
+#[cfg(not(feature = "video"))]
+pub fn find_similar_videos(_files: &[FileInfo], _threshold: u32) -> Result<Vec<SimilarGroup>> {
+    anyhow::bail!(
+        "Video similarity is not available in this build; reinstall with `cargo install neatcli --features video`"
+    )
+}
+
+#[cfg(feature = "video")]
+mod ffmpeg {
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+
+    use anyhow::{bail, Context, Result};
+    use image::DynamicImage;
+
+    /// Fail with an install hint unless both `ffmpeg` and `ffprobe` can be run
+    pub fn check_installed() -> Result<()> {
+        for tool in ["ffmpeg", "ffprobe"] {
+            let found = Command::new(tool)
+                .arg("-version")
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success());
+            if !found {
+                bail!(
+                    "`{}` was not found; install ffmpeg (https://ffmpeg.org) and make sure it is on your PATH",
+                    tool
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Length of a video in seconds
+    fn duration(path: &Path) -> Result<f64> {
+        let output = Command::new("ffprobe")
+            .args(["-v", "error", "-show_entries", "format=duration"])
+            .args(["-of", "default=noprint_wrappers=1:nokey=1"])
+            .arg(path)
+            .output()
+            .context("Failed to run ffprobe")?;
+        if !output.status.success() {
+            bail!("ffprobe could not read {}", path.display());
+        }
+
+        String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse()
+            .with_context(|| format!("No duration for {}", path.display()))
+    }
+
+    /// Decode one frame at each of `points` (fractions of the duration)
+    pub fn sample_frames(path: &Path, points: &[f64]) -> Result<Vec<DynamicImage>> {
+        let duration = duration(path)?;
+        points
+            .iter()
+            .map(|point| frame_at(path, duration * point))
+            .collect()
+    }
+
+    /// Decode the frame at `seconds` as a PNG piped from ffmpeg
+    fn frame_at(path: &Path, seconds: f64) -> Result<DynamicImage> {
+        let output = Command::new("ffmpeg")
+            .args(["-v", "error", "-ss", &format!("{:.3}", seconds), "-i"])
+            .arg(path)
+            .args(["-frames:v", "1", "-f", "image2pipe", "-vcodec", "png", "-"])
+            .output()
+            .context("Failed to run ffmpeg")?;
+        if !output.status.success() || output.stdout.is_empty() {
+            bail!("ffmpeg could not extract a frame from {}", path.display());
+        }
+
+        image::load_from_memory(&output.stdout)
+            .with_context(|| format!("Invalid frame from {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn make_file_info(name: &str) -> FileInfo {
+        FileInfo {
+            path: PathBuf::from(name),
+            name: name.to_string(),
+            extension: Some("mp4".to_string()),
+            extension_raw: Some("mp4".to_string()),
+            size: 1000,
+            modified: SystemTime::now(),
+            created: None,
+        }
+    }
+
+    /// A 256-bit frame hash with the first `ones` bits set
+    fn frame_hash(ones: usize) -> ImageHash {
+        let mut bytes = [0u8; 32];
+        for bit in 0..ones {
+            bytes[bit / 8] |= 1 << (bit % 8);
+        }
+        ImageHash::from_bytes(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_is_video_supported() {
+        assert!(is_video_supported(Path::new("clip.MP4")));
+        assert!(is_video_supported(Path::new("movie.mkv")));
+        assert!(!is_video_supported(Path::new("photo.jpg")));
+        assert!(!is_video_supported(Path::new("noext")));
+    }
+
+    #[test]
+    fn test_frames_distance_averages_frames() {
+        let a = vec![frame_hash(0), frame_hash(0)];
+        let b = vec![frame_hash(4), frame_hash(8)];
+        assert_eq!(frames_distance(&a, &b), 6);
+        assert_eq!(frames_distance(&a, &a), 0);
+        // Only frames at the same sample points are compared
+        assert_eq!(frames_distance(&a, &b[..1]), 4);
+        assert_eq!(frames_distance(&a, &[]), u32::MAX);
+    }
+
+    #[test]
+    fn test_group_similar_videos() {
+        let original = make_file_info("original.mp4");
+        let reencode = make_file_info("reencode.mp4");
+        let other = make_file_info("other.mp4");
+        let unreadable = make_file_info("broken.mp4");
+
+        let hashes = vec![
+            (&original, Some(vec![frame_hash(0), frame_hash(10)])),
+            (&unreadable, None),
+            (&reencode, Some(vec![frame_hash(2), frame_hash(12)])),
+            (&other, Some(vec![frame_hash(120), frame_hash(200)])),
+        ];
+
+        let groups = group_similar_videos(&hashes, 10);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].representative.name, "original.mp4");
+        assert_eq!(groups[0].similar.len(), 1);
+        assert_eq!(groups[0].similar[0].0.name, "reencode.mp4");
+        assert_eq!(groups[0].similar[0].1, 2);
+    }
+}
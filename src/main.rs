@@ -12,101 +12,268 @@ mod watcher;
 pub use core::*;
 pub use utils::*;
 
+use std::io::IsTerminal;
+
 use anyhow::Result;
 use clap::Parser;
 
-use crate::cli::{Cli, Commands};
+use crate::cli::{resolve_format, Cli, Commands};
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    // Disable ANSI colors when asked to, or when output is piped/redirected
+    if cli.no_color || std::env::var_os("NO_COLOR").is_some() || !std::io::stdout().is_terminal() {
+        colored::control::set_override(false);
+    }
+    progress::set_mode(cli.progress.unwrap_or_else(progress::ProgressMode::detect));
+    scanner::set_fast_scan(cli.fast_scan);
+    logger::set_verify_undo(cli.verify_undo);
+    logging::init(cli.verbose, cli.log_file.as_deref())?;
+    let units = cli.units.unwrap_or_default();
+
+    if let Commands::Organize {
+        label: Some(label), ..
+    }
+    | Commands::Clean {
+        label: Some(label), ..
+    } = &cli.command
+    {
+        logger::set_batch_label(label.clone());
+    }
+
     match cli.command {
         Commands::Organize {
             paths,
             by_type,
             by_date,
             by_extension,
+            ext_case,
+            group_small,
             by_camera,
             by_date_taken,
             by_artist,
             by_album,
+            by_size,
+            by_alpha,
+            smart,
+            output_dir,
+            keep_structure,
             dry_run,
             execute,
+            explain,
+            simulate_conflicts,
+            diff,
             ignore,
+            no_default_ignores,
             min_size,
             max_size,
             after,
+            since,
             before,
+            taken_after,
+            taken_before,
+            taken_only,
+            min_width,
+            min_height,
+            max_width,
+            max_height,
+            images_only,
             copy,
+            preserve_times,
+            leave_symlink,
+            link_duplicates_in_place,
+            link,
+            fail_fast,
+            throttle,
+            atomic,
+            delete_empty_after,
+            include_empty_dirs,
+            report,
+            follow_up_command,
+            summary_json,
+            format,
+            plan,
+            apply,
             recursive,
             startswith,
             endswith,
             contains,
             regex,
+            case_sensitive,
             mime,
+            sniff,
+            only_ext,
+            not_ext,
+            only_ext_file,
+            not_ext_file,
             content,
+            category,
             template,
+            preset,
+            name_regex,
+            regex_fallback,
             on_conflict,
+            collision_suffix,
+            label: _,
         } => {
             commands::organize::run(
                 &paths,
                 by_type,
                 by_date,
                 by_extension,
+                ext_case,
+                group_small,
                 by_camera,
                 by_date_taken,
                 by_artist,
                 by_album,
+                by_size,
+                by_alpha,
+                smart,
+                output_dir.as_deref(),
+                keep_structure,
                 dry_run,
                 execute,
-                cli.verbose,
+                explain,
+                simulate_conflicts,
+                diff,
+                cli.verbose > 0,
+                cli.summary_only,
                 ignore,
+                no_default_ignores,
                 min_size,
                 max_size,
                 after,
+                since,
                 before,
+                taken_after,
+                taken_before,
+                taken_only,
+                min_width,
+                min_height,
+                max_width,
+                max_height,
+                images_only,
                 copy,
+                preserve_times,
+                leave_symlink,
+                link_duplicates_in_place,
+                link,
+                fail_fast,
+                throttle,
+                atomic,
+                delete_empty_after,
+                include_empty_dirs,
+                report.as_deref(),
+                follow_up_command.as_deref(),
+                summary_json,
+                format,
+                plan.as_deref(),
+                apply.as_deref(),
                 recursive,
                 startswith,
                 endswith,
                 contains,
                 regex,
+                case_sensitive,
                 mime,
+                sniff,
+                only_ext,
+                not_ext,
+                only_ext_file.as_deref(),
+                not_ext_file.as_deref(),
                 content,
+                category,
                 template,
+                preset,
+                name_regex,
+                regex_fallback,
                 on_conflict,
+                collision_suffix,
+                units,
+            )?;
+        }
+
+        Commands::Preview {
+            path,
+            mode,
+            recursive,
+            template,
+            older_than,
+        } => {
+            commands::preview::run(
+                &path,
+                mode,
+                recursive,
+                template,
+                older_than,
+                cli.summary_only,
+                units,
             )?;
         }
 
         Commands::Clean {
             path,
             older_than,
+            larger_than,
+            smaller_than,
             empty_folders,
+            remove_junk,
+            empty_files,
+            broken_symlinks,
+            duplicates,
+            keep_recent,
+            per_dir,
+            depth,
             dry_run,
             execute,
             trash,
+            throttle,
+            summary_json,
             min_size,
             max_size,
             after,
             before,
+            confirm_threshold,
+            label: _,
         } => {
             commands::clean::run(
                 &path,
                 older_than,
+                larger_than,
+                smaller_than,
                 empty_folders,
+                remove_junk,
+                empty_files,
+                broken_symlinks,
+                duplicates,
+                keep_recent,
+                per_dir,
+                depth,
                 dry_run,
                 execute,
                 trash,
+                throttle,
                 min_size,
                 max_size,
                 after,
                 before,
+                confirm_threshold,
+                cli.summary_only,
+                summary_json,
+                units,
             )?;
         }
 
         Commands::Duplicates {
             path,
             delete,
+            interactive,
+            merge,
+            across,
+            prefer_keep,
+            by_dir,
             dry_run,
             execute,
             trash,
@@ -114,36 +281,130 @@ fn main() -> Result<()> {
             max_size,
             after,
             before,
+            format,
             json,
             csv,
+            pattern,
+            depth,
+            no_default_ignores,
+            threads,
+            jsonl,
+            peek_archives,
         } => {
             commands::duplicates::run(
-                &path, delete, dry_run, execute, trash, min_size, max_size, after, before, json,
-                csv,
+                &path,
+                delete,
+                interactive,
+                merge,
+                &across,
+                prefer_keep.as_deref(),
+                by_dir,
+                dry_run,
+                execute,
+                trash,
+                min_size,
+                max_size,
+                after,
+                before,
+                resolve_format(format, json, csv),
+                pattern,
+                depth,
+                no_default_ignores,
+                threads,
+                jsonl,
+                peek_archives,
+                cli.summary_only,
+                units,
             )?;
         }
 
         Commands::Similar {
             path,
             threshold,
+            video,
             delete,
             dry_run,
             execute,
             trash,
+            threads,
+            taken_after,
+            taken_before,
+            taken_only,
+        } => {
+            commands::similar::run(
+                &path,
+                threshold,
+                video,
+                delete,
+                dry_run,
+                execute,
+                trash,
+                threads,
+                taken_after,
+                taken_before,
+                taken_only,
+                units,
+            )?;
+        }
+
+        Commands::Stats {
+            path,
+            format,
+            json,
+            compare,
+            depth,
+            with_duplicates,
+        } => {
+            let format = resolve_format(format, json, false);
+            commands::stats::run(
+                &path,
+                format,
+                compare.as_deref(),
+                depth,
+                with_duplicates,
+                units,
+            )?;
+        }
+
+        Commands::Export {
+            path,
+            format,
+            depth,
         } => {
-            commands::similar::run(&path, threshold, delete, dry_run, execute, trash)?;
+            commands::export::run(&path, format, depth, units)?;
         }
 
-        Commands::Stats { path, json } => {
-            commands::stats::run(&path, json)?;
+        Commands::Classify { path } => {
+            commands::classify::run(&path)?;
         }
 
-        Commands::Undo => {
-            commands::undo::run()?;
+        Commands::Size { path, top } => {
+            commands::size::run(&path, top, units)?;
         }
 
-        Commands::History => {
-            commands::history::run()?;
+        Commands::Flatten {
+            path,
+            dry_run,
+            execute,
+        } => {
+            commands::flatten::run(&path, dry_run, execute, cli.summary_only, units)?;
+        }
+
+        Commands::TidyNames {
+            path,
+            recursive,
+            dry_run,
+            execute,
+        } => {
+            commands::tidy_names::run(&path, recursive, dry_run, execute, cli.summary_only, units)?;
+        }
+
+        Commands::Undo { dry_run } => {
+            commands::undo::run(dry_run)?;
+        }
+
+        Commands::History { format, json } => {
+            commands::history::run(resolve_format(format, json, false))?;
         }
 
         Commands::Watch {
@@ -152,9 +413,24 @@ fn main() -> Result<()> {
             by_date,
             by_extension,
             config,
+            config_reload,
             auto,
+            profile,
+            debounce,
+            stats_interval,
         } => {
-            commands::watch::run(&path, by_type, by_date, by_extension, config, auto)?;
+            commands::watch::run(
+                &path,
+                by_type,
+                by_date,
+                by_extension,
+                config,
+                config_reload,
+                auto,
+                profile.as_deref(),
+                debounce.as_deref(),
+                stats_interval.as_deref(),
+            )?;
         }
 
         Commands::Config { action } => {
@@ -162,15 +438,15 @@ fn main() -> Result<()> {
         }
 
         Commands::Tui { path } => {
-            tui::run_tui(&path)?;
+            tui::run_tui(&path, units)?;
         }
 
         Commands::Quick { action } => {
-            commands::quick::run(action)?;
+            commands::quick::run(action, units)?;
         }
 
         Commands::Profile { action } => {
-            commands::profile::run(action)?;
+            commands::profile::run(action, units)?;
         }
 
         Commands::Completions { shell } => {
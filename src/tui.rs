@@ -18,8 +18,10 @@ use ratatui::{
 };
 
 use crate::classifier::{Category, Classifier};
+use crate::duplicates::{find_duplicates, DuplicateGroup};
+use crate::logger::Logger;
 use crate::organizer::{plan_moves, OrganizeMode, PlannedMove};
-use crate::scanner::{format_size, scan_directory, FileInfo, ScanOptions};
+use crate::scanner::{scan_directory, FileInfo, ScanOptions, SizeUnits};
 
 /// Current view mode
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -30,6 +32,8 @@ pub enum ViewMode {
     BatchAction,
     #[allow(dead_code)]
     ConflictResolver,
+    Duplicates,
+    ConfirmDuplicates,
 }
 
 /// Batch operation types
@@ -141,6 +145,14 @@ pub struct App {
     pub conflicts: Vec<Conflict>,
     /// Current conflict index
     pub conflict_index: usize,
+    /// Duplicate groups shown in the duplicates view
+    pub duplicate_groups: Vec<DuplicateGroup>,
+    /// Index of the file to keep in each duplicate group
+    pub duplicate_keep: Vec<usize>,
+    /// Cursor as (group index, file index)
+    pub duplicate_cursor: (usize, usize),
+    /// Units for displayed sizes
+    pub units: SizeUnits,
 }
 
 impl App {
@@ -173,12 +185,16 @@ impl App {
             view_mode: ViewMode::FileList,
             organize_mode: SelectedMode::ByType,
             planned_moves: Vec::new(),
-            classifier: Classifier::new(),
+            classifier: Classifier::configured().clone(),
             should_quit: false,
             status_message: "Press ? for help".to_string(),
             batch_operation: None,
             conflicts: Vec::new(),
             conflict_index: 0,
+            duplicate_groups: Vec::new(),
+            duplicate_keep: Vec::new(),
+            duplicate_cursor: (0, 0),
+            units: SizeUnits::default(),
         })
     }
 
@@ -342,6 +358,126 @@ impl App {
         Ok(())
     }
 
+    /// Scan current files for duplicates and open the duplicates view
+    pub fn show_duplicates(&mut self) -> Result<()> {
+        let groups = find_duplicates(&self.files)?;
+        if groups.is_empty() {
+            self.status_message = "No duplicate files found".to_string();
+            return Ok(());
+        }
+        self.load_duplicate_groups(groups);
+        Ok(())
+    }
+
+    /// Populate the duplicates view (first file in each group is kept by default)
+    pub fn load_duplicate_groups(&mut self, groups: Vec<DuplicateGroup>) {
+        self.duplicate_keep = vec![0; groups.len()];
+        self.duplicate_groups = groups;
+        self.duplicate_cursor = (0, 0);
+        self.view_mode = ViewMode::Duplicates;
+        self.status_message = format!(
+            "{} duplicate groups - Space:keep  x:delete others  Esc:back",
+            self.duplicate_groups.len()
+        );
+    }
+
+    /// Move the duplicates cursor down, crossing into the next group
+    pub fn duplicate_move_down(&mut self) {
+        let (group, file) = self.duplicate_cursor;
+        let Some(current) = self.duplicate_groups.get(group) else {
+            return;
+        };
+        if file + 1 < current.files.len() {
+            self.duplicate_cursor = (group, file + 1);
+        } else if group + 1 < self.duplicate_groups.len() {
+            self.duplicate_cursor = (group + 1, 0);
+        }
+    }
+
+    /// Move the duplicates cursor up, crossing into the previous group
+    pub fn duplicate_move_up(&mut self) {
+        let (group, file) = self.duplicate_cursor;
+        if file > 0 {
+            self.duplicate_cursor = (group, file - 1);
+        } else if group > 0 {
+            let prev_len = self.duplicate_groups[group - 1].files.len();
+            self.duplicate_cursor = (group - 1, prev_len.saturating_sub(1));
+        }
+    }
+
+    /// Mark the file under the cursor as the one to keep in its group
+    pub fn mark_duplicate_keep(&mut self) {
+        let (group, file) = self.duplicate_cursor;
+        if let Some(keep) = self.duplicate_keep.get_mut(group) {
+            *keep = file;
+        }
+    }
+
+    /// Ask before trashing the other files in the current group
+    pub fn request_duplicate_trash(&mut self) {
+        let (group_index, _) = self.duplicate_cursor;
+        let Some(group) = self.duplicate_groups.get(group_index) else {
+            return;
+        };
+        self.view_mode = ViewMode::ConfirmDuplicates;
+        self.status_message = format!("Move {} duplicates to trash? (y/n)", group.files.len() - 1);
+    }
+
+    /// Move every file in the current group except the kept one to the trash
+    pub fn trash_duplicate_others(&mut self) -> Result<()> {
+        self.view_mode = ViewMode::Duplicates;
+        let (group_index, _) = self.duplicate_cursor;
+        if group_index >= self.duplicate_groups.len() {
+            return Ok(());
+        }
+
+        let keep = self.duplicate_keep[group_index];
+        let mut logger = Logger::new("tui duplicates");
+        let mut deleted = 0;
+        let mut last_error = None;
+
+        for (i, file) in self.duplicate_groups[group_index].files.iter().enumerate() {
+            if i == keep {
+                continue;
+            }
+            match trash::delete(&file.path) {
+                Ok(_) => {
+                    deleted += 1;
+                    logger.log_trash(file.path.clone());
+                }
+                Err(e) => last_error = Some(format!("{}: {}", file.name, e)),
+            }
+        }
+        logger.save()?;
+
+        if let Some(e) = last_error {
+            // Leave the group in place so the user can retry
+            self.status_message = format!("Error: {}", e);
+            return Ok(());
+        }
+
+        self.duplicate_groups.remove(group_index);
+        self.duplicate_keep.remove(group_index);
+        self.refresh_files()?;
+
+        if self.duplicate_groups.is_empty() {
+            self.view_mode = ViewMode::FileList;
+            self.status_message = format!(
+                "✓ Moved {} duplicates to trash - all groups resolved",
+                deleted
+            );
+        } else {
+            let group = group_index.min(self.duplicate_groups.len() - 1);
+            self.duplicate_cursor = (group, 0);
+            self.status_message = format!(
+                "✓ Moved {} duplicates to trash - {} groups left",
+                deleted,
+                self.duplicate_groups.len()
+            );
+        }
+        Ok(())
+    }
+
     /// Refresh file list
     pub fn refresh_files(&mut self) -> Result<()> {
         let options = ScanOptions {
@@ -366,7 +502,7 @@ impl App {
 }
 
 /// Run the TUI
-pub fn run_tui(path: &Path) -> Result<()> {
+pub fn run_tui(path: &Path, units: SizeUnits) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -376,6 +512,7 @@ pub fn run_tui(path: &Path) -> Result<()> {
 
     // Create app state
     let mut app = App::new(path)?;
+    app.units = units;
 
     // Main loop
     let res = run_app(&mut terminal, &mut app);
@@ -417,9 +554,16 @@ fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut A
                             app.status_message = format!("Mode: {}", app.organize_mode.name());
                         }
                         KeyCode::Char('b') => app.show_batch_menu(),
+                        KeyCode::Char('u') => {
+                            if let Err(e) = app.show_duplicates() {
+                                app.status_message = format!("Error: {}", e);
+                            }
+                            // Hashing progress is drawn outside ratatui; repaint everything
+                            terminal.clear()?;
+                        }
                         KeyCode::Enter | KeyCode::Char('p') => app.generate_preview(),
                         KeyCode::Char('?') => {
-                            app.status_message = "↑↓:nav  Space:select  a:all  d:deselect  m:mode  b:batch  Enter:preview  q:quit".to_string();
+                            app.status_message = "↑↓:nav  Space:select  a:all  d:deselect  m:mode  b:batch  u:duplicates  Enter:preview  q:quit".to_string();
                         }
                         _ => {}
                     },
@@ -467,76 +611,91 @@ fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>, app: &mut A
                         }
                         _ => {}
                     },
+                    ViewMode::Duplicates => match key.code {
+                        KeyCode::Up | KeyCode::Char('k') => app.duplicate_move_up(),
+                        KeyCode::Down | KeyCode::Char('j') => app.duplicate_move_down(),
+                        KeyCode::Char(' ') => app.mark_duplicate_keep(),
+                        KeyCode::Char('x') => app.request_duplicate_trash(),
+                        KeyCode::Char('q') | KeyCode::Esc => {
+                            app.view_mode = ViewMode::FileList;
+                            app.status_message = "Cancelled".to_string();
+                            app.duplicate_groups.clear();
+                            app.duplicate_keep.clear();
+                        }
+                        _ => {}
+                    },
+                    ViewMode::ConfirmDuplicates => match key.code {
+                        KeyCode::Char('y') | KeyCode::Enter => {
+                            if let Err(e) = app.trash_duplicate_others() {
+                                app.status_message = format!("Error: {}", e);
+                            }
+                        }
+                        KeyCode::Char('n') | KeyCode::Esc => {
+                            app.view_mode = ViewMode::Duplicates;
+                            app.status_message = "Cancelled".to_string();
+                        }
+                        _ => {}
+                    },
                     ViewMode::ConflictResolver => match key.code {
-                        KeyCode::Char('s') => {
+                        KeyCode::Char('s') if !app.conflicts.is_empty() => {
                             // Skip this conflict
-                            if !app.conflicts.is_empty() {
-                                app.conflicts[app.conflict_index].resolution =
-                                    Some(ConflictResolution::Skip);
-                                if app.conflict_index < app.conflicts.len() - 1 {
-                                    app.conflict_index += 1;
-                                } else {
-                                    app.view_mode = ViewMode::FileList;
-                                    app.status_message = "All conflicts resolved".to_string();
-                                    app.conflicts.clear();
-                                    app.conflict_index = 0;
-                                }
+                            app.conflicts[app.conflict_index].resolution =
+                                Some(ConflictResolution::Skip);
+                            if app.conflict_index < app.conflicts.len() - 1 {
+                                app.conflict_index += 1;
+                            } else {
+                                app.view_mode = ViewMode::FileList;
+                                app.status_message = "All conflicts resolved".to_string();
+                                app.conflicts.clear();
+                                app.conflict_index = 0;
                             }
                         }
-                        KeyCode::Char('o') => {
+                        KeyCode::Char('o') if !app.conflicts.is_empty() => {
                             // Overwrite
-                            if !app.conflicts.is_empty() {
-                                app.conflicts[app.conflict_index].resolution =
-                                    Some(ConflictResolution::Overwrite);
-                                if app.conflict_index < app.conflicts.len() - 1 {
-                                    app.conflict_index += 1;
-                                } else {
-                                    app.view_mode = ViewMode::FileList;
-                                    app.status_message = "All conflicts resolved".to_string();
-                                    app.conflicts.clear();
-                                    app.conflict_index = 0;
-                                }
+                            app.conflicts[app.conflict_index].resolution =
+                                Some(ConflictResolution::Overwrite);
+                            if app.conflict_index < app.conflicts.len() - 1 {
+                                app.conflict_index += 1;
+                            } else {
+                                app.view_mode = ViewMode::FileList;
+                                app.status_message = "All conflicts resolved".to_string();
+                                app.conflicts.clear();
+                                app.conflict_index = 0;
                             }
                         }
-                        KeyCode::Char('r') => {
+                        KeyCode::Char('r') if !app.conflicts.is_empty() => {
                             // Rename
-                            if !app.conflicts.is_empty() {
-                                app.conflicts[app.conflict_index].resolution =
-                                    Some(ConflictResolution::Rename);
-                                if app.conflict_index < app.conflicts.len() - 1 {
-                                    app.conflict_index += 1;
-                                } else {
-                                    app.view_mode = ViewMode::FileList;
-                                    app.status_message = "All conflicts resolved".to_string();
-                                    app.conflicts.clear();
-                                    app.conflict_index = 0;
-                                }
+                            app.conflicts[app.conflict_index].resolution =
+                                Some(ConflictResolution::Rename);
+                            if app.conflict_index < app.conflicts.len() - 1 {
+                                app.conflict_index += 1;
+                            } else {
+                                app.view_mode = ViewMode::FileList;
+                                app.status_message = "All conflicts resolved".to_string();
+                                app.conflicts.clear();
+                                app.conflict_index = 0;
                             }
                         }
-                        KeyCode::Char('k') => {
+                        KeyCode::Char('k') if !app.conflicts.is_empty() => {
                             // Keep both
-                            if !app.conflicts.is_empty() {
-                                app.conflicts[app.conflict_index].resolution =
-                                    Some(ConflictResolution::KeepBoth);
-                                if app.conflict_index < app.conflicts.len() - 1 {
-                                    app.conflict_index += 1;
-                                } else {
-                                    app.view_mode = ViewMode::FileList;
-                                    app.status_message = "All conflicts resolved".to_string();
-                                    app.conflicts.clear();
-                                    app.conflict_index = 0;
-                                }
+                            app.conflicts[app.conflict_index].resolution =
+                                Some(ConflictResolution::KeepBoth);
+                            if app.conflict_index < app.conflicts.len() - 1 {
+                                app.conflict_index += 1;
+                            } else {
+                                app.view_mode = ViewMode::FileList;
+                                app.status_message = "All conflicts resolved".to_string();
+                                app.conflicts.clear();
+                                app.conflict_index = 0;
                             }
                         }
-                        KeyCode::Left => {
-                            if app.conflict_index > 0 {
-                                app.conflict_index -= 1;
-                            }
+                        KeyCode::Left if app.conflict_index > 0 => {
+                            app.conflict_index -= 1;
                         }
-                        KeyCode::Right => {
-                            if app.conflict_index < app.conflicts.len().saturating_sub(1) {
-                                app.conflict_index += 1;
-                            }
+                        KeyCode::Right
+                            if app.conflict_index < app.conflicts.len().saturating_sub(1) =>
+                        {
+                            app.conflict_index += 1;
                         }
                         KeyCode::Esc => {
                             app.view_mode = ViewMode::FileList;
@@ -584,6 +743,8 @@ fn ui(f: &mut Frame, app: &App) {
         ViewMode::Confirm => render_confirm(f, app, chunks[1]),
         ViewMode::BatchAction => render_batch_menu(f, app, chunks[1]),
         ViewMode::ConflictResolver => render_conflict_resolver(f, app, chunks[1]),
+        ViewMode::Duplicates => render_duplicates(f, app, chunks[1]),
+        ViewMode::ConfirmDuplicates => render_confirm_duplicates(f, app, chunks[1]),
     }
 
     // Status bar
@@ -612,7 +773,7 @@ fn render_file_list(f: &mut Frame, app: &App, area: Rect) {
                 selected,
                 icon,
                 file.name,
-                format_size(file.size)
+                app.units.format(file.size)
             );
 
             let style = if app.selected.contains(&i) {
@@ -678,6 +839,23 @@ fn render_confirm(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(paragraph, area);
 }
 
+fn render_confirm_duplicates(f: &mut Frame, app: &App, area: Rect) {
+    let (group_index, _) = app.duplicate_cursor;
+    let group = &app.duplicate_groups[group_index];
+    let kept = &group.files[app.duplicate_keep[group_index]];
+    let text = format!(
+        "\n\n  Move {} duplicates to trash, keeping {}?\n\n  Press 'y' to confirm, 'n' to cancel",
+        group.files.len() - 1,
+        kept.name
+    );
+
+    let paragraph = Paragraph::new(text)
+        .style(Style::default().fg(Color::Yellow))
+        .block(Block::default().borders(Borders::ALL).title(" Confirm "));
+
+    f.render_widget(paragraph, area);
+}
+
 fn render_batch_menu(f: &mut Frame, app: &App, area: Rect) {
     let text = format!(
         "\n\n  {} files selected\n\n  Actions:\n\n    [t] Move to Trash\n    [d] Delete permanently\n    [o] Organize ({})\n\n    [Esc] Cancel",
@@ -747,6 +925,57 @@ fn render_conflict_resolver(f: &mut Frame, app: &App, area: Rect) {
     f.render_widget(paragraph, area);
 }
 
+fn render_duplicates(f: &mut Frame, app: &App, area: Rect) {
+    let mut items: Vec<ListItem> = Vec::new();
+    let mut highlighted = 0;
+
+    for (g, group) in app.duplicate_groups.iter().enumerate() {
+        items.push(
+            ListItem::new(format!(
+                "Group {} ({}, {} copies)",
+                g + 1,
+                app.units.format(group.size),
+                group.files.len()
+            ))
+            .style(
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            ),
+        );
+
+        for (i, file) in group.files.iter().enumerate() {
+            if app.duplicate_cursor == (g, i) {
+                highlighted = items.len();
+            }
+            let (marker, style) = if app.duplicate_keep.get(g) == Some(&i) {
+                ("[keep]", Style::default().fg(Color::Green))
+            } else {
+                ("[del] ", Style::default().fg(Color::Red))
+            };
+            items.push(ListItem::new(format!("  {} {}", marker, file.path.display())).style(style));
+        }
+    }
+
+    let mut state = ListState::default();
+    state.select(Some(highlighted));
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Duplicates (Space=keep, x=trash others, Esc=back) "),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::DarkGray)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("➤ ");
+
+    f.render_stateful_widget(list, area, &mut state);
+}
+
 fn category_icon(category: &Category) -> &'static str {
     match category {
         Category::Images => "🖼️",
@@ -759,3 +988,64 @@ fn category_icon(category: &Category) -> &'static str {
         Category::Other => "📁",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn fixture_group(names: &[&str]) -> DuplicateGroup {
+        DuplicateGroup {
+            hash: "abc".to_string(),
+            files: names
+                .iter()
+                .map(|n| FileInfo::fixture(format!("/fixture/{}", n), 10))
+                .collect(),
+            size: 10,
+        }
+    }
+
+    #[test]
+    fn test_duplicates_view_keep_toggle() {
+        let dir = tempdir().unwrap();
+        let mut app = App::new(dir.path()).unwrap();
+
+        app.load_duplicate_groups(vec![
+            fixture_group(&["a.txt", "b.txt"]),
+            fixture_group(&["c.txt", "d.txt", "e.txt"]),
+        ]);
+        assert_eq!(app.view_mode, ViewMode::Duplicates);
+        assert_eq!(app.duplicate_keep, vec![0, 0]);
+
+        // Navigation crosses group boundaries
+        app.duplicate_move_down();
+        app.duplicate_move_down();
+        assert_eq!(app.duplicate_cursor, (1, 0));
+        app.duplicate_move_down();
+        app.duplicate_move_down();
+        app.duplicate_move_down();
+        assert_eq!(app.duplicate_cursor, (1, 2));
+
+        app.mark_duplicate_keep();
+        assert_eq!(app.duplicate_keep, vec![0, 2]);
+
+        app.duplicate_move_up();
+        app.duplicate_move_up();
+        app.duplicate_move_up();
+        assert_eq!(app.duplicate_cursor, (0, 1));
+        app.mark_duplicate_keep();
+        assert_eq!(app.duplicate_keep, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_duplicates_trash_asks_first() {
+        let dir = tempdir().unwrap();
+        let mut app = App::new(dir.path()).unwrap();
+        app.load_duplicate_groups(vec![fixture_group(&["a.txt", "b.txt", "c.txt"])]);
+
+        app.request_duplicate_trash();
+        assert_eq!(app.view_mode, ViewMode::ConfirmDuplicates);
+        assert_eq!(app.status_message, "Move 2 duplicates to trash? (y/n)");
+        assert_eq!(app.duplicate_groups.len(), 1);
+    }
+}
@@ -1,9 +1,37 @@
 //! Export functionality for reports (JSON, CSV)
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::io::Write;
 
 use crate::duplicates::DuplicateGroup;
+use crate::logger::{FileOperation, OperationBatch};
+use crate::organizer::PlannedMove;
+
+/// Output format selected with `--format`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Human-readable output, printed by each command itself
+    #[default]
+    Table,
+    Json,
+    Csv,
+}
+
+impl Format {
+    /// Whether this is one of the machine-readable formats
+    pub fn is_machine(self) -> bool {
+        self != Format::Table
+    }
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
 
 /// Serializable duplicate file for export
 #[derive(Serialize)]
@@ -22,37 +50,62 @@ struct ExportDuplicateGroup {
     files: Vec<ExportFile>,
 }
 
+impl From<&DuplicateGroup> for ExportDuplicateGroup {
+    fn from(g: &DuplicateGroup) -> Self {
+        let files: Vec<ExportFile> = g
+            .files
+            .iter()
+            .map(|f| ExportFile {
+                path: f.path.display().to_string(),
+                size: f.size,
+                modified: format!("{:?}", f.modified),
+            })
+            .collect();
+
+        ExportDuplicateGroup {
+            hash: g.hash.clone(),
+            count: g.files.len(),
+            wasted_space: g.wasted_space(),
+            files,
+        }
+    }
+}
+
+/// Export duplicates in the given format (table output is left to the caller)
+pub fn export_duplicates<W: Write>(
+    duplicates: &[DuplicateGroup],
+    format: Format,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    match format {
+        Format::Table => Ok(()),
+        Format::Json => export_duplicates_json(duplicates, writer),
+        Format::Csv => export_duplicates_csv(duplicates, writer),
+    }
+}
+
 /// Export duplicates as JSON
 pub fn export_duplicates_json<W: Write>(
     duplicates: &[DuplicateGroup],
     writer: &mut W,
 ) -> std::io::Result<()> {
-    let groups: Vec<ExportDuplicateGroup> = duplicates
-        .iter()
-        .map(|g| {
-            let files: Vec<ExportFile> = g
-                .files
-                .iter()
-                .map(|f| ExportFile {
-                    path: f.path.display().to_string(),
-                    size: f.size,
-                    modified: format!("{:?}", f.modified),
-                })
-                .collect();
-
-            ExportDuplicateGroup {
-                hash: g.hash.clone(),
-                count: g.files.len(),
-                wasted_space: g.wasted_space(),
-                files,
-            }
-        })
-        .collect();
+    let groups: Vec<ExportDuplicateGroup> = duplicates.iter().map(Into::into).collect();
 
     let json = serde_json::to_string_pretty(&groups)?;
     writeln!(writer, "{}", json)
 }
 
+/// Export a single duplicate group as one line of newline-delimited JSON
+///
+/// Called once per group so results can be streamed while scanning continues.
+pub fn export_duplicates_jsonl<W: Write>(
+    group: &DuplicateGroup,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    let json = serde_json::to_string(&ExportDuplicateGroup::from(group))?;
+    writeln!(writer, "{}", json)
+}
+
 /// Export duplicates as CSV
 pub fn export_duplicates_csv<W: Write>(
     duplicates: &[DuplicateGroup],
@@ -67,7 +120,7 @@ pub fn export_duplicates_csv<W: Write>(
                 "{},{},{},{}",
                 group_idx + 1,
                 group.hash,
-                file.path.display(),
+                csv_field(&file.path.display().to_string()),
                 file.size
             )?;
         }
@@ -76,23 +129,263 @@ pub fn export_duplicates_csv<W: Write>(
     Ok(())
 }
 
-/// Serializable stats for export
-#[derive(Serialize)]
+/// Serializable stats for export, read back by `stats --compare`
+#[derive(Serialize, Deserialize)]
 pub struct ExportStats {
     pub total_files: usize,
     pub total_size: u64,
     pub categories: Vec<CategoryStats>,
+    /// Only meaningful for the run that produced it, so not read back
+    #[serde(skip_deserializing)]
+    pub threshold_breaches: Vec<ThresholdBreach>,
+    /// Set by `stats --with-duplicates`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicate_wasted_space: Option<u64>,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CategoryStats {
     pub name: String,
     pub count: usize,
     pub size: u64,
 }
 
+/// A category that went over one of its configured limits
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ThresholdBreach {
+    pub category: String,
+    /// Which limit was exceeded: "size" or "count"
+    pub metric: &'static str,
+    pub limit: u64,
+    pub actual: u64,
+}
+
+/// Export stats in the given format (table output is left to the caller)
+pub fn export_stats<W: Write>(
+    stats: &ExportStats,
+    format: Format,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    match format {
+        Format::Table => Ok(()),
+        Format::Json => export_stats_json(stats, writer),
+        Format::Csv => export_stats_csv(stats, writer),
+    }
+}
+
 /// Export stats as JSON
 pub fn export_stats_json<W: Write>(stats: &ExportStats, writer: &mut W) -> std::io::Result<()> {
     let json = serde_json::to_string_pretty(stats)?;
     writeln!(writer, "{}", json)
 }
+
+/// Export stats as CSV, one row per category
+pub fn export_stats_csv<W: Write>(stats: &ExportStats, writer: &mut W) -> std::io::Result<()> {
+    writeln!(writer, "category,count,size")?;
+    for category in &stats.categories {
+        writeln!(
+            writer,
+            "{},{},{}",
+            csv_field(&category.name),
+            category.count,
+            category.size
+        )?;
+    }
+    Ok(())
+}
+
+/// Serializable history batch for export
+#[derive(Serialize)]
+struct ExportBatch<'a> {
+    timestamp: String,
+    command: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<&'a str>,
+    operation_count: usize,
+    operations: &'a [FileOperation],
+}
+
+/// Export operation history in the given format (table output is left to the caller)
+pub fn export_history<W: Write>(
+    batches: &[OperationBatch],
+    format: Format,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    match format {
+        Format::Table => Ok(()),
+        Format::Json => export_history_json(batches, writer),
+        Format::Csv => export_history_csv(batches, writer),
+    }
+}
+
+/// Export operation history as JSON, oldest batch first
+pub fn export_history_json<W: Write>(
+    batches: &[OperationBatch],
+    writer: &mut W,
+) -> std::io::Result<()> {
+    let batches: Vec<ExportBatch> = batches
+        .iter()
+        .map(|batch| ExportBatch {
+            timestamp: batch.timestamp.to_rfc3339(),
+            command: &batch.command,
+            label: batch.label.as_deref(),
+            operation_count: batch.operations.len(),
+            operations: &batch.operations,
+        })
+        .collect();
+
+    let json = serde_json::to_string_pretty(&batches)?;
+    writeln!(writer, "{}", json)
+}
+
+/// Export operation history as CSV, one row per operation, oldest first
+pub fn export_history_csv<W: Write>(
+    batches: &[OperationBatch],
+    writer: &mut W,
+) -> std::io::Result<()> {
+    writeln!(writer, "timestamp,command,operation,from,to")?;
+    for batch in batches {
+        for op in &batch.operations {
+            writeln!(
+                writer,
+                "{},{},{:?},{},{}",
+                batch.timestamp.to_rfc3339(),
+                csv_field(&batch.command),
+                op.operation_type,
+                csv_field(&op.from.display().to_string()),
+                csv_field(&op.to.display().to_string())
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Export planned moves in the given format (table output is left to the caller)
+pub fn export_moves<W: Write>(
+    moves: &[PlannedMove],
+    format: Format,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    match format {
+        Format::Table => Ok(()),
+        Format::Json => {
+            let json = serde_json::to_string_pretty(moves)?;
+            writeln!(writer, "{}", json)
+        }
+        Format::Csv => {
+            writeln!(writer, "from,to,size")?;
+            for mv in moves {
+                writeln!(
+                    writer,
+                    "{},{},{}",
+                    csv_field(&mv.from.display().to_string()),
+                    csv_field(&mv.to.display().to_string()),
+                    mv.size
+                )?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// One file of a directory inventory (`neat export`)
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct InventoryEntry {
+    pub path: String,
+    pub size: u64,
+    /// Modification time as RFC 3339
+    pub modified: String,
+    /// Lowercased extension
+    pub extension: Option<String>,
+    pub category: String,
+    pub mime: Option<String>,
+}
+
+/// Export a directory inventory in the given format (table output is left to the caller)
+pub fn export_inventory<W: Write>(
+    entries: &[InventoryEntry],
+    format: Format,
+    writer: &mut W,
+) -> std::io::Result<()> {
+    match format {
+        Format::Table => Ok(()),
+        Format::Json => {
+            let json = serde_json::to_string_pretty(entries)?;
+            writeln!(writer, "{}", json)
+        }
+        Format::Csv => {
+            writeln!(writer, "path,size,modified,extension,category,mime")?;
+            for entry in entries {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{}",
+                    csv_field(&entry.path),
+                    entry.size,
+                    entry.modified,
+                    csv_field(entry.extension.as_deref().unwrap_or_default()),
+                    csv_field(&entry.category),
+                    entry.mime.as_deref().unwrap_or_default()
+                )?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("/tmp/report.pdf"), "/tmp/report.pdf");
+        assert_eq!(csv_field("/tmp/a,b.pdf"), "\"/tmp/a,b.pdf\"");
+        assert_eq!(csv_field("say \"hi\".txt"), "\"say \"\"hi\"\".txt\"");
+    }
+
+    #[test]
+    fn test_export_moves_formats() {
+        let moves = vec![PlannedMove {
+            from: PathBuf::from("/base/a.pdf"),
+            to: PathBuf::from("/base/Documents/a.pdf"),
+            size: 12,
+        }];
+
+        let mut csv = Vec::new();
+        export_moves(&moves, Format::Csv, &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "from,to,size\n/base/a.pdf,/base/Documents/a.pdf,12\n"
+        );
+
+        let mut json = Vec::new();
+        export_moves(&moves, Format::Json, &mut json).unwrap();
+        let parsed: Vec<PlannedMove> = serde_json::from_slice(&json).unwrap();
+        assert_eq!(parsed, moves);
+
+        let mut table = Vec::new();
+        export_moves(&moves, Format::Table, &mut table).unwrap();
+        assert!(table.is_empty());
+    }
+
+    #[test]
+    fn test_export_inventory_csv() {
+        let entries = vec![InventoryEntry {
+            path: "/base/a, b.pdf".to_string(),
+            size: 3,
+            modified: "2024-05-01T10:00:00+00:00".to_string(),
+            extension: Some("pdf".to_string()),
+            category: "Documents".to_string(),
+            mime: Some("application/pdf".to_string()),
+        }];
+
+        let mut csv = Vec::new();
+        export_inventory(&entries, Format::Csv, &mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "path,size,modified,extension,category,mime\n\
+             \"/base/a, b.pdf\",3,2024-05-01T10:00:00+00:00,pdf,Documents,application/pdf\n"
+        );
+    }
+}
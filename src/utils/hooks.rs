@@ -1,7 +1,7 @@
 //! Shell hook executor for post-action commands
 
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, ExitStatus};
 
 use anyhow::{Context, Result};
 
@@ -15,26 +15,51 @@ use anyhow::{Context, Result};
 /// - {dir} - Destination directory
 pub fn execute_hook(command: &str, source: &Path, dest: &Path) -> Result<()> {
     let expanded = substitute_vars(command, source, dest);
+    shell(&expanded)
+        .status()
+        .with_context(|| format!("Failed to execute hook: {}", expanded))?;
 
-    #[cfg(unix)]
-    {
-        Command::new("sh")
-            .arg("-c")
-            .arg(&expanded)
-            .status()
-            .with_context(|| format!("Failed to execute hook: {}", expanded))?;
-    }
+    Ok(())
+}
 
-    #[cfg(windows)]
-    {
-        Command::new("cmd")
-            .arg("/C")
-            .arg(&expanded)
-            .status()
-            .with_context(|| format!("Failed to execute hook: {}", expanded))?;
-    }
+/// Execute a shell command once after a whole run, returning its exit status
+///
+/// Supported variables:
+/// - {count} - Number of files moved or copied
+/// - {bytes} - Total size of those files in bytes
+/// - {path} - The organized directory
+pub fn execute_run_hook(
+    command: &str,
+    count: usize,
+    bytes: u64,
+    path: &Path,
+) -> Result<ExitStatus> {
+    let expanded = substitute_run_vars(command, count, bytes, path);
+    shell(&expanded)
+        .status()
+        .with_context(|| format!("Failed to execute hook: {}", expanded))
+}
 
-    Ok(())
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c").arg(command);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C").arg(command);
+    cmd
+}
+
+/// Substitute run summary variables in command string
+fn substitute_run_vars(command: &str, count: usize, bytes: u64, path: &Path) -> String {
+    command
+        .replace("{count}", &count.to_string())
+        .replace("{bytes}", &bytes.to_string())
+        .replace("{path}", &path.to_string_lossy())
 }
 
 /// Substitute variables in command string
@@ -111,4 +136,17 @@ mod tests {
 
         assert_eq!(result, "moved Makefile");
     }
+
+    #[test]
+    fn test_substitute_run_vars() {
+        let path = PathBuf::from("/home/user/Downloads");
+
+        let cmd = "notify-send \"Organized {count} files ({bytes} bytes) in {path}\"";
+        let result = substitute_run_vars(cmd, 42, 1048576, &path);
+
+        assert_eq!(
+            result,
+            "notify-send \"Organized 42 files (1048576 bytes) in /home/user/Downloads\""
+        );
+    }
 }
@@ -1,12 +1,14 @@
 //! Operation logger for undo functionality
 
 use std::fs::{self, File};
-use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::xxh3_64;
 
 /// A single file operation
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +16,23 @@ pub struct FileOperation {
     pub from: PathBuf,
     pub to: PathBuf,
     pub operation_type: OperationType,
+    /// Content hash of the file at `to` when it was moved (small files only),
+    /// so undo can tell whether it was replaced since
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+}
+
+/// Files larger than this are not hashed when logging moves (`--verify-undo`)
+const HASH_SIZE_LIMIT: u64 = 16 * 1024 * 1024;
+
+/// Hash a file's contents for later verification, if it is small enough to read quickly
+pub fn content_hash(path: &Path) -> Option<String> {
+    let meta = fs::metadata(path).ok()?;
+    if !meta.is_file() || meta.len() > HASH_SIZE_LIMIT {
+        return None;
+    }
+    let data = fs::read(path).ok()?;
+    Some(format!("{:016x}", xxh3_64(&data)))
 }
 
 /// Type of operation
@@ -21,6 +40,16 @@ pub struct FileOperation {
 pub enum OperationType {
     Move,
     Delete,
+    /// A symlink created at `from` pointing to `to`
+    Symlink,
+    /// A file at `from` moved to the OS trash
+    Trash,
+    /// An empty folder at `from` removed after its files were moved out
+    RemoveDir,
+    /// A symlink created at `from` pointing to the original file at `to`, which stays put
+    Link,
+    /// A folder at `from` created to hold links
+    CreateDir,
 }
 
 /// A batch of operations
@@ -28,9 +57,66 @@ pub enum OperationType {
 pub struct OperationBatch {
     pub timestamp: DateTime<Utc>,
     pub command: String,
+    /// Note given with `--label`, to tell runs of the same command apart
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
     pub operations: Vec<FileOperation>,
 }
 
+impl OperationBatch {
+    /// The label if one was given, otherwise the command
+    pub fn display_name(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.command)
+    }
+}
+
+/// Label for batches logged by this run, set once from `--label`
+static BATCH_LABEL: OnceLock<String> = OnceLock::new();
+
+/// Label every batch this run writes to history
+pub fn set_batch_label(label: String) {
+    let _ = BATCH_LABEL.set(label);
+}
+
+static VERIFY_UNDO: OnceLock<bool> = OnceLock::new();
+
+/// Record a content hash with every move logged for the rest of the process
+/// (`--verify-undo`; first call wins)
+pub fn set_verify_undo(enabled: bool) {
+    let _ = VERIFY_UNDO.set(enabled);
+}
+
+/// Folder under ~/.neat holding the journals of batches still being written
+const JOURNAL_DIR: &str = "journal";
+
+/// Keep at most this many batches in history
+const MAX_BATCHES: usize = 50;
+
+#[cfg(test)]
+thread_local! {
+    /// History folder used instead of ~/.neat by tests on this thread
+    static TEST_NEAT_DIR: std::cell::RefCell<Option<PathBuf>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Keep history written by this test thread in `dir`
+#[cfg(test)]
+pub fn set_test_neat_dir(dir: &Path) {
+    TEST_NEAT_DIR.with(|d| *d.borrow_mut() = Some(dir.to_path_buf()));
+}
+
+/// The ~/.neat folder, created if missing
+fn neat_dir() -> Result<PathBuf> {
+    #[cfg(test)]
+    if let Some(dir) = TEST_NEAT_DIR.with(|d| d.borrow().clone()) {
+        return Ok(dir);
+    }
+    let home = dirs::home_dir().context("Could not find home directory")?;
+    let neat_dir = home.join(".neat");
+    fs::create_dir_all(&neat_dir)?;
+    Ok(neat_dir)
+}
+
 /// Operation history
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct History {
@@ -40,14 +126,26 @@ pub struct History {
 impl History {
     /// Get the history file path
     fn history_path() -> Result<PathBuf> {
-        let home = dirs::home_dir().context("Could not find home directory")?;
-        let neat_dir = home.join(".neat");
-        fs::create_dir_all(&neat_dir)?;
-        Ok(neat_dir.join("history.json"))
+        Ok(neat_dir()?.join("history.json"))
     }
 
-    /// Load history from file
+    /// Load history from file, adding the batches of runs that were interrupted
+    /// after a checkpoint
     pub fn load() -> Result<Self> {
+        let mut history = Self::read()?;
+        let journals = neat_dir()?.join(JOURNAL_DIR);
+        let recovered = history.recover_journals(&journals);
+        if !recovered.is_empty() {
+            history.save()?;
+            for path in recovered {
+                let _ = fs::remove_file(path);
+            }
+        }
+        Ok(history)
+    }
+
+    /// Load history.json alone
+    fn read() -> Result<Self> {
         let path = Self::history_path()?;
 
         if !path.exists() {
@@ -84,18 +182,56 @@ impl History {
     }
 
     /// Add a new batch of operations
-    pub fn add_batch(&mut self, command: String, operations: Vec<FileOperation>) {
+    pub fn add_batch(
+        &mut self,
+        command: String,
+        label: Option<String>,
+        operations: Vec<FileOperation>,
+    ) {
         let batch = OperationBatch {
             timestamp: Utc::now(),
             command,
+            label,
             operations,
         };
         self.batches.push(batch);
 
         // Keep only the last 50 batches
-        if self.batches.len() > 50 {
+        if self.batches.len() > MAX_BATCHES {
+            self.batches.remove(0);
+        }
+    }
+
+    /// Add the batches left in `dir` by runs that ended without saving, returning
+    /// the journals read. Journals still locked by a running command are left alone.
+    fn recover_journals(&mut self, dir: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Vec::new();
+        };
+        let mut recovered = Vec::new();
+        for path in entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+        {
+            let Ok(file) = File::open(&path) else {
+                continue;
+            };
+            if file.try_lock().is_err() {
+                continue;
+            }
+            if let Some(batch) = read_journal(file).filter(|b| !b.operations.is_empty()) {
+                // Keep batches in the order they ran, so undo takes the latest first
+                let at = self
+                    .batches
+                    .partition_point(|b| b.timestamp <= batch.timestamp);
+                self.batches.insert(at, batch);
+            }
+            recovered.push(path);
+        }
+        while self.batches.len() > MAX_BATCHES {
             self.batches.remove(0);
         }
+        recovered
     }
 
     /// Get the last batch for undo
@@ -109,27 +245,156 @@ impl History {
     }
 }
 
+/// Read a journal: a batch header line followed by one operation per line. A line
+/// cut short by a crash ends the batch.
+fn read_journal(file: File) -> Option<OperationBatch> {
+    let mut lines = BufReader::new(file).lines().map_while(|line| line.ok());
+    let mut batch: OperationBatch = serde_json::from_str(&lines.next()?).ok()?;
+    batch.operations = lines
+        .map_while(|line| serde_json::from_str(&line).ok())
+        .collect();
+    Some(batch)
+}
+
+/// The on-disk journal of a batch still being written, locked while its run lasts
+struct Journal {
+    path: PathBuf,
+    file: File,
+    /// Operations already appended
+    written: usize,
+}
+
+impl Journal {
+    fn create(command: &str, label: Option<&String>) -> Result<Self> {
+        let dir = neat_dir()?.join(JOURNAL_DIR);
+        fs::create_dir_all(&dir)?;
+        let timestamp = Utc::now();
+        let path = dir.join(format!(
+            "{}-{}.jsonl",
+            timestamp.format("%Y%m%d%H%M%S%f"),
+            std::process::id()
+        ));
+        let file = File::create(&path).context("Failed to create history journal")?;
+        file.lock().context("Failed to lock history journal")?;
+
+        let header = OperationBatch {
+            timestamp,
+            command: command.to_string(),
+            label: label.cloned(),
+            operations: Vec::new(),
+        };
+        let mut journal = Journal {
+            path,
+            file,
+            written: 0,
+        };
+        journal.write_line(&header)?;
+        Ok(journal)
+    }
+
+    fn write_line<T: Serialize>(&mut self, value: &T) -> Result<()> {
+        let mut line = serde_json::to_vec(value)?;
+        line.push(b'\n');
+        self.file
+            .write_all(&line)
+            .context("Failed to write history journal")
+    }
+
+    /// Append the operations not yet in the journal and flush them to disk
+    fn append(&mut self, operations: &[FileOperation]) -> Result<()> {
+        for operation in &operations[self.written..] {
+            self.write_line(operation)?;
+        }
+        self.written = operations.len();
+        self.file
+            .sync_data()
+            .context("Failed to write history journal")
+    }
+}
+
 /// Logger for tracking operations
 pub struct Logger {
     operations: Vec<FileOperation>,
     command: String,
+    label: Option<String>,
+    /// Journal written by checkpoints, if any were taken
+    journal: Option<Journal>,
 }
 
 impl Logger {
-    /// Create a new logger for a command
+    /// Create a new logger for a command, labeled with the run's `--label` if any
     pub fn new(command: &str) -> Self {
         Logger {
             operations: Vec::new(),
             command: command.to_string(),
+            label: BATCH_LABEL.get().cloned(),
+            journal: None,
         }
     }
 
-    /// Log a move operation
+    /// Log a move operation, recording the content hash of small files with
+    /// `--verify-undo`
     pub fn log_move(&mut self, from: PathBuf, to: PathBuf) {
+        let hash = if VERIFY_UNDO.get().copied().unwrap_or(false) {
+            content_hash(&to)
+        } else {
+            None
+        };
         self.operations.push(FileOperation {
             from,
             to,
             operation_type: OperationType::Move,
+            hash,
+        });
+    }
+
+    /// Log a symlink left at `link` pointing to `target`
+    pub fn log_symlink(&mut self, link: PathBuf, target: PathBuf) {
+        self.operations.push(FileOperation {
+            from: link,
+            to: target,
+            operation_type: OperationType::Symlink,
+            hash: None,
+        });
+    }
+
+    /// Log a symlink created at `link` in place of moving `original`
+    pub fn log_link(&mut self, link: PathBuf, original: PathBuf) {
+        self.operations.push(FileOperation {
+            from: link,
+            to: original,
+            operation_type: OperationType::Link,
+            hash: None,
+        });
+    }
+
+    /// Log a folder created for a link tree
+    pub fn log_create_dir(&mut self, path: PathBuf) {
+        self.operations.push(FileOperation {
+            from: path,
+            to: PathBuf::new(),
+            operation_type: OperationType::CreateDir,
+            hash: None,
+        });
+    }
+
+    /// Log a file moved to the OS trash
+    pub fn log_trash(&mut self, path: PathBuf) {
+        self.operations.push(FileOperation {
+            from: path,
+            to: PathBuf::new(),
+            operation_type: OperationType::Trash,
+            hash: None,
+        });
+    }
+
+    /// Log an empty folder removed after organizing
+    pub fn log_remove_dir(&mut self, path: PathBuf) {
+        self.operations.push(FileOperation {
+            from: path,
+            to: PathBuf::new(),
+            operation_type: OperationType::RemoveDir,
+            hash: None,
         });
     }
 
@@ -139,9 +404,25 @@ impl Logger {
             from: path,
             to: PathBuf::new(),
             operation_type: OperationType::Delete,
+            hash: None,
         });
     }
 
+    /// Append the operations logged since the last checkpoint to this batch's journal,
+    /// so they can be undone even if the run is interrupted
+    pub fn checkpoint(&mut self) -> Result<()> {
+        if self.operations.is_empty() {
+            return Ok(());
+        }
+        let journal = match &mut self.journal {
+            Some(journal) => journal,
+            None => self
+                .journal
+                .insert(Journal::create(&self.command, self.label.as_ref())?),
+        };
+        journal.append(&self.operations)
+    }
+
     /// Save logged operations to history
     pub fn save(self) -> Result<()> {
         if self.operations.is_empty() {
@@ -149,14 +430,65 @@ impl Logger {
         }
 
         let mut history = History::load()?;
-        history.add_batch(self.command, self.operations);
+        history.add_batch(self.command, self.label, self.operations);
         history.save()?;
+
+        if let Some(journal) = self.journal {
+            let Journal { path, file, .. } = journal;
+            drop(file);
+            let _ = fs::remove_file(path);
+        }
         Ok(())
     }
 
     /// Get the count of logged operations
-    #[allow(dead_code)]
     pub fn count(&self) -> usize {
         self.operations.len()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_moves(logger: &mut Logger, range: std::ops::Range<usize>) {
+        for i in range {
+            logger.log_move(
+                PathBuf::from(format!("/in/{}.txt", i)),
+                PathBuf::from(format!("/out/{}.txt", i)),
+            );
+        }
+    }
+
+    #[test]
+    fn test_interrupted_batch_is_recovered_from_its_journal() {
+        let neat = tempfile::tempdir().unwrap();
+        set_test_neat_dir(neat.path());
+
+        let mut logger = Logger::new("organize");
+        log_moves(&mut logger, 0..150);
+        logger.checkpoint().unwrap();
+        log_moves(&mut logger, 150..250);
+        logger.checkpoint().unwrap();
+        // A running command's journal isn't taken over
+        assert!(History::load().unwrap().is_empty());
+
+        // Interrupted: the last checkpoint is all that made it to disk
+        log_moves(&mut logger, 250..260);
+        drop(logger);
+
+        let history = History::load().unwrap();
+        assert_eq!(history.batches.len(), 1);
+        assert_eq!(history.batches[0].command, "organize");
+        let operations = &history.batches[0].operations;
+        assert_eq!(operations.len(), 250);
+        assert_eq!(operations[249].to, PathBuf::from("/out/249.txt"));
+
+        // Recovered once, then kept in history.json
+        assert_eq!(
+            fs::read_dir(neat.path().join(JOURNAL_DIR)).unwrap().count(),
+            0
+        );
+        assert_eq!(History::load().unwrap().batches.len(), 1);
+    }
+}
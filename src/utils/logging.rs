@@ -0,0 +1,75 @@
+//! Diagnostic logging (`-v`/`-vv` and `--log-file`), kept apart from the
+//! colored output users read
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use env_logger::{Builder, Target};
+use log::LevelFilter;
+
+/// Log level for a `-v` count. Without a log file nothing is logged unless asked
+/// for; a log file records down to debug so a run can be explained afterwards.
+pub fn level_for(verbosity: u8, to_file: bool) -> LevelFilter {
+    let level = match verbosity {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    };
+    if to_file {
+        level.max(LevelFilter::Debug)
+    } else {
+        level
+    }
+}
+
+/// Install the global logger, writing to stderr or appending to `log_file`.
+/// Each line is `<timestamp> <LEVEL> <module>: <event> key=value ...`.
+pub fn init(verbosity: u8, log_file: Option<&Path>) -> Result<()> {
+    let mut builder = Builder::new();
+    builder
+        .filter_level(level_for(verbosity, log_file.is_some()))
+        .format(|buf, record| {
+            writeln!(
+                buf,
+                "{} {:<5} {}: {}",
+                buf.timestamp(),
+                record.level(),
+                record.module_path().unwrap_or(record.target()),
+                record.args()
+            )
+        });
+
+    if let Some(path) = log_file {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open log file: {:?}", path))?;
+        builder.target(Target::Pipe(Box::new(file)));
+    }
+
+    builder.try_init().context("Failed to set up logging")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_for_verbosity() {
+        assert_eq!(level_for(0, false), LevelFilter::Off);
+        assert_eq!(level_for(1, false), LevelFilter::Info);
+        assert_eq!(level_for(2, false), LevelFilter::Debug);
+        assert_eq!(level_for(5, false), LevelFilter::Trace);
+    }
+
+    #[test]
+    fn test_log_file_records_at_least_debug() {
+        assert_eq!(level_for(0, true), LevelFilter::Debug);
+        assert_eq!(level_for(1, true), LevelFilter::Debug);
+        assert_eq!(level_for(3, true), LevelFilter::Trace);
+    }
+}
@@ -3,7 +3,9 @@
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
+use std::time::SystemTime;
 
+use chrono::{NaiveDateTime, TimeZone, Utc};
 use exif::{Exif, In, Reader, Tag, Value};
 
 /// Extract GPS coordinate from EXIF data (latitude or longitude)
@@ -50,6 +52,10 @@ pub struct ImageMetadata {
     pub gps_latitude: Option<f64>,
     /// GPS longitude (decimal degrees, negative for West)
     pub gps_longitude: Option<f64>,
+    /// EXIF Orientation (1-8); 5-8 mean the stored image is displayed rotated 90°
+    pub orientation: Option<u32>,
+    /// Pixel width and height as stored, before applying the orientation
+    pub dimensions: Option<(u32, u32)>,
 }
 
 impl ImageMetadata {
@@ -76,12 +82,23 @@ impl ImageMetadata {
         let gps_latitude = extract_gps_coordinate(&exif, Tag::GPSLatitude, Tag::GPSLatitudeRef);
         let gps_longitude = extract_gps_coordinate(&exif, Tag::GPSLongitude, Tag::GPSLongitudeRef);
 
+        let uint_field = |tag: Tag| {
+            exif.get_field(tag, In::PRIMARY)
+                .and_then(|f| f.value.get_uint(0))
+        };
+        let orientation = uint_field(Tag::Orientation);
+        let dimensions = uint_field(Tag::PixelXDimension)
+            .zip(uint_field(Tag::PixelYDimension))
+            .or_else(|| uint_field(Tag::ImageWidth).zip(uint_field(Tag::ImageLength)));
+
         Some(ImageMetadata {
             camera_make,
             camera_model,
             date_taken,
             gps_latitude,
             gps_longitude,
+            orientation,
+            dimensions,
         })
     }
 
@@ -132,8 +149,43 @@ impl ImageMetadata {
         }
         None
     }
+
+    /// Parse the date taken into a point in time (EXIF dates carry no timezone,
+    /// so they are read as UTC like the other date filters)
+    pub fn date_taken_time(&self) -> Option<SystemTime> {
+        let clean = self.date_taken.as_ref()?.trim_matches('"').trim();
+        let parsed = NaiveDateTime::parse_from_str(clean, "%Y:%m:%d %H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(clean, "%Y-%m-%d %H:%M:%S"))
+            .ok()?;
+        Some(Utc.from_utc_datetime(&parsed).into())
+    }
 }
 
+/// Shape of a photo as displayed: `Portrait`, `Landscape` or `Square`, or
+/// `Unknown` without dimensions. Orientations 5-8 swap width and height.
+pub fn orientation_bucket(
+    dimensions: Option<(u32, u32)>,
+    orientation: Option<u32>,
+) -> &'static str {
+    let Some((width, height)) = dimensions.filter(|&(w, h)| w > 0 && h > 0) else {
+        return "Unknown";
+    };
+    let (width, height) = match orientation {
+        Some(5..=8) => (height, width),
+        _ => (width, height),
+    };
+    match width.cmp(&height) {
+        std::cmp::Ordering::Less => "Portrait",
+        std::cmp::Ordering::Greater => "Landscape",
+        std::cmp::Ordering::Equal => "Square",
+    }
+}
+
+/// Camera RAW formats built on TIFF, which the EXIF reader parses directly.
+/// Formats with their own container (CR3, RAF, ORF, RW2) are not included and
+/// fall back to the file's modification time.
+const RAW_EXIF_EXTENSIONS: &[&str] = &["cr2", "nef", "nrw", "arw", "sr2", "dng", "pef", "srw"];
+
 /// Check if a file is a supported image format for EXIF extraction
 pub fn is_exif_supported(path: &Path) -> bool {
     let ext = path
@@ -141,10 +193,13 @@ pub fn is_exif_supported(path: &Path) -> bool {
         .and_then(|e| e.to_str())
         .map(|e| e.to_lowercase());
 
-    matches!(
-        ext.as_deref(),
-        Some("jpg") | Some("jpeg") | Some("tiff") | Some("tif") | Some("heic") | Some("heif")
-    )
+    match ext.as_deref() {
+        Some("jpg") | Some("jpeg") | Some("tiff") | Some("tif") | Some("heic") | Some("heif") => {
+            true
+        }
+        Some(ext) => RAW_EXIF_EXTENSIONS.contains(&ext),
+        None => false,
+    }
 }
 
 /// Audio metadata extracted from music files
@@ -296,8 +351,50 @@ mod tests {
         assert!(is_exif_supported(Path::new("photo.JPEG")));
         assert!(is_exif_supported(Path::new("photo.tiff")));
         assert!(is_exif_supported(Path::new("photo.heic")));
+        assert!(is_exif_supported(Path::new("photo.cr2")));
+        assert!(is_exif_supported(Path::new("photo.NEF")));
+        assert!(is_exif_supported(Path::new("photo.arw")));
+        assert!(is_exif_supported(Path::new("photo.dng")));
+        assert!(is_exif_supported(Path::new("photo.pef")));
+        assert!(!is_exif_supported(Path::new("photo.cr3")));
         assert!(!is_exif_supported(Path::new("photo.png")));
         assert!(!is_exif_supported(Path::new("document.pdf")));
+        assert!(!is_exif_supported(Path::new("README")));
+    }
+
+    /// A minimal little-endian TIFF (the layout of CR2/NEF/ARW/DNG) holding
+    /// one DateTime tag
+    fn tiff_with_date(date: &str) -> Vec<u8> {
+        let mut bytes = b"II*\0".to_vec();
+        bytes.extend_from_slice(&8u32.to_le_bytes()); // first IFD offset
+        bytes.extend_from_slice(&1u16.to_le_bytes()); // one entry
+        bytes.extend_from_slice(&0x0132u16.to_le_bytes()); // DateTime
+        bytes.extend_from_slice(&2u16.to_le_bytes()); // ASCII
+        bytes.extend_from_slice(&(date.len() as u32 + 1).to_le_bytes());
+        bytes.extend_from_slice(&26u32.to_le_bytes()); // value offset
+        bytes.extend_from_slice(&0u32.to_le_bytes()); // no next IFD
+        bytes.extend_from_slice(date.as_bytes());
+        bytes.push(0);
+        bytes
+    }
+
+    #[test]
+    fn test_raw_exif_date_taken() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("IMG_0001.dng");
+        std::fs::write(&path, tiff_with_date("2023:05:04 10:20:30")).unwrap();
+
+        let meta = ImageMetadata::from_path(&path).unwrap();
+        assert_eq!(meta.date_taken_folder(), Some("2023/05".to_string()));
+    }
+
+    #[test]
+    fn test_unreadable_raw_falls_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("IMG_0002.cr2");
+        std::fs::write(&path, "truncated download").unwrap();
+
+        assert!(ImageMetadata::from_path(&path).is_none());
     }
 
     #[test]
@@ -312,6 +409,32 @@ mod tests {
         assert!(!is_audio_supported(Path::new("document.pdf")));
     }
 
+    #[test]
+    fn test_orientation_bucket() {
+        assert_eq!(orientation_bucket(Some((4000, 3000)), Some(1)), "Landscape");
+        assert_eq!(orientation_bucket(Some((3000, 4000)), None), "Portrait");
+        assert_eq!(orientation_bucket(Some((2000, 2000)), Some(6)), "Square");
+        assert_eq!(orientation_bucket(None, Some(1)), "Unknown");
+        assert_eq!(orientation_bucket(Some((0, 100)), None), "Unknown");
+    }
+
+    #[test]
+    fn test_orientation_bucket_rotated() {
+        // A phone stores portrait shots as landscape pixels tagged as rotated
+        for orientation in 5..=8 {
+            assert_eq!(
+                orientation_bucket(Some((4032, 3024)), Some(orientation)),
+                "Portrait"
+            );
+        }
+        for orientation in 1..=4 {
+            assert_eq!(
+                orientation_bucket(Some((4032, 3024)), Some(orientation)),
+                "Landscape"
+            );
+        }
+    }
+
     #[test]
     fn test_date_taken_folder_parsing() {
         let meta1 = ImageMetadata {
@@ -327,6 +450,25 @@ mod tests {
         assert_eq!(meta2.date_taken_folder(), Some("2023/12".to_string()));
     }
 
+    #[test]
+    fn test_date_taken_time_parsing() {
+        let expected: SystemTime = Utc.with_ymd_and_hms(2024, 6, 15, 10, 30, 0).unwrap().into();
+        for raw in ["\"2024:06:15 10:30:00\"", "2024-06-15 10:30:00"] {
+            let meta = ImageMetadata {
+                date_taken: Some(raw.to_string()),
+                ..Default::default()
+            };
+            assert_eq!(meta.date_taken_time(), Some(expected));
+        }
+
+        let meta = ImageMetadata {
+            date_taken: Some("not a date".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(meta.date_taken_time(), None);
+        assert_eq!(ImageMetadata::default().date_taken_time(), None);
+    }
+
     #[test]
     fn test_date_taken_folder_none() {
         let meta = ImageMetadata::default();
@@ -427,6 +569,8 @@ mod tests {
         assert!(meta.date_taken.is_none());
         assert!(meta.gps_latitude.is_none());
         assert!(meta.gps_longitude.is_none());
+        assert!(meta.orientation.is_none());
+        assert!(meta.dimensions.is_none());
     }
 
     #[test]
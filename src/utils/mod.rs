@@ -4,4 +4,7 @@ pub mod error;
 pub mod export;
 pub mod hooks;
 pub mod logger;
+pub mod logging;
 pub mod metadata;
+pub mod progress;
+pub mod throttle;
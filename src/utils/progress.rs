@@ -0,0 +1,185 @@
+//! Progress reporting that adapts to the output (terminal, CI logs, or none)
+
+use std::io::{self, IsTerminal, Write};
+use std::sync::{Mutex, OnceLock};
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle, TermLike};
+
+/// How long-running operations report progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// Animated progress bar redrawn in place
+    Bar,
+    /// One line of text for every 10% of progress
+    Plain,
+    /// No progress output at all
+    Off,
+}
+
+impl ProgressMode {
+    /// Bars on a terminal, plain lines when stderr is piped or captured (e.g. CI)
+    pub fn detect() -> Self {
+        if io::stderr().is_terminal() {
+            ProgressMode::Bar
+        } else {
+            ProgressMode::Plain
+        }
+    }
+}
+
+static MODE: OnceLock<ProgressMode> = OnceLock::new();
+
+/// Set the progress mode for the rest of the process (first call wins)
+pub fn set_mode(mode: ProgressMode) {
+    let _ = MODE.set(mode);
+}
+
+/// The current progress mode (`Bar` if never set)
+pub fn mode() -> ProgressMode {
+    MODE.get().copied().unwrap_or(ProgressMode::Bar)
+}
+
+/// Jobs with fewer steps than this finish too fast for a bar to be useful
+/// (it would only flicker), so they get a hidden one
+pub const MIN_PROGRESS_LEN: u64 = 20;
+
+/// Create a progress bar for `len` steps in the current mode.
+/// `label` names the operation in plain mode; `template` styles the bar.
+pub fn progress_bar(len: u64, label: &str, template: &str) -> ProgressBar {
+    bar_for_mode(mode(), len, label, template)
+}
+
+fn bar_for_mode(mode: ProgressMode, len: u64, label: &str, template: &str) -> ProgressBar {
+    if len < MIN_PROGRESS_LEN {
+        return ProgressBar::hidden();
+    }
+
+    match mode {
+        ProgressMode::Bar => {
+            let pb = ProgressBar::new(len);
+            pb.set_style(
+                ProgressStyle::default_bar()
+                    .template(template)
+                    .unwrap()
+                    .progress_chars("█▓░"),
+            );
+            pb
+        }
+        ProgressMode::Plain => {
+            let target = ProgressDrawTarget::term_like(Box::new(PlainLines::default()));
+            let pb = ProgressBar::with_draw_target(Some(len), target);
+            pb.set_style(ProgressStyle::with_template("{prefix}: {percent}%").unwrap());
+            pb.set_prefix(label.to_string());
+            pb
+        }
+        ProgressMode::Off => ProgressBar::hidden(),
+    }
+}
+
+/// Draw target that appends a line to stderr each time progress reaches
+/// another 10%, instead of redrawing a bar
+#[derive(Debug, Default)]
+struct PlainLines {
+    last: Mutex<String>,
+}
+
+impl PlainLines {
+    fn print(&self, line: &str) -> io::Result<()> {
+        let Some(line) = round_percent(line.trim_end()) else {
+            return Ok(());
+        };
+        let mut last = self.last.lock().unwrap();
+        if *last == line {
+            return Ok(());
+        }
+        writeln!(io::stderr(), "{}", line)?;
+        *last = line;
+        Ok(())
+    }
+}
+
+/// Round the trailing "N%" of a plain progress line down to a multiple of 10
+fn round_percent(line: &str) -> Option<String> {
+    let (label, percent) = line.strip_suffix('%')?.rsplit_once(": ")?;
+    let percent: u64 = percent.parse().ok()?;
+    Some(format!("{}: {}%", label, percent / 10 * 10))
+}
+
+impl TermLike for PlainLines {
+    fn width(&self) -> u16 {
+        80
+    }
+
+    fn move_cursor_up(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor_down(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor_right(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn move_cursor_left(&self, _n: usize) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn write_line(&self, s: &str) -> io::Result<()> {
+        self.print(s)
+    }
+
+    fn write_str(&self, s: &str) -> io::Result<()> {
+        self.print(s)
+    }
+
+    fn clear_line(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_small_jobs_get_no_bar() {
+        let small = bar_for_mode(ProgressMode::Plain, 3, "Moving", "{bar}");
+        assert!(small.is_hidden());
+        small.inc(3);
+        assert_eq!(small.position(), 3);
+
+        let large = bar_for_mode(ProgressMode::Plain, MIN_PROGRESS_LEN, "Moving", "{bar}");
+        assert!(!large.is_hidden());
+        assert_eq!(large.length(), Some(MIN_PROGRESS_LEN));
+    }
+
+    #[test]
+    fn test_round_percent() {
+        assert_eq!(
+            round_percent("Hashing files: 47%").as_deref(),
+            Some("Hashing files: 40%")
+        );
+        assert_eq!(
+            round_percent("Moving: 100%").as_deref(),
+            Some("Moving: 100%")
+        );
+        assert_eq!(round_percent(""), None);
+        assert_eq!(round_percent("Moving"), None);
+    }
+
+    #[test]
+    fn test_plain_lines_skip_repeats() {
+        let term = PlainLines::default();
+        term.print("Moving: 12%").unwrap();
+        term.print("Moving: 17%").unwrap();
+        assert_eq!(*term.last.lock().unwrap(), "Moving: 10%");
+        term.print("").unwrap();
+        assert_eq!(*term.last.lock().unwrap(), "Moving: 10%");
+    }
+}
@@ -0,0 +1,63 @@
+//! Rate limiting for bulk file operations (e.g. on network drives)
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Paces a loop to at most a fixed number of operations per second
+#[derive(Debug)]
+pub struct Throttle {
+    interval: Option<Duration>,
+    next: Option<Instant>,
+}
+
+impl Throttle {
+    /// A throttle allowing `ops_per_sec` operations per second (None = unlimited)
+    pub fn new(ops_per_sec: Option<f64>) -> Self {
+        Self {
+            interval: ops_per_sec.map(|rate| Duration::from_secs_f64(1.0 / rate)),
+            next: None,
+        }
+    }
+
+    /// Sleep until the next operation is allowed. The first call never waits.
+    pub fn wait(&mut self) {
+        let Some(interval) = self.interval else {
+            return;
+        };
+        let now = Instant::now();
+        let start = match self.next {
+            Some(next) if next > now => {
+                thread::sleep(next - now);
+                next
+            }
+            _ => now,
+        };
+        self.next = Some(start + interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_paces_operations() {
+        // 4 operations at 20/s: three 50ms gaps after the first
+        let mut throttle = Throttle::new(Some(20.0));
+        let start = Instant::now();
+        for _ in 0..4 {
+            throttle.wait();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[test]
+    fn test_throttle_unlimited_never_waits() {
+        let mut throttle = Throttle::new(None);
+        let start = Instant::now();
+        for _ in 0..1000 {
+            throttle.wait();
+        }
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+}
@@ -1,8 +1,10 @@
 //! Watch mode - monitor directory for changes and auto-organize
 
-use std::path::Path;
-use std::sync::mpsc::channel;
-use std::time::Duration;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use colored::*;
@@ -11,20 +13,134 @@ use notify_debouncer_mini::{new_debouncer, DebouncedEventKind};
 
 use crate::classifier::Classifier;
 use crate::config::Config as NeatConfig;
-use crate::organizer::{execute_moves, plan_moves, ConflictStrategy, OrganizeMode, PlannedMove};
-use crate::scanner::FileInfo;
+use crate::organizer::{
+    execute_moves, plan_move_with_rules, ConflictStrategy, MoveReason, OrganizeMode, PlannedMove,
+};
+use crate::scanner::{matches_glob, FileInfo, ScanOptions};
 
-/// Watch a directory and auto-organize new files
+/// How long the watcher waits for events to settle unless `--debounce` says otherwise
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Parse a watch interval such as "500ms", "10s", "5m" or "1h" (a bare number is seconds)
+pub fn parse_interval(s: &str) -> Result<Duration> {
+    let s = s.trim().to_lowercase();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num_str, unit) = s.split_at(split);
+    let num: u64 = num_str.parse().map_err(|_| {
+        anyhow::anyhow!(
+            "Invalid interval: {}. Use formats like 500ms, 10s, 5m, 1h",
+            s
+        )
+    })?;
+
+    match unit.trim() {
+        "ms" => Ok(Duration::from_millis(num)),
+        "" | "s" | "sec" => Ok(Duration::from_secs(num)),
+        "m" | "min" => Ok(Duration::from_secs(num * 60)),
+        "h" => Ok(Duration::from_secs(num * 3600)),
+        other => anyhow::bail!(
+            "Unknown interval unit '{}' in {}. Use ms, s, m or h",
+            other,
+            s
+        ),
+    }
+}
+
+/// How long a file's size must hold still before it's organized
+const STABILITY_WINDOW: Duration = Duration::from_millis(500);
+
+/// Extensions browsers and download tools use while a file is still arriving
+const IN_PROGRESS_EXTENSIONS: &[&str] = &["part", "crdownload", "download", "tmp"];
+
+/// What happened to one detected file
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchOutcome {
+    /// Left alone by the profile filters
+    Filtered,
+    /// Already where the mode or a rule would put it
+    AlreadyOrganized,
+    /// Moved (or, without `--auto`, would be moved) to this folder
+    Destination(String),
+}
+
+/// Running totals of what the watcher saw, printed every `--stats-interval`
+#[derive(Debug, Default)]
+pub struct WatchTally {
+    pub detected: usize,
+    pub filtered: usize,
+    pub already_organized: usize,
+    /// Files per destination folder, relative to the watched one
+    pub destinations: BTreeMap<String, usize>,
+}
+
+impl WatchTally {
+    /// Count one detected file
+    pub fn record(&mut self, outcome: WatchOutcome) {
+        self.detected += 1;
+        match outcome {
+            WatchOutcome::Filtered => self.filtered += 1,
+            WatchOutcome::AlreadyOrganized => self.already_organized += 1,
+            WatchOutcome::Destination(folder) => {
+                *self.destinations.entry(folder).or_default() += 1;
+            }
+        }
+    }
+
+    /// Files that were (or would be) moved
+    pub fn moved(&self) -> usize {
+        self.destinations.values().sum()
+    }
+
+    fn print(&self, auto_execute: bool) {
+        let verb = if auto_execute { "moved" } else { "would move" };
+        println!(
+            "{} {} files detected: {} {}, {} already organized, {} filtered",
+            "Σ".cyan(),
+            self.detected,
+            self.moved(),
+            verb,
+            self.already_organized,
+            self.filtered
+        );
+        for (folder, count) in &self.destinations {
+            println!("    {:>5}  {}", count, folder.cyan());
+        }
+        println!();
+    }
+}
+
+/// Watch a directory and auto-organize new files, printing the running tally
+/// every `stats_interval` if given
+#[allow(clippy::too_many_arguments)]
 pub fn watch_directory(
     path: &Path,
     mode: OrganizeMode,
-    config: Option<&NeatConfig>,
+    filter: &ScanOptions,
+    strategy: ConflictStrategy,
+    mut config: Option<NeatConfig>,
+    config_reload: Option<&Path>,
     auto_execute: bool,
+    debounce: Duration,
+    stats_interval: Option<Duration>,
 ) -> Result<()> {
     let canonical_path = path
         .canonicalize()
         .with_context(|| format!("Path does not exist: {:?}", path))?;
 
+    // Event paths are absolute, so resolve the config path the same way.
+    // Its folder is watched rather than the file, since editors often replace files on save.
+    let config_reload = config_reload
+        .map(|config_path| -> Result<_> {
+            let dir = config_path
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(Path::new("."))
+                .canonicalize()
+                .with_context(|| format!("Config folder does not exist: {:?}", config_path))?;
+            Ok(dir.join(config_path.file_name().unwrap_or_default()))
+        })
+        .transpose()?;
+
     println!(
         "{} Watching {} for new files...",
         "👁".cyan(),
@@ -36,154 +152,518 @@ pub fn watch_directory(
     let (tx, rx) = channel();
 
     // Create a debouncer to avoid processing the same file multiple times
-    let mut debouncer =
-        new_debouncer(Duration::from_secs(2), tx).context("Failed to create file watcher")?;
+    let mut debouncer = new_debouncer(debounce, tx).context("Failed to create file watcher")?;
 
     debouncer
         .watcher()
         .watch(&canonical_path, RecursiveMode::NonRecursive)
         .context("Failed to watch directory")?;
 
+    if let Some(ref config_path) = config_reload {
+        if let Some(config_dir) = config_path.parent().filter(|dir| *dir != canonical_path) {
+            debouncer
+                .watcher()
+                .watch(config_dir, RecursiveMode::NonRecursive)
+                .context("Failed to watch config file")?;
+        }
+        println!(
+            "{} Reloading rules when {} changes",
+            "↻".cyan(),
+            config_path.display()
+        );
+    }
+
     let _classifier = Classifier::new();
+    let ignore_patterns = filter.ignore_globs()?;
+    let mut tally = WatchTally::default();
+    let mut next_report = Instant::now() + stats_interval.unwrap_or_default();
+    // Detected files wait here until their size holds still
+    let mut pending: HashMap<PathBuf, PendingFile> = HashMap::new();
 
     loop {
-        match rx.recv() {
-            Ok(Ok(events)) => {
+        // Wake up for the next report and to re-check files waiting to settle
+        let wake = [
+            stats_interval.map(|_| next_report),
+            pending
+                .values()
+                .map(|file| file.since + STABILITY_WINDOW)
+                .min(),
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+        let received = match wake {
+            Some(wake) => match rx.recv_timeout(wake.saturating_duration_since(Instant::now())) {
+                Ok(received) => Some(received),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(e) => {
+                    eprintln!("{} Channel error: {:?}", "✗".red(), e);
+                    break;
+                }
+            },
+            None => match rx.recv() {
+                Ok(received) => Some(received),
+                Err(e) => {
+                    eprintln!("{} Channel error: {:?}", "✗".red(), e);
+                    break;
+                }
+            },
+        };
+
+        match received {
+            Some(Ok(events)) => {
                 for event in events {
                     if event.kind == DebouncedEventKind::Any {
                         let file_path = &event.path;
 
+                        // Rules changed on disk: swap them in and keep watching
+                        if let Some(ref config_path) = config_reload {
+                            if file_path == config_path {
+                                reload_config(config_path, &mut config);
+                                continue;
+                            }
+                            // Other files next to the config aren't ours to organize
+                            if file_path.parent() != Some(canonical_path.as_path()) {
+                                continue;
+                            }
+                        }
+
                         // Skip directories and hidden files
                         if file_path.is_dir() {
                             continue;
                         }
 
                         if let Some(name) = file_path.file_name() {
-                            if name.to_string_lossy().starts_with('.') {
+                            if !filter.include_hidden && name.to_string_lossy().starts_with('.') {
                                 continue;
                             }
                         }
 
-                        // Skip if file no longer exists (was moved/deleted)
-                        if !file_path.exists() {
+                        // The finished file arrives under its real name after a rename
+                        if is_in_progress(file_path) {
                             continue;
                         }
 
-                        // Get file info
-                        if let Ok(file_info) = FileInfo::from_path(file_path) {
-                            println!(
-                                "{} New file detected: {}",
-                                "→".cyan(),
-                                file_info.name.bold()
+                        // Skip if file no longer exists (was moved/deleted); otherwise
+                        // wait for its size to hold still, restarting the wait if it grew
+                        if let Ok(metadata) = fs::metadata(file_path) {
+                            pending.insert(
+                                file_path.clone(),
+                                PendingFile {
+                                    size: metadata.len(),
+                                    since: Instant::now(),
+                                },
                             );
+                        }
+                    }
+                }
+            }
+            Some(Err(e)) => {
+                eprintln!("{} Watch error: {:?}", "⚠".yellow(), e);
+            }
+            None => {}
+        }
 
-                            // Check custom rules first
-                            let destination = if let Some(cfg) = config {
-                                if let Some(rule) = cfg.find_matching_rule(&file_info.name) {
-                                    println!(
-                                        "  {} Matched rule: {}",
-                                        "✓".green(),
-                                        rule.name.cyan()
-                                    );
-                                    Some(rule.get_destination(
-                                        &canonical_path,
-                                        &file_info.name,
-                                        file_info.extension.as_deref(),
-                                    ))
-                                } else {
-                                    None
-                                }
-                            } else {
-                                None
-                            };
-
-                            // Use standard organization if no rule matched
-                            let moves = if let Some(dest) = destination {
-                                vec![PlannedMove {
-                                    from: file_info.path.clone(),
-                                    to: dest,
-                                    size: file_info.size,
-                                }]
-                            } else {
-                                plan_moves(std::slice::from_ref(&file_info), &canonical_path, mode)
-                            };
-
-                            if moves.is_empty() {
-                                println!("  {} Already organized", "✓".green());
-                                continue;
-                            }
+        let now = Instant::now();
+        for file_path in take_settled(&mut pending, STABILITY_WINDOW, now) {
+            organize_detected(
+                &file_path,
+                &canonical_path,
+                mode,
+                filter,
+                strategy,
+                &ignore_patterns,
+                config.as_ref(),
+                auto_execute,
+                &mut tally,
+            );
+        }
 
-                            let mv = &moves[0];
-                            let dest_folder = mv
-                                .to
-                                .parent()
-                                .map(|p| p.strip_prefix(&canonical_path).unwrap_or(p))
-                                .map(|p| p.display().to_string())
-                                .unwrap_or_default();
-
-                            if auto_execute {
-                                // Get the matched rule to check for post_action
-                                let matched_rule =
-                                    config.and_then(|cfg| cfg.find_matching_rule(&file_info.name));
-
-                                match execute_moves(&moves, "watch", ConflictStrategy::Rename) {
-                                    Ok(_) => {
-                                        println!(
-                                            "  {} Moved to {}",
-                                            "✓".green(),
-                                            dest_folder.cyan()
-                                        );
-
-                                        // Execute post_action hook if configured
-                                        if let Some(rule) = matched_rule {
-                                            if let Some(ref hook_cmd) = rule.post_action {
-                                                use crate::hooks::execute_hook;
-                                                let mv = &moves[0];
-                                                if let Err(e) =
-                                                    execute_hook(hook_cmd, &mv.from, &mv.to)
-                                                {
-                                                    println!(
-                                                        "  {} Hook failed: {}",
-                                                        "⚠".yellow(),
-                                                        e
-                                                    );
-                                                } else {
-                                                    println!("  {} Hook executed", "⚡".cyan());
-                                                }
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        println!("  {} Failed: {}", "✗".red(), e);
-                                    }
-                                }
-                            } else {
-                                println!(
-                                    "  {} Would move to: {}",
-                                    "→".yellow(),
-                                    dest_folder.cyan()
-                                );
-                                println!(
-                                    "    {} Add {} flag to auto-move files",
-                                    "ℹ".blue(),
-                                    "--auto".yellow()
-                                );
-                            }
+        if let Some(interval) = stats_interval {
+            if now >= next_report {
+                tally.print(auto_execute);
+                next_report = now + interval;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Plan (and with `auto_execute`, perform) the move of a detected file that has
+/// finished arriving, recording what happened in `tally`
+#[allow(clippy::too_many_arguments)]
+fn organize_detected(
+    file_path: &Path,
+    base: &Path,
+    mode: OrganizeMode,
+    filter: &ScanOptions,
+    strategy: ConflictStrategy,
+    ignore_patterns: &[glob::Pattern],
+    config: Option<&NeatConfig>,
+    auto_execute: bool,
+    tally: &mut WatchTally,
+) {
+    let Ok(file_info) = FileInfo::from_path(file_path) else {
+        return;
+    };
+    println!(
+        "{} New file detected: {}",
+        "→".cyan(),
+        file_info.name.bold()
+    );
+
+    if !accepts(filter, ignore_patterns, &file_info) {
+        tally.record(WatchOutcome::Filtered);
+        println!("  {} Skipped by profile filters", "-".dimmed());
+        println!();
+        return;
+    }
+
+    // Custom rules first, then the organize mode
+    let moves: Vec<PlannedMove> = match plan_move_with_rules(&file_info, base, mode, config) {
+        Some((planned, reason)) => {
+            if let MoveReason::Rule(name) = reason {
+                println!("  {} Matched rule: {}", "✓".green(), name.cyan());
+            }
+            vec![planned]
+        }
+        None => Vec::new(),
+    };
+
+    if moves.is_empty() {
+        tally.record(WatchOutcome::AlreadyOrganized);
+        println!("  {} Already organized", "✓".green());
+        return;
+    }
+
+    let mv = &moves[0];
+    let dest_folder = mv
+        .to
+        .parent()
+        .map(|p| p.strip_prefix(base).unwrap_or(p))
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+    tally.record(WatchOutcome::Destination(dest_folder.clone()));
+
+    if auto_execute {
+        // Get the matched rule to check for post_action
+        let matched_rule = config.and_then(|cfg| cfg.find_matching_rule(&file_info.name));
+
+        match execute_moves(&moves, "watch", strategy) {
+            Ok(result) if result.moved == 0 && result.errors.is_empty() => {
+                println!(
+                    "  {} Skipped: already exists in {}",
+                    "-".dimmed(),
+                    dest_folder.cyan()
+                );
+            }
+            Ok(_) => {
+                println!("  {} Moved to {}", "✓".green(), dest_folder.cyan());
 
-                            println!();
+                // Execute post_action hook if configured
+                if let Some(rule) = matched_rule {
+                    if let Some(ref hook_cmd) = rule.post_action {
+                        use crate::hooks::execute_hook;
+                        if let Err(e) = execute_hook(hook_cmd, &mv.from, &mv.to) {
+                            println!("  {} Hook failed: {}", "⚠".yellow(), e);
+                        } else {
+                            println!("  {} Hook executed", "⚡".cyan());
                         }
                     }
                 }
             }
-            Ok(Err(e)) => {
-                eprintln!("{} Watch error: {:?}", "⚠".yellow(), e);
-            }
             Err(e) => {
-                eprintln!("{} Channel error: {:?}", "✗".red(), e);
-                break;
+                println!("  {} Failed: {}", "✗".red(), e);
             }
         }
+    } else {
+        println!("  {} Would move to: {}", "→".yellow(), dest_folder.cyan());
+        println!(
+            "    {} Add {} flag to auto-move files",
+            "ℹ".blue(),
+            "--auto".yellow()
+        );
     }
 
-    Ok(())
+    println!();
+}
+
+/// Whether a detected file passes the filters and ignore patterns (from `--profile`)
+fn accepts(filter: &ScanOptions, ignore_patterns: &[glob::Pattern], file: &FileInfo) -> bool {
+    !matches_glob(ignore_patterns, &file.path) && filter.matches(file)
+}
+
+/// Whether a file is a partial download (e.g. `movie.mp4.crdownload`)
+fn is_in_progress(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| IN_PROGRESS_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// A detected file waiting for its size to hold still
+#[derive(Debug, Clone, Copy)]
+struct PendingFile {
+    /// Size when last checked
+    size: u64,
+    /// When the size was last seen to change (or the file was first detected)
+    since: Instant,
+}
+
+/// Re-check the pending files whose size was last seen at least `window` ago. Those
+/// that kept their size are removed and returned, ready to organize; those still
+/// growing wait another `window`, and files that vanished are dropped.
+fn take_settled(
+    pending: &mut HashMap<PathBuf, PendingFile>,
+    window: Duration,
+    now: Instant,
+) -> Vec<PathBuf> {
+    let mut settled = Vec::new();
+    pending.retain(|path, file| {
+        if now.duration_since(file.since) < window {
+            return true;
+        }
+        match fs::metadata(path).map(|m| m.len()) {
+            Ok(size) if size == file.size => {
+                settled.push(path.clone());
+                false
+            }
+            Ok(size) => {
+                println!(
+                    "{} Still being written, waiting: {}",
+                    "…".dimmed(),
+                    path.display()
+                );
+                *file = PendingFile { size, since: now };
+                true
+            }
+            Err(_) => false,
+        }
+    });
+    settled.sort();
+    settled
+}
+
+/// Re-read the config at `path` into `config`. A config that fails to load leaves
+/// the previous one in place. Returns whether the config was replaced.
+pub fn reload_config(path: &Path, config: &mut Option<NeatConfig>) -> bool {
+    match NeatConfig::load(path) {
+        Ok(new_config) => {
+            println!(
+                "{} Reloaded config ({} rules)",
+                "↻".cyan(),
+                new_config.rules.len()
+            );
+            *config = Some(new_config);
+            true
+        }
+        Err(e) => {
+            eprintln!(
+                "{} Keeping previous config, reload failed: {:#}",
+                "⚠".yellow(),
+                e
+            );
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const INVOICE_RULE: &str = r#"
+[[rules]]
+name = "Invoices"
+pattern = "*invoice*.pdf"
+destination = "Documents/Invoices"
+"#;
+
+    const SCREENSHOT_RULE: &str = r#"
+[[rules]]
+name = "Screenshots"
+pattern = "Screenshot*.png"
+destination = "Images/Screenshots"
+"#;
+
+    #[test]
+    fn test_watch_tally_accumulates_outcomes() {
+        let mut tally = WatchTally::default();
+        for outcome in [
+            WatchOutcome::Destination("Images".to_string()),
+            WatchOutcome::Destination("Documents".to_string()),
+            WatchOutcome::Filtered,
+            WatchOutcome::Destination("Images".to_string()),
+            WatchOutcome::AlreadyOrganized,
+            WatchOutcome::Destination("Documents/Invoices".to_string()),
+        ] {
+            tally.record(outcome);
+        }
+
+        assert_eq!(tally.detected, 6);
+        assert_eq!(tally.filtered, 1);
+        assert_eq!(tally.already_organized, 1);
+        assert_eq!(tally.moved(), 4);
+        assert_eq!(
+            tally.destinations.into_iter().collect::<Vec<_>>(),
+            [
+                ("Documents".to_string(), 1),
+                ("Documents/Invoices".to_string(), 1),
+                ("Images".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_profile_mode_and_filters_apply_to_detected_file() {
+        use crate::commands::profile::ProfileOptions;
+        use crate::organizer::plan_move_with_rules;
+
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path().canonicalize().unwrap();
+        fs::write(base.join("notes.txt"), "hello").unwrap();
+        fs::write(base.join("draft.tmp"), "hello").unwrap();
+
+        let options = ProfileOptions {
+            by_extension: true,
+            on_conflict: "skip".to_string(),
+            ignore: vec!["*.tmp".to_string()],
+            ..Default::default()
+        };
+        let filter = options.scan_options().unwrap();
+        let ignore_patterns = filter.ignore_globs().unwrap();
+
+        let notes = FileInfo::from_path(&base.join("notes.txt")).unwrap();
+        assert!(accepts(&filter, &ignore_patterns, &notes));
+        let (planned, _) = plan_move_with_rules(&notes, &base, options.mode(), None).unwrap();
+        assert_eq!(planned.to, base.join("TXT").join("notes.txt"));
+
+        let draft = FileInfo::from_path(&base.join("draft.tmp")).unwrap();
+        assert!(!accepts(&filter, &ignore_patterns, &draft));
+
+        // The profile's on_conflict decides what happens to a taken destination
+        let neat = tempfile::tempdir().unwrap();
+        crate::logger::set_test_neat_dir(neat.path());
+        fs::create_dir(base.join("TXT")).unwrap();
+        fs::write(base.join("TXT").join("notes.txt"), "older").unwrap();
+        assert_eq!(options.conflict_strategy(), ConflictStrategy::Skip);
+        let mut tally = WatchTally::default();
+        organize_detected(
+            &base.join("notes.txt"),
+            &base,
+            options.mode(),
+            &filter,
+            options.conflict_strategy(),
+            &ignore_patterns,
+            None,
+            true,
+            &mut tally,
+        );
+        assert!(base.join("notes.txt").exists());
+        assert_eq!(
+            fs::read_to_string(base.join("TXT").join("notes.txt")).unwrap(),
+            "older"
+        );
+        assert!(!base.join("TXT").join("notes_1.txt").exists());
+    }
+
+    #[test]
+    fn test_in_progress_downloads_are_skipped() {
+        assert!(is_in_progress(Path::new("/dl/movie.mp4.crdownload")));
+        assert!(is_in_progress(Path::new("/dl/archive.zip.part")));
+        assert!(is_in_progress(Path::new("/dl/song.mp3.download")));
+        assert!(is_in_progress(Path::new("/dl/setup.TMP")));
+        assert!(!is_in_progress(Path::new("/dl/movie.mp4")));
+        assert!(!is_in_progress(Path::new("/dl/partial.pdf")));
+        assert!(!is_in_progress(Path::new("/dl/Makefile")));
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("500ms").unwrap(), Duration::from_millis(500));
+        assert_eq!(parse_interval("10s").unwrap(), Duration::from_secs(10));
+        assert_eq!(parse_interval("10").unwrap(), Duration::from_secs(10));
+        assert_eq!(parse_interval("5m").unwrap(), Duration::from_secs(300));
+        assert_eq!(parse_interval("1h").unwrap(), Duration::from_secs(3600));
+        assert!(parse_interval("1d").is_err());
+        assert!(parse_interval("m").is_err());
+        assert!(parse_interval("").is_err());
+    }
+
+    #[test]
+    fn test_growing_file_is_deferred() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("download.iso");
+        let gone = dir.path().join("gone.iso");
+        fs::write(&path, "start").unwrap();
+        let window = Duration::from_millis(500);
+        let start = Instant::now();
+        let mut pending = HashMap::new();
+        for file in [&path, &gone] {
+            let pending_file = PendingFile {
+                size: 5,
+                since: start,
+            };
+            pending.insert(file.clone(), pending_file);
+        }
+
+        // Nothing is checked before the window is up
+        assert!(take_settled(&mut pending, window, start).is_empty());
+        assert_eq!(pending.len(), 2);
+
+        // It grew meanwhile: wait another window; the vanished file is dropped
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"more data")
+            .unwrap();
+        assert!(take_settled(&mut pending, window, start + window).is_empty());
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[&path].size, 14);
+        assert!(take_settled(&mut pending, window, start + window + window / 2).is_empty());
+
+        // Once writing stops the file is ready
+        let settled = take_settled(&mut pending, window, start + window * 2);
+        assert_eq!(settled, vec![path]);
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_reload_config_picks_up_new_rule() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, INVOICE_RULE).unwrap();
+        let mut config = Some(NeatConfig::load(&path).unwrap());
+        let screenshot = "Screenshot 2024.png";
+        assert!(config
+            .as_ref()
+            .unwrap()
+            .find_matching_rule(screenshot)
+            .is_none());
+
+        fs::write(&path, format!("{}{}", INVOICE_RULE, SCREENSHOT_RULE)).unwrap();
+        assert!(reload_config(&path, &mut config));
+
+        let rule = config.as_ref().unwrap().find_matching_rule(screenshot);
+        assert_eq!(rule.map(|r| r.name.as_str()), Some("Screenshots"));
+    }
+
+    #[test]
+    fn test_reload_config_keeps_previous_on_parse_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, INVOICE_RULE).unwrap();
+        let mut config = Some(NeatConfig::load(&path).unwrap());
+
+        fs::write(&path, "[[rules]\nname = ").unwrap();
+        assert!(!reload_config(&path, &mut config));
+        assert_eq!(config.as_ref().unwrap().rules.len(), 1);
+        assert!(config
+            .as_ref()
+            .unwrap()
+            .find_matching_rule("march-invoice.pdf")
+            .is_some());
+    }
 }
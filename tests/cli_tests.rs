@@ -44,6 +44,191 @@ fn test_organize_by_extension() {
     assert!(dir.path().join("Images").join("image.jpg").exists());
 }
 
+#[test]
+fn test_organize_ext_case_lower() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("IMG_0001.JPG")).unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--by-extension")
+        .arg("--ext-case")
+        .arg("lower")
+        .arg("--execute")
+        .assert()
+        .success();
+
+    assert!(dir.path().join("jpg").join("IMG_0001.JPG").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_organize_leave_symlink() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("report.txt");
+    fs::write(&file, "quarterly numbers").unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--leave-symlink")
+        .arg("--execute")
+        .assert()
+        .success();
+
+    let moved = dir
+        .path()
+        .canonicalize()
+        .unwrap()
+        .join("Documents")
+        .join("report.txt");
+    assert!(moved.is_file());
+    assert!(fs::symlink_metadata(&file)
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert_eq!(fs::read_link(&file).unwrap(), moved);
+    assert_eq!(fs::read_to_string(&file).unwrap(), "quarterly numbers");
+}
+
+#[test]
+fn test_organize_name_regex_routing() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("backend_001.log")).unwrap();
+    File::create(dir.path().join("readme.txt")).unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--name-regex")
+        .arg(r"^(?P<proj>[a-z]+)_\d+\.log$")
+        .arg("--template")
+        .arg("logs/{proj}/{filename}")
+        .arg("--regex-fallback")
+        .arg("misc")
+        .arg("--execute")
+        .assert()
+        .success();
+
+    assert!(dir
+        .path()
+        .join("logs")
+        .join("backend")
+        .join("backend_001.log")
+        .exists());
+    assert!(dir.path().join("misc").join("readme.txt").exists());
+}
+
+#[test]
+fn test_organize_failure_midway_is_undoable() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    for name in ["a.txt", "b.txt", "c.txt", "d.txt", "photo.jpg"] {
+        fs::write(dir.path().join(name), name).unwrap();
+    }
+    // A plain file named "Images" makes creating Images/x/ fail for photo.jpg
+    fs::write(dir.path().join("Images"), "blocker").unwrap();
+
+    // A plan fixes the order, so photo.jpg fails after the others have moved
+    let plan: Vec<serde_json::Value> = ["a.txt", "b.txt", "c.txt", "d.txt", "photo.jpg"]
+        .iter()
+        .map(|name| {
+            let folder = if *name == "photo.jpg" {
+                "Images"
+            } else {
+                "Documents"
+            };
+            serde_json::json!({
+                "from": dir.path().join(name),
+                "to": dir.path().join(folder).join("x").join(name),
+                "size": name.len(),
+            })
+        })
+        .collect();
+    let plan_path = home.path().join("plan.json");
+    fs::write(&plan_path, serde_json::to_string(&plan).unwrap()).unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg("--apply")
+        .arg(&plan_path)
+        .arg("--fail-fast")
+        .arg("--execute")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Stopped at the first error"));
+
+    // Whatever moved before the failure must be recorded in history
+    let history: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(home.path().join(".neat/history.json"))
+            .unwrap_or_else(|_| "{\"batches\":[]}".to_string()),
+    )
+    .unwrap();
+    let recorded = history["batches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b["operations"].as_array().unwrap().len())
+        .sum::<usize>();
+    let moved = ["a.txt", "b.txt", "c.txt", "d.txt"]
+        .iter()
+        .filter(|n| !dir.path().join(n).exists())
+        .count();
+    assert_eq!(moved, 4);
+    assert_eq!(recorded, moved);
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("undo")
+        .assert()
+        .success();
+
+    for name in ["a.txt", "b.txt", "c.txt", "d.txt", "photo.jpg"] {
+        assert!(dir.path().join(name).exists());
+    }
+}
+
+#[test]
+fn test_organize_continues_past_uncreatable_dir() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "a").unwrap();
+    fs::write(dir.path().join("photo.jpg"), "jpg").unwrap();
+    fs::write(dir.path().join("Images"), "blocker").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--template")
+        .arg("{category}/x/{filename}")
+        .arg("--ignore")
+        .arg("Images")
+        .arg("--execute")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Failed to create directory"));
+
+    assert!(dir
+        .path()
+        .join("Documents")
+        .join("x")
+        .join("a.txt")
+        .exists());
+    assert!(dir.path().join("photo.jpg").exists());
+}
+
 #[test]
 fn test_organize_dry_run() {
     let dir = tempdir().unwrap();
@@ -80,6 +265,85 @@ fn test_duplicates_detection() {
         .stdout(predicate::str::contains("dup1.txt").or(predicate::str::contains("dup2.txt")));
 }
 
+#[test]
+fn test_duplicates_depth_limits_scan() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("top.txt"), "shared content").unwrap();
+    fs::create_dir(dir.path().join("cache")).unwrap();
+    fs::write(dir.path().join("cache").join("deep.txt"), "shared content").unwrap();
+    fs::write(dir.path().join("a.bin"), "pair").unwrap();
+    fs::write(dir.path().join("b.bin"), "pair").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("duplicates")
+        .arg(dir.path())
+        .args(["--depth", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("a.bin"))
+        .stdout(predicate::str::contains("deep.txt").not())
+        .stdout(predicate::str::contains("top.txt").not());
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("duplicates")
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("deep.txt"));
+}
+
+#[test]
+fn test_stats_depth_limits_scan() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("top.txt"), "top").unwrap();
+    fs::create_dir(dir.path().join("nested")).unwrap();
+    fs::write(dir.path().join("nested").join("deep.txt"), "deep").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("stats")
+        .arg(dir.path())
+        .args(["--json", "--depth", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"total_files\": 1"));
+}
+
+#[test]
+fn test_duplicates_across_reports_only_cross_tree_groups() {
+    let downloads = tempdir().unwrap();
+    let archive = tempdir().unwrap();
+    fs::write(downloads.path().join("report.pdf"), "quarterly report").unwrap();
+    fs::write(archive.path().join("report-2024.pdf"), "quarterly report").unwrap();
+    fs::write(downloads.path().join("a.txt"), "local copy").unwrap();
+    fs::write(downloads.path().join("b.txt"), "local copy").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("duplicates")
+        .arg("--across")
+        .arg(downloads.path())
+        .arg(archive.path())
+        .arg("--json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("report-2024.pdf"))
+        .stdout(predicate::str::contains("a.txt").not());
+
+    // Overlapping folders would match files against themselves
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("duplicates")
+        .arg("--across")
+        .arg(downloads.path())
+        .arg(downloads.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("must not overlap"));
+}
+
 #[test]
 fn test_duplicates_json_export() {
     let dir = tempdir().unwrap();
@@ -98,6 +362,50 @@ fn test_duplicates_json_export() {
         .stdout(predicate::str::contains("\"hash\""));
 }
 
+#[test]
+fn test_duplicates_threads_one_matches_default() {
+    let dir = tempdir().unwrap();
+    for i in 0..4 {
+        fs::write(dir.path().join(format!("a{}.txt", i)), "alpha").unwrap();
+        fs::write(dir.path().join(format!("b{}.txt", i)), "bravo!").unwrap();
+    }
+    fs::write(dir.path().join("unique.txt"), "unique").unwrap();
+
+    let groups = |extra: &[&str]| {
+        let output = Command::cargo_bin("neatcli")
+            .unwrap()
+            .arg("duplicates")
+            .arg(dir.path())
+            .arg("--json")
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let mut groups: Vec<Vec<String>> = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|g| {
+                let mut files: Vec<String> = g["files"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|f| f["path"].as_str().unwrap().to_string())
+                    .collect();
+                files.sort();
+                files
+            })
+            .collect();
+        groups.sort();
+        groups
+    };
+
+    let default = groups(&[]);
+    assert_eq!(default.len(), 2);
+    assert_eq!(groups(&["--threads", "1"]), default);
+}
+
 #[test]
 fn test_duplicates_csv_export() {
     let dir = tempdir().unwrap();
@@ -129,6 +437,28 @@ fn test_stats_command() {
         .stdout(predicate::str::contains("Files by Type"));
 }
 
+#[test]
+fn test_stats_units() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("file.txt"), vec![b'a'; 2000]).unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .args(["stats", "--units", "si"])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2.00 kB"));
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .args(["--units", "iec", "stats"])
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1.95 KiB"));
+}
+
 #[test]
 fn test_stats_json_export() {
     let dir = tempdir().unwrap();
@@ -143,6 +473,153 @@ fn test_stats_json_export() {
         .stdout(predicate::str::contains("\"total_files\""));
 }
 
+#[test]
+fn test_stats_with_duplicates_reports_wasted_space() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "duplicate body").unwrap();
+    fs::write(dir.path().join("b.txt"), "duplicate body").unwrap();
+    fs::write(dir.path().join("c.txt"), "unique").unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("stats")
+        .arg(dir.path())
+        .args(["--format", "json", "--with-duplicates"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"duplicate_wasted_space\": 14"));
+
+    // Opt-in: left out of the export otherwise
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("stats")
+        .arg(dir.path())
+        .args(["--format", "json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("duplicate_wasted_space").not());
+}
+
+#[test]
+fn test_stats_compare_with_snapshot() {
+    let dir = tempdir().unwrap();
+    let snapshots = tempdir().unwrap();
+    fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+
+    let output = Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("stats")
+        .arg(dir.path())
+        .arg("--json")
+        .output()
+        .unwrap();
+    let snapshot = snapshots.path().join("week1.json");
+    fs::write(&snapshot, output.stdout).unwrap();
+
+    fs::write(dir.path().join("clip.mp4"), "0123456789").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("stats")
+        .arg(dir.path())
+        .arg("--compare")
+        .arg(&snapshot)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Changes Since Snapshot"))
+        .stdout(predicate::str::is_match(r"Videos\s+\+1 files\s+\+10 B").unwrap());
+}
+
+#[test]
+fn test_stats_json_reports_threshold_breaches() {
+    let home = tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(
+        home.path().join(".neat/config.toml"),
+        "[thresholds.Images]\nmax_size = \"1KB\"\n\n[thresholds.Documents]\nmax_count = 5\n",
+    )
+    .unwrap();
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.png"), vec![0u8; 2048]).unwrap();
+    fs::write(dir.path().join("notes.txt"), "short").unwrap();
+
+    let output = Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("stats")
+        .arg(dir.path())
+        .arg("--json")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let breaches = stats["threshold_breaches"].as_array().unwrap();
+    assert_eq!(breaches.len(), 1);
+    assert_eq!(breaches[0]["category"], "Images");
+    assert_eq!(breaches[0]["metric"], "size");
+    assert_eq!(breaches[0]["limit"], 1024);
+    assert_eq!(breaches[0]["actual"], 2048);
+}
+
+#[test]
+fn test_classify_shows_category_mime_and_rule() {
+    let home = tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(
+        home.path().join(".neat/config.toml"),
+        "[[rules]]\nname = \"Screenshots\"\npattern = \"Screenshot*.png\"\ndestination = \"Images/Screenshots\"\n",
+    )
+    .unwrap();
+
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("Screenshot 2024.png");
+    fs::write(&file, b"\x89PNG\r\n\x1a\n0000").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("classify")
+        .arg(&file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Category     Images"))
+        .stdout(predicate::str::contains("MIME type    image/png"))
+        .stdout(predicate::str::contains("Screenshots"));
+
+    // Only single files can be classified
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("classify")
+        .arg(dir.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_size_command() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("videos").join("2024")).unwrap();
+    fs::write(dir.path().join("videos").join("a.mp4"), vec![0u8; 3000]).unwrap();
+    fs::write(
+        dir.path().join("videos").join("2024").join("b.mp4"),
+        vec![0u8; 2000],
+    )
+    .unwrap();
+    fs::write(dir.path().join("small.txt"), vec![0u8; 10]).unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("size")
+        .arg(dir.path())
+        .arg("--top")
+        .arg("1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("4.88 KB"))
+        .stdout(predicate::str::contains("videos/"))
+        .stdout(predicate::str::contains("small.txt").not())
+        .stdout(predicate::str::contains("and 1 more"));
+}
+
 #[test]
 fn test_size_filter() {
     let dir = tempdir().unwrap();
@@ -221,7 +698,12 @@ fn test_template_flag() {
         .success();
 
     // Should be organized by template: Images/jpg/photo.jpg
-    assert!(dir.path().join("Images").join("jpg").join("photo.jpg").exists());
+    assert!(dir
+        .path()
+        .join("Images")
+        .join("jpg")
+        .join("photo.jpg")
+        .exists());
 }
 
 #[test]
@@ -249,6 +731,97 @@ fn test_min_size_filter() {
     assert!(dir.path().join("Other").join("big.bin").exists());
 }
 
+/// List every path under `dir` with its contents (None for directories)
+fn snapshot_tree(dir: &std::path::Path) -> Vec<(std::path::PathBuf, Option<Vec<u8>>)> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            entries.push((path.clone(), None));
+            entries.extend(snapshot_tree(&path));
+        } else {
+            entries.push((path.clone(), Some(fs::read(&path).unwrap())));
+        }
+    }
+    entries.sort();
+    entries
+}
+
+#[test]
+fn test_preview_modes_never_modify_files() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "same").unwrap();
+    fs::write(dir.path().join("b.txt"), "same").unwrap();
+    fs::write(dir.path().join("photo.jpg"), "jpg").unwrap();
+    fs::create_dir_all(dir.path().join("empty")).unwrap();
+
+    let before = snapshot_tree(dir.path());
+
+    for (mode, expected) in [
+        ("organize", "Documents"),
+        ("duplicates", "Duplicate Files Found"),
+        ("clean", "empty folders found"),
+    ] {
+        let mut cmd = Command::cargo_bin("neatcli").unwrap();
+        cmd.arg("preview")
+            .arg(dir.path())
+            .arg("--mode")
+            .arg(mode)
+            .arg("--older-than")
+            .arg("0d")
+            .arg("--recursive")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(expected));
+
+        assert_eq!(
+            snapshot_tree(dir.path()),
+            before,
+            "mode {} changed files",
+            mode
+        );
+    }
+}
+
+#[test]
+fn test_summary_only_hides_details() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("report.txt"), "same").unwrap();
+    fs::write(dir.path().join("copy.txt"), "same").unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("organize")
+        .arg(dir.path())
+        .arg("--summary-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 files to move"))
+        .stdout(predicate::str::contains("Documents").not())
+        .stdout(predicate::str::contains("report.txt").not());
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("duplicates")
+        .arg(dir.path())
+        .arg("--summary-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 duplicate files in 1 groups"))
+        .stdout(predicate::str::contains("Group 1").not())
+        .stdout(predicate::str::contains("copy.txt").not());
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("clean")
+        .arg(dir.path())
+        .arg("--older-than")
+        .arg("0d")
+        .arg("--summary-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 files"))
+        .stdout(predicate::str::contains("would be deleted"))
+        .stdout(predicate::str::contains("report.txt").not());
+}
+
 #[test]
 fn test_clean_dry_run() {
     let dir = tempdir().unwrap();
@@ -266,3 +839,2016 @@ fn test_clean_dry_run() {
     assert!(dir.path().join("file.txt").exists());
 }
 
+#[test]
+fn test_clean_rejects_invalid_confirm_threshold() {
+    let dir = tempdir().unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("clean")
+        .arg(dir.path())
+        .args(["--older-than", "1d", "--confirm-threshold", "lots"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_throttle_rejects_unusable_rates() {
+    let dir = tempdir().unwrap();
+    for rate in ["0", "-5", "1e-300", "fast"] {
+        Command::cargo_bin("neatcli")
+            .unwrap()
+            .arg("organize")
+            .arg(dir.path())
+            .arg(format!("--throttle={}", rate))
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Invalid rate"));
+    }
+}
+
+#[test]
+fn test_clean_empty_folders_remove_junk() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("old")).unwrap();
+    fs::write(dir.path().join("old/.DS_Store"), "junk").unwrap();
+    fs::write(dir.path().join("keep.txt"), "content").unwrap();
+
+    // Without the flag, the .DS_Store keeps the folder
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("clean")
+        .arg(dir.path())
+        .arg("--empty-folders")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No empty folders found"));
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("clean")
+        .arg(dir.path())
+        .arg("--empty-folders")
+        .arg("--remove-junk")
+        .arg("--execute")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 empty folders found"));
+
+    assert!(!dir.path().join("old").exists());
+    assert!(dir.path().join("keep.txt").exists());
+}
+
+#[test]
+fn test_clean_empty_files_preview() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("zero.txt")).unwrap();
+    fs::write(dir.path().join("data.txt"), "content").unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("clean")
+        .arg(dir.path())
+        .arg("--empty-files")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("zero.txt"))
+        .stdout(predicate::str::contains("data.txt").not())
+        .stdout(predicate::str::contains("1 empty files found"));
+
+    // Dry-run by default
+    assert!(dir.path().join("zero.txt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_clean_broken_symlinks_preview() {
+    let dir = tempdir().unwrap();
+    let dead = dir.path().join("dead_link");
+    std::os::unix::fs::symlink(dir.path().join("gone.txt"), &dead).unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("clean")
+        .arg(dir.path())
+        .arg("--broken-symlinks")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("dead_link"))
+        .stdout(predicate::str::contains("1 broken symlinks found"));
+
+    // Dry-run by default
+    assert!(fs::symlink_metadata(&dead).is_ok());
+}
+
+#[test]
+fn test_clean_depth_limits_scan() {
+    let dir = tempdir().unwrap();
+    let nested = dir.path().join("project").join("src");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(dir.path().join("top.txt"), "content").unwrap();
+    fs::write(nested.join("deep.txt"), "content").unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("clean")
+        .arg(dir.path())
+        .arg("--older-than")
+        .arg("0d")
+        .arg("--depth")
+        .arg("1")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("top.txt"))
+        .stdout(predicate::str::contains("deep.txt").not());
+}
+
+#[test]
+fn test_piped_output_has_no_color_codes() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("file.txt")).unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("stats")
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files by Type"))
+        .stdout(predicate::str::contains("\u{1b}[").not());
+}
+
+#[test]
+fn test_no_color_flag() {
+    let dir = tempdir().unwrap();
+    File::create(dir.path().join("file.txt")).unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("--no-color")
+        .arg("stats")
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\u{1b}[").not());
+}
+
+#[test]
+fn test_duplicates_jsonl_export() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a1.txt"), "first content").unwrap();
+    fs::write(dir.path().join("a2.txt"), "first content").unwrap();
+    fs::write(dir.path().join("b1.txt"), "second content!").unwrap();
+    fs::write(dir.path().join("b2.txt"), "second content!").unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    let output = cmd
+        .arg("duplicates")
+        .arg(dir.path())
+        .arg("--jsonl")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2);
+    for line in lines {
+        let value: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert!(value.is_object());
+        assert_eq!(value["count"], 2);
+    }
+}
+
+/// Write a minimal PCM WAV file and tag it with artist/album metadata
+fn write_tagged_wav(path: &std::path::Path, artist: &str, album: &str) {
+    use lofty::config::WriteOptions;
+    use lofty::prelude::*;
+    use lofty::tag::{Tag, TagType};
+
+    let samples = [0u8; 800];
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + samples.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&8000u32.to_le_bytes()); // sample rate
+    wav.extend_from_slice(&8000u32.to_le_bytes()); // byte rate
+    wav.extend_from_slice(&1u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&8u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&samples);
+    fs::write(path, wav).unwrap();
+
+    let mut tag = Tag::new(TagType::Id3v2);
+    tag.set_artist(artist.to_string());
+    tag.set_album(album.to_string());
+    tag.save_to_path(path, WriteOptions::default()).unwrap();
+}
+
+#[test]
+fn test_preset_music() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    write_tagged_wav(&dir.path().join("track.wav"), "Test Artist", "Test Album");
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--preset")
+        .arg("music")
+        .arg("--execute")
+        .assert()
+        .success();
+
+    assert!(dir
+        .path()
+        .join("Test Artist")
+        .join("Test Album")
+        .join("track.wav")
+        .exists());
+}
+
+#[test]
+fn test_preset_unknown_fails() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("file.txt"), "content").unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.arg("organize")
+        .arg(dir.path())
+        .arg("--preset")
+        .arg("foo")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid value 'foo'"))
+        .stderr(predicate::str::contains("photos"))
+        .stderr(predicate::str::contains("by-date-taken"));
+}
+
+#[test]
+fn test_organize_category_filter() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("photo.jpg"), "jpg").unwrap();
+    fs::write(dir.path().join("notes.txt"), "txt").unwrap();
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--category")
+        .arg("Images")
+        .arg("--execute")
+        .assert()
+        .success();
+
+    assert!(dir.path().join("Images").join("photo.jpg").exists());
+    assert!(dir.path().join("notes.txt").exists());
+
+    let mut cmd = Command::cargo_bin("neatcli").unwrap();
+    cmd.env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--category")
+        .arg("pictures")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Documents"));
+}
+
+#[test]
+fn test_organize_report_appends_across_runs() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    let out = tempdir().unwrap();
+    let report = out.path().join("report.log");
+
+    for name in ["a.txt", "b.jpg"] {
+        fs::write(dir.path().join(name), name).unwrap();
+        Command::cargo_bin("neatcli")
+            .unwrap()
+            .env("HOME", home.path())
+            .arg("organize")
+            .arg(dir.path())
+            .arg("--report")
+            .arg(&report)
+            .arg("--execute")
+            .assert()
+            .success();
+    }
+
+    let content = fs::read_to_string(&report).unwrap();
+    assert_eq!(content.matches("| organize --by-type ===").count(), 2);
+    assert!(content.contains("Documents (1 files"));
+    assert!(content.contains("Images (1 files"));
+}
+
+#[test]
+fn test_duplicates_pattern_limits_scope() {
+    let dir = tempdir().unwrap();
+    for name in ["a.jpg", "b.jpg", "c.txt"] {
+        fs::write(dir.path().join(name), "same content").unwrap();
+    }
+
+    let output = Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("duplicates")
+        .arg(dir.path())
+        .arg("--pattern")
+        .arg("*.jpg")
+        .arg("--json")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let groups = json.as_array().unwrap();
+    assert_eq!(groups.len(), 1);
+    let mut names: Vec<String> = groups[0]["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| {
+            let path = std::path::Path::new(f["path"].as_str().unwrap());
+            path.file_name().unwrap().to_string_lossy().to_string()
+        })
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["a.jpg", "b.jpg"]);
+}
+
+#[test]
+fn test_organize_since_keeps_only_recent_files() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("recent.txt"), "new").unwrap();
+    let old = dir.path().join("stale.txt");
+    fs::write(&old, "old").unwrap();
+    let ten_days_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(10 * 86_400);
+    filetime::set_file_mtime(&old, filetime::FileTime::from_system_time(ten_days_ago)).unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .args(["--since", "7d"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("recent.txt"))
+        .stdout(predicate::str::contains("stale.txt").not());
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .args(["--since", "7d", "--after", "2024-01-01"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_organize_taken_filter_falls_back_to_mtime() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("new.txt"), "new").unwrap();
+    let old = dir.path().join("old.txt");
+    fs::write(&old, "old").unwrap();
+    let jan_2020 = filetime::FileTime::from_unix_time(1_577_836_800, 0);
+    filetime::set_file_mtime(&old, jan_2020).unwrap();
+
+    // Files without EXIF are filtered by modification time...
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--taken-after")
+        .arg("2024-01-01")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("new.txt"))
+        .stdout(predicate::str::contains("old.txt").not());
+
+    // ...or excluded entirely with --taken-only
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--taken-after")
+        .arg("2024-01-01")
+        .arg("--taken-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No files found to organize."));
+}
+
+#[test]
+fn test_organize_by_camera_uses_configured_fallback_folder() {
+    let home = tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(
+        home.path().join(".neat/config.toml"),
+        "[settings.fallback_folders]\ncamera = \"Sans appareil\"\n",
+    )
+    .unwrap();
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("photo.jpg"), "no exif here").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--by-camera")
+        .arg("--execute")
+        .assert()
+        .success();
+
+    assert!(dir.path().join("Sans appareil/photo.jpg").exists());
+}
+
+#[test]
+fn test_fallback_folders_survive_malformed_config() {
+    let home = tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(home.path().join(".neat/config.toml"), "[settings\n").unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("song.mp3"), "no tags here").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    for args in [
+        ["organize", path, "--by-artist", "--dry-run"],
+        ["quick", "music", path, "--dry-run"],
+    ] {
+        Command::cargo_bin("neatcli")
+            .unwrap()
+            .env("HOME", home.path())
+            .args(args)
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("Ignoring invalid config"));
+    }
+}
+
+#[test]
+fn test_organize_by_type_uses_configured_categories() {
+    let home = tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(
+        home.path().join(".neat/config.toml"),
+        "[categories]\nWork = [\"pdf\", \"docx\"]\nMedia = [\"jpg\", \"mp3\"]\n",
+    )
+    .unwrap();
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("report.pdf"), "report").unwrap();
+    fs::write(dir.path().join("song.mp3"), "song").unwrap();
+    fs::write(dir.path().join("main.rs"), "fn main() {}").unwrap();
+
+    // Configured names are valid --category values
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--category")
+        .arg("work")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 files"));
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--execute")
+        .assert()
+        .success();
+
+    assert!(dir.path().join("Work/report.pdf").exists());
+    assert!(dir.path().join("Media/song.mp3").exists());
+    assert!(dir.path().join("Other/main.rs").exists());
+}
+
+#[test]
+fn test_stats_malformed_config_warns_and_falls_back() {
+    let home = tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(home.path().join(".neat/config.toml"), "[settings\n").unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("photo.jpg"), "photo").unwrap();
+
+    for args in [&[][..], &["--format", "json"]] {
+        Command::cargo_bin("neatcli")
+            .unwrap()
+            .env("HOME", home.path())
+            .arg("stats")
+            .arg(dir.path())
+            .args(args)
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Images"))
+            .stderr(predicate::str::contains("Ignoring invalid config"));
+    }
+}
+
+#[test]
+fn test_invalid_categories_warn_and_fall_back() {
+    let home = tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(
+        home.path().join(".neat/config.toml"),
+        "[categories]\nWork = [\"pdf\"]\nPapers = [\"pdf\"]\n",
+    )
+    .unwrap();
+
+    // Commands that don't classify files don't look at the categories
+    for args in [&["--version"][..], &["config", "show"]] {
+        Command::cargo_bin("neatcli")
+            .unwrap()
+            .env("HOME", home.path())
+            .args(args)
+            .assert()
+            .success();
+    }
+
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("report.pdf"), "report").unwrap();
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--execute")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Ignoring invalid config"));
+
+    assert!(dir.path().join("Documents/report.pdf").exists());
+}
+
+#[test]
+fn test_organize_plan_then_apply() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    let out = tempdir().unwrap();
+    let plan = out.path().join("plan.json");
+    fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+    fs::write(dir.path().join("photo.jpg"), "photo").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--plan")
+        .arg(&plan)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Saved 2 planned moves"));
+
+    // Planning leaves the tree untouched
+    assert!(dir.path().join("notes.txt").exists());
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&plan).unwrap()).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg("--apply")
+        .arg(&plan)
+        .arg("--execute")
+        .assert()
+        .success();
+
+    assert!(dir.path().join("Documents/notes.txt").exists());
+    assert!(dir.path().join("Images/photo.jpg").exists());
+}
+
+#[test]
+fn test_organize_apply_rejects_escaping_destination() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    let plan = home.path().join("plan.json");
+    fs::create_dir_all(dir.path().join("inbox")).unwrap();
+    fs::write(dir.path().join("inbox/notes.txt"), "notes").unwrap();
+    let entries = serde_json::json!([{
+        "from": dir.path().join("inbox/notes.txt"),
+        "to": dir.path().join("Documents/../../escaped.txt"),
+        "size": 5,
+    }]);
+    fs::write(&plan, entries.to_string()).unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg("--apply")
+        .arg(&plan)
+        .arg("--execute")
+        .assert()
+        .stdout(predicate::str::contains("refusing to move it"));
+
+    assert!(dir.path().join("inbox/notes.txt").exists());
+    assert!(!dir.path().parent().unwrap().join("escaped.txt").exists());
+}
+
+#[test]
+fn test_organize_apply_rejects_stale_plan() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    let out = tempdir().unwrap();
+    let plan = out.path().join("plan.json");
+    fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--plan")
+        .arg(&plan)
+        .assert()
+        .success();
+
+    fs::write(dir.path().join("notes.txt"), "notes, now longer").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg("--apply")
+        .arg(&plan)
+        .arg("--execute")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("size changed"));
+
+    assert!(dir.path().join("notes.txt").exists());
+}
+
+#[test]
+fn test_progress_off_prints_no_progress() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    for i in 0..20 {
+        fs::write(dir.path().join(format!("file{}.txt", i)), "x").unwrap();
+    }
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--execute")
+        .arg("--progress")
+        .arg("off")
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+
+    assert!(dir.path().join("Documents/file0.txt").exists());
+    assert!(dir.path().join("Documents/file19.txt").exists());
+}
+
+#[test]
+fn test_progress_defaults_to_plain_when_piped() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    for i in 0..20 {
+        fs::write(dir.path().join(format!("notes{}.txt", i)), "notes").unwrap();
+    }
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--execute")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Moving: 0%"));
+}
+
+#[test]
+fn test_progress_skipped_for_small_jobs() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--execute")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Moving").not());
+}
+
+#[test]
+fn test_tidy_names_renames_files() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("notes .txt"), "notes").unwrap();
+    fs::write(dir.path().join("cafe\u{0301}.txt"), "menu").unwrap();
+    fs::write(dir.path().join("clean.txt"), "clean").unwrap();
+
+    // Preview leaves names alone
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("tidy-names")
+        .arg(dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 files would be renamed"));
+    assert!(dir.path().join("notes .txt").exists());
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("tidy-names")
+        .arg(dir.path())
+        .arg("--execute")
+        .assert()
+        .success();
+
+    let mut names: Vec<String> = fs::read_dir(dir.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["caf\u{00E9}.txt", "clean.txt", "notes.txt"]);
+}
+
+#[test]
+fn test_organize_keep_structure_mirrors_tree() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    let out = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("trips/2024")).unwrap();
+    fs::write(dir.path().join("trips/2024/beach.jpg"), "beach").unwrap();
+    fs::write(dir.path().join("readme.txt"), "readme").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--recursive")
+        .arg("--keep-structure")
+        .arg("--output-dir")
+        .arg(out.path())
+        .arg("--execute")
+        .assert()
+        .success();
+
+    assert!(out.path().join("trips/2024/beach.jpg").exists());
+    assert!(out.path().join("readme.txt").exists());
+    assert!(!dir.path().join("trips/2024/beach.jpg").exists());
+
+    // Mirroring needs somewhere to mirror into
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--keep-structure")
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_organize_delete_empty_after_removes_emptied_folders() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("inbox/old/scans")).unwrap();
+    fs::write(dir.path().join("inbox/old/scans/receipt.pdf"), "receipt").unwrap();
+    fs::write(dir.path().join("inbox/photo.jpg"), "photo").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--recursive")
+        .arg("--delete-empty-after")
+        .arg("--execute")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 empty folders removed"));
+
+    assert!(dir.path().join("Documents/receipt.pdf").exists());
+    assert!(dir.path().join("Images/photo.jpg").exists());
+    assert!(!dir.path().join("inbox").exists());
+
+    // Undo puts the folders back along with the files
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("undo")
+        .assert()
+        .success();
+
+    assert!(dir.path().join("inbox/old/scans/receipt.pdf").exists());
+    assert!(dir.path().join("inbox/photo.jpg").exists());
+}
+
+#[test]
+fn test_organize_delete_empty_after_keeps_untouched_folders() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    for empty in [".git/refs/tags", "node_modules/x/empty", "keep-me"] {
+        fs::create_dir_all(dir.path().join(empty)).unwrap();
+    }
+    fs::write(dir.path().join(".neatignore"), "node_modules\n").unwrap();
+    fs::create_dir_all(dir.path().join("inbox")).unwrap();
+    fs::write(dir.path().join("inbox/notes.txt"), "notes").unwrap();
+    fs::write(dir.path().join("photo.jpg"), "photo").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    // Non-recursive: nothing moves out of a subfolder, so none is removed
+    let stdout = neat_stdout(
+        home.path(),
+        &["organize", path, "--delete-empty-after", "--execute"],
+    );
+    assert!(!stdout.contains("empty folders removed"));
+    assert!(dir.path().join("keep-me").is_dir());
+
+    // Recursive: only the folder emptied by the run goes
+    let stdout = neat_stdout(
+        home.path(),
+        &[
+            "organize",
+            path,
+            "--recursive",
+            "--delete-empty-after",
+            "--execute",
+        ],
+    );
+    assert!(stdout.contains("1 empty folders removed"));
+    assert!(!dir.path().join("inbox").exists());
+    assert!(dir.path().join("keep-me").is_dir());
+    assert!(dir.path().join(".git/refs/tags").is_dir());
+    assert!(dir.path().join("node_modules/x/empty").is_dir());
+}
+
+#[test]
+fn test_organize_summary_json_reports_counts() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("photo.jpg"), "photo").unwrap();
+    fs::write(dir.path().join("notes.txt"), "notes!").unwrap();
+
+    let output = Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .args(["--execute", "--summary-json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let summary: serde_json::Value = serde_json::from_str(stdout.lines().last().unwrap()).unwrap();
+    assert_eq!(summary["moved"], 2);
+    assert_eq!(summary["skipped"], 0);
+    assert_eq!(summary["errors"], serde_json::json!([]));
+    assert_eq!(summary["total_size"], 11);
+}
+
+#[test]
+fn test_organize_summary_json_totals_every_path() {
+    let home = tempdir().unwrap();
+    let first = tempdir().unwrap();
+    let second = tempdir().unwrap();
+    fs::write(first.path().join("photo.jpg"), "photo").unwrap();
+    fs::write(second.path().join("notes.txt"), "notes!").unwrap();
+
+    let output = Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(first.path())
+        .arg(second.path())
+        .args(["--execute", "--summary-json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<_> = stdout.lines().filter(|l| l.starts_with('{')).collect();
+    assert_eq!(lines.len(), 1);
+    let summary: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!(summary["moved"], 2);
+    assert_eq!(summary["total_size"], 11);
+}
+
+#[test]
+fn test_clean_summary_json_reports_counts() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("empty/nested")).unwrap();
+    fs::write(dir.path().join("keep.txt"), "keep").unwrap();
+
+    let output = Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("clean")
+        .arg(dir.path())
+        .args(["--empty-folders", "--execute", "--summary-json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let summary: serde_json::Value = serde_json::from_str(stdout.lines().last().unwrap()).unwrap();
+    assert_eq!(summary["removed"], 0);
+    assert_eq!(summary["removed_dirs"], 2);
+}
+
+#[test]
+fn test_history_json_lists_organize_batch() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("photo.jpg"), "photo").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--execute")
+        .assert()
+        .success();
+
+    let output = Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .args(["history", "--json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let batches: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let batch = &batches[0];
+    assert_eq!(batch["command"], "organize --by-type");
+    assert_eq!(batch["operation_count"], 1);
+    assert!(batch["operations"][0]["to"]
+        .as_str()
+        .unwrap()
+        .ends_with("photo.jpg"));
+}
+
+#[test]
+fn test_organize_label_persists_in_history() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("photo.jpg"), "photo").unwrap();
+    let target = dir.path().to_str().unwrap();
+
+    neat_stdout(
+        home.path(),
+        &[
+            "organize",
+            target,
+            "--execute",
+            "--label",
+            "Q2 photo import",
+        ],
+    );
+
+    let json = neat_stdout(home.path(), &["history", "--format", "json"]);
+    let batches: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(batches[0]["label"], "Q2 photo import");
+    assert_eq!(batches[0]["command"], "organize --by-type");
+
+    let table = neat_stdout(home.path(), &["history"]);
+    assert!(table.contains("Q2 photo import"));
+    assert!(table.contains("organize --by-type"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_organize_follow_up_command_gets_run_totals() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "12345").unwrap();
+    fs::write(dir.path().join("b.jpg"), "123").unwrap();
+    let out = home.path().join("follow-up.txt");
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--execute")
+        .arg("--follow-up-command")
+        .arg(format!(
+            "echo '{{count}} {{bytes}} {{path}}' > '{}'",
+            out.display()
+        ))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Follow-up command finished"));
+
+    let canonical = dir.path().canonicalize().unwrap();
+    assert_eq!(
+        fs::read_to_string(&out).unwrap().trim(),
+        format!("2 8 {}", canonical.display())
+    );
+
+    // A failing command is reported, but the organize run itself succeeded
+    fs::write(dir.path().join("c.txt"), "x").unwrap();
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--execute")
+        .arg("--follow-up-command")
+        .arg("exit 3")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("exited with status 3"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_organize_follow_up_command_runs_once_for_all_paths() {
+    let home = tempdir().unwrap();
+    let parent = tempdir().unwrap();
+    let first = parent.path().join("first");
+    let second = parent.path().join("second");
+    fs::create_dir_all(&first).unwrap();
+    fs::create_dir_all(&second).unwrap();
+    fs::write(first.join("a.txt"), "12345").unwrap();
+    fs::write(second.join("b.jpg"), "123").unwrap();
+    let out = home.path().join("follow-up.txt");
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(&first)
+        .arg(&second)
+        .arg("--execute")
+        .arg("--follow-up-command")
+        .arg(format!(
+            "echo '{{count}} {{bytes}} {{path}}' >> '{}'",
+            out.display()
+        ))
+        .assert()
+        .success();
+
+    let canonical = parent.path().canonicalize().unwrap();
+    assert_eq!(
+        fs::read_to_string(&out).unwrap().trim(),
+        format!("2 8 {}", canonical.display())
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_organize_link_symlink_builds_view_and_undo_removes_it() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("photo.jpg"), "photo").unwrap();
+    fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--link")
+        .arg("symlink")
+        .arg("--execute")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 files linked"));
+
+    // Originals stay put; the view links resolve to them
+    let photo = dir.path().join("photo.jpg").canonicalize().unwrap();
+    let link = dir.path().join("Images/photo.jpg");
+    assert!(fs::symlink_metadata(&link)
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert_eq!(link.canonicalize().unwrap(), photo);
+    assert_eq!(fs::read_to_string(&link).unwrap(), "photo");
+    assert!(dir.path().join("notes.txt").is_file());
+    assert!(dir.path().join("Documents/notes.txt").exists());
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("undo")
+        .assert()
+        .success();
+
+    assert!(!dir.path().join("Images").exists());
+    assert!(!dir.path().join("Documents").exists());
+    assert_eq!(fs::read_to_string(&photo).unwrap(), "photo");
+    assert!(dir.path().join("notes.txt").is_file());
+}
+
+#[test]
+fn test_organize_simulate_conflicts_reports_collisions() {
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("a")).unwrap();
+    fs::create_dir_all(dir.path().join("b")).unwrap();
+    fs::write(dir.path().join("a/notes.txt"), "first").unwrap();
+    fs::write(dir.path().join("b/notes.txt"), "second").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--recursive")
+        .arg("--simulate-conflicts")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 of 2 moves would collide"));
+
+    // Nothing is moved
+    assert!(dir.path().join("a/notes.txt").exists());
+    assert!(dir.path().join("b/notes.txt").exists());
+}
+
+#[test]
+fn test_organize_diff_output() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("photo.jpg"), "photo").unwrap();
+    fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--diff")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "- notes.txt\n+ Documents/notes.txt\n- photo.jpg\n+ Images/photo.jpg\n",
+        ));
+}
+
+#[test]
+fn test_organize_skips_os_junk_by_default() {
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("__MACOSX")).unwrap();
+    fs::write(dir.path().join("__MACOSX/._report.pdf"), "resource fork").unwrap();
+    fs::write(dir.path().join("Thumbs.db"), "cache").unwrap();
+    fs::write(dir.path().join("report.pdf"), "report").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--recursive")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 files"))
+        .stdout(predicate::str::contains("Thumbs.db").not());
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--recursive")
+        .arg("--no-default-ignores")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Thumbs.db"));
+}
+
+#[test]
+fn test_organize_explain_shows_reasons() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--explain")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "notes.txt → Documents/ [mode:by-type]",
+        ));
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--template")
+        .arg("Archive/{ext}")
+        .arg("--explain")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[template]"));
+}
+
+#[test]
+fn test_watch_rejects_recursive_profile() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    neat_stdout(home.path(), &["profile", "save", "deep", "--recursive"]);
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("watch")
+        .arg(dir.path())
+        .arg("--profile")
+        .arg("deep")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Profile 'deep' is recursive"));
+}
+
+#[test]
+fn test_organize_explain_names_local_rule() {
+    let home = tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(
+        home.path().join(".neat/config.toml"),
+        "[[rules]]\nname = \"Global\"\npattern = \"*.pdf\"\ndestination = \"Global\"\n",
+    )
+    .unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("q3.pdf"), "report").unwrap();
+    fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    // Global rules don't route organize
+    let stdout = neat_stdout(home.path(), &["organize", path, "--explain"]);
+    assert!(stdout.contains("q3.pdf → Documents/ [mode:by-type]"));
+    assert!(!stdout.contains("rule:"));
+
+    fs::write(
+        dir.path().join(".neat.toml"),
+        "[[rules]]\nname = \"Reports\"\npattern = \"*.pdf\"\ndestination = \"Reports\"\n",
+    )
+    .unwrap();
+    let stdout = neat_stdout(home.path(), &["organize", path, "--explain"]);
+    assert!(stdout.contains("q3.pdf → Reports/ [rule:Reports]"));
+    assert!(stdout.contains("notes.txt → Documents/ [mode:by-type]"));
+}
+
+#[test]
+fn test_clean_duplicates_with_older_than_in_one_pass() {
+    let dir = tempdir().unwrap();
+    let old = dir.path().join("old.log");
+    fs::write(&old, "ancient").unwrap();
+    let long_ago = filetime::FileTime::from_unix_time(1_000_000_000, 0);
+    filetime::set_file_mtime(&old, long_ago).unwrap();
+    fs::write(dir.path().join("a.jpg"), "same bytes").unwrap();
+    fs::write(dir.path().join("b.jpg"), "same bytes").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("clean")
+        .arg(dir.path())
+        .arg("--older-than")
+        .arg("30d")
+        .arg("--duplicates")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("old files and duplicates"))
+        .stdout(predicate::str::contains("old.log"))
+        .stdout(predicate::str::contains("Duplicate copies:"))
+        .stdout(predicate::str::contains("2 files"))
+        .stdout(predicate::str::contains("1 old, 1 duplicates"));
+
+    // Preview only
+    assert!(old.exists());
+}
+
+#[test]
+fn test_organize_mime_sniff_finds_renamed_image() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("not-really.txt"),
+        b"\x89PNG\r\n\x1A\n\0\0\0\rIHDR",
+    )
+    .unwrap();
+    fs::write(dir.path().join("notes.txt"), "just text").unwrap();
+
+    // By extension, nothing is an image
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--mime")
+        .arg("image/*")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No files found to organize."));
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--mime")
+        .arg("image/*")
+        .arg("--sniff")
+        .arg("--summary-only")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 files"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_quick_cleanup_trashes_old_downloads() {
+    let home = tempdir().unwrap();
+    let downloads = home.path().join("Downloads");
+    fs::create_dir_all(home.path().join(".config")).unwrap();
+    fs::write(
+        home.path().join(".config/user-dirs.dirs"),
+        "XDG_DOWNLOAD_DIR=\"$HOME/Downloads\"\n",
+    )
+    .unwrap();
+    fs::create_dir_all(&downloads).unwrap();
+    let old = downloads.join("old.zip");
+    fs::write(&old, "ancient").unwrap();
+    filetime::set_file_mtime(&old, filetime::FileTime::from_unix_time(1_000_000_000, 0)).unwrap();
+    let recent = downloads.join("recent.zip");
+    fs::write(&recent, "fresh").unwrap();
+
+    let quick_cleanup = || {
+        let mut cmd = Command::cargo_bin("neatcli").unwrap();
+        cmd.env("HOME", home.path())
+            .env_remove("XDG_CONFIG_HOME")
+            .env_remove("XDG_DATA_HOME")
+            .args(["quick", "cleanup", "--trash"]);
+        cmd
+    };
+
+    quick_cleanup()
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("old.zip"));
+    assert!(old.exists());
+
+    quick_cleanup()
+        .arg("--yes")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Moved to trash"));
+    assert!(!old.exists());
+    assert!(recent.exists());
+    assert!(home
+        .path()
+        .join(".local/share/Trash/files/old.zip")
+        .exists());
+}
+
+fn neat_stdout(home: &std::path::Path, args: &[&str]) -> String {
+    let output = Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home)
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?} failed", args);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn test_duplicates_peek_archives_reports_loose_copy() {
+    use std::io::Write;
+
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("manual.pdf"), "the same manual").unwrap();
+    let mut zip = zip::ZipWriter::new(File::create(dir.path().join("backup.zip")).unwrap());
+    zip.start_file("old/manual.pdf", zip::write::SimpleFileOptions::default())
+        .unwrap();
+    zip.write_all(b"the same manual").unwrap();
+    zip.finish().unwrap();
+
+    let target = dir.path().to_str().unwrap();
+    let stdout = neat_stdout(home.path(), &["duplicates", target, "--peek-archives"]);
+    assert!(stdout.contains("Also Stored in Archives"));
+    assert!(stdout.contains("manual.pdf"));
+    assert!(stdout.contains("old/manual.pdf"));
+
+    // Without the flag archives aren't opened
+    let stdout = neat_stdout(home.path(), &["duplicates", target]);
+    assert!(!stdout.contains("old/manual.pdf"));
+    assert!(dir.path().join("manual.pdf").exists());
+}
+
+#[test]
+fn test_organize_by_alpha() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("dune.epub"), "d").unwrap();
+    fs::write(dir.path().join("1984.epub"), "n").unwrap();
+    let target = dir.path().to_str().unwrap();
+
+    neat_stdout(
+        home.path(),
+        &["organize", target, "--by-alpha", "--execute"],
+    );
+    assert!(dir.path().join("D").join("dune.epub").exists());
+    assert!(dir.path().join("#").join("1984.epub").exists());
+}
+
+#[test]
+fn test_flatten_reverses_organize() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("photo.jpg"), "photo").unwrap();
+    fs::write(dir.path().join("report.pdf"), "report").unwrap();
+    let target = dir.path().to_str().unwrap();
+
+    neat_stdout(home.path(), &["organize", target, "--execute"]);
+    assert!(dir.path().join("Images").join("photo.jpg").exists());
+
+    let preview = neat_stdout(home.path(), &["flatten", target]);
+    assert!(preview.contains("2 files would move out of 2 folders"));
+    assert!(dir.path().join("Images").exists());
+
+    neat_stdout(home.path(), &["flatten", target, "--execute"]);
+    assert!(dir.path().join("photo.jpg").exists());
+    assert!(dir.path().join("report.pdf").exists());
+    assert!(!dir.path().join("Images").exists());
+    assert!(!dir.path().join("Documents").exists());
+
+    // Undo puts the files back in their category folders
+    neat_stdout(home.path(), &["undo"]);
+    assert!(dir.path().join("Images").join("photo.jpg").exists());
+    assert!(dir.path().join("Documents").join("report.pdf").exists());
+}
+
+#[test]
+fn test_export_inventory_json() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+    fs::write(dir.path().join("photo.jpg"), "img").unwrap();
+    fs::write(dir.path().join("sub").join("report.pdf"), "report").unwrap();
+    fs::write(dir.path().join("README"), "hi").unwrap();
+    let target = dir.path().to_str().unwrap();
+
+    let json = neat_stdout(home.path(), &["export", target, "--format", "json"]);
+    let entries: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+    assert_eq!(entries.len(), 3);
+
+    let find = |name: &str| {
+        entries
+            .iter()
+            .find(|e| e["path"].as_str().unwrap().ends_with(name))
+            .unwrap_or_else(|| panic!("{} missing from inventory", name))
+    };
+    assert_eq!(find("photo.jpg")["category"], "Images");
+    assert_eq!(find("photo.jpg")["mime"], "image/jpeg");
+    assert_eq!(find("report.pdf")["category"], "Documents");
+    assert_eq!(find("report.pdf")["size"], 6);
+    assert_eq!(find("README")["category"], "Other");
+    assert!(find("README")["extension"].is_null());
+    assert!(find("photo.jpg")["modified"]
+        .as_str()
+        .unwrap()
+        .contains('T'));
+
+    // Read-only: nothing moved
+    assert!(dir.path().join("photo.jpg").exists());
+}
+
+#[test]
+fn test_format_option_on_duplicates() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "same content").unwrap();
+    fs::write(dir.path().join("b.txt"), "same content").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let json = neat_stdout(home.path(), &["duplicates", path, "--format", "json"]);
+    let groups: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(groups[0]["count"], 2);
+
+    let csv = neat_stdout(home.path(), &["duplicates", path, "--format", "csv"]);
+    let mut lines = csv.lines();
+    assert_eq!(lines.next(), Some("group,hash,path,size"));
+    assert_eq!(lines.count(), 2);
+
+    let table = neat_stdout(home.path(), &["duplicates", path, "--format", "table"]);
+    assert!(table.contains("Scanning"));
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .args(["duplicates", path, "--format", "xml"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("table, json, or csv"));
+}
+
+#[test]
+fn test_format_option_on_stats() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("a.txt"), "hello").unwrap();
+    fs::write(dir.path().join("b.jpg"), "image").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let json = neat_stdout(home.path(), &["stats", path, "--format", "json"]);
+    let stats: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(stats["total_files"], 2);
+
+    let csv = neat_stdout(home.path(), &["stats", path, "--format", "csv"]);
+    let mut lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.remove(0), "category,count,size");
+    lines.sort();
+    assert_eq!(lines, vec!["Documents,1,5", "Images,1,5"]);
+}
+
+#[test]
+fn test_format_option_on_history() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("photo.jpg"), "photo").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--execute")
+        .assert()
+        .success();
+
+    let json = neat_stdout(home.path(), &["history", "--format", "json"]);
+    let batches: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(batches[0]["operation_count"], 1);
+
+    let csv = neat_stdout(home.path(), &["history", "--format", "csv"]);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "timestamp,command,operation,from,to");
+    assert_eq!(lines.len(), 2);
+    assert!(lines[1].contains(",organize --by-type,Move,"));
+    assert!(lines[1].ends_with("photo.jpg"));
+}
+
+#[test]
+fn test_format_option_on_organize_plan() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("report.pdf"), "pdf").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let json = neat_stdout(home.path(), &["organize", path, "--format", "json"]);
+    let moves: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert!(moves[0]["to"]
+        .as_str()
+        .unwrap()
+        .ends_with("Documents/report.pdf"));
+
+    let csv = neat_stdout(home.path(), &["organize", path, "--format", "csv"]);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "from,to,size");
+    assert!(lines[1].ends_with("Documents/report.pdf,3"));
+
+    // Only a preview: nothing was moved
+    assert!(dir.path().join("report.pdf").exists());
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .args(["organize", path, "--format", "json", "--execute"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_organize_extension_list_files() {
+    let dir = tempdir().unwrap();
+    let lists = tempdir().unwrap();
+    fs::write(dir.path().join("photo.jpg"), "jpg").unwrap();
+    fs::write(dir.path().join("scan.PNG"), "png").unwrap();
+    fs::write(dir.path().join("notes.txt"), "txt").unwrap();
+    let only = lists.path().join("only.txt");
+    fs::write(&only, "# images we archive\njpg\n\n.png\n").unwrap();
+    let not = lists.path().join("not.txt");
+    fs::write(&not, "txt\n").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--only-ext-file")
+        .arg(&only)
+        .arg("--exclude-ext-from")
+        .arg(&not)
+        .arg("--execute")
+        .env("HOME", lists.path())
+        .assert()
+        .success();
+
+    assert!(dir.path().join("Images/photo.jpg").exists());
+    assert!(dir.path().join("Images/scan.PNG").exists());
+    assert!(dir.path().join("notes.txt").exists());
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--only-ext-file")
+        .arg(lists.path().join("missing.txt"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--only-ext-file"));
+}
+
+#[test]
+fn test_undo_dry_run_previews_without_undoing() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("report.pdf"), "pdf").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--execute")
+        .assert()
+        .success();
+    let moved = dir.path().join("Documents").join("report.pdf");
+    assert!(moved.exists());
+
+    for flag in ["--dry-run", "--list"] {
+        Command::cargo_bin("neatcli")
+            .unwrap()
+            .env("HOME", home.path())
+            .args(["undo", flag])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("restore"))
+            .stdout(predicate::str::contains("report.pdf"))
+            .stdout(predicate::str::contains(
+                "1 would be restored, 0 can't be undone",
+            ));
+    }
+
+    // Nothing moved back and the batch is still there to undo
+    assert!(moved.exists());
+    let history = neat_stdout(home.path(), &["history", "--format", "json"]);
+    let batches: serde_json::Value = serde_json::from_str(&history).unwrap();
+    assert_eq!(batches.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_undo_skips_replaced_file_only_with_verify_undo() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    let path = dir.path().to_str().unwrap();
+    let moved = dir.path().join("Documents/report.pdf");
+    let history = || fs::read_to_string(home.path().join(".neat/history.json")).unwrap();
+
+    // By default moved files aren't read, so no hash is recorded
+    fs::write(dir.path().join("report.pdf"), "pdf").unwrap();
+    neat_stdout(home.path(), &["organize", path, "--execute"]);
+    assert!(!history().contains("\"hash\""));
+    neat_stdout(home.path(), &["undo"]);
+
+    neat_stdout(
+        home.path(),
+        &["organize", path, "--execute", "--verify-undo"],
+    );
+    assert!(history().contains("\"hash\""));
+    fs::write(&moved, "replaced").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("undo")
+        .assert()
+        .stderr(predicate::str::contains("Changed since it was moved"));
+    assert_eq!(fs::read_to_string(&moved).unwrap(), "replaced");
+}
+
+#[test]
+fn test_log_file_records_moves() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("report.pdf"), "pdf").unwrap();
+    let log = home.path().join("neat.log");
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--execute")
+        .arg("--log-file")
+        .arg(&log)
+        .assert()
+        .success()
+        // Diagnostics go to the file, not the terminal
+        .stderr(predicate::str::contains("moved from=").not());
+
+    let contents = fs::read_to_string(&log).unwrap();
+    assert!(contents.contains("INFO"));
+    assert!(contents.contains("scanned root="));
+    assert!(contents.contains("planned from="));
+    let moved = contents
+        .lines()
+        .find(|line| line.contains("moved from="))
+        .unwrap();
+    assert!(moved.contains("report.pdf"));
+    assert!(moved.contains("Documents"));
+}
+
+#[test]
+fn test_organize_collision_suffix() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("Documents")).unwrap();
+    fs::write(dir.path().join("Documents/report.pdf"), "old").unwrap();
+    fs::write(dir.path().join("report.pdf"), "new").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .args(["--collision-suffix", " ({n})", "--execute"])
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Documents/report (1).pdf")).unwrap(),
+        "new"
+    );
+
+    // The config setting applies when no flag is given
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(
+        home.path().join(".neat/config.toml"),
+        "[settings]\ncollision_suffix = \"-copy{n}\"\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("report.pdf"), "newer").unwrap();
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--execute")
+        .assert()
+        .success();
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Documents/report-copy1.pdf")).unwrap(),
+        "newer"
+    );
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .args(["--collision-suffix", "_{count}"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown placeholder"));
+}
+
+#[test]
+fn test_invalid_config_collision_suffix_only_fails_organize() {
+    let home = tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(
+        home.path().join(".neat/config.toml"),
+        "[settings]\ncollision_suffix = \"_{count}\"\n",
+    )
+    .unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("report.pdf"), "report").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .args(["config", "show"])
+        .assert()
+        .success();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid collision_suffix in config",
+        ));
+
+    // The flag wins, so the bad setting isn't looked at
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .args(["--collision-suffix", " ({n})"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_organize_malformed_config_warns_and_falls_back() {
+    let home = tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(home.path().join(".neat/config.toml"), "[settings\n").unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("report.pdf"), "report").unwrap();
+
+    Command::cargo_bin("neatcli")
+        .unwrap()
+        .env("HOME", home.path())
+        .arg("organize")
+        .arg(dir.path())
+        .arg("--dry-run")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Documents"))
+        .stderr(predicate::str::contains("Ignoring invalid config"));
+}
+
+#[test]
+fn test_organize_contains_case_sensitive() {
+    let home = tempdir().unwrap();
+    for case_sensitive in [false, true] {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("Foo.txt"), "upper").unwrap();
+        fs::write(dir.path().join("foo.txt"), "lower").unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        let mut args = vec!["organize", path, "--contains", "Foo", "--execute"];
+        if case_sensitive {
+            args.push("--case-sensitive");
+        }
+        neat_stdout(home.path(), &args);
+
+        let documents = dir.path().join("Documents");
+        assert!(documents.join("Foo.txt").exists());
+        assert_eq!(documents.join("foo.txt").exists(), !case_sensitive);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_organize_link_duplicates_in_place_stores_one_copy() {
+    use std::os::unix::fs::MetadataExt;
+
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("report.txt"), "quarterly numbers").unwrap();
+    fs::write(dir.path().join("report (1).txt"), "quarterly numbers").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let stdout = neat_stdout(
+        home.path(),
+        &["organize", path, "--link-duplicates-in-place", "--execute"],
+    );
+    assert!(stdout.contains("1 identical files hard-linked"));
+
+    let documents = dir.path().join("Documents");
+    let inode = |name: &str| fs::metadata(documents.join(name)).unwrap().ino();
+    assert_eq!(inode("report.txt"), inode("report (1).txt"));
+
+    // Undo puts both names back with their content
+    neat_stdout(home.path(), &["undo"]);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("report (1).txt")).unwrap(),
+        "quarterly numbers"
+    );
+    assert!(dir.path().join("report.txt").exists());
+}
+
+#[test]
+fn test_config_init_merges_rules_into_existing_file() {
+    let home = tempdir().unwrap();
+    let config = home.path().join(".neat/config.toml");
+    fs::create_dir_all(config.parent().unwrap()).unwrap();
+    let original = "# Keep videos small\n[thresholds.Videos]\nmax_size = \"2GB\"\n";
+    fs::write(&config, original).unwrap();
+
+    let stdout = neat_stdout(home.path(), &["config", "init"]);
+    assert!(stdout.contains("Merged sample rules"));
+
+    // The file is only added to: comments and sizes stay as written
+    let content = fs::read_to_string(&config).unwrap();
+    assert!(content.starts_with(original));
+    assert_eq!(content.matches("[[rules]]").count(), 3);
+
+    let stdout = neat_stdout(home.path(), &["config", "init"]);
+    assert!(stdout.contains("already has all sample rules"));
+}
+
+#[test]
+fn test_organize_local_config_rule_overrides_global() {
+    let home = tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(
+        home.path().join(".neat/config.toml"),
+        "[[rules]]\nname = \"Reports\"\npattern = \"*.pdf\"\ndestination = \"Global\"\n",
+    )
+    .unwrap();
+
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join(".neat.toml"),
+        "[[rules]]\nname = \"Reports\"\npattern = \"*.pdf\"\ndestination = \"Projects/Reports\"\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("q3.pdf"), "report").unwrap();
+    fs::write(dir.path().join("notes.txt"), "notes").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    neat_stdout(home.path(), &["organize", path, "--execute"]);
+
+    assert!(dir.path().join("Projects/Reports/q3.pdf").exists());
+    assert!(!dir.path().join("Global").exists());
+    assert!(dir.path().join("Documents/notes.txt").exists());
+    assert!(dir.path().join(".neat.toml").exists());
+}
+
+#[test]
+fn test_organize_local_config_without_rules_ignores_global_rules() {
+    let home = tempdir().unwrap();
+    fs::create_dir_all(home.path().join(".neat")).unwrap();
+    fs::write(
+        home.path().join(".neat/config.toml"),
+        "[[rules]]\nname = \"Reports\"\npattern = \"*.pdf\"\ndestination = \"Global\"\n",
+    )
+    .unwrap();
+
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join(".neat.toml"),
+        "[settings]\ncollision_suffix = \" ({n})\"\n",
+    )
+    .unwrap();
+    fs::create_dir_all(dir.path().join("Documents")).unwrap();
+    fs::write(dir.path().join("Documents/q3.pdf"), "old").unwrap();
+    fs::write(dir.path().join("q3.pdf"), "new").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    neat_stdout(home.path(), &["organize", path, "--execute"]);
+
+    // Organized by type as without the file, renamed with the local suffix
+    assert!(!dir.path().join("Global").exists());
+    assert_eq!(
+        fs::read_to_string(dir.path().join("Documents/q3 (1).pdf")).unwrap(),
+        "new"
+    );
+}
+
+#[test]
+fn test_organize_include_empty_dirs_moves_them_to_bucket() {
+    let home = tempdir().unwrap();
+    let dir = tempdir().unwrap();
+    fs::create_dir_all(dir.path().join("old/2019")).unwrap();
+    fs::create_dir_all(dir.path().join("scratch")).unwrap();
+    fs::create_dir_all(dir.path().join("untouched")).unwrap();
+    fs::create_dir_all(dir.path().join(".git/refs/tags")).unwrap();
+    fs::write(dir.path().join("old/2019/notes.txt"), "notes").unwrap();
+    fs::write(dir.path().join("scratch/todo.txt"), "todo").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let stdout = neat_stdout(
+        home.path(),
+        &[
+            "organize",
+            path,
+            "--recursive",
+            "--include-empty-dirs",
+            "--execute",
+        ],
+    );
+    assert!(stdout.contains("2 empty folders moved to Empty/"));
+    assert!(dir.path().join("Documents/notes.txt").exists());
+    assert!(dir.path().join("Empty/old/2019").is_dir());
+    assert!(dir.path().join("Empty/scratch").is_dir());
+    assert!(!dir.path().join("old").exists());
+    assert!(!dir.path().join("scratch").exists());
+    // Folders the run didn't empty, and hidden ones, stay where they are
+    assert!(dir.path().join("untouched").is_dir());
+    assert!(dir.path().join(".git/refs/tags").is_dir());
+
+    // Undo puts the folders back where they were
+    neat_stdout(home.path(), &["undo"]);
+    assert!(dir.path().join("old/2019").is_dir());
+    assert!(dir.path().join("scratch").is_dir());
+    assert!(dir.path().join("old/2019/notes.txt").exists());
+}